@@ -7,6 +7,7 @@ mod horned_big;
 mod horned_compare;
 mod horned_dump;
 mod horned_materialize;
+mod horned_merge3;
 mod horned_parse;
 mod horned_round;
 mod horned_summary;
@@ -29,6 +30,7 @@ fn app() -> App<'static> {
         .subcommand(horned_compare::app("compare"))
         .subcommand(horned_dump::app("dump"))
         .subcommand(horned_materialize::app("materialize"))
+        .subcommand(horned_merge3::app("merge3"))
         .subcommand(horned_parse::app("parse"))
         .subcommand(horned_round::app("round"))
         .subcommand(horned_summary::app("summary"))
@@ -43,6 +45,7 @@ fn matcher(matches: ArgMatches) -> Result<(), HornedError> {
             "compare" => horned_compare::matcher(submatches),
             "dump" => horned_dump::matcher(submatches),
             "materialize" => horned_materialize::matcher(submatches),
+            "merge3" => horned_merge3::matcher(submatches),
             "parse" => horned_parse::matcher(submatches),
             "round" => horned_round::matcher(submatches),
             "summary" => horned_summary::matcher(submatches),
@@ -44,7 +44,9 @@ pub(crate) fn matcher(matches: &ArgMatches) -> Result<(), HornedError> {
     let r = parse_path(Path::new(input), parser_config(matches))?;
 
     match r {
-        horned_owl::io::ParserOutput::OFNParser(ont, map) => {
+        horned_owl::io::ParserOutput::OBOParser(ont, map)
+        | horned_owl::io::ParserOutput::OFNParser(ont, map)
+        | horned_owl::io::ParserOutput::OMNParser(ont, map) => {
             let hash_map: HashMap<&String, &String> = map.mappings().collect();
             println!("Ontology:\n{:#?}\n\nMapping:\n{:#?}", ont, hash_map);
             Ok(())
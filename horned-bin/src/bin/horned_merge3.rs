@@ -0,0 +1,129 @@
+extern crate clap;
+extern crate horned_owl;
+
+use clap::arg;
+use clap::App;
+use clap::Arg;
+use clap::ArgMatches;
+
+use horned_bin::{
+    config::{parser_app, parser_config},
+    format_type, parse_path_as, write_with_prefixes,
+};
+use horned_owl::curie::PrefixMapping;
+use horned_owl::error::HornedError;
+use horned_owl::io::ofn::writer::AsFunctional;
+use horned_owl::merge::merge3;
+use horned_owl::model::{AnnotatedComponent, RcStr};
+use horned_owl::ontology::component_mapped::RcComponentMappedOntology;
+
+use std::{fs::File, io::stdout, path::Path};
+
+#[allow(dead_code)]
+fn main() -> Result<(), HornedError> {
+    let matches = app("horned-merge3").get_matches();
+    matcher(&matches)
+}
+
+pub(crate) fn app(name: &str) -> App<'static> {
+    parser_app(
+        App::new(name)
+            .version("0.1")
+            .about("Three-way merge of OWL files, usable as a git merge driver")
+            .author("Phillip Lord")
+            .arg(
+                arg!(
+                    --format <FORMAT> "The format of the files, if not that of OURS"
+                )
+                .required(false),
+            )
+            .arg(
+                arg!(
+                    --output <FILE> "Write the merged ontology to FILE rather than stdout"
+                )
+                .required(false),
+            )
+            .arg(
+                Arg::with_name("BASE")
+                    .help("The common ancestor of the files to merge")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("OURS")
+                    .help("Our version of the file")
+                    .required(true)
+                    .index(2),
+            )
+            .arg(
+                Arg::with_name("THEIRS")
+                    .help("Their version of the file")
+                    .required(true)
+                    .index(3),
+            ),
+    )
+}
+
+fn print_versions(side: &str, versions: &[AnnotatedComponent<RcStr>], mapping: &PrefixMapping) {
+    if versions.is_empty() {
+        eprintln!("\t{}:\t(absent)", side);
+    }
+    for ac in versions {
+        eprintln!("\t{}:\t{}", side, ac.as_functional_with_prefixes(mapping));
+    }
+}
+
+pub(crate) fn matcher(matches: &ArgMatches) -> Result<(), HornedError> {
+    let config = parser_config(matches);
+
+    let base = matches.value_of("BASE").unwrap();
+    let ours = matches.value_of("OURS").unwrap();
+    let theirs = matches.value_of("THEIRS").unwrap();
+
+    let format = match matches.value_of("format") {
+        Some(format) => format,
+        None => Path::new(ours)
+            .extension()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| {
+                HornedError::CommandError(format!("Cannot tell the format of {}", ours))
+            })?,
+    };
+    if format_type(format).is_none() {
+        return Err(HornedError::CommandError(format!(
+            "Format is unknown: {}",
+            format
+        )));
+    }
+    let parse = |path: &str| parse_path_as(Path::new(path), format_type(format), config);
+
+    let (base, _, _) = parse(base)?.decompose();
+    let (ours, mapping, _) = parse(ours)?.decompose();
+    let (theirs, _, _) = parse(theirs)?.decompose();
+
+    let (merged, conflicts) = merge3(&base, &ours, &theirs);
+    let prefixes = mapping.clone().unwrap_or_default();
+    for conflict in &conflicts {
+        eprintln!("Conflict:");
+        print_versions("base", &conflict.base, &prefixes);
+        print_versions("ours", &conflict.ours, &prefixes);
+        print_versions("theirs", &conflict.theirs, &prefixes);
+    }
+
+    let amo: RcComponentMappedOntology = merged.into();
+    match matches.value_of("output") {
+        Some(output) => {
+            write_with_prefixes(format, &mut File::create(output)?, &amo, mapping.as_ref())?
+        }
+        None => write_with_prefixes(format, &mut stdout(), &amo, mapping.as_ref())?,
+    }
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(HornedError::CommandError(format!(
+            "{} conflicts found; the merged ontology contains our version of each",
+            conflicts.len()
+        )))
+    }
+}
@@ -46,6 +46,14 @@ pub(crate) fn matcher(matches: &ArgMatches) -> Result<(), HornedError> {
             let amo: RcComponentMappedOntology = so.into();
             horned_owl::io::owx::writer::write(&mut stdout(), &amo, Some(&pm))
         }
+        horned_owl::io::ParserOutput::OBOParser(so, pm) => {
+            let amo: RcComponentMappedOntology = so.into();
+            horned_owl::io::owx::writer::write(&mut stdout(), &amo, Some(&pm))
+        }
+        horned_owl::io::ParserOutput::OMNParser(so, pm) => {
+            let amo: RcComponentMappedOntology = so.into();
+            horned_owl::io::owx::writer::write(&mut stdout(), &amo, Some(&pm))
+        }
         horned_owl::io::ParserOutput::OWXParser(so, pm) => {
             let amo: RcComponentMappedOntology = so.into();
             horned_owl::io::owx::writer::write(&mut stdout(), &amo, Some(&pm))
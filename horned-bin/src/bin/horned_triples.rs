@@ -39,6 +39,12 @@ pub(crate) fn app(name: &str) -> App<'static> {
                 .help("Only triples which match given string")
                 .required(false),
         )
+        .arg(
+            Arg::with_name("ntriples")
+                .long("ntriples")
+                .help("Dump the triples as N-Triples, one per line")
+                .required(false),
+        )
         .arg(
             Arg::with_name("round")
                 .long("round")
@@ -53,6 +59,7 @@ pub(crate) fn matcher(matches: &ArgMatches) -> Result<(), HornedError> {
         .ok_or_else(|| HornedError::CommandError("A file name must be specified".to_string()))?;
 
     let filter = matches.value_of("filter");
+    let ntriples = matches.is_present("ntriples");
 
     let file = File::open(input)?;
     let bufreader = BufReader::new(file);
@@ -72,7 +79,13 @@ pub(crate) fn matcher(matches: &ArgMatches) -> Result<(), HornedError> {
                 true
             }
         })
-        .map(|t| format!("{}\n\t{}\n\t{}", t.0, t.1, t.2))
+        .map(|t| {
+            if ntriples {
+                format!("{} {} {} .", t.0, t.1, t.2)
+            } else {
+                format!("{}\n\t{}\n\t{}", t.0, t.1, t.2)
+            }
+        })
         .collect();
 
     for t in v {
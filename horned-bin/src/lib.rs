@@ -1,6 +1,7 @@
 //! Support for Horned command line programmes
 
 use horned_owl::{
+    curie::PrefixMapping,
     error::HornedError,
     io::{ParserConfiguration, ParserOutput, ResourceType},
     model::{Build, ForIRI, RcAnnotatedComponent, RcStr, IRI},
@@ -21,10 +22,28 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: StdWrite>(
     format: &str,
     write: &mut W,
     ont: &ComponentMappedOntology<A, AA>,
+) -> Result<(), HornedError> {
+    write_with_prefixes(format, write, ont, None)
+}
+
+/// Write `ont` in `format`, using the given `PrefixMapping` for
+/// those formats which support one.
+pub fn write_with_prefixes<A: ForIRI, AA: ForIndex<A>, W: StdWrite>(
+    format: &str,
+    write: &mut W,
+    ont: &ComponentMappedOntology<A, AA>,
+    mapping: Option<&PrefixMapping>,
 ) -> Result<(), HornedError> {
     match format {
-        "owx" => horned_owl::io::owx::writer::write(write, ont, None),
+        "obo" => horned_owl::io::obo::writer::write(write, ont),
+        "json" => horned_owl::io::obographs::writer::write(write, ont),
+        "ofn" => horned_owl::io::ofn::writer::write(write, ont, mapping),
+        "omn" => horned_owl::io::omn::writer::write(write, ont, mapping),
+        "owx" => horned_owl::io::owx::writer::write(write, ont, mapping),
         "owl" => horned_owl::io::rdf::writer::write(write, ont),
+        "ttl" => horned_owl::io::rdf::turtle::writer::write(write, ont, mapping),
+        "nt" => horned_owl::io::rdf::ntriples::writer::write(write, ont),
+        "jsonld" => horned_owl::io::rdf::jsonld::writer::write(write, ont, mapping),
         _ => Err(HornedError::CommandError(format!(
             "Format is unknown: {}",
             format
@@ -33,10 +52,25 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: StdWrite>(
 }
 
 pub fn path_type(path: &Path) -> Option<ResourceType> {
-    match path.extension().and_then(|s| s.to_str()) {
-        Some("ofn") => Some(ResourceType::OFN),
-        Some("owx") => Some(ResourceType::OWX),
-        Some("owl") => Some(ResourceType::RDF),
+    path.extension()
+        .and_then(|s| s.to_str())
+        .and_then(format_type)
+}
+
+/// Return the type of resource written in `format`, given as a file
+/// extension.
+pub fn format_type(format: &str) -> Option<ResourceType> {
+    match format {
+        "obo" => Some(ResourceType::OBO),
+        "ofn" => Some(ResourceType::OFN),
+        "omn" => Some(ResourceType::OMN),
+        "owx" => Some(ResourceType::OWX),
+        "owl" => Some(ResourceType::RDF),
+        "ttl" => Some(ResourceType::TTL),
+        "nt" => Some(ResourceType::NT),
+        "nq" => Some(ResourceType::NQ),
+        "jsonld" => Some(ResourceType::JSONLD),
+        "json" => Some(ResourceType::OBOGraphs),
         _ => None,
     }
 }
@@ -45,12 +79,40 @@ pub fn parse_path(
     path: &Path,
     config: ParserConfiguration,
 ) -> Result<ParserOutput<RcStr, RcAnnotatedComponent>, HornedError> {
-    Ok(match path_type(path) {
+    parse_path_as(path, path_type(path), config)
+}
+
+/// Parse `path` as a resource of type `resource_type`, whatever its
+/// extension.
+pub fn parse_path_as(
+    path: &Path,
+    resource_type: Option<ResourceType>,
+    config: ParserConfiguration,
+) -> Result<ParserOutput<RcStr, RcAnnotatedComponent>, HornedError> {
+    Ok(match resource_type {
+        Some(ResourceType::OBO) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            ParserOutput::obo(horned_owl::io::obo::reader::read(&mut bufreader, config)?)
+        }
+        Some(ResourceType::OBOGraphs) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            ParserOutput::obo(horned_owl::io::obographs::reader::read(
+                &mut bufreader,
+                config,
+            )?)
+        }
         Some(ResourceType::OFN) => {
             let file = File::open(path)?;
             let mut bufreader = BufReader::new(file);
             ParserOutput::ofn(horned_owl::io::ofn::reader::read(&mut bufreader, config)?)
         }
+        Some(ResourceType::OMN) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            ParserOutput::omn(horned_owl::io::omn::reader::read(&mut bufreader, config)?)
+        }
         Some(ResourceType::OWX) => {
             let file = File::open(path)?;
             let mut bufreader = BufReader::new(file);
@@ -61,6 +123,49 @@ pub fn parse_path(
             let iri = horned_owl::resolve::path_to_file_iri(&b, path);
             ParserOutput::rdf(horned_owl::io::rdf::closure_reader::read(&iri, config)?)
         }
+        Some(ResourceType::TTL) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            let b = Build::new();
+            ParserOutput::rdf(horned_owl::io::rdf::turtle::reader::read_with_build(
+                &mut bufreader,
+                &b,
+                config,
+            )?)
+        }
+        Some(ResourceType::NT) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            let b = Build::new();
+            ParserOutput::rdf(horned_owl::io::rdf::ntriples::reader::read_with_build(
+                &mut bufreader,
+                &b,
+                config,
+            )?)
+        }
+        Some(ResourceType::NQ) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            let b = Build::new();
+            ParserOutput::rdf(
+                horned_owl::io::rdf::ntriples::reader::read_quads_with_build(
+                    &mut bufreader,
+                    &b,
+                    &Default::default(),
+                    config,
+                )?,
+            )
+        }
+        Some(ResourceType::JSONLD) => {
+            let file = File::open(path)?;
+            let mut bufreader = BufReader::new(file);
+            let b = Build::new();
+            ParserOutput::rdf(horned_owl::io::rdf::jsonld::reader::read_with_build(
+                &mut bufreader,
+                &b,
+                config,
+            )?)
+        }
         None => {
             return Err(HornedError::CommandError(format!(
                 "Cannot parse a file of this format: {:?}",
@@ -81,6 +186,15 @@ pub fn parse_imports(
         Some(ResourceType::OFN) => {
             ParserOutput::ofn(horned_owl::io::owx::reader::read(&mut bufreader, config)?)
         }
+        Some(ResourceType::OBO) => {
+            ParserOutput::obo(horned_owl::io::obo::reader::read(&mut bufreader, config)?)
+        }
+        Some(ResourceType::OBOGraphs) => ParserOutput::obo(
+            horned_owl::io::obographs::reader::read(&mut bufreader, config)?,
+        ),
+        Some(ResourceType::OMN) => {
+            ParserOutput::omn(horned_owl::io::omn::reader::read(&mut bufreader, config)?)
+        }
         Some(ResourceType::OWX) => {
             ParserOutput::owx(horned_owl::io::owx::reader::read(&mut bufreader, config)?)
         }
@@ -90,6 +204,41 @@ pub fn parse_imports(
             p.parse_imports()?;
             ParserOutput::rdf(p.as_ontology_and_incomplete()?)
         }
+        Some(ResourceType::TTL) => {
+            let b = Build::new();
+            let mut p =
+                horned_owl::io::rdf::turtle::reader::parser_with_build(&mut bufreader, &b, config);
+            p.parse_imports()?;
+            ParserOutput::rdf(p.as_ontology_and_incomplete()?)
+        }
+        Some(ResourceType::NT) => {
+            let b = Build::new();
+            let mut p = horned_owl::io::rdf::ntriples::reader::parser_with_build(
+                &mut bufreader,
+                &b,
+                config,
+            );
+            p.parse_imports()?;
+            ParserOutput::rdf(p.as_ontology_and_incomplete()?)
+        }
+        Some(ResourceType::NQ) => {
+            let b = Build::new();
+            let mut p = horned_owl::io::rdf::ntriples::reader::quads_parser_with_build(
+                &mut bufreader,
+                &b,
+                &Default::default(),
+                config,
+            );
+            p.parse_imports()?;
+            ParserOutput::rdf(p.as_ontology_and_incomplete()?)
+        }
+        Some(ResourceType::JSONLD) => {
+            let b = Build::new();
+            let mut p =
+                horned_owl::io::rdf::jsonld::reader::parser_with_build(&mut bufreader, &b, config);
+            p.parse_imports()?;
+            ParserOutput::rdf(p.as_ontology_and_incomplete()?)
+        }
         None => {
             return Err(HornedError::CommandError(format!(
                 "Cannot parse a file of this format: {:?}",
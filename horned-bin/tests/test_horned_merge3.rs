@@ -0,0 +1,55 @@
+use assert_cmd::prelude::*; // Add methods on commands
+use predicates::prelude::*; // Used for writing assertions
+use std::fs;
+use std::process::Command; // Run programs
+
+#[test]
+fn integration_merge3_unchanged() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("horned-merge3")?;
+
+    cmd.arg("../src/ont/owl-functional/and.ofn")
+        .arg("../src/ont/owl-functional/and.ofn")
+        .arg("../src/ont/owl-functional/and.ofn");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Ontology("));
+
+    Ok(())
+}
+
+#[test]
+fn integration_merge3_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("horned-merge3-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+
+    let ontology = |axioms: &str| {
+        format!(
+            "Prefix(:=<http://example.com/>)\nOntology(<http://example.com/o>\n{})\n",
+            axioms
+        )
+    };
+    fs::write(dir.join("base"), ontology("SubClassOf(:A :B)"))?;
+    fs::write(
+        dir.join("ours"),
+        ontology("SubClassOf(Annotation(:comment \"edited\") :A :B)"),
+    )?;
+    fs::write(dir.join("theirs"), ontology(""))?;
+
+    let mut cmd = Command::cargo_bin("horned-merge3")?;
+    cmd.arg("--format")
+        .arg("ofn")
+        .arg("--output")
+        .arg(dir.join("ours"))
+        .arg(dir.join("base"))
+        .arg(dir.join("ours"))
+        .arg(dir.join("theirs"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("theirs:\t(absent)"));
+
+    let merged = fs::read_to_string(dir.join("ours"))?;
+    fs::remove_dir_all(&dir)?;
+    assert!(merged.contains("SubClassOf(Annotation(:comment \"edited\") :A :B)"));
+
+    Ok(())
+}
@@ -48,3 +48,15 @@ fn integration_parse_ontology_xml() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn integration_parse_ontology_ttl() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("horned-parse")?;
+
+    cmd.arg("../src/ont/owl-ttl/and.ttl");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Parse Complete"));
+
+    Ok(())
+}
@@ -0,0 +1,907 @@
+//! Values of the datatypes of the [OWL 2 datatype
+//! map](https://www.w3.org/TR/owl2-syntax/#Datatype_Maps).
+//!
+//! A [`Literal`] stores only its lexical form, so an ontology can
+//! contain literals such as `"abc"^^xsd:integer` which have no
+//! value. This module parses literals into a typed [`Value`], and
+//! finds the ill-typed literals in an ontology.
+//!
+//! Literals of datatypes which are not in the datatype map, such as
+//! those defined in an ontology, or `rdfs:Literal`, have no value
+//! here, but are not ill-typed.
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::error::HornedError;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::{is_xsd_datatype, Namespace};
+
+mod range;
+pub(crate) use self::range::same_literal;
+pub use self::range::{compare, expand, range_violations, RangeViolation};
+
+/// An arbitrary precision decimal number.
+///
+/// This is the value space of `xsd:decimal` and of all the integer
+/// datatypes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Decimal {
+    negative: bool,
+    /// Digits before the decimal point, without leading zeros
+    integer: String,
+    /// Digits after the decimal point, without trailing zeros
+    fraction: String,
+}
+
+impl Decimal {
+    /// Parse the lexical form of an `xsd:decimal`.
+    pub fn parse(s: &str) -> Option<Decimal> {
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let integer = integer.trim_start_matches('0').to_string();
+        let fraction = fraction.trim_end_matches('0').to_string();
+        let zero = integer.is_empty() && fraction.is_empty();
+        Some(Decimal {
+            negative: negative && !zero,
+            integer,
+            fraction,
+        })
+    }
+
+    /// Parse the lexical form of an `xsd:integer`.
+    pub fn parse_integer(s: &str) -> Option<Decimal> {
+        if s.contains('.') {
+            return None;
+        }
+        Decimal::parse(s)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.fraction.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.integer.is_empty() && self.fraction.is_empty()
+    }
+
+    /// Return the value as an `i128` if it is an integer in range.
+    pub fn to_i128(&self) -> Option<i128> {
+        if !self.is_integer() {
+            return None;
+        }
+        if self.is_zero() {
+            return Some(0);
+        }
+        let sign = if self.negative { "-" } else { "" };
+        format!("{}{}", sign, self.integer).parse().ok()
+    }
+
+    /// Return the value as the nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    /// The number of digits after the decimal point.
+    pub fn fraction_digits(&self) -> usize {
+        self.fraction.len()
+    }
+
+    /// The total number of significant digits.
+    pub fn total_digits(&self) -> usize {
+        if self.integer.is_empty() {
+            self.fraction.trim_start_matches('0').len().max(1)
+        } else {
+            self.integer.len() + self.fraction.len()
+        }
+    }
+
+    fn cmp_magnitude(&self, other: &Decimal) -> Ordering {
+        self.integer
+            .len()
+            .cmp(&other.integer.len())
+            .then_with(|| self.integer.cmp(&other.integer))
+            .then_with(|| self.fraction.cmp(&other.fraction))
+    }
+
+    /// The digits of the magnitude scaled by `10^scale`, least
+    /// significant first.
+    fn digits(&self, scale: usize) -> Vec<u32> {
+        let padding = scale - self.fraction.len();
+        self.integer
+            .bytes()
+            .chain(self.fraction.bytes())
+            .map(|b| u32::from(b - b'0'))
+            .chain(std::iter::repeat_n(0, padding))
+            .rev()
+            .collect()
+    }
+
+    fn from_digits(negative: bool, digits: &[u32], scale: usize) -> Decimal {
+        let mut s: String = digits
+            .iter()
+            .rev()
+            .map(|d| char::from_digit(*d, 10).unwrap())
+            .collect();
+        if s.len() <= scale {
+            s.insert_str(0, &"0".repeat(scale + 1 - s.len()));
+        }
+        let (integer, fraction) = s.split_at(s.len() - scale);
+        let mut d = Decimal::parse(&format!("{}.{}", integer, fraction)).unwrap();
+        d.negative = negative && !d.is_zero();
+        d
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(mut self) -> Decimal {
+        self.negative = !self.negative && !self.is_zero();
+        self
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        let scale = self.fraction.len().max(other.fraction.len());
+        let (larger, smaller) = match self.cmp_magnitude(&other) {
+            Ordering::Less => (other, self),
+            _ => (self, other),
+        };
+        let (a, b) = (larger.digits(scale), smaller.digits(scale));
+
+        let mut digits = Vec::with_capacity(a.len() + 1);
+        let mut carry = 0;
+        if larger.negative == smaller.negative {
+            for (i, d) in a.iter().enumerate() {
+                let sum = d + b.get(i).unwrap_or(&0) + carry;
+                digits.push(sum % 10);
+                carry = sum / 10;
+            }
+            digits.push(carry);
+        } else {
+            for (i, d) in a.iter().enumerate() {
+                let sub = b.get(i).unwrap_or(&0) + carry;
+                carry = u32::from(*d < sub);
+                digits.push(d + 10 * carry - sub);
+            }
+        }
+        Decimal::from_digits(larger.negative, &digits, scale)
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        self + -other
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: Decimal) -> Decimal {
+        let (a, b) = (
+            self.digits(self.fraction.len()),
+            other.digits(other.fraction.len()),
+        );
+
+        let mut digits = vec![0; a.len() + b.len() + 1];
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0;
+            for (j, y) in b.iter().enumerate() {
+                let product = digits[i + j] + x * y + carry;
+                digits[i + j] = product % 10;
+                carry = product / 10;
+            }
+            digits[i + b.len()] += carry;
+        }
+        Decimal::from_digits(
+            self.negative != other.negative,
+            &digits,
+            self.fraction.len() + other.fraction.len(),
+        )
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_magnitude(other),
+            (true, true) => other.cmp_magnitude(self),
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        if self.integer.is_empty() {
+            write!(f, "0")?;
+        } else {
+            write!(f, "{}", self.integer)?;
+        }
+        if !self.fraction.is_empty() {
+            write!(f, ".{}", self.fraction)?;
+        }
+        Ok(())
+    }
+}
+
+/// A value of `owl:rational`, in lowest terms.
+///
+/// Unlike [`Decimal`], the numerator and denominator are limited to
+/// 128 bits.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rational {
+    pub numerator: i128,
+    pub denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Option<Rational> {
+        if denominator == 0 {
+            return None;
+        }
+        let (mut a, mut b) = (numerator.checked_abs()?, denominator.checked_abs()?);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let sign = denominator.signum();
+        Some(Rational {
+            numerator: sign * numerator / a.max(1),
+            denominator: sign * denominator / a.max(1),
+        })
+    }
+
+    /// Parse the lexical form of an `owl:rational`, which is an
+    /// integer numerator and a positive denominator separated by `/`.
+    pub fn parse(s: &str) -> Option<Rational> {
+        let (n, d) = s.split_once('/')?;
+        if d.starts_with(['+', '-']) {
+            return None;
+        }
+        Rational::new(
+            Decimal::parse_integer(n)?.to_i128()?,
+            Decimal::parse_integer(d)?.to_i128()?,
+        )
+    }
+
+    /// Return `d` as a rational, if it fits.
+    pub fn from_decimal(d: &Decimal) -> Option<Rational> {
+        let denominator = 10i128.checked_pow(d.fraction_digits() as u32)?;
+        let sign = if d.negative { "-" } else { "" };
+        let numerator = format!("{}{}{}", sign, d.integer, d.fraction)
+            .parse()
+            .ok()?;
+        Rational::new(numerator, denominator)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        // Denominators are positive, so this does not change the order
+        match (
+            self.numerator.checked_mul(other.denominator),
+            other.numerator.checked_mul(self.denominator),
+        ) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => (self.numerator as f64 / self.denominator as f64)
+                .total_cmp(&(other.numerator as f64 / other.denominator as f64)),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A value of `xsd:dateTime` or `xsd:dateTimeStamp`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: Decimal,
+    /// The offset from UTC in minutes
+    pub timezone: Option<i16>,
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse exactly `n` ASCII digits.
+fn digits(s: &str, n: usize) -> Option<u8> {
+    if s.len() == n && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl DateTime {
+    /// Parse the lexical form of an `xsd:dateTime`.
+    pub fn parse(s: &str) -> Option<DateTime> {
+        let (date, time) = s.split_once('T')?;
+        let (negative, date) = match date.strip_prefix('-') {
+            Some(date) => (true, date),
+            None => (false, date),
+        };
+
+        let mut date = date.rsplitn(3, '-');
+        let (day, month, year) = (date.next()?, date.next()?, date.next()?);
+        if year.len() < 4
+            || year.len() > 4 && year.starts_with('0')
+            || !year.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let year: i64 = year.parse().ok()?;
+        let year = if negative { -year } else { year };
+        let (month, day) = (digits(month, 2)?, digits(day, 2)?);
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        let (time, timezone) = if let Some(time) = time.strip_suffix('Z') {
+            (time, Some(0))
+        } else if time.len() > 6 && time[time.len() - 6..].starts_with(['+', '-']) {
+            let (time, tz) = time.split_at(time.len() - 6);
+            let (h, m) = tz[1..].split_once(':')?;
+            let (h, m) = (digits(h, 2)? as i16, digits(m, 2)? as i16);
+            if m > 59 || h * 60 + m > 14 * 60 {
+                return None;
+            }
+            (
+                time,
+                Some(if tz.starts_with('-') {
+                    -(h * 60 + m)
+                } else {
+                    h * 60 + m
+                }),
+            )
+        } else {
+            (time, None)
+        };
+
+        let mut time = time.splitn(3, ':');
+        let (hour, minute, second) = (time.next()?, time.next()?, time.next()?);
+        let (hour, minute) = (digits(hour, 2)?, digits(minute, 2)?);
+        let (whole, _) = second.split_once('.').unwrap_or((second, ""));
+        digits(whole, 2)?;
+        if second.ends_with('.') {
+            return None;
+        }
+        let second = Decimal::parse(second)?;
+        let sixty = Decimal::parse("60").unwrap();
+        let midnight = hour == 24 && minute == 0 && second.is_zero();
+        if (hour > 23 && !midnight) || minute > 59 || second >= sixty {
+            return None;
+        }
+
+        Some(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            timezone,
+        })
+    }
+}
+
+/// A typed value of a literal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A value of `xsd:decimal` or one of the integer datatypes.
+    Decimal(Decimal),
+    Rational(Rational),
+    Double(f64),
+    Float(f32),
+    Boolean(bool),
+    DateTime(DateTime),
+    /// A value of `xsd:hexBinary` or `xsd:base64Binary`.
+    Binary(Vec<u8>),
+    AnyURI(String),
+    /// A value of `xsd:string`, or one of the datatypes derived from
+    /// it.
+    String(String),
+    /// A string with a language tag, from `rdf:langString` or
+    /// `rdf:PlainLiteral`.
+    LangString {
+        literal: String,
+        lang: String,
+    },
+}
+
+/// Check the lexical form of an `xsd:double` or `xsd:float`, which
+/// is stricter than that accepted by `str::parse`.
+fn is_floating_point(s: &str) -> bool {
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if unsigned == "INF" || s == "NaN" {
+        return true;
+    }
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e.strip_prefix(['+', '-']).unwrap_or(e))),
+        None => (unsigned, None),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    !(integer.is_empty() && fraction.is_empty())
+        && integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+        && exponent.is_none_or(|e| !e.is_empty() && e.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn floating_point<F: std::str::FromStr>(s: &str, nan: F, inf: F, neg_inf: F) -> Option<F> {
+    match s {
+        "NaN" => Some(nan),
+        "INF" | "+INF" => Some(inf),
+        "-INF" => Some(neg_inf),
+        _ if is_floating_point(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn is_language_tag(s: &str) -> bool {
+    let mut parts = s.split('-');
+    let first = parts.next().unwrap_or("");
+    (1..=8).contains(&first.len())
+        && first.bytes().all(|b| b.is_ascii_alphabetic())
+        && parts.all(|p| (1..=8).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+fn is_name_start(c: char, colon: bool) -> bool {
+    c.is_alphabetic() || c == '_' || (colon && c == ':')
+}
+
+fn is_name_char(c: char, colon: bool) -> bool {
+    is_name_start(c, colon) || c.is_alphanumeric() || c == '-' || c == '.' || c == '\u{B7}'
+}
+
+fn is_name(s: &str, colon: bool) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| is_name_start(c, colon)) && chars.all(|c| is_name_char(c, colon))
+}
+
+fn hex_binary(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn base64_binary(s: &str) -> Option<Vec<u8>> {
+    let s: Vec<u8> = s.bytes().filter(|b| *b != b' ').collect();
+    if !s.len().is_multiple_of(4) {
+        return None;
+    }
+    let padding = s.iter().rev().take_while(|b| **b == b'=').count();
+    if padding > 2 {
+        return None;
+    }
+
+    let mut bits = 0u32;
+    let mut n = 0;
+    let mut bytes = vec![];
+    for b in &s[..s.len() - padding] {
+        let v = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | v as u32;
+        n += 6;
+        if n >= 8 {
+            n -= 8;
+            bytes.push((bits >> n) as u8);
+            bits &= (1 << n) - 1;
+        }
+    }
+    // Bits left over from padding must be zero
+    if bits != 0 {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// The range of values of each of the integer datatypes, as
+/// inclusive bounds.
+fn integer_range(local: &str) -> Option<(Option<i128>, Option<i128>)> {
+    Some(match local {
+        "integer" => (None, None),
+        "nonNegativeInteger" => (Some(0), None),
+        "nonPositiveInteger" => (None, Some(0)),
+        "positiveInteger" => (Some(1), None),
+        "negativeInteger" => (None, Some(-1)),
+        "long" => (Some(i64::MIN as i128), Some(i64::MAX as i128)),
+        "int" => (Some(i32::MIN as i128), Some(i32::MAX as i128)),
+        "short" => (Some(i16::MIN as i128), Some(i16::MAX as i128)),
+        "byte" => (Some(i8::MIN as i128), Some(i8::MAX as i128)),
+        "unsignedLong" => (Some(0), Some(u64::MAX as i128)),
+        "unsignedInt" => (Some(0), Some(u32::MAX as i128)),
+        "unsignedShort" => (Some(0), Some(u16::MAX as i128)),
+        "unsignedByte" => (Some(0), Some(u8::MAX as i128)),
+        _ => return None,
+    })
+}
+
+fn in_range(d: &Decimal, (min, max): (Option<i128>, Option<i128>)) -> bool {
+    let bound = |b: i128| Decimal::parse(&b.to_string()).unwrap();
+    min.is_none_or(|min| *d >= bound(min)) && max.is_none_or(|max| *d <= bound(max))
+}
+
+/// Parse the lexical form `s` of an XSD datatype with local name
+/// `local`.
+fn parse_xsd(s: &str, local: &str) -> Option<Option<Value>> {
+    let value = match local {
+        "decimal" => Value::Decimal(Decimal::parse(s)?),
+        "double" => Value::Double(floating_point(
+            s,
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        )?),
+        "float" => Value::Float(floating_point(
+            s,
+            f32::NAN,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+        )?),
+        "boolean" => Value::Boolean(match s {
+            "true" | "1" => true,
+            "false" | "0" => false,
+            _ => return None,
+        }),
+        "dateTime" => Value::DateTime(DateTime::parse(s)?),
+        "dateTimeStamp" => {
+            let dt = DateTime::parse(s)?;
+            dt.timezone?;
+            Value::DateTime(dt)
+        }
+        "hexBinary" => Value::Binary(hex_binary(s)?),
+        "base64Binary" => Value::Binary(base64_binary(s)?),
+        "anyURI" => Value::AnyURI(s.to_string()),
+        "string" => Value::String(s.to_string()),
+        "normalizedString" | "token" | "language" | "Name" | "NCName" | "NMTOKEN" => {
+            if s.contains(['\r', '\n', '\t']) {
+                return None;
+            }
+            let token = !s.starts_with(' ') && !s.ends_with(' ') && !s.contains("  ");
+            let valid = match local {
+                "normalizedString" => true,
+                "token" => token,
+                "language" => is_language_tag(s),
+                "Name" => is_name(s, true),
+                "NCName" => is_name(s, false),
+                _ => !s.is_empty() && s.chars().all(|c| is_name_char(c, true)),
+            };
+            if !valid {
+                return None;
+            }
+            Value::String(s.to_string())
+        }
+        _ => match integer_range(local) {
+            Some(range) => {
+                let d = Decimal::parse_integer(s)?;
+                if !in_range(&d, range) {
+                    return None;
+                }
+                Value::Decimal(d)
+            }
+            None => return Some(None),
+        },
+    };
+    Some(Some(value))
+}
+
+/// Parse the lexical form `s` of the datatype `datatype`.
+///
+/// Returns `Ok(None)` if `datatype` is not in the OWL 2 datatype
+/// map, and an error if `s` is not in the lexical space of
+/// `datatype`. `owl:real` has no lexical forms, so every literal of
+/// it is an error.
+pub fn parse(s: &str, datatype: &str) -> Result<Option<Value>, HornedError> {
+    let value = if is_xsd_datatype(datatype) {
+        parse_xsd(s, &datatype[Namespace::XSD.as_ref().len()..])
+    } else if let Some(local) = datatype.strip_prefix(Namespace::OWL.as_ref()) {
+        match local {
+            "rational" => Rational::parse(s).map(|r| Some(Value::Rational(r))),
+            "real" => None,
+            _ => Some(None),
+        }
+    } else if datatype == format!("{}PlainLiteral", Namespace::RDF.as_ref()) {
+        let (literal, lang) = s.rsplit_once('@').unwrap_or((s, ""));
+        if lang.is_empty() {
+            Some(Some(Value::String(literal.to_string())))
+        } else if is_language_tag(lang) {
+            Some(Some(Value::LangString {
+                literal: literal.to_string(),
+                lang: lang.to_string(),
+            }))
+        } else {
+            None
+        }
+    } else {
+        Some(None)
+    };
+
+    value.ok_or_else(|| HornedError::invalid(format!("{:?} is not a valid {}", s, datatype)))
+}
+
+/// Return the value of `l`.
+///
+/// See [`parse`].
+pub fn value<A: ForIRI>(l: &Literal<A>) -> Result<Option<Value>, HornedError> {
+    match l {
+        Literal::Simple { literal } => Ok(Some(Value::String(literal.clone()))),
+        Literal::Language { literal, lang } => {
+            if is_language_tag(lang) {
+                Ok(Some(Value::LangString {
+                    literal: literal.clone(),
+                    lang: lang.clone(),
+                }))
+            } else {
+                Err(HornedError::invalid(format!(
+                    "{:?} is not a valid language tag",
+                    lang
+                )))
+            }
+        }
+        Literal::Datatype {
+            literal,
+            datatype_iri,
+        } => parse(literal, datatype_iri),
+    }
+}
+
+/// A literal whose lexical form is not valid for its datatype.
+#[derive(Debug)]
+pub struct IllTypedLiteral<A> {
+    pub component: AnnotatedComponent<A>,
+    pub literal: Literal<A>,
+    pub error: HornedError,
+}
+
+struct Literals<A>(Vec<Literal<A>>);
+
+impl<A: ForIRI> Visit<A> for Literals<A> {
+    fn visit_literal(&mut self, l: &Literal<A>) {
+        self.0.push(l.clone());
+    }
+}
+
+/// Return every ill-typed literal in `ont`, including those in
+/// annotations.
+pub fn ill_typed<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<IllTypedLiteral<A>> {
+    let mut walk = Walk::new(Literals(vec![]));
+    let mut ill_typed = vec![];
+    for ac in ont.i().iter() {
+        walk.annotated_component(ac);
+        for literal in walk.as_mut_visit().0.drain(..) {
+            if let Err(error) = value(&literal) {
+                ill_typed.push(IllTypedLiteral {
+                    component: ac.clone(),
+                    literal,
+                    error,
+                });
+            }
+        }
+    }
+    ill_typed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    fn xsd(s: &str, local: &str) -> Result<Option<Value>, HornedError> {
+        parse(s, &format!("{}{}", Namespace::XSD.as_ref(), local))
+    }
+
+    fn decimal(s: &str) -> Value {
+        Value::Decimal(Decimal::parse(s).unwrap())
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(Decimal::parse("-000.500").unwrap().to_string(), "-0.5");
+        assert_eq!(Decimal::parse("-0.0").unwrap().to_string(), "0");
+        assert_eq!(Decimal::parse("+12").unwrap().to_string(), "12");
+        assert!(Decimal::parse(".").is_none());
+        assert!(Decimal::parse("1e5").is_none());
+
+        let mut v: Vec<_> = ["10", "-2.5", "9.99", "-10", "0", "0.01"]
+            .iter()
+            .map(|s| Decimal::parse(s).unwrap())
+            .collect();
+        v.sort();
+        let v: Vec<_> = v.iter().map(|d| d.to_string()).collect();
+        assert_eq!(v, vec!["-10", "-2.5", "0", "0.01", "9.99", "10"]);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let d = |s| Decimal::parse(s).unwrap();
+        assert_eq!((d("0.1") + d("0.2")).to_string(), "0.3");
+        assert_eq!((d("99.5") + d("0.5")).to_string(), "100");
+        assert_eq!((d("0.1") - d("0.25")).to_string(), "-0.15");
+        assert_eq!((d("-1.5") + d("1.5")).to_string(), "0");
+        assert_eq!((d("-2") - d("-7.01")).to_string(), "5.01");
+        assert_eq!((d("1.5") * d("-0.02")).to_string(), "-0.03");
+        assert_eq!((d("0") * d("-3")).to_string(), "0");
+        assert_eq!(
+            (d("99999999999999999999999999999999999999999") * d("10.1")).to_string(),
+            "1009999999999999999999999999999999999999989.9"
+        );
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(xsd("42", "integer").unwrap(), Some(decimal("42")));
+        assert!(xsd("abc", "integer").is_err());
+        assert!(xsd("1.0", "integer").is_err());
+        assert!(xsd("-1", "nonNegativeInteger").is_err());
+        assert!(xsd("0", "positiveInteger").is_err());
+        assert!(xsd("127", "byte").is_ok());
+        assert!(xsd("128", "byte").is_err());
+        assert!(xsd("18446744073709551615", "unsignedLong").is_ok());
+        assert!(xsd("18446744073709551616", "unsignedLong").is_err());
+        assert!(xsd("123456789012345678901234567890123456789012", "integer").is_ok());
+    }
+
+    #[test]
+    fn floating_point() {
+        assert_eq!(xsd("1.5e3", "double").unwrap(), Some(Value::Double(1500.0)));
+        assert_eq!(
+            xsd("-INF", "float").unwrap(),
+            Some(Value::Float(f32::NEG_INFINITY))
+        );
+        assert!(xsd("inf", "double").is_err());
+        assert!(xsd("1e", "double").is_err());
+        assert!(matches!(
+            xsd("NaN", "double").unwrap(),
+            Some(Value::Double(d)) if d.is_nan()
+        ));
+    }
+
+    #[test]
+    fn others() {
+        assert_eq!(xsd("1", "boolean").unwrap(), Some(Value::Boolean(true)));
+        assert!(xsd("yes", "boolean").is_err());
+        assert_eq!(
+            xsd("0FB7", "hexBinary").unwrap(),
+            Some(Value::Binary(vec![0x0f, 0xb7]))
+        );
+        assert_eq!(
+            xsd("aGk=", "base64Binary").unwrap(),
+            Some(Value::Binary(b"hi".to_vec()))
+        );
+        assert!(xsd("aGk", "base64Binary").is_err());
+        assert!(xsd("en-GB", "language").is_ok());
+        assert!(xsd("a b", "NCName").is_err());
+        assert!(xsd("a:b", "NCName").is_err());
+        assert!(xsd("a:b", "Name").is_ok());
+        assert!(xsd(" a", "token").is_err());
+        assert_eq!(xsd("1", "gYear").unwrap(), None);
+    }
+
+    #[test]
+    fn date_time() {
+        let dt = DateTime::parse("2024-02-29T12:30:15.25+01:00").unwrap();
+        assert_eq!((dt.year, dt.month, dt.day), (2024, 2, 29));
+        assert_eq!(dt.second, Decimal::parse("15.25").unwrap());
+        assert_eq!(dt.timezone, Some(60));
+
+        assert!(DateTime::parse("2023-02-29T12:30:15").is_none());
+        assert!(DateTime::parse("2023-01-01T24:00:00").is_some());
+        assert!(DateTime::parse("2023-01-01T24:00:01").is_none());
+        assert!(DateTime::parse("2023-01-01").is_none());
+        assert!(xsd("2023-01-01T00:00:00", "dateTimeStamp").is_err());
+        assert!(xsd("2023-01-01T00:00:00Z", "dateTimeStamp").is_ok());
+    }
+
+    #[test]
+    fn owl_and_rdf() {
+        let owl = Namespace::OWL.as_ref();
+        assert_eq!(
+            parse("2/-4", &format!("{}rational", owl)).ok(),
+            None,
+            "denominator must be positive"
+        );
+        assert_eq!(
+            parse("-2/4", &format!("{}rational", owl)).unwrap(),
+            Some(Value::Rational(Rational::new(-1, 2).unwrap()))
+        );
+        assert!(parse("1", &format!("{}real", owl)).is_err());
+        assert_eq!(
+            parse(
+                "hello@en",
+                &format!("{}PlainLiteral", Namespace::RDF.as_ref())
+            )
+            .unwrap(),
+            Some(Value::LangString {
+                literal: "hello".to_string(),
+                lang: "en".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn ill_typed_in_ontology() {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(
+                "
+  DataPropertyAssertion(:d :i \"abc\"^^xsd:integer)
+  DataPropertyAssertion(:d :i \"1\"^^xsd:integer)
+  AnnotationAssertion(rdfs:comment :i \"maybe\"^^xsd:boolean)
+",
+            );
+
+        let mut literals: Vec<_> = ill_typed(&ont)
+            .into_iter()
+            .map(|i| match i.literal {
+                Literal::Datatype { literal, .. } => literal,
+                _ => panic!(),
+            })
+            .collect();
+        literals.sort();
+        assert_eq!(literals, vec!["abc", "maybe"]);
+    }
+}
@@ -0,0 +1,812 @@
+//! Membership and emptiness of data ranges.
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+
+use regex::Regex;
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::{is_xsd_datatype, Facet, Namespace};
+
+use super::*;
+
+fn is<A: ForIRI>(iri: &IRI<A>, ns: Namespace, local: &str) -> bool {
+    iri.strip_prefix(ns.as_ref()) == Some(local)
+}
+
+/// Return `v` as a rational, if it is a number which fits.
+fn rational(v: &Value) -> Option<Rational> {
+    match v {
+        Value::Decimal(d) => Rational::from_decimal(d),
+        Value::Rational(r) => Some(*r),
+        _ => None,
+    }
+}
+
+/// Seconds since the epoch, and the fraction of a second, of `dt`
+/// normalised to UTC.
+fn timeline(dt: &DateTime) -> (i128, String) {
+    // Days from the civil calendar, after Howard Hinnant
+    let (y, m, d) = (dt.year as i128, dt.month as i128, dt.day as i128);
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let whole: i128 = dt.second.integer.parse().unwrap_or(0);
+    let seconds = days * 86400 + dt.hour as i128 * 3600 + dt.minute as i128 * 60 + whole
+        - dt.timezone.unwrap_or(0) as i128 * 60;
+    (seconds, dt.second.fraction.clone())
+}
+
+/// Compare two values, if they are in the same ordered value space.
+pub fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Decimal(a), Value::Decimal(b)) => Some(a.cmp(b)),
+        (Value::Decimal(_) | Value::Rational(_), Value::Decimal(_) | Value::Rational(_)) => {
+            match (rational(a), rational(b)) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => None,
+            }
+        }
+        (Value::Double(a), Value::Double(b)) => a.partial_cmp(b),
+        (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+        (Value::DateTime(a), Value::DateTime(b))
+            if a.timezone.is_some() == b.timezone.is_some() =>
+        {
+            Some(timeline(a).cmp(&timeline(b)))
+        }
+        _ => None,
+    }
+}
+
+/// Return true if `a` and `b` are the same data value.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        // In OWL, NaN is identical to itself, and 0 and -0 differ
+        (Value::Double(a), Value::Double(b)) => {
+            a.to_bits() == b.to_bits() || a.is_nan() && b.is_nan()
+        }
+        (Value::Float(a), Value::Float(b)) => {
+            a.to_bits() == b.to_bits() || a.is_nan() && b.is_nan()
+        }
+        (Value::Decimal(_) | Value::Rational(_) | Value::DateTime(_), _) => {
+            compare(a, b) == Some(Ordering::Equal)
+        }
+        _ => a == b,
+    }
+}
+
+/// Return true if `a` and `b` are the same data value, or are the
+/// same literal of a datatype which is not in the datatype map.
+pub(crate) fn same_literal<A: ForIRI>(a: &Literal<A>, b: &Literal<A>) -> bool {
+    match (value(a), value(b)) {
+        (Ok(Some(va)), Ok(Some(vb))) => same_value(&va, &vb),
+        (Ok(None), Ok(None)) => a == b,
+        _ => false,
+    }
+}
+
+/// Return true if `v` is in the value space of `datatype`, or `None`
+/// if the datatype is not in the datatype map.
+fn value_in<A: ForIRI>(v: &Value, datatype: &IRI<A>) -> Option<bool> {
+    if is(datatype, Namespace::RDFS, "Literal") {
+        return Some(true);
+    }
+    if is(datatype, Namespace::RDF, "PlainLiteral") {
+        return Some(matches!(v, Value::String(_) | Value::LangString { .. }));
+    }
+    if is(datatype, Namespace::RDF, "langString") {
+        return Some(matches!(v, Value::LangString { .. }));
+    }
+    if is(datatype, Namespace::OWL, "real") || is(datatype, Namespace::OWL, "rational") {
+        return Some(matches!(v, Value::Decimal(_) | Value::Rational(_)));
+    }
+    if !is_xsd_datatype(&datatype[..]) {
+        return None;
+    }
+
+    let local = &datatype[Namespace::XSD.as_ref().len()..];
+    if matches!(parse_xsd("", local), Some(None)) {
+        return None;
+    }
+    Some(match (local, v) {
+        ("decimal", Value::Decimal(_)) => true,
+        ("decimal", Value::Rational(r)) => {
+            // Only fractions with a denominator of 2s and 5s terminate
+            let mut d = r.denominator;
+            for f in [2, 5] {
+                while d % f == 0 {
+                    d /= f;
+                }
+            }
+            d == 1
+        }
+        ("double", Value::Double(_))
+        | ("float", Value::Float(_))
+        | ("boolean", Value::Boolean(_))
+        | ("dateTime", Value::DateTime(_))
+        | ("hexBinary" | "base64Binary", Value::Binary(_))
+        | ("anyURI", Value::AnyURI(_))
+        | ("string", Value::String(_)) => true,
+        ("dateTimeStamp", Value::DateTime(dt)) => dt.timezone.is_some(),
+        (_, Value::String(s)) => matches!(parse_xsd(s, local), Some(Some(Value::String(_)))),
+        (_, Value::Decimal(d)) => {
+            integer_range(local).is_some_and(|range| d.is_integer() && in_range(d, range))
+        }
+        (_, Value::Rational(r)) => integer_range(local).is_some_and(|range| {
+            r.denominator == 1
+                && in_range(&Decimal::parse(&r.numerator.to_string()).unwrap(), range)
+        }),
+        (_, _) => false,
+    })
+}
+
+/// The length of `v`, for the length facets.
+fn length(v: &Value) -> Option<usize> {
+    match v {
+        Value::String(s) | Value::AnyURI(s) | Value::LangString { literal: s, .. } => {
+            Some(s.chars().count())
+        }
+        Value::Binary(b) => Some(b.len()),
+        _ => None,
+    }
+}
+
+/// Return true if `lang` matches the basic language range `range`.
+fn lang_matches(lang: &str, range: &str) -> bool {
+    let (lang, range) = (lang.to_lowercase(), range.to_lowercase());
+    range == "*" || lang == range || lang.starts_with(&format!("{}-", range))
+}
+
+/// Return true if the literal `l`, with value `v`, meets `facet`.
+fn meets<A: ForIRI>(l: &Literal<A>, v: &Value, facet: &FacetRestriction<A>) -> bool {
+    let lexical = l.literal();
+    let bound = match value(&facet.l) {
+        Ok(Some(bound)) => bound,
+        _ => return false,
+    };
+    let n = || match &bound {
+        Value::Decimal(d) => d.to_i128().and_then(|n| usize::try_from(n).ok()),
+        _ => None,
+    };
+
+    match facet.f {
+        Facet::MinInclusive => matches!(
+            compare(v, &bound),
+            Some(Ordering::Greater | Ordering::Equal)
+        ),
+        Facet::MinExclusive => compare(v, &bound) == Some(Ordering::Greater),
+        Facet::MaxInclusive => matches!(compare(v, &bound), Some(Ordering::Less | Ordering::Equal)),
+        Facet::MaxExclusive => compare(v, &bound) == Some(Ordering::Less),
+        Facet::Length => length(v).is_some() && length(v) == n(),
+        Facet::MinLength => length(v).zip(n()).is_some_and(|(len, n)| len >= n),
+        Facet::MaxLength => length(v).zip(n()).is_some_and(|(len, n)| len <= n),
+        Facet::TotalDigits => match v {
+            Value::Decimal(d) => n().is_some_and(|n| d.total_digits() <= n),
+            _ => false,
+        },
+        Facet::FractionDigits => match v {
+            Value::Decimal(d) => n().is_some_and(|n| d.fraction_digits() <= n),
+            _ => false,
+        },
+        Facet::Pattern => match &bound {
+            // XSD patterns match the whole of the lexical form
+            Value::String(p) => {
+                Regex::new(&format!("^(?:{})$", p)).is_ok_and(|re| re.is_match(lexical))
+            }
+            _ => false,
+        },
+        Facet::LangRange => match (v, &bound) {
+            (Value::LangString { lang, .. }, Value::String(range)) => lang_matches(lang, range),
+            _ => false,
+        },
+    }
+}
+
+impl<A: ForIRI> DataRange<A> {
+    /// Return true if `l` is in this data range.
+    ///
+    /// Ill-typed literals are in no data range. Datatypes which are not
+    /// in the OWL 2 datatype map contain only literals of exactly that
+    /// datatype; datatype definitions are not expanded, see
+    /// [`expand`].
+    pub fn contains(&self, l: &Literal<A>) -> bool {
+        let v = match value(l) {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+
+        match self {
+            DataRange::Datatype(dt) => match &v {
+                Some(v) => value_in(v, &dt.0).unwrap_or(false),
+                None => {
+                    is(&dt.0, Namespace::RDFS, "Literal")
+                        || matches!(l, Literal::Datatype { datatype_iri, .. } if *datatype_iri == dt.0)
+                }
+            },
+            DataRange::DataIntersectionOf(v) => v.iter().all(|dr| dr.contains(l)),
+            DataRange::DataUnionOf(v) => v.iter().any(|dr| dr.contains(l)),
+            DataRange::DataComplementOf(dr) => !dr.contains(l),
+            DataRange::DataOneOf(ls) => ls.iter().any(|other| same_literal(l, other)),
+            DataRange::DatatypeRestriction(dt, facets) => {
+                DataRange::Datatype(dt.clone()).contains(l)
+                    && v.as_ref()
+                        .is_some_and(|v| facets.iter().all(|f| meets(l, v, f)))
+            }
+        }
+    }
+
+    /// Return true if `l` is known not to be in this data range.
+    ///
+    /// Unlike `!self.contains(l)`, this is false where membership
+    /// depends on datatypes which are not in the OWL 2 datatype map.
+    fn excludes(&self, l: &Literal<A>) -> bool {
+        let v = match value(l) {
+            Ok(v) => v,
+            Err(_) => return true,
+        };
+
+        match self {
+            DataRange::Datatype(dt) => v
+                .as_ref()
+                .is_some_and(|v| value_in(v, &dt.0) == Some(false)),
+            DataRange::DataIntersectionOf(v) => v.iter().any(|dr| dr.excludes(l)),
+            DataRange::DataUnionOf(v) => v.iter().all(|dr| dr.excludes(l)),
+            DataRange::DataComplementOf(dr) => dr.contains(l),
+            DataRange::DataOneOf(ls) => ls.iter().all(|other| match (&v, value(other)) {
+                (Some(a), Ok(Some(b))) => !same_value(a, &b),
+                (_, Err(_)) => true,
+                _ => false,
+            }),
+            DataRange::DatatypeRestriction(dt, facets) => {
+                DataRange::Datatype(dt.clone()).excludes(l)
+                    || v.as_ref().is_some_and(|v| {
+                        value_in(v, &dt.0).is_some() && facets.iter().any(|f| !meets(l, v, f))
+                    })
+            }
+        }
+    }
+
+    /// Return true if this data range contains no literals.
+    ///
+    /// This is sound, but not complete: a data range for which this
+    /// returns false may still be empty, for example when facets
+    /// other than the bounds and lengths contradict each other.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            DataRange::Datatype(_) => false,
+            DataRange::DataOneOf(ls) => ls.iter().all(|l| value(l).is_err()),
+            DataRange::DataUnionOf(v) => v.iter().all(|dr| dr.is_empty()),
+            DataRange::DataComplementOf(dr) => {
+                matches!(dr.as_ref(), DataRange::Datatype(dt) if is(&dt.0, Namespace::RDFS, "Literal"))
+            }
+            DataRange::DatatypeRestriction(..) => {
+                Constraint::of(std::slice::from_ref(self)).is_empty()
+            }
+            DataRange::DataIntersectionOf(v) => {
+                v.iter().any(|dr| dr.is_empty())
+                    || v.iter().any(|dr| match dr {
+                        DataRange::DataComplementOf(c) => v.contains(c),
+                        DataRange::DataOneOf(ls) => {
+                            ls.iter().all(|l| v.iter().any(|dr| dr.excludes(l)))
+                        }
+                        _ => false,
+                    })
+                    || Constraint::of(v).is_empty()
+            }
+        }
+    }
+}
+
+/// The kinds of value which are disjoint from each other.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Family {
+    Number,
+    Double,
+    Float,
+    String,
+    LangString,
+    Boolean,
+    DateTime,
+    Binary,
+    AnyURI,
+}
+
+fn families<A: ForIRI>(dr: &DataRange<A>) -> Option<BTreeSet<Family>> {
+    let of_value = |v: &Value| match v {
+        Value::Decimal(_) | Value::Rational(_) => Family::Number,
+        Value::Double(_) => Family::Double,
+        Value::Float(_) => Family::Float,
+        Value::Boolean(_) => Family::Boolean,
+        Value::DateTime(_) => Family::DateTime,
+        Value::Binary(_) => Family::Binary,
+        Value::AnyURI(_) => Family::AnyURI,
+        Value::String(_) => Family::String,
+        Value::LangString { .. } => Family::LangString,
+    };
+
+    match dr {
+        DataRange::Datatype(dt) | DataRange::DatatypeRestriction(dt, _) => {
+            let iri = &dt.0;
+            if is(iri, Namespace::RDF, "PlainLiteral") {
+                return Some(
+                    [Family::String, Family::LangString]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                );
+            }
+            // A representative value of each family, to find which
+            // family a datatype is in
+            [
+                Value::Decimal(Decimal::parse("0").unwrap()),
+                Value::Double(0.0),
+                Value::Float(0.0),
+                Value::String("a".to_string()),
+                Value::LangString {
+                    literal: "a".to_string(),
+                    lang: "en".to_string(),
+                },
+                Value::Boolean(true),
+                Value::DateTime(DateTime::parse("2000-01-01T00:00:00Z").unwrap()),
+                Value::Binary(vec![]),
+                Value::AnyURI("a".to_string()),
+            ]
+            .iter()
+            .map(|v| value_in(v, iri).map(|is_in| (is_in, of_value(v))))
+            .collect::<Option<Vec<_>>>()
+            .filter(|_| !is(iri, Namespace::RDFS, "Literal"))
+            .map(|v| v.into_iter().filter(|(i, _)| *i).map(|(_, f)| f).collect())
+        }
+        DataRange::DataIntersectionOf(v) => v
+            .iter()
+            .filter_map(families)
+            .reduce(|a, b| a.intersection(&b).cloned().collect()),
+        DataRange::DataUnionOf(v) => v
+            .iter()
+            .map(families)
+            .collect::<Option<Vec<_>>>()
+            .map(|v| v.into_iter().flatten().collect()),
+        // Ill-typed literals have no family, but those of datatypes
+        // outside the datatype map could be in any
+        DataRange::DataOneOf(ls) => ls
+            .iter()
+            .filter_map(|l| value(l).ok())
+            .map(|v| v.map(|v| of_value(&v)))
+            .collect(),
+        DataRange::DataComplementOf(_) => None,
+    }
+}
+
+/// Bounds on the values of an intersection of data ranges.
+#[derive(Default)]
+struct Constraint {
+    families: Option<BTreeSet<Family>>,
+    lower: Option<(Value, bool)>,
+    upper: Option<(Value, bool)>,
+    integer: bool,
+    min_length: usize,
+    max_length: Option<usize>,
+}
+
+impl Constraint {
+    fn of<A: ForIRI>(v: &[DataRange<A>]) -> Constraint {
+        let mut c = Constraint {
+            families: families(&DataRange::DataIntersectionOf(v.to_vec())),
+            ..Default::default()
+        };
+
+        for dr in v {
+            let (dt, facets) = match dr {
+                DataRange::Datatype(dt) => (dt, &[][..]),
+                DataRange::DatatypeRestriction(dt, facets) => (dt, &facets[..]),
+                _ => continue,
+            };
+            if let Some((min, max)) =
+                dt.0.strip_prefix(Namespace::XSD.as_ref())
+                    .and_then(integer_range)
+            {
+                c.integer = true;
+                let bound = |n: i128| Value::Decimal(Decimal::parse(&n.to_string()).unwrap());
+                if let Some(min) = min {
+                    c.lower(bound(min), true);
+                }
+                if let Some(max) = max {
+                    c.upper(bound(max), true);
+                }
+            }
+
+            for f in facets {
+                let bound = match value(&f.l) {
+                    Ok(Some(bound)) => bound,
+                    _ => continue,
+                };
+                let n = match &bound {
+                    Value::Decimal(d) => d.to_i128().and_then(|n| usize::try_from(n).ok()),
+                    _ => None,
+                };
+                match (&f.f, n) {
+                    (Facet::MinInclusive, _) => c.lower(bound, true),
+                    (Facet::MinExclusive, _) => c.lower(bound, false),
+                    (Facet::MaxInclusive, _) => c.upper(bound, true),
+                    (Facet::MaxExclusive, _) => c.upper(bound, false),
+                    (Facet::Length, Some(n)) => {
+                        c.min_length = c.min_length.max(n);
+                        c.max_length = Some(c.max_length.map_or(n, |m| m.min(n)));
+                    }
+                    (Facet::MinLength, Some(n)) => c.min_length = c.min_length.max(n),
+                    (Facet::MaxLength, Some(n)) => {
+                        c.max_length = Some(c.max_length.map_or(n, |m| m.min(n)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        c
+    }
+
+    fn lower(&mut self, bound: Value, inclusive: bool) {
+        let tighter = match &self.lower {
+            None => true,
+            Some((current, _)) => compare(&bound, current) == Some(Ordering::Greater),
+        };
+        if tighter {
+            self.lower = Some((bound, inclusive));
+        }
+    }
+
+    fn upper(&mut self, bound: Value, inclusive: bool) {
+        let tighter = match &self.upper {
+            None => true,
+            Some((current, _)) => compare(&bound, current) == Some(Ordering::Less),
+        };
+        if tighter {
+            self.upper = Some((bound, inclusive));
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        if self.families.as_ref().is_some_and(|f| f.is_empty()) {
+            return true;
+        }
+        if self.max_length.is_some_and(|max| self.min_length > max) {
+            return true;
+        }
+
+        let (lower, upper) = match (&self.lower, &self.upper) {
+            (Some(lower), Some(upper)) => (lower, upper),
+            _ => return false,
+        };
+        match compare(&lower.0, &upper.0) {
+            Some(Ordering::Greater) => return true,
+            Some(Ordering::Equal) if !(lower.1 && upper.1) => return true,
+            _ => {}
+        }
+
+        // Check that there is an integer between the bounds
+        if self.integer {
+            if let (Some(lower), Some(upper)) = (rational(&lower.0), rational(&upper.0)) {
+                let ceil = lower.numerator.div_euclid(lower.denominator)
+                    + i128::from(lower.numerator.rem_euclid(lower.denominator) != 0);
+                let floor = upper.numerator.div_euclid(upper.denominator);
+                let exact = |r: Rational, n: i128| r.denominator == 1 && r.numerator == n;
+                let first = if !self.lower.as_ref().unwrap().1 && exact(lower, ceil) {
+                    ceil + 1
+                } else {
+                    ceil
+                };
+                let last = if !self.upper.as_ref().unwrap().1 && exact(upper, floor) {
+                    floor - 1
+                } else {
+                    floor
+                };
+                return first > last;
+            }
+        }
+        false
+    }
+}
+
+/// Replace each datatype in `dr` which is defined in `definitions`
+/// with its definition.
+pub fn expand<A: ForIRI>(
+    dr: &DataRange<A>,
+    definitions: &HashMap<IRI<A>, DataRange<A>>,
+) -> DataRange<A> {
+    fn expand_depth<A: ForIRI>(
+        dr: &DataRange<A>,
+        definitions: &HashMap<IRI<A>, DataRange<A>>,
+        depth: usize,
+    ) -> DataRange<A> {
+        // Definitions must not be cyclic, but stop if they are
+        if depth > definitions.len() {
+            return dr.clone();
+        }
+        let all = |v: &Vec<DataRange<A>>| {
+            v.iter()
+                .map(|dr| expand_depth(dr, definitions, depth + 1))
+                .collect()
+        };
+        match dr {
+            DataRange::Datatype(dt) => match definitions.get(&dt.0) {
+                Some(def) => expand_depth(def, definitions, depth + 1),
+                None => dr.clone(),
+            },
+            DataRange::DataIntersectionOf(v) => DataRange::DataIntersectionOf(all(v)),
+            DataRange::DataUnionOf(v) => DataRange::DataUnionOf(all(v)),
+            DataRange::DataComplementOf(dr) => {
+                DataRange::DataComplementOf(Box::new(expand_depth(dr, definitions, depth + 1)))
+            }
+            _ => dr.clone(),
+        }
+    }
+    expand_depth(dr, definitions, 0)
+}
+
+/// A data property assertion whose literal is known not to be in the
+/// range of the property.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RangeViolation<A> {
+    pub assertion: AnnotatedComponent<A>,
+    pub range: AnnotatedComponent<A>,
+}
+
+/// Check every `DataPropertyAssertion` in `ont` against the
+/// `DataPropertyRange` axioms of its property.
+///
+/// Datatype definitions in `ont` are expanded, but ranges of
+/// superproperties are not considered. Nothing is known about
+/// datatypes which are neither defined nor in the datatype map, so
+/// they are never violated.
+pub fn range_violations<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<RangeViolation<A>> {
+    let definitions: HashMap<_, _> = ont
+        .i()
+        .component_for_kind(ComponentKind::DatatypeDefinition)
+        .filter_map(|ac| match &ac.component {
+            Component::DatatypeDefinition(DatatypeDefinition { kind, range }) => {
+                Some((kind.0.clone(), range.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut ranges: HashMap<_, Vec<_>> = HashMap::new();
+    for ac in ont.i().component_for_kind(ComponentKind::DataPropertyRange) {
+        if let Component::DataPropertyRange(DataPropertyRange { dp, dr }) = &ac.component {
+            ranges
+                .entry(dp.clone())
+                .or_default()
+                .push((ac, expand(dr, &definitions)));
+        }
+    }
+
+    let mut violations = vec![];
+    for ac in ont
+        .i()
+        .component_for_kind(ComponentKind::DataPropertyAssertion)
+    {
+        if let Component::DataPropertyAssertion(DataPropertyAssertion { dp, to, .. }) =
+            &ac.component
+        {
+            for (range, dr) in ranges.get(dp).into_iter().flatten() {
+                if dr.excludes(to) {
+                    violations.push(RangeViolation {
+                        assertion: ac.clone(),
+                        range: (*range).clone(),
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    fn b() -> Build<RcStr> {
+        Build::new_rc()
+    }
+
+    fn xsd(local: &str) -> Datatype<RcStr> {
+        b().datatype(format!("{}{}", Namespace::XSD.as_ref(), local))
+    }
+
+    fn lit(s: &str, local: &str) -> Literal<RcStr> {
+        Literal::Datatype {
+            literal: s.to_string(),
+            datatype_iri: xsd(local).0,
+        }
+    }
+
+    fn restriction(local: &str, facets: Vec<(Facet, Literal<RcStr>)>) -> DataRange<RcStr> {
+        DataRange::DatatypeRestriction(
+            xsd(local),
+            facets
+                .into_iter()
+                .map(|(f, l)| FacetRestriction { f, l })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn datatypes() {
+        let integer: DataRange<_> = xsd("integer").into();
+        assert!(integer.contains(&lit("5", "int")));
+        assert!(integer.contains(&lit("5", "integer")));
+        assert!(!integer.contains(&lit("5.5", "decimal")));
+        assert!(!integer.contains(&lit("abc", "integer")));
+        assert!(!integer.contains(&lit("5", "string")));
+
+        let decimal: DataRange<_> = xsd("decimal").into();
+        assert!(decimal.contains(&lit("5", "byte")));
+        assert!(!decimal.contains(&lit("5", "double")));
+
+        let byte: DataRange<_> = xsd("byte").into();
+        assert!(!byte.contains(&lit("300", "integer")));
+
+        let string: DataRange<_> = xsd("string").into();
+        assert!(string.contains(&Literal::Simple {
+            literal: "x".to_string()
+        }));
+    }
+
+    #[test]
+    fn composition() {
+        let b = b();
+        let dr = DataRange::DataUnionOf(vec![
+            xsd("boolean").into(),
+            DataRange::DataOneOf(vec![lit("1", "integer"), lit("2", "integer")]),
+        ]);
+        assert!(dr.contains(&lit("true", "boolean")));
+        assert!(dr.contains(&lit("02", "int")));
+        assert!(!dr.contains(&lit("3", "integer")));
+
+        let dr = DataRange::DataIntersectionOf(vec![
+            xsd("integer").into(),
+            DataRange::DataComplementOf(Box::new(xsd("nonNegativeInteger").into())),
+        ]);
+        assert!(dr.contains(&lit("-1", "integer")));
+        assert!(!dr.contains(&lit("1", "integer")));
+
+        let custom = b.datatype("http://example.com/custom");
+        assert!(
+            DataRange::Datatype(custom.clone()).contains(&Literal::Datatype {
+                literal: "x".to_string(),
+                datatype_iri: custom.0,
+            })
+        );
+    }
+
+    #[test]
+    fn facets() {
+        let dr = restriction(
+            "integer",
+            vec![
+                (Facet::MinExclusive, lit("0", "integer")),
+                (Facet::MaxInclusive, lit("10", "integer")),
+            ],
+        );
+        assert!(!dr.contains(&lit("0", "integer")));
+        assert!(dr.contains(&lit("10", "integer")));
+        assert!(!dr.contains(&lit("11", "integer")));
+
+        let dr = restriction(
+            "string",
+            vec![
+                (Facet::MinLength, lit("2", "integer")),
+                (Facet::MaxLength, lit("3", "integer")),
+                (Facet::Pattern, lit("[a-z]+", "string")),
+            ],
+        );
+        assert!(dr.contains(&lit("abc", "string")));
+        assert!(!dr.contains(&lit("a", "string")));
+        assert!(!dr.contains(&lit("abcd", "string")));
+        assert!(!dr.contains(&lit("ab1", "string")));
+
+        let dr = restriction(
+            "dateTime",
+            vec![(Facet::MinInclusive, lit("2000-01-01T00:00:00Z", "dateTime"))],
+        );
+        assert!(dr.contains(&lit("2000-01-01T00:30:00+00:30", "dateTime")));
+        assert!(!dr.contains(&lit("2000-01-01T00:30:00+01:00", "dateTime")));
+    }
+
+    #[test]
+    fn emptiness() {
+        assert!(!DataRange::Datatype(xsd("integer")).is_empty());
+        assert!(restriction(
+            "integer",
+            vec![
+                (Facet::MinExclusive, lit("1", "integer")),
+                (Facet::MaxExclusive, lit("2", "integer")),
+            ],
+        )
+        .is_empty());
+        assert!(!restriction(
+            "decimal",
+            vec![
+                (Facet::MinExclusive, lit("1", "integer")),
+                (Facet::MaxExclusive, lit("2", "integer")),
+            ],
+        )
+        .is_empty());
+        assert!(
+            DataRange::DataIntersectionOf(vec![xsd("integer").into(), xsd("string").into()])
+                .is_empty()
+        );
+        assert!(DataRange::DataIntersectionOf(vec![
+            xsd("nonNegativeInteger").into(),
+            xsd("negativeInteger").into()
+        ])
+        .is_empty());
+        assert!(DataRange::DataIntersectionOf(vec![
+            xsd("integer").into(),
+            DataRange::DataComplementOf(Box::new(xsd("integer").into()))
+        ])
+        .is_empty());
+        assert!(restriction(
+            "string",
+            vec![
+                (Facet::MinLength, lit("3", "integer")),
+                (Facet::MaxLength, lit("2", "integer")),
+            ],
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn emptiness_unknown() {
+        // Literals and datatypes outside the datatype map may or may
+        // not have values in common, so are never empty
+        let custom = Literal::Datatype {
+            literal: "x".to_string(),
+            datatype_iri: b().iri("http://example.com/t"),
+        };
+        assert!(!DataRange::DataOneOf(vec![lit("2000", "gYear")]).is_empty());
+        assert!(!DataRange::DataOneOf(vec![custom]).is_empty());
+        assert!(!DataRange::DataIntersectionOf(vec![
+            b().datatype("http://example.com/t").into(),
+            DataRange::DataOneOf(vec![lit("5", "integer")])
+        ])
+        .is_empty());
+        assert!(!DataRange::DataIntersectionOf(vec![
+            xsd("gYear").into(),
+            DataRange::DataOneOf(vec![lit("2000", "gYear")])
+        ])
+        .is_empty());
+    }
+
+    #[test]
+    fn ontology() {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(
+                "
+  DatatypeDefinition(:small DatatypeRestriction(xsd:integer xsd:maxInclusive \"10\"^^xsd:integer))
+  DataPropertyRange(:d :small)
+  DataPropertyAssertion(:d :i \"5\"^^xsd:integer)
+  DataPropertyAssertion(:d :i \"50\"^^xsd:integer)
+  DataPropertyRange(:e :MyType)
+  DataPropertyAssertion(:e :i \"1\"^^xsd:integer)
+",
+            );
+
+        let violations = range_violations(&ont);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            &violations[0].assertion.component,
+            Component::DataPropertyAssertion(DataPropertyAssertion { to, .. })
+                if to.literal() == "50"
+        ));
+    }
+}
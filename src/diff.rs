@@ -0,0 +1,551 @@
+//! Compare two ontologies structurally.
+//!
+//! # Overview
+//!
+//! [`diff`] finds the components which are added to, or removed from,
+//! one ontology to give another. Components which are logically equal,
+//! as by [`AnnotatedComponent::logical_eq`], but have different
+//! annotations are reported as changed rather than as both added and
+//! removed.
+//!
+//! Anonymous individuals with the same name in both ontologies are
+//! the same individual. The others are matched up to renaming: each
+//! is identified by the structure of the components in which it
+//! occurs, rather than by its name.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::model::*;
+use crate::ontology::set::SetOntology;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::visitor::mutable::{VisitMut, WalkMut};
+
+/// The differences between two ontologies.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diff<A> {
+    /// The components which are only in the new ontology
+    pub added: Vec<AnnotatedComponent<A>>,
+    /// The components which are only in the old ontology
+    pub removed: Vec<AnnotatedComponent<A>>,
+    /// The components, as `(old, new)`, which are in both ontologies
+    /// with different annotations
+    pub changed: Vec<(AnnotatedComponent<A>, AnnotatedComponent<A>)>,
+}
+
+impl<A: ForIRI> Diff<A> {
+    fn new() -> Diff<A> {
+        Diff {
+            added: vec![],
+            removed: vec![],
+            changed: vec![],
+        }
+    }
+
+    /// Return true if the ontologies are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Group the differences by the entity which each component
+    /// concerns, as given by [`subject`].
+    ///
+    /// Components which do not concern an entity, such as ontology
+    /// annotations or rules, are grouped under `None`.
+    pub fn by_entity(&self) -> BTreeMap<Option<IRI<A>>, Diff<A>> {
+        fn group<'a, A: ForIRI>(
+            groups: &'a mut BTreeMap<Option<IRI<A>>, Diff<A>>,
+            ac: &AnnotatedComponent<A>,
+        ) -> &'a mut Diff<A> {
+            groups
+                .entry(subject(&ac.component))
+                .or_insert_with(Diff::new)
+        }
+
+        let mut groups = BTreeMap::new();
+        for ac in &self.added {
+            group(&mut groups, ac).added.push(ac.clone());
+        }
+        for ac in &self.removed {
+            group(&mut groups, ac).removed.push(ac.clone());
+        }
+        for (old, new) in &self.changed {
+            group(&mut groups, old)
+                .changed
+                .push((old.clone(), new.clone()));
+        }
+        groups
+    }
+}
+
+/// Return the entity which `c` is about: the entity declared, the
+/// subclass or subproperty, the property characterised, the subject of
+/// an assertion, or the first named entity of an n-ary axiom.
+pub fn subject<A: ForIRI>(c: &Component<A>) -> Option<IRI<A>> {
+    fn class<A: ForIRI>(ce: &ClassExpression<A>) -> Option<IRI<A>> {
+        match ce {
+            ClassExpression::Class(c) => Some(c.0.clone()),
+            _ => None,
+        }
+    }
+    fn ope<A: ForIRI>(ope: &ObjectPropertyExpression<A>) -> Option<IRI<A>> {
+        match ope {
+            ObjectPropertyExpression::ObjectProperty(op)
+            | ObjectPropertyExpression::InverseObjectProperty(op) => Some(op.0.clone()),
+        }
+    }
+    fn individual<A: ForIRI>(i: &Individual<A>) -> Option<IRI<A>> {
+        match i {
+            Individual::Named(ni) => Some(ni.0.clone()),
+            Individual::Anonymous(_) => None,
+        }
+    }
+
+    match c {
+        Component::DeclareClass(DeclareClass(e)) => Some(e.0.clone()),
+        Component::DeclareObjectProperty(DeclareObjectProperty(e)) => Some(e.0.clone()),
+        Component::DeclareAnnotationProperty(DeclareAnnotationProperty(e)) => Some(e.0.clone()),
+        Component::DeclareDataProperty(DeclareDataProperty(e)) => Some(e.0.clone()),
+        Component::DeclareNamedIndividual(DeclareNamedIndividual(e)) => Some(e.0.clone()),
+        Component::DeclareDatatype(DeclareDatatype(e)) => Some(e.0.clone()),
+        Component::SubClassOf(SubClassOf { sub, sup }) => class(sub).or_else(|| class(sup)),
+        Component::EquivalentClasses(EquivalentClasses(v))
+        | Component::DisjointClasses(DisjointClasses(v)) => v.iter().find_map(class),
+        Component::DisjointUnion(DisjointUnion(c, _)) => Some(c.0.clone()),
+        Component::SubObjectPropertyOf(SubObjectPropertyOf { sub, sup }) => match sub {
+            SubObjectPropertyExpression::ObjectPropertyExpression(sub) => ope(sub),
+            SubObjectPropertyExpression::ObjectPropertyChain(_) => ope(sup),
+        },
+        Component::EquivalentObjectProperties(EquivalentObjectProperties(v))
+        | Component::DisjointObjectProperties(DisjointObjectProperties(v)) => {
+            v.iter().find_map(ope)
+        }
+        Component::InverseObjectProperties(InverseObjectProperties(op, _)) => Some(op.0.clone()),
+        Component::ObjectPropertyDomain(ObjectPropertyDomain { ope: p, .. })
+        | Component::ObjectPropertyRange(ObjectPropertyRange { ope: p, .. })
+        | Component::FunctionalObjectProperty(FunctionalObjectProperty(p))
+        | Component::InverseFunctionalObjectProperty(InverseFunctionalObjectProperty(p))
+        | Component::ReflexiveObjectProperty(ReflexiveObjectProperty(p))
+        | Component::IrreflexiveObjectProperty(IrreflexiveObjectProperty(p))
+        | Component::SymmetricObjectProperty(SymmetricObjectProperty(p))
+        | Component::AsymmetricObjectProperty(AsymmetricObjectProperty(p))
+        | Component::TransitiveObjectProperty(TransitiveObjectProperty(p)) => ope(p),
+        Component::SubDataPropertyOf(SubDataPropertyOf { sub: dp, .. })
+        | Component::DataPropertyDomain(DataPropertyDomain { dp, .. })
+        | Component::DataPropertyRange(DataPropertyRange { dp, .. })
+        | Component::FunctionalDataProperty(FunctionalDataProperty(dp)) => Some(dp.0.clone()),
+        Component::EquivalentDataProperties(EquivalentDataProperties(v))
+        | Component::DisjointDataProperties(DisjointDataProperties(v)) => {
+            v.first().map(|dp| dp.0.clone())
+        }
+        Component::DatatypeDefinition(DatatypeDefinition { kind, .. }) => Some(kind.0.clone()),
+        Component::HasKey(HasKey { ce, .. }) => class(ce),
+        Component::SameIndividual(SameIndividual(v))
+        | Component::DifferentIndividuals(DifferentIndividuals(v)) => v.iter().find_map(individual),
+        Component::ClassAssertion(ClassAssertion { i, .. }) => individual(i),
+        Component::ObjectPropertyAssertion(ObjectPropertyAssertion { from, .. })
+        | Component::NegativeObjectPropertyAssertion(NegativeObjectPropertyAssertion {
+            from,
+            ..
+        })
+        | Component::DataPropertyAssertion(DataPropertyAssertion { from, .. })
+        | Component::NegativeDataPropertyAssertion(NegativeDataPropertyAssertion {
+            from, ..
+        }) => individual(from),
+        Component::AnnotationAssertion(AnnotationAssertion { subject, .. }) => match subject {
+            AnnotationSubject::IRI(iri) => Some(iri.clone()),
+            AnnotationSubject::AnonymousIndividual(_) => None,
+        },
+        Component::SubAnnotationPropertyOf(SubAnnotationPropertyOf { sub: ap, .. })
+        | Component::AnnotationPropertyDomain(AnnotationPropertyDomain { ap, .. })
+        | Component::AnnotationPropertyRange(AnnotationPropertyRange { ap, .. }) => {
+            Some(ap.0.clone())
+        }
+        Component::OntologyID(_)
+        | Component::DocIRI(_)
+        | Component::OntologyAnnotation(_)
+        | Component::Import(_)
+        | Component::Rule(_) => None,
+    }
+}
+
+/// Collects the anonymous individuals of a component.
+struct Anonymous<A>(BTreeSet<AnonymousIndividual<A>>);
+
+impl<A: ForIRI> Visit<A> for Anonymous<A> {
+    fn visit_anonymous_individual(&mut self, ai: &AnonymousIndividual<A>) {
+        self.0.insert(ai.clone());
+    }
+}
+
+/// Renames anonymous individuals, to a default if they have no name
+/// and there is one.
+struct Rename<'a, A> {
+    names: &'a HashMap<AnonymousIndividual<A>, AnonymousIndividual<A>>,
+    default: Option<&'a AnonymousIndividual<A>>,
+}
+
+impl<'a, A: ForIRI> VisitMut<A> for Rename<'a, A> {
+    fn visit_anonymous_individual(&mut self, ai: &mut AnonymousIndividual<A>) {
+        if let Some(name) = self.names.get(ai).or(self.default) {
+            *ai = name.clone();
+        }
+    }
+}
+
+pub(crate) fn rename<A: ForIRI>(
+    ac: &mut AnnotatedComponent<A>,
+    names: &HashMap<AnonymousIndividual<A>, AnonymousIndividual<A>>,
+    default: Option<&AnonymousIndividual<A>>,
+) {
+    let mut walk = WalkMut::new(Rename { names, default });
+    walk.annotated_component(ac);
+}
+
+fn hash<T: Hash>(t: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    t.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn anonymous<A: ForIRI>(ac: &AnnotatedComponent<A>) -> BTreeSet<AnonymousIndividual<A>> {
+    let mut walk = Walk::new(Anonymous(BTreeSet::new()));
+    walk.component(&ac.component);
+    walk.into_visit().0
+}
+
+/// Return canonical names for the anonymous individuals of each of
+/// `sides` other than those in `fixed`, so that renaming them in the
+/// input does not change the names.
+///
+/// Anonymous individuals are coloured by the components in which they
+/// occur, and the colours are refined with those of their neighbours
+/// until they stop distinguishing any more individuals. The sides are
+/// refined together, so that individuals of each with the same
+/// structure have the same colour. Individuals in `fixed` keep their
+/// names.
+fn canonical<A: ForIRI>(
+    sides: &[&[&AnnotatedComponent<A>]],
+    fixed: &BTreeSet<AnonymousIndividual<A>>,
+) -> Vec<HashMap<AnonymousIndividual<A>, AnonymousIndividual<A>>> {
+    let b = Build::new();
+    let mut occurrences: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (side, components) in sides.iter().enumerate() {
+        for (n, ac) in components.iter().enumerate() {
+            for ai in anonymous(ac) {
+                if !fixed.contains(&ai) {
+                    occurrences.entry((side, ai)).or_default().push(n);
+                }
+            }
+        }
+    }
+
+    let this = b.anon("this");
+    let other = b.anon("other");
+    let fixed: HashMap<_, _> = fixed.iter().map(|ai| (ai.clone(), ai.clone())).collect();
+    let mut colours: HashMap<(usize, AnonymousIndividual<A>), AnonymousIndividual<A>> =
+        HashMap::new();
+    let mut distinct = 0;
+    while !occurrences.is_empty() {
+        let known: Vec<_> = (0..sides.len())
+            .map(|side| {
+                let mut names = fixed.clone();
+                names.extend(
+                    colours
+                        .iter()
+                        .filter(|((s, _), _)| *s == side)
+                        .map(|((_, ai), colour)| (ai.clone(), colour.clone())),
+                );
+                names
+            })
+            .collect();
+        let mut next = HashMap::new();
+        for ((side, ai), ns) in &occurrences {
+            let mut names = known[*side].clone();
+            names.insert(ai.clone(), this.clone());
+            let mut hashes: Vec<_> = ns
+                .iter()
+                .map(|n| {
+                    let mut ac: AnnotatedComponent<A> = sides[*side][*n].component.clone().into();
+                    rename(&mut ac, &names, Some(&other));
+                    hash(&ac.component)
+                })
+                .collect();
+            hashes.sort_unstable();
+            next.insert(
+                (*side, ai.clone()),
+                b.anon(format!("_:c{:x}", hash(&hashes))),
+            );
+        }
+        colours = next;
+        let count = colours.values().collect::<HashSet<_>>().len();
+        if count == distinct {
+            break;
+        }
+        distinct = count;
+    }
+
+    // Individuals which are still not distinguished are numbered
+    let mut seen: HashMap<_, usize> = HashMap::new();
+    let mut names = vec![HashMap::new(); sides.len()];
+    for key in occurrences.keys() {
+        let colour = &colours[key];
+        let count = seen.entry((key.0, colour.clone())).or_default();
+        *count += 1;
+        names[key.0].insert(
+            key.1.clone(),
+            b.anon(format!("{}-{}", colour.0.borrow(), count)),
+        );
+    }
+    names
+}
+
+/// Return a renaming of the anonymous individuals of `new` to the
+/// corresponding individuals of `old`.
+///
+/// Individuals with the same name in both ontologies correspond. The
+/// others are matched up to renaming, by the structure of the
+/// components in which they occur, and those which match none keep
+/// their names.
+pub(crate) fn correspondence<A: ForIRI>(
+    old: &[&AnnotatedComponent<A>],
+    new: &[&AnnotatedComponent<A>],
+) -> HashMap<AnonymousIndividual<A>, AnonymousIndividual<A>> {
+    let all = |components: &[&AnnotatedComponent<A>]| -> BTreeSet<_> {
+        components.iter().flat_map(|ac| anonymous(ac)).collect()
+    };
+    let news = all(new);
+    let fixed: BTreeSet<_> = all(old).intersection(&news).cloned().collect();
+
+    let mut names = canonical(&[old, new], &fixed);
+    let colours = names.pop().unwrap();
+    let olds: HashMap<_, _> = names
+        .pop()
+        .unwrap()
+        .into_iter()
+        .map(|(ai, colour)| (colour, ai))
+        .collect();
+    news.into_iter()
+        .map(|ai| {
+            let name = colours
+                .get(&ai)
+                .and_then(|colour| olds.get(colour))
+                .unwrap_or(&ai)
+                .clone();
+            (ai, name)
+        })
+        .collect()
+}
+
+/// Return the differences between the `old` and `new` ontologies.
+///
+/// The components of the result are those of the ontologies, with
+/// their original anonymous individuals.
+pub fn diff<A: ForIRI>(old: &SetOntology<A>, new: &SetOntology<A>) -> Diff<A> {
+    let old: Vec<_> = old.iter().collect();
+    let new: Vec<_> = new.iter().collect();
+    let names = correspondence(&old, &new);
+    let old_renamed: Vec<_> = old.iter().map(|ac| (*ac).clone()).collect();
+    let new_renamed: Vec<_> = new
+        .iter()
+        .map(|ac| {
+            let mut ac = (*ac).clone();
+            rename(&mut ac, &names, None);
+            ac
+        })
+        .collect();
+
+    // Index the components which are not in the other ontology by
+    // their logical component
+    let only = |these: &[&AnnotatedComponent<A>],
+                renamed: &[AnnotatedComponent<A>],
+                other: &[AnnotatedComponent<A>]| {
+        let other: HashSet<_> = other.iter().collect();
+        let mut only: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (ac, c) in these.iter().zip(renamed) {
+            if !other.contains(c) {
+                only.entry(c.component.clone())
+                    .or_default()
+                    .push((c.clone(), (*ac).clone()));
+            }
+        }
+        only
+    };
+    let mut removed = only(&old, &old_renamed, &new_renamed);
+    let mut added = only(&new, &new_renamed, &old_renamed);
+
+    let mut diff = Diff::new();
+    for (c, olds) in removed.iter_mut() {
+        if let Some(news) = added.get_mut(c) {
+            olds.sort();
+            news.sort();
+            let n = olds.len().min(news.len());
+            diff.changed.extend(
+                olds.drain(..n)
+                    .zip(news.drain(..n))
+                    .map(|((_, old), (_, new))| (old, new)),
+            );
+        }
+    }
+    diff.removed = removed.into_values().flatten().map(|(_, ac)| ac).collect();
+    diff.added = added.into_values().flatten().map(|(_, ac)| ac).collect();
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::RcStr;
+
+    use pretty_assertions::assert_eq;
+
+    fn read(ofn: &str) -> SetOntology<RcStr> {
+        crate::io::ofn::reader::read_example(ofn)
+    }
+
+    fn iri(s: &str) -> IRI<RcStr> {
+        Build::new_rc().iri(format!("http://example.com/{}", s))
+    }
+
+    #[test]
+    fn added_removed_changed() {
+        let old = read(
+            "Declaration(Class(:A))
+             SubClassOf(:A :B)
+             SubClassOf(Annotation(rdfs:comment \"old\") :B :C)
+             ClassAssertion(:A :i)",
+        );
+        let new = read(
+            "Declaration(Class(:A))
+             SubClassOf(:A :D)
+             SubClassOf(Annotation(rdfs:comment \"new\") :B :C)
+             ClassAssertion(:A :i)
+             ClassAssertion(:B :i)",
+        );
+        let diff = diff(&old, &new);
+
+        let b = Build::new_rc();
+        let sub = |x: &str, y: &str| -> Component<RcStr> {
+            SubClassOf {
+                sub: b.class(iri(x)).into(),
+                sup: b.class(iri(y)).into(),
+            }
+            .into()
+        };
+        let components = |v: &[AnnotatedComponent<RcStr>]| -> Vec<_> {
+            v.iter().map(|ac| ac.component.clone()).collect()
+        };
+        assert_eq!(components(&diff.removed), vec![sub("A", "B")]);
+        assert_eq!(
+            components(&diff.added),
+            vec![
+                sub("A", "D"),
+                ClassAssertion {
+                    ce: b.class(iri("B")).into(),
+                    i: b.named_individual(iri("i")).into()
+                }
+                .into()
+            ]
+        );
+        assert_eq!(diff.changed.len(), 1);
+        let (o, n) = &diff.changed[0];
+        assert!(o.logical_eq(n));
+        assert_ne!(o.ann, n.ann);
+        assert_eq!(o.component, sub("B", "C"));
+
+        let groups = diff.by_entity();
+        assert_eq!(
+            groups.keys().cloned().collect::<Vec<_>>(),
+            vec![Some(iri("A")), Some(iri("B")), Some(iri("i"))]
+        );
+        assert_eq!(groups[&Some(iri("A"))].removed.len(), 1);
+        assert_eq!(groups[&Some(iri("A"))].added.len(), 1);
+        assert_eq!(groups[&Some(iri("B"))].changed.len(), 1);
+        assert_eq!(groups[&Some(iri("i"))].added.len(), 1);
+    }
+
+    #[test]
+    fn same() {
+        let ofn = "Declaration(Class(:A)) SubClassOf(:A :B)";
+        assert!(diff(&read(ofn), &read(ofn)).is_empty());
+    }
+
+    #[test]
+    fn anonymous() {
+        let old = read(
+            "ObjectPropertyAssertion(:r :a _:x)
+             ClassAssertion(:B _:x)
+             ObjectPropertyAssertion(:r :a _:y)
+             ClassAssertion(:C _:y)
+             ObjectPropertyAssertion(:s _:y _:z)
+             AnnotationAssertion(rdfs:label _:z \"z\")",
+        );
+        let renamed = read(
+            "ObjectPropertyAssertion(:r :a _:q)
+             ClassAssertion(:B _:q)
+             ObjectPropertyAssertion(:r :a _:p)
+             ClassAssertion(:C _:p)
+             ObjectPropertyAssertion(:s _:p _:o)
+             AnnotationAssertion(rdfs:label _:o \"z\")",
+        );
+        assert!(diff(&old, &renamed).is_empty());
+
+        // Swapping the classes of the individuals is a change
+        let swapped = read(
+            "ObjectPropertyAssertion(:r :a _:x)
+             ClassAssertion(:C _:x)
+             ObjectPropertyAssertion(:r :a _:y)
+             ClassAssertion(:B _:y)
+             ObjectPropertyAssertion(:s _:y _:z)
+             AnnotationAssertion(rdfs:label _:z \"z\")",
+        );
+        let d = diff(&old, &swapped);
+        assert_eq!(d.added.len(), 2);
+        assert_eq!(d.removed.len(), 2);
+        assert!(d.changed.is_empty());
+
+        // Individuals keep the names which they have in both
+        let base = read("ClassAssertion(:A _:x) ObjectPropertyAssertion(:r _:x :i)");
+        let ours = read("ObjectPropertyAssertion(:r _:x :i)");
+        let d = diff(&base, &ours);
+        assert!(d.added.is_empty());
+        let removed: Vec<_> = base
+            .iter()
+            .filter(|ac| matches!(ac.component, Component::ClassAssertion(_)))
+            .cloned()
+            .collect();
+        assert_eq!(d.removed, removed);
+
+        // And those which are renamed are matched by structure
+        let edited = read(
+            "ObjectPropertyAssertion(:r :a _:x)
+             ClassAssertion(:B _:x)
+             ObjectPropertyAssertion(:r :a _:m)
+             ClassAssertion(:C _:m)
+             ObjectPropertyAssertion(:s _:m _:n)
+             AnnotationAssertion(rdfs:label _:n \"z\")
+             ClassAssertion(:D :a)",
+        );
+        let d = diff(&old, &edited);
+        assert_eq!(d.added.len(), 1);
+        assert!(d.removed.is_empty());
+    }
+
+    #[test]
+    fn anonymous_unrelated() {
+        // Individuals are matched however much else the other
+        // ontology contains
+        let old = read("ObjectPropertyAssertion(:r _:a _:b)");
+        let new = read(
+            "ObjectPropertyAssertion(:r _:x _:y)
+             ObjectPropertyAssertion(:s _:c1 _:c2)
+             ObjectPropertyAssertion(:s _:c2 _:c3)
+             ObjectPropertyAssertion(:s _:c3 _:c4)
+             ObjectPropertyAssertion(:s _:c4 _:c5)",
+        );
+        let d = diff(&old, &new);
+        assert!(d.removed.is_empty());
+        assert_eq!(d.added.len(), 4);
+    }
+}
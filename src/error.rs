@@ -100,6 +100,18 @@ impl From<rio_xml::RdfXmlError> for HornedError {
     }
 }
 
+impl From<rio_turtle::TurtleError> for HornedError {
+    fn from(e: rio_turtle::TurtleError) -> Self {
+        Self::ParserError(e.into(), Location::Unknown)
+    }
+}
+
+impl From<serde_json::Error> for HornedError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::ParserError(e.into(), Location::Unknown)
+    }
+}
+
 impl<R: RuleType + 'static> From<pest::error::Error<R>> for HornedError {
     fn from(e: pest::error::Error<R>) -> Self {
         let location = e.location.clone().into();
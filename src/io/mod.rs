@@ -1,7 +1,10 @@
 //! Parsers and renderers for several of the ontology formats listed in the
 //! [W3C recommendation](https://www.w3.org/TR/owl2-overview/#Syntaxes).
 
+pub mod obo;
+pub mod obographs;
 pub mod ofn;
+pub mod omn;
 pub mod owx;
 pub mod rdf;
 
@@ -15,22 +18,39 @@ use crate::{
 };
 
 pub enum ResourceType {
+    OBO,
     OFN,
+    OMN,
     OWX,
     RDF,
+    TTL,
+    NT,
+    NQ,
+    JSONLD,
+    OBOGraphs,
 }
 
 pub enum ParserOutput<A: ForIRI, AA: ForIndex<A>> {
+    OBOParser(SetOntology<A>, PrefixMapping),
     OFNParser(SetOntology<A>, PrefixMapping),
+    OMNParser(SetOntology<A>, PrefixMapping),
     OWXParser(SetOntology<A>, PrefixMapping),
     RDFParser(ConcreteRDFOntology<A, AA>, IncompleteParse<A>),
 }
 
 impl<A: ForIRI, AA: ForIndex<A>> ParserOutput<A, AA> {
+    pub fn obo(sop: (SetOntology<A>, PrefixMapping)) -> ParserOutput<A, AA> {
+        ParserOutput::OBOParser(sop.0, sop.1)
+    }
+
     pub fn ofn(sop: (SetOntology<A>, PrefixMapping)) -> ParserOutput<A, AA> {
         ParserOutput::OFNParser(sop.0, sop.1)
     }
 
+    pub fn omn(sop: (SetOntology<A>, PrefixMapping)) -> ParserOutput<A, AA> {
+        ParserOutput::OMNParser(sop.0, sop.1)
+    }
+
     pub fn owx(sop: (SetOntology<A>, PrefixMapping)) -> ParserOutput<A, AA> {
         ParserOutput::OWXParser(sop.0, sop.1)
     }
@@ -64,7 +84,9 @@ impl<A: ForIRI, AA: ForIndex<A>> ParserOutput<A, AA> {
         Option<IncompleteParse<A>>,
     ) {
         match self {
+            ParserOutput::OBOParser(o, m) => (o, Some(m), None),
             ParserOutput::OFNParser(o, m) => (o, Some(m), None),
+            ParserOutput::OMNParser(o, m) => (o, Some(m), None),
             ParserOutput::OWXParser(o, m) => (o, Some(m), None),
             ParserOutput::RDFParser(o, i) => (o.into(), None, Some(i)),
         }
@@ -74,7 +96,9 @@ impl<A: ForIRI, AA: ForIndex<A>> ParserOutput<A, AA> {
 impl<A: ForIRI, AA: ForIndex<A>> From<ParserOutput<A, AA>> for SetOntology<A> {
     fn from(p: ParserOutput<A, AA>) -> SetOntology<A> {
         match p {
+            ParserOutput::OBOParser(so, _) => so,
             ParserOutput::OFNParser(so, _) => so,
+            ParserOutput::OMNParser(so, _) => so,
             ParserOutput::OWXParser(so, _) => so,
             ParserOutput::RDFParser(rdfo, _) => rdfo.into(),
         }
@@ -84,7 +108,9 @@ impl<A: ForIRI, AA: ForIndex<A>> From<ParserOutput<A, AA>> for SetOntology<A> {
 impl<A: ForIRI, AA: ForIndex<A>> From<ParserOutput<A, AA>> for ComponentMappedOntology<A, AA> {
     fn from(p: ParserOutput<A, AA>) -> ComponentMappedOntology<A, AA> {
         match p {
+            ParserOutput::OBOParser(so, _) => so.into(),
             ParserOutput::OFNParser(so, _) => so.into(),
+            ParserOutput::OMNParser(so, _) => so.into(),
             ParserOutput::OWXParser(so, _) => so.into(),
             ParserOutput::RDFParser(rdfo, _) => rdfo.into(),
         }
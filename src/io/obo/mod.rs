@@ -0,0 +1,144 @@
+//! The [OBO flat file format](https://owlcollab.github.io/oboformat/doc/obo-syntax.html).
+//!
+//! OBO documents are mapped to OWL following the [OBO 1.4
+//! semantics](https://owlcollab.github.io/oboformat/doc/obo-syntax.html#5):
+//! `[Term]`, `[Typedef]` and `[Instance]` stanzas become classes,
+//! object properties and named individuals, and their clauses become
+//! the corresponding axioms and annotation assertions.
+//!
+//! Prefixed identifiers such as `GO:0008150` are mapped to
+//! `http://purl.obolibrary.org/obo/GO_0008150`, and unprefixed ones
+//! such as `part_of` to an IRI in the namespace of the ontology.
+pub mod reader;
+pub mod writer;
+
+/// The namespace of OBO Foundry identifiers.
+pub(crate) const OBO: &str = "http://purl.obolibrary.org/obo/";
+
+/// The namespace of the vocabulary for the OBO to OWL mapping.
+pub(crate) const OBO_IN_OWL: &str = "http://www.geneontology.org/formats/oboInOwl#";
+
+pub(crate) const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// The annotation property for `def` clauses.
+pub(crate) const DEFINITION: &str = "http://purl.obolibrary.org/obo/IAO_0000115";
+
+/// The annotation property for `replaced_by` clauses.
+pub(crate) const REPLACED_BY: &str = "http://purl.obolibrary.org/obo/IAO_0100001";
+
+/// Tags which map to an annotation assertion with a literal value,
+/// and the local name of their property in `OBO_IN_OWL`; tags which
+/// are not listed, and have no other mapping, use their own name.
+pub(crate) const LITERAL_TAGS: &[(&str, &str)] = &[
+    ("namespace", "hasOBONamespace"),
+    ("alt_id", "hasAlternativeId"),
+    ("created_by", "created_by"),
+    ("creation_date", "creation_date"),
+    ("consider", "consider"),
+];
+
+/// The scopes of synonyms, and the local name of their property in
+/// `OBO_IN_OWL`.
+pub(crate) const SYNONYM_SCOPES: &[(&str, &str)] = &[
+    ("EXACT", "hasExactSynonym"),
+    ("NARROW", "hasNarrowSynonym"),
+    ("BROAD", "hasBroadSynonym"),
+    ("RELATED", "hasRelatedSynonym"),
+];
+
+/// Boolean typedef tags which assert a characteristic of the property.
+pub(crate) const CHARACTERISTIC_TAGS: &[&str] = &[
+    "is_transitive",
+    "is_symmetric",
+    "is_reflexive",
+    "is_asymmetric",
+    "is_functional",
+    "is_inverse_functional",
+];
+
+/// The IRI of an OBO identifier.
+///
+/// `ontology` is the value of the `ontology` header tag, and
+/// `idspaces` any `idspace` declarations in the header.
+pub(crate) fn id_to_iri(id: &str, ontology: &str, idspaces: &[(String, String)]) -> String {
+    if id.contains("://") || id.starts_with("urn:") {
+        return id.to_string();
+    }
+    match id.split_once(':') {
+        Some(("xsd", local)) => format!("{}{}", XSD, local),
+        Some((prefix, local)) => match idspaces.iter().find(|(p, _)| p == prefix) {
+            Some((_, base)) => format!("{}{}", base, local),
+            None => format!("{}{}_{}", OBO, prefix, local),
+        },
+        None => format!("{}#{}", ontology_iri_base(ontology), id),
+    }
+}
+
+/// The OBO identifier of an IRI; this is the inverse of `id_to_iri`.
+pub(crate) fn iri_to_id(iri: &str, ontology: &str, idspaces: &[(String, String)]) -> String {
+    for (prefix, base) in idspaces {
+        if let Some(local) = iri.strip_prefix(base.as_str()) {
+            return format!("{}:{}", prefix, local);
+        }
+    }
+    if let Some(local) = iri.strip_prefix(XSD) {
+        return format!("xsd:{}", local);
+    }
+    if let Some(local) = iri
+        .strip_prefix(ontology_iri_base(ontology).as_str())
+        .and_then(|s| s.strip_prefix('#'))
+    {
+        return local.to_string();
+    }
+    if let Some((prefix, local)) = iri.strip_prefix(OBO).and_then(|s| s.split_once('_')) {
+        if !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return format!("{}:{}", prefix, local);
+        }
+    }
+    iri.to_string()
+}
+
+/// The base of the IRIs of unprefixed identifiers.
+fn ontology_iri_base(ontology: &str) -> String {
+    if ontology.contains("://") {
+        ontology.to_string()
+    } else {
+        format!("{}{}", OBO, ontology)
+    }
+}
+
+/// The IRI of the ontology with the given `ontology` header tag.
+pub(crate) fn ontology_iri(ontology: &str) -> String {
+    if ontology.contains("://") {
+        ontology.to_string()
+    } else {
+        format!("{}{}.owl", OBO, ontology)
+    }
+}
+
+/// The version IRI of the ontology with the given `ontology` and
+/// `data-version` header tags.
+pub(crate) fn version_iri(ontology: &str, data_version: &str) -> String {
+    format!("{}{}/{}/{}.owl", OBO, ontology, data_version, ontology)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        let idspaces = vec![("ex".to_string(), "http://example.com/".to_string())];
+        for (id, iri) in [
+            ("GO:0008150", "http://purl.obolibrary.org/obo/GO_0008150"),
+            ("part_of", "http://purl.obolibrary.org/obo/go#part_of"),
+            ("xsd:string", "http://www.w3.org/2001/XMLSchema#string"),
+            ("http://example.com/a", "http://example.com/a"),
+        ] {
+            assert_eq!(id_to_iri(id, "go", &idspaces), iri);
+            assert_eq!(iri_to_id(iri, "go", &[]), id);
+        }
+        assert_eq!(id_to_iri("ex:a", "go", &idspaces), "http://example.com/a");
+        assert_eq!(iri_to_id("http://example.com/a", "go", &idspaces), "ex:a");
+    }
+}
@@ -0,0 +1,55 @@
+use pest::iterators::Pairs;
+use pest_derive::Parser;
+
+use crate::error::HornedError;
+
+/// The OBO flat file format lexer.
+#[derive(Debug, Parser)]
+#[grammar = "grammars/obo.pest"]
+pub struct OboLexer;
+
+impl OboLexer {
+    /// Parse an input string using the given production rule.
+    ///
+    /// This is basically a specialized version of [`pest::Parser::parse`]
+    /// that only accepts [`Rule`], and does not need the `Parser` trait to
+    /// be in scope.
+    ///
+    /// [`Rule`]: ./enum.Rule.html
+    /// [`pest::Parser::parse`]: https://docs.rs/pest/latest/pest/trait.Parser.html
+    pub fn lex(rule: Rule, input: &str) -> Result<Pairs<'_, Rule>, HornedError> {
+        <Self as pest::Parser<Rule>>::parse(rule, input).map_err(From::from)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    use test_generator::test_resources;
+
+    #[test_resources("src/ont/obo/*.obo")]
+    fn lex_resource(resource: &str) {
+        let ont_s = slurp::read_all_to_string(resource).unwrap();
+        match OboLexer::lex(Rule::OboDocument, &ont_s) {
+            Ok(mut pairs) => assert_eq!(pairs.next().unwrap().as_str(), ont_s),
+            Err(e) => panic!("parser failed: {}", e),
+        }
+    }
+
+    #[test]
+    fn trailing_modifiers() {
+        let mut pairs = OboLexer::lex(
+            Rule::GenericClause,
+            "is_a: GO:0008150 {source=\"GOC:x\"} ! biological_process",
+        )
+        .unwrap()
+        .next()
+        .unwrap()
+        .into_inner();
+
+        assert_eq!(pairs.next().unwrap().as_str(), "is_a");
+        assert_eq!(pairs.next().unwrap().as_str(), "GO:0008150 ");
+        assert_eq!(pairs.next().unwrap().as_rule(), Rule::Qualifiers);
+    }
+}
@@ -0,0 +1,929 @@
+//! Read an ontology in the OBO flat file format.
+//!
+//! Unprefixed `[Typedef]` identifiers with an `xref` to the Basic
+//! Formal Ontology or the Relation Ontology, such as `part_of` with
+//! `xref: BFO:0000050`, are read as the IRI of the `xref`; the
+//! unprefixed identifier is kept as an `oboInOwl:shorthand`
+//! annotation. Trailing modifiers become annotations on the axiom of
+//! their clause.
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::BufRead;
+
+use curie::PrefixMapping;
+use pest::iterators::Pair;
+
+use crate::error::HornedError;
+use crate::io::ParserConfiguration;
+use crate::model::*;
+use crate::vocab::{AnnotationBuiltIn, RDFS};
+
+use super::*;
+
+mod lexer;
+
+use self::lexer::OboLexer;
+use self::lexer::Rule;
+
+type Result<T> = std::result::Result<T, HornedError>;
+
+/// Prefixes of `[Typedef]` xrefs which replace an unprefixed identifier.
+const RELATION_PREFIXES: &[&str] = &["BFO", "RO"];
+
+struct Context<'a, A: ForIRI> {
+    build: &'a Build<A>,
+    ontology: String,
+    idspaces: Vec<(String, String)>,
+    /// Unprefixed typedef identifiers, and the xref used in their place
+    shorthands: HashMap<String, String>,
+    /// Typedefs which are annotation properties
+    metadata_tags: HashSet<String>,
+}
+
+impl<'a, A: ForIRI> Context<'a, A> {
+    fn iri(&self, id: &str) -> IRI<A> {
+        let id = self.shorthands.get(id).map(String::as_str).unwrap_or(id);
+        self.build
+            .iri(id_to_iri(id, &self.ontology, &self.idspaces))
+    }
+
+    fn oio(&self, local: &str) -> IRI<A> {
+        self.build.iri(format!("{}{}", OBO_IN_OWL, local))
+    }
+
+    fn literal(&self, literal: String, datatype: Option<&str>) -> Literal<A> {
+        match datatype {
+            None | Some("xsd:string") => Literal::Simple { literal },
+            Some(dt) => Literal::Datatype {
+                literal,
+                datatype_iri: self.iri(dt),
+            },
+        }
+    }
+}
+
+/// Replace the escape sequences of OBO strings.
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('W') => out.push(' '),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn quoted(pair: Pair<Rule>) -> String {
+    unescape(pair.into_inner().next().unwrap().as_str())
+}
+
+/// The tag and value of a generic clause.
+fn tag_value(pair: &Pair<Rule>) -> Option<(String, String)> {
+    if pair.as_rule() != Rule::GenericClause {
+        return None;
+    }
+    let mut inner = pair.clone().into_inner();
+    let tag = inner.next().unwrap().as_str().to_string();
+    let value = match inner.next() {
+        Some(p) if p.as_rule() == Rule::UnquotedString => unescape(p.as_str().trim()),
+        _ => String::new(),
+    };
+    Some((tag, value))
+}
+
+/// Find the context of the document before reading any stanzas.
+fn context<'a, A: ForIRI>(
+    header: &Pair<Rule>,
+    stanzas: &[Pair<Rule>],
+    build: &'a Build<A>,
+) -> Context<'a, A> {
+    let mut ctx = Context {
+        build,
+        ontology: String::new(),
+        idspaces: Vec::new(),
+        shorthands: HashMap::new(),
+        metadata_tags: HashSet::new(),
+    };
+
+    for clause in header.clone().into_inner() {
+        if clause.as_rule() == Rule::IdspaceClause {
+            let mut inner = clause.into_inner();
+            let prefix = inner.next().unwrap().as_str().to_string();
+            let base = inner.next().unwrap().as_str().to_string();
+            ctx.idspaces.push((prefix, base));
+        } else if let Some((tag, value)) = tag_value(&clause) {
+            if tag == "ontology" {
+                ctx.ontology = value;
+            }
+        }
+    }
+
+    for stanza in stanzas {
+        let mut inner = stanza.clone().into_inner();
+        if inner.next().unwrap().as_str() != "Typedef" {
+            continue;
+        }
+
+        let mut id = None;
+        let mut xref = None;
+        for clause in inner {
+            if let Some((tag, value)) = tag_value(&clause) {
+                match tag.as_str() {
+                    "id" => id = Some(value),
+                    "is_metadata_tag" if value == "true" => {
+                        if let Some(id) = &id {
+                            ctx.metadata_tags.insert(id.clone());
+                        }
+                    }
+                    _ => {}
+                }
+            } else if clause.as_rule() == Rule::XrefClause && xref.is_none() {
+                let x = clause
+                    .into_inner()
+                    .next()
+                    .unwrap()
+                    .into_inner()
+                    .next()
+                    .unwrap();
+                let x = unescape(x.as_str());
+                if let Some((prefix, _)) = x.split_once(':') {
+                    if RELATION_PREFIXES.contains(&prefix) {
+                        xref = Some(x);
+                    }
+                }
+            }
+        }
+
+        if let (Some(id), Some(xref)) = (id, xref) {
+            if !id.contains(':') {
+                ctx.shorthands.insert(id, xref);
+            }
+        }
+    }
+
+    ctx
+}
+
+/// Convert trailing modifiers into annotations.
+fn qualifiers<A: ForIRI>(
+    pair: Option<Pair<Rule>>,
+    ctx: &Context<'_, A>,
+) -> BTreeSet<Annotation<A>> {
+    let mut anns = BTreeSet::new();
+    if let Some(pair) = pair.filter(|p| p.as_rule() == Rule::Qualifiers) {
+        for q in pair.into_inner() {
+            let mut inner = q.into_inner();
+            let key = inner.next().unwrap().as_str();
+            let value = inner.next().unwrap();
+            let literal = match value.as_rule() {
+                Rule::QuotedString => quoted(value),
+                _ => unescape(value.as_str()),
+            };
+            anns.insert(Annotation {
+                ap: AnnotationProperty(ctx.oio(key)),
+                av: Literal::Simple { literal }.into(),
+            });
+        }
+    }
+    anns
+}
+
+/// Convert an xref, and its description if any, into an annotation.
+fn xref<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+) -> (Annotation<A>, BTreeSet<Annotation<A>>) {
+    let mut inner = pair.into_inner();
+    let id = unescape(inner.next().unwrap().as_str());
+    let mut anns = BTreeSet::new();
+    if let Some(desc) = inner.next() {
+        anns.insert(Annotation {
+            ap: AnnotationProperty(ctx.build.iri(RDFS::Label)),
+            av: Literal::Simple {
+                literal: quoted(desc),
+            }
+            .into(),
+        });
+    }
+    let ann = Annotation {
+        ap: AnnotationProperty(ctx.oio("hasDbXref")),
+        av: Literal::Simple { literal: id }.into(),
+    };
+    (ann, anns)
+}
+
+/// Convert a list of xrefs into `hasDbXref` annotations.
+fn xref_list<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> BTreeSet<Annotation<A>> {
+    pair.into_inner().map(|x| xref(x, ctx).0).collect()
+}
+
+// ---------------------------------------------------------------------------
+
+fn header<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    components: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut id = OntologyID::default();
+    if !ctx.ontology.is_empty() {
+        id.iri = Some(ctx.build.iri(ontology_iri(&ctx.ontology)));
+    }
+
+    for clause in pair.into_inner() {
+        let rule = clause.as_rule();
+        if let Some((tag, value)) = tag_value(&clause) {
+            let ap = match tag.as_str() {
+                "ontology" => continue,
+                "data-version" => {
+                    id.viri = Some(ctx.build.iri(version_iri(&ctx.ontology, &value)));
+                    continue;
+                }
+                "import" => {
+                    let iri = if value.contains("://") {
+                        value
+                    } else {
+                        ontology_iri(&value)
+                    };
+                    components.push(Import(ctx.build.iri(iri)).into());
+                    continue;
+                }
+                "format-version" => ctx.oio("hasOBOFormatVersion"),
+                "remark" => ctx.build.iri(RDFS::Comment),
+                _ => ctx.oio(&tag),
+            };
+            components.push(
+                OntologyAnnotation(Annotation {
+                    ap: AnnotationProperty(ap),
+                    av: Literal::Simple { literal: value }.into(),
+                })
+                .into(),
+            );
+            continue;
+        }
+
+        let (parent, description) = match rule {
+            Rule::SubsetdefClause => ("SubsetProperty", RDFS::Comment),
+            Rule::SynonymTypedefClause => ("SynonymTypeProperty", RDFS::Label),
+            _ => continue,
+        };
+        let mut inner = clause.into_inner();
+        let ap = AnnotationProperty(ctx.iri(&unescape(inner.next().unwrap().as_str())));
+        let desc = quoted(inner.next().unwrap());
+        let subject = AnnotationSubject::IRI(ap.0.clone());
+
+        components.push(DeclareAnnotationProperty(ap.clone()).into());
+        components.push(
+            SubAnnotationPropertyOf {
+                sub: ap.clone(),
+                sup: AnnotationProperty(ctx.oio(parent)),
+            }
+            .into(),
+        );
+        components.push(
+            AnnotationAssertion::new(
+                subject.clone(),
+                Annotation {
+                    ap: AnnotationProperty(ctx.build.iri(description)),
+                    av: Literal::Simple { literal: desc }.into(),
+                },
+            )
+            .into(),
+        );
+        if let Some(scope) = inner.next().filter(|p| p.as_rule() == Rule::SynonymScope) {
+            components.push(
+                AnnotationAssertion::new(
+                    subject,
+                    Annotation {
+                        ap: AnnotationProperty(ctx.oio("hasScope")),
+                        av: Literal::Simple {
+                            literal: scope.as_str().to_string(),
+                        }
+                        .into(),
+                    },
+                )
+                .into(),
+            );
+        }
+    }
+
+    components.push(id.into());
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum StanzaKind {
+    Term,
+    Typedef,
+    /// A typedef which is an annotation property
+    MetadataTag,
+    Instance,
+}
+
+struct Stanza<'c, 'a, A: ForIRI> {
+    ctx: &'c Context<'a, A>,
+    kind: StanzaKind,
+    iri: IRI<A>,
+    components: &'c mut Vec<AnnotatedComponent<A>>,
+    intersection: Vec<ClassExpression<A>>,
+    union: Vec<ClassExpression<A>>,
+}
+
+impl<'c, 'a, A: ForIRI> Stanza<'c, 'a, A> {
+    fn push<C: Into<Component<A>>>(&mut self, c: C, anns: BTreeSet<Annotation<A>>) {
+        self.components.push(AnnotatedComponent::new(c, anns));
+    }
+
+    fn annotate(&mut self, ap: IRI<A>, av: AnnotationValue<A>, anns: BTreeSet<Annotation<A>>) {
+        let aa = AnnotationAssertion::new(
+            AnnotationSubject::IRI(self.iri.clone()),
+            Annotation {
+                ap: AnnotationProperty(ap),
+                av,
+            },
+        );
+        self.push(aa, anns);
+    }
+
+    fn class(&self) -> ClassExpression<A> {
+        Class(self.iri.clone()).into()
+    }
+
+    fn property(&self) -> ObjectPropertyExpression<A> {
+        ObjectProperty(self.iri.clone()).into()
+    }
+
+    fn clause(&mut self, pair: Pair<Rule>) -> Result<()> {
+        let ctx = self.ctx;
+        let rule = pair.as_rule();
+        let span = pair.as_span();
+        let mut inner = pair.into_inner();
+
+        match rule {
+            Rule::DefClause => {
+                let text = quoted(inner.next().unwrap());
+                let mut anns = xref_list(inner.next().unwrap(), ctx);
+                anns.extend(qualifiers(inner.next(), ctx));
+                self.annotate(
+                    ctx.build.iri(DEFINITION),
+                    Literal::Simple { literal: text }.into(),
+                    anns,
+                );
+            }
+            Rule::SynonymClause => {
+                let text = quoted(inner.next().unwrap());
+                let scope = inner.next().unwrap().as_str();
+                let (_, local) = SYNONYM_SCOPES.iter().find(|(s, _)| *s == scope).unwrap();
+
+                let mut anns = BTreeSet::new();
+                let mut next = inner.next().unwrap();
+                if next.as_rule() == Rule::Id {
+                    anns.insert(Annotation {
+                        ap: AnnotationProperty(ctx.oio("hasSynonymType")),
+                        av: ctx.iri(&unescape(next.as_str())).into(),
+                    });
+                    next = inner.next().unwrap();
+                }
+                anns.extend(xref_list(next, ctx));
+                anns.extend(qualifiers(inner.next(), ctx));
+                self.annotate(
+                    ctx.oio(local),
+                    Literal::Simple { literal: text }.into(),
+                    anns,
+                );
+            }
+            Rule::XrefClause => {
+                let (ann, mut anns) = xref(inner.next().unwrap(), ctx);
+                anns.extend(qualifiers(inner.next(), ctx));
+                self.annotate(ann.ap.0, ann.av, anns);
+            }
+            Rule::RelationshipClause => {
+                let rel = ctx.iri(&unescape(inner.next().unwrap().as_str()));
+                let target = ctx.iri(&unescape(inner.next().unwrap().as_str()));
+                let anns = qualifiers(inner.next(), ctx);
+                match self.kind {
+                    StanzaKind::Term => {
+                        let sc = SubClassOf::new(
+                            ClassExpression::ObjectSomeValuesFrom {
+                                ope: ObjectProperty(rel).into(),
+                                bce: Box::new(Class(target).into()),
+                            },
+                            self.class(),
+                        );
+                        self.push(sc, anns);
+                    }
+                    StanzaKind::Instance => {
+                        let opa = ObjectPropertyAssertion {
+                            ope: ObjectProperty(rel).into(),
+                            from: NamedIndividual(self.iri.clone()).into(),
+                            to: NamedIndividual(target).into(),
+                        };
+                        self.push(opa, anns);
+                    }
+                    StanzaKind::Typedef | StanzaKind::MetadataTag => {
+                        self.annotate(rel, target.into(), anns)
+                    }
+                }
+            }
+            Rule::IntersectionClause => {
+                let first = ctx.iri(&unescape(inner.next().unwrap().as_str()));
+                let ce = match inner.next().filter(|p| p.as_rule() == Rule::Id) {
+                    Some(target) => ClassExpression::ObjectSomeValuesFrom {
+                        ope: ObjectProperty(first).into(),
+                        bce: Box::new(Class(ctx.iri(&unescape(target.as_str()))).into()),
+                    },
+                    None => Class(first).into(),
+                };
+                self.intersection.push(ce);
+            }
+            Rule::PropertyValueClause => {
+                let ap = ctx.iri(&unescape(inner.next().unwrap().as_str()));
+                let value = inner.next().unwrap();
+                let mut datatype = None;
+                let mut anns = BTreeSet::new();
+                for p in inner {
+                    match p.as_rule() {
+                        Rule::Id => datatype = Some(unescape(p.as_str())),
+                        _ => anns = qualifiers(Some(p), ctx),
+                    }
+                }
+
+                let av = match (value.as_rule(), datatype) {
+                    (Rule::QuotedString, dt) => ctx.literal(quoted(value), dt.as_deref()).into(),
+                    (_, Some(dt)) => ctx.literal(unescape(value.as_str()), Some(&dt)).into(),
+                    (_, None) => ctx.iri(&unescape(value.as_str())).into(),
+                };
+                self.annotate(ap, av, anns);
+            }
+            Rule::ChainClause => {
+                inner.next();
+                let a = ObjectProperty(ctx.iri(&unescape(inner.next().unwrap().as_str())));
+                let b = ObjectProperty(ctx.iri(&unescape(inner.next().unwrap().as_str())));
+                let anns = qualifiers(inner.next(), ctx);
+                if self.kind != StanzaKind::Typedef {
+                    return Err(HornedError::invalid_at(
+                        "property chains are only allowed in typedefs",
+                        span,
+                    ));
+                }
+                let sop = SubObjectPropertyOf {
+                    sub: SubObjectPropertyExpression::ObjectPropertyChain(vec![a.into(), b.into()]),
+                    sup: self.property(),
+                };
+                self.push(sop, anns);
+            }
+            Rule::GenericClause => {
+                let tag = inner.next().unwrap().as_str();
+                let value = match inner.peek() {
+                    Some(p) if p.as_rule() == Rule::UnquotedString => {
+                        unescape(inner.next().unwrap().as_str().trim())
+                    }
+                    _ => String::new(),
+                };
+                let anns = qualifiers(inner.next(), ctx);
+                self.generic(tag, value, anns);
+            }
+            rule => unreachable!("unexpected rule in stanza: {:?}", rule),
+        }
+        Ok(())
+    }
+
+    fn generic(&mut self, tag: &str, value: String, anns: BTreeSet<Annotation<A>>) {
+        use StanzaKind::*;
+        let ctx = self.ctx;
+        let target = || ctx.iri(&value);
+
+        match (tag, self.kind) {
+            ("id", _) | ("is_metadata_tag", _) => {}
+            ("name", _) => self.annotate(
+                ctx.build.iri(RDFS::Label),
+                Literal::Simple { literal: value }.into(),
+                anns,
+            ),
+            ("comment", _) => self.annotate(
+                ctx.build.iri(RDFS::Comment),
+                Literal::Simple { literal: value }.into(),
+                anns,
+            ),
+            ("subset", _) => self.annotate(ctx.oio("inSubset"), target().into(), anns),
+            ("is_obsolete", _) => {
+                if value == "true" {
+                    let lit = ctx.literal(value, Some("xsd:boolean"));
+                    self.annotate(
+                        ctx.build.iri(AnnotationBuiltIn::Deprecated),
+                        lit.into(),
+                        anns,
+                    )
+                }
+            }
+            ("replaced_by", _) => self.annotate(ctx.build.iri(REPLACED_BY), target().into(), anns),
+            ("is_a", Term) => {
+                let sc = SubClassOf::new(Class(target()).into(), self.class());
+                self.push(sc, anns)
+            }
+            ("is_a", Typedef) => {
+                let sop = SubObjectPropertyOf {
+                    sub: self.property().into(),
+                    sup: ObjectProperty(target()).into(),
+                };
+                self.push(sop, anns)
+            }
+            ("is_a", MetadataTag) => {
+                let sap = SubAnnotationPropertyOf {
+                    sub: AnnotationProperty(self.iri.clone()),
+                    sup: AnnotationProperty(target()),
+                };
+                self.push(sap, anns)
+            }
+            ("union_of", Term) => self.union.push(Class(target()).into()),
+            ("equivalent_to", Term) => {
+                let ec = EquivalentClasses(vec![self.class(), Class(target()).into()]);
+                self.push(ec, anns)
+            }
+            ("equivalent_to", Typedef) => {
+                let eop = EquivalentObjectProperties(vec![
+                    self.property(),
+                    ObjectProperty(target()).into(),
+                ]);
+                self.push(eop, anns)
+            }
+            ("disjoint_from", Term) => {
+                let dc = DisjointClasses(vec![self.class(), Class(target()).into()]);
+                self.push(dc, anns)
+            }
+            ("disjoint_from", Typedef) => {
+                let dop = DisjointObjectProperties(vec![
+                    self.property(),
+                    ObjectProperty(target()).into(),
+                ]);
+                self.push(dop, anns)
+            }
+            ("instance_of", Instance) => {
+                let ca = ClassAssertion::new(
+                    Class(target()).into(),
+                    NamedIndividual(self.iri.clone()).into(),
+                );
+                self.push(ca, anns)
+            }
+            ("domain", Typedef) => {
+                let opd = ObjectPropertyDomain::new(self.property(), Class(target()).into());
+                self.push(opd, anns)
+            }
+            ("range", Typedef) => {
+                let opr = ObjectPropertyRange::new(self.property(), Class(target()).into());
+                self.push(opr, anns)
+            }
+            ("inverse_of", Typedef) => {
+                let iop = InverseObjectProperties(
+                    ObjectProperty(self.iri.clone()),
+                    ObjectProperty(target()),
+                );
+                self.push(iop, anns)
+            }
+            ("transitive_over", Typedef) => {
+                let sop = SubObjectPropertyOf {
+                    sub: SubObjectPropertyExpression::ObjectPropertyChain(vec![
+                        self.property(),
+                        ObjectProperty(target()).into(),
+                    ]),
+                    sup: self.property(),
+                };
+                self.push(sop, anns)
+            }
+            (tag, Typedef) if CHARACTERISTIC_TAGS.contains(&tag) => {
+                if value != "true" {
+                    return;
+                }
+                let ope = self.property();
+                let c: Component<A> = match tag {
+                    "is_transitive" => TransitiveObjectProperty(ope).into(),
+                    "is_symmetric" => SymmetricObjectProperty(ope).into(),
+                    "is_reflexive" => ReflexiveObjectProperty(ope).into(),
+                    "is_asymmetric" => AsymmetricObjectProperty(ope).into(),
+                    "is_functional" => FunctionalObjectProperty(ope).into(),
+                    _ => InverseFunctionalObjectProperty(ope).into(),
+                };
+                self.push(c, anns)
+            }
+            (tag, _) => {
+                let local = LITERAL_TAGS
+                    .iter()
+                    .find(|(t, _)| *t == tag)
+                    .map(|(_, l)| *l)
+                    .unwrap_or(tag);
+                self.annotate(
+                    ctx.oio(local),
+                    Literal::Simple { literal: value }.into(),
+                    anns,
+                )
+            }
+        }
+    }
+
+    fn finish(mut self) {
+        let intersection = std::mem::take(&mut self.intersection);
+        if !intersection.is_empty() {
+            let ce = if intersection.len() == 1 {
+                intersection.into_iter().next().unwrap()
+            } else {
+                ClassExpression::ObjectIntersectionOf(intersection)
+            };
+            self.push(EquivalentClasses(vec![self.class(), ce]), BTreeSet::new());
+        }
+
+        let union = std::mem::take(&mut self.union);
+        if !union.is_empty() {
+            let ce = if union.len() == 1 {
+                union.into_iter().next().unwrap()
+            } else {
+                ClassExpression::ObjectUnionOf(union)
+            };
+            self.push(EquivalentClasses(vec![self.class(), ce]), BTreeSet::new());
+        }
+    }
+}
+
+fn stanza<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    components: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let span = pair.as_span();
+    let mut inner = pair.into_inner();
+    let stanza_type = inner.next().unwrap();
+    let clauses: Vec<_> = inner.collect();
+
+    let id = clauses
+        .iter()
+        .filter_map(tag_value)
+        .find(|(tag, _)| tag == "id")
+        .map(|(_, value)| value)
+        .ok_or_else(|| HornedError::invalid_at("stanza has no id", span))?;
+
+    let kind = match stanza_type.as_str() {
+        "Term" => StanzaKind::Term,
+        "Typedef" if ctx.metadata_tags.contains(&id) => StanzaKind::MetadataTag,
+        "Typedef" => StanzaKind::Typedef,
+        "Instance" => StanzaKind::Instance,
+        _ => {
+            return Err(HornedError::invalid_at(
+                "unknown stanza type",
+                stanza_type.as_span(),
+            ))
+        }
+    };
+
+    let iri = ctx.iri(&id);
+    let declaration: Component<A> = match kind {
+        StanzaKind::Term => DeclareClass(Class(iri.clone())).into(),
+        StanzaKind::Typedef => DeclareObjectProperty(ObjectProperty(iri.clone())).into(),
+        StanzaKind::MetadataTag => {
+            DeclareAnnotationProperty(AnnotationProperty(iri.clone())).into()
+        }
+        StanzaKind::Instance => DeclareNamedIndividual(NamedIndividual(iri.clone())).into(),
+    };
+    components.push(declaration.into());
+
+    let mut stanza = Stanza {
+        ctx,
+        kind,
+        iri,
+        components,
+        intersection: Vec::new(),
+        union: Vec::new(),
+    };
+    stanza.annotate(
+        ctx.oio("id"),
+        Literal::Simple {
+            literal: id.clone(),
+        }
+        .into(),
+        BTreeSet::new(),
+    );
+    if ctx.shorthands.contains_key(&id) {
+        stanza.annotate(
+            ctx.oio("shorthand"),
+            Literal::Simple { literal: id }.into(),
+            BTreeSet::new(),
+        );
+    }
+
+    for clause in clauses {
+        stanza.clause(clause)?;
+    }
+    stanza.finish();
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+
+pub fn read<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    bufread: R,
+    _config: ParserConfiguration,
+) -> Result<(O, PrefixMapping)> {
+    let b = Build::new();
+    read_with_build(bufread, &b)
+}
+
+pub fn read_with_build<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    mut bufread: R,
+    build: &Build<A>,
+) -> Result<(O, PrefixMapping)> {
+    let mut doc = String::new();
+    bufread.read_to_string(&mut doc)?;
+    let pair = OboLexer::lex(Rule::OboDocument, &doc)?.next().unwrap();
+
+    let mut inner = pair.into_inner();
+    let header_pair = inner.next().unwrap();
+    let stanzas: Vec<_> = inner.filter(|p| p.as_rule() == Rule::Stanza).collect();
+    let ctx = context(&header_pair, &stanzas, build);
+
+    let mut components = Vec::new();
+    header(header_pair, &ctx, &mut components)?;
+    for s in stanzas {
+        stanza(s, &ctx, &mut components)?;
+    }
+
+    let mut o = O::default();
+    for c in components {
+        o.insert(c);
+    }
+
+    let mut mapping = PrefixMapping::default();
+    mapping
+        .add_prefix("obo", OBO)
+        .expect("valid prefix should not fail");
+    mapping
+        .add_prefix("oboInOwl", OBO_IN_OWL)
+        .expect("valid prefix should not fail");
+    for (prefix, base) in &ctx.idspaces {
+        // An idspace which is not a valid prefix is still used to
+        // read identifiers, but cannot abbreviate IRIs
+        let _ = mapping.add_prefix(prefix, base);
+    }
+
+    Ok((o, mapping))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::ontology::set::SetOntology;
+
+    fn read_str(obo: &str) -> SetOntology<RcStr> {
+        read(obo.as_bytes(), Default::default()).unwrap().0
+    }
+
+    fn annotation(ap: &str, literal: &str) -> Annotation<RcStr> {
+        let b = Build::new_rc();
+        Annotation {
+            ap: b.annotation_property(ap),
+            av: Literal::Simple {
+                literal: literal.to_string(),
+            }
+            .into(),
+        }
+    }
+
+    const GO: &str = "format-version: 1.2
+ontology: go
+
+[Term]
+id: GO:0000001
+name: mitochondrion inheritance
+def: \"The distribution of mitochondria.\" [GOC:mcc, PMID:10873824]
+synonym: \"mitochondrial inheritance\" EXACT []
+is_a: GO:0048308 ! organelle inheritance
+relationship: part_of GO:0048311 ! mitochondrion distribution
+
+[Term]
+id: GO:0000002
+intersection_of: GO:0048308
+intersection_of: part_of GO:0048311
+
+[Typedef]
+id: part_of
+name: part of
+xref: BFO:0000050
+is_transitive: true
+";
+
+    #[test]
+    fn term() {
+        let ont = read_str(GO);
+        let b = Build::new_rc();
+        let a = b.class("http://purl.obolibrary.org/obo/GO_0000001");
+        let part_of = b.object_property("http://purl.obolibrary.org/obo/BFO_0000050");
+
+        assert!(ont.i().contains(&DeclareClass(a.clone()).into()));
+        assert!(ont.i().contains(
+            &SubClassOf::new(
+                b.class("http://purl.obolibrary.org/obo/GO_0048308").into(),
+                a.clone().into()
+            )
+            .into()
+        ));
+        assert!(ont.i().contains(
+            &SubClassOf::new(
+                ClassExpression::ObjectSomeValuesFrom {
+                    ope: part_of.clone().into(),
+                    bce: Box::new(b.class("http://purl.obolibrary.org/obo/GO_0048311").into()),
+                },
+                a.clone().into()
+            )
+            .into()
+        ));
+        assert!(ont
+            .i()
+            .contains(&TransitiveObjectProperty(part_of.into()).into()));
+    }
+
+    #[test]
+    fn def_with_xrefs() {
+        let ont = read_str(GO);
+        let b = Build::new_rc();
+        let xref = |x| annotation("http://www.geneontology.org/formats/oboInOwl#hasDbXref", x);
+
+        assert!(ont.i().contains(&AnnotatedComponent::new(
+            AnnotationAssertion::new(
+                b.iri("http://purl.obolibrary.org/obo/GO_0000001").into(),
+                annotation(DEFINITION, "The distribution of mitochondria."),
+            ),
+            vec![xref("GOC:mcc"), xref("PMID:10873824")]
+                .into_iter()
+                .collect(),
+        )));
+        assert!(ont.i().contains(
+            &AnnotationAssertion::new(
+                b.iri("http://purl.obolibrary.org/obo/GO_0000001").into(),
+                annotation(
+                    "http://www.geneontology.org/formats/oboInOwl#hasExactSynonym",
+                    "mitochondrial inheritance"
+                ),
+            )
+            .into()
+        ));
+    }
+
+    #[test]
+    fn intersection_of() {
+        let ont = read_str(GO);
+        let b = Build::new_rc();
+
+        assert!(ont.i().contains(
+            &EquivalentClasses(vec![
+                b.class("http://purl.obolibrary.org/obo/GO_0000002").into(),
+                ClassExpression::ObjectIntersectionOf(vec![
+                    b.class("http://purl.obolibrary.org/obo/GO_0048308").into(),
+                    ClassExpression::ObjectSomeValuesFrom {
+                        ope: b
+                            .object_property("http://purl.obolibrary.org/obo/BFO_0000050")
+                            .into(),
+                        bce: Box::new(b.class("http://purl.obolibrary.org/obo/GO_0048311").into()),
+                    }
+                ])
+            ])
+            .into()
+        ));
+    }
+
+    #[test]
+    fn header() {
+        let ont = read_str(GO);
+        let b = Build::new_rc();
+
+        assert!(ont.i().contains(
+            &OntologyID {
+                iri: Some(b.iri("http://purl.obolibrary.org/obo/go.owl")),
+                viri: None,
+            }
+            .into()
+        ));
+        assert!(ont.i().contains(
+            &OntologyAnnotation(annotation(
+                "http://www.geneontology.org/formats/oboInOwl#hasOBOFormatVersion",
+                "1.2"
+            ))
+            .into()
+        ));
+    }
+
+    #[test]
+    fn missing_id() {
+        let r: Result<(SetOntology<RcStr>, _)> =
+            read("[Term]\nname: x\n".as_bytes(), Default::default());
+        assert!(matches!(r, Err(HornedError::ValidityError(_, _))));
+    }
+}
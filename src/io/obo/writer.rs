@@ -0,0 +1,810 @@
+//! Write an ontology in the OBO flat file format.
+//!
+//! This reverses the mapping of the reader, so that reading and then
+//! writing an OBO document preserves its content, although not its
+//! layout: stanzas are sorted by identifier and clauses by tag.
+//!
+//! Only entities which are declared, or are the subject of a logical
+//! axiom, are written as stanzas. Components with no representation
+//! in OBO, such as data properties, nested class expressions and SWRL
+//! rules, are not written, nor are axiom annotations other than
+//! those in the `oboInOwl` namespace.
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::error::HornedError;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::{AnnotationBuiltIn, RDFS};
+
+use super::*;
+
+/// The order of clauses in a stanza; unknown tags are written last.
+const TAG_ORDER: &[&str] = &[
+    "id",
+    "is_anonymous",
+    "name",
+    "namespace",
+    "alt_id",
+    "def",
+    "comment",
+    "subset",
+    "synonym",
+    "xref",
+    "builtin",
+    "property_value",
+    "instance_of",
+    "domain",
+    "range",
+    "is_metadata_tag",
+    "is_asymmetric",
+    "is_reflexive",
+    "is_symmetric",
+    "is_transitive",
+    "is_functional",
+    "is_inverse_functional",
+    "is_a",
+    "intersection_of",
+    "union_of",
+    "equivalent_to",
+    "disjoint_from",
+    "inverse_of",
+    "transitive_over",
+    "holds_over_chain",
+    "equivalent_to_chain",
+    "relationship",
+    "is_obsolete",
+    "replaced_by",
+    "consider",
+    "created_by",
+    "creation_date",
+];
+
+/// The order of clauses in the header; unknown tags are written last.
+const HEADER_ORDER: &[&str] = &[
+    "format-version",
+    "data-version",
+    "date",
+    "saved-by",
+    "auto-generated-by",
+    "import",
+    "subsetdef",
+    "synonymtypedef",
+    "idspace",
+    "default-namespace",
+    "remark",
+    "ontology",
+];
+
+fn position(order: &[&str], tag: &str) -> usize {
+    order.iter().position(|t| *t == tag).unwrap_or(order.len())
+}
+
+/// Escape a string for a quoted value.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Escape a string for an unquoted value.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '!' | '{' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn literal_str<A: ForIRI>(av: &AnnotationValue<A>) -> Option<&str> {
+    match av {
+        AnnotationValue::Literal(l) => Some(l.literal()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum StanzaKind {
+    Term,
+    Typedef,
+    Instance,
+}
+
+impl StanzaKind {
+    fn name(&self) -> &'static str {
+        match self {
+            StanzaKind::Term => "Term",
+            StanzaKind::Typedef => "Typedef",
+            StanzaKind::Instance => "Instance",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Stanza {
+    kind: Option<StanzaKind>,
+    clauses: Vec<(String, String)>,
+}
+
+struct Document<A: ForIRI> {
+    ontology: String,
+    shorthands: HashMap<IRI<A>, String>,
+    /// Prefixes whose IRIs are not in the OBO namespace
+    idspaces: Vec<(String, String)>,
+    /// Properties which are written as `subsetdef` clauses
+    subsets: HashSet<IRI<A>>,
+    /// Properties which are written as `synonymtypedef` clauses
+    synonym_types: HashSet<IRI<A>>,
+    header: Vec<(String, String)>,
+    /// The description and scope of each `subsets` and `synonym_types`
+    header_definitions: BTreeMap<IRI<A>, (String, Option<String>)>,
+    stanzas: BTreeMap<IRI<A>, Stanza>,
+}
+
+impl<A: ForIRI> Document<A> {
+    fn id(&self, iri: &IRI<A>) -> String {
+        match self.shorthands.get(iri) {
+            Some(s) => s.clone(),
+            None => iri_to_id(iri.as_ref(), &self.ontology, &self.idspaces),
+        }
+    }
+
+    fn oio<'i>(&self, iri: &'i IRI<A>) -> Option<&'i str> {
+        iri.as_ref().strip_prefix(OBO_IN_OWL)
+    }
+
+    fn stanza(&mut self, iri: &IRI<A>, kind: Option<StanzaKind>) -> &mut Stanza {
+        let stanza = self.stanzas.entry(iri.clone()).or_default();
+        if stanza.kind.is_none() {
+            stanza.kind = kind;
+        }
+        stanza
+    }
+
+    fn clause(&mut self, iri: &IRI<A>, kind: Option<StanzaKind>, tag: &str, value: String) {
+        self.stanza(iri, kind)
+            .clauses
+            .push((tag.to_string(), value));
+    }
+
+    /// Convert `oboInOwl` axiom annotations into trailing modifiers.
+    fn qualifiers<'a>(&self, anns: impl Iterator<Item = &'a Annotation<A>>) -> String
+    where
+        A: 'a,
+    {
+        let qs: Vec<_> = anns
+            .filter_map(|ann| {
+                let key = self.oio(&ann.ap.0)?;
+                let value = literal_str(&ann.av)?;
+                Some(format!("{}={}", key, quote(value)))
+            })
+            .collect();
+        if qs.is_empty() {
+            String::new()
+        } else {
+            format!(" {{{}}}", qs.join(", "))
+        }
+    }
+
+    /// The `xref` list of a `def` or `synonym` clause.
+    fn xrefs(&self, anns: &BTreeSet<Annotation<A>>) -> String {
+        let xrefs: Vec<_> = anns
+            .iter()
+            .filter(|ann| self.oio(&ann.ap.0) == Some("hasDbXref"))
+            .filter_map(|ann| literal_str(&ann.av))
+            .map(|x| x.replace(',', "\\,"))
+            .collect();
+        format!("[{}]", xrefs.join(", "))
+    }
+
+    fn other(&self, anns: &BTreeSet<Annotation<A>>, skip: &[&str]) -> String {
+        self.qualifiers(
+            anns.iter()
+                .filter(|ann| !self.oio(&ann.ap.0).is_some_and(|k| skip.contains(&k))),
+        )
+    }
+
+    /// Find the shorthands, idspaces and header properties, which
+    /// affect how other components are written.
+    fn prepare(&mut self, ac: &AnnotatedComponent<A>) {
+        match &ac.component {
+            Component::AnnotationAssertion(AnnotationAssertion {
+                subject: AnnotationSubject::IRI(iri),
+                ann,
+            }) => match (self.oio(&ann.ap.0), literal_str(&ann.av)) {
+                (Some("shorthand"), Some(s)) => {
+                    self.shorthands.insert(iri.clone(), s.to_string());
+                }
+                (Some("id"), Some(id)) => self.idspace(iri.as_ref(), id),
+                _ => {}
+            },
+            Component::SubAnnotationPropertyOf(SubAnnotationPropertyOf { sub, sup }) => {
+                match self.oio(&sup.0) {
+                    Some("SubsetProperty") => {
+                        self.subsets.insert(sub.0.clone());
+                    }
+                    Some("SynonymTypeProperty") => {
+                        self.synonym_types.insert(sub.0.clone());
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add an idspace if `id` is not mapped to `iri` by default.
+    fn idspace(&mut self, iri: &str, id: &str) {
+        if let Some((prefix, local)) = id.split_once(':') {
+            if id_to_iri(id, &self.ontology, &self.idspaces) == iri {
+                return;
+            }
+            if let Some(base) = iri.strip_suffix(local) {
+                self.idspaces.push((prefix.to_string(), base.to_string()));
+            }
+        }
+    }
+
+    fn header_annotation(&mut self, ann: &Annotation<A>) {
+        let value = match literal_str(&ann.av) {
+            Some(v) => escape(v),
+            None => return,
+        };
+        let tag = if ann.ap.0.as_ref() == RDFS::Comment.as_ref() {
+            "remark"
+        } else {
+            match self.oio(&ann.ap.0) {
+                Some("hasOBOFormatVersion") => "format-version",
+                Some(tag) => tag,
+                None => return,
+            }
+        };
+        self.header.push((tag.to_string(), value));
+    }
+
+    fn component(&mut self, ac: &AnnotatedComponent<A>) {
+        use StanzaKind::*;
+        let q = self.qualifiers(ac.ann.iter());
+
+        match &ac.component {
+            Component::Import(Import(iri)) => {
+                self.header.push(("import".to_string(), iri.to_string()))
+            }
+            Component::OntologyAnnotation(OntologyAnnotation(ann)) => self.header_annotation(ann),
+            Component::DeclareClass(DeclareClass(c)) => {
+                self.stanza(&c.0, Some(Term));
+            }
+            Component::DeclareObjectProperty(DeclareObjectProperty(op)) => {
+                self.stanza(&op.0, Some(Typedef));
+            }
+            Component::DeclareNamedIndividual(DeclareNamedIndividual(i)) => {
+                self.stanza(&i.0, Some(Instance));
+            }
+            Component::DeclareAnnotationProperty(DeclareAnnotationProperty(ap)) => {
+                let iri = &ap.0;
+                if !(self.subsets.contains(iri)
+                    || self.synonym_types.contains(iri)
+                    || self.oio(iri).is_some())
+                {
+                    self.clause(iri, Some(Typedef), "is_metadata_tag", "true".to_string());
+                }
+            }
+            Component::SubClassOf(SubClassOf {
+                sub: ClassExpression::Class(c),
+                sup,
+            }) => match sup {
+                ClassExpression::Class(d) => {
+                    let v = format!("{}{}", self.id(&d.0), q);
+                    self.clause(&c.0, Some(Term), "is_a", v);
+                }
+                ClassExpression::ObjectSomeValuesFrom {
+                    ope: ObjectPropertyExpression::ObjectProperty(r),
+                    bce,
+                } => {
+                    if let ClassExpression::Class(d) = bce.as_ref() {
+                        let v = format!("{} {}{}", self.id(&r.0), self.id(&d.0), q);
+                        self.clause(&c.0, Some(Term), "relationship", v);
+                    }
+                }
+                _ => {}
+            },
+            Component::EquivalentClasses(EquivalentClasses(ces)) => {
+                if let [ClassExpression::Class(c), ce] = ces.as_slice() {
+                    self.equivalent_class(c, ce, q);
+                }
+            }
+            Component::DisjointClasses(DisjointClasses(ces)) => {
+                if let [ClassExpression::Class(c), ClassExpression::Class(d)] = ces.as_slice() {
+                    let v = format!("{}{}", self.id(&d.0), q);
+                    self.clause(&c.0, Some(Term), "disjoint_from", v);
+                }
+            }
+            Component::ClassAssertion(ClassAssertion {
+                ce: ClassExpression::Class(c),
+                i: Individual::Named(i),
+            }) => {
+                let v = format!("{}{}", self.id(&c.0), q);
+                self.clause(&i.0, Some(Instance), "instance_of", v);
+            }
+            Component::ObjectPropertyAssertion(ObjectPropertyAssertion {
+                ope: ObjectPropertyExpression::ObjectProperty(r),
+                from: Individual::Named(a),
+                to: Individual::Named(b),
+            }) => {
+                let v = format!("{} {}{}", self.id(&r.0), self.id(&b.0), q);
+                self.clause(&a.0, Some(Instance), "relationship", v);
+            }
+            Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                sub,
+                sup: ObjectPropertyExpression::ObjectProperty(sup),
+            }) => match sub {
+                SubObjectPropertyExpression::ObjectPropertyExpression(
+                    ObjectPropertyExpression::ObjectProperty(sub),
+                ) => {
+                    let v = format!("{}{}", self.id(&sup.0), q);
+                    self.clause(&sub.0, Some(Typedef), "is_a", v);
+                }
+                SubObjectPropertyExpression::ObjectPropertyChain(chain) => {
+                    if let [ObjectPropertyExpression::ObjectProperty(a), ObjectPropertyExpression::ObjectProperty(b)] =
+                        chain.as_slice()
+                    {
+                        let (tag, v) = if a == sup {
+                            ("transitive_over", format!("{}{}", self.id(&b.0), q))
+                        } else {
+                            (
+                                "holds_over_chain",
+                                format!("{} {}{}", self.id(&a.0), self.id(&b.0), q),
+                            )
+                        };
+                        self.clause(&sup.0, Some(Typedef), tag, v);
+                    }
+                }
+                _ => {}
+            },
+            Component::EquivalentObjectProperties(EquivalentObjectProperties(opes)) => {
+                if let [ObjectPropertyExpression::ObjectProperty(p), ObjectPropertyExpression::ObjectProperty(r)] =
+                    opes.as_slice()
+                {
+                    let v = format!("{}{}", self.id(&r.0), q);
+                    self.clause(&p.0, Some(Typedef), "equivalent_to", v);
+                }
+            }
+            Component::DisjointObjectProperties(DisjointObjectProperties(opes)) => {
+                if let [ObjectPropertyExpression::ObjectProperty(p), ObjectPropertyExpression::ObjectProperty(r)] =
+                    opes.as_slice()
+                {
+                    let v = format!("{}{}", self.id(&r.0), q);
+                    self.clause(&p.0, Some(Typedef), "disjoint_from", v);
+                }
+            }
+            Component::ObjectPropertyDomain(ObjectPropertyDomain {
+                ope: ObjectPropertyExpression::ObjectProperty(p),
+                ce: ClassExpression::Class(c),
+            }) => {
+                let v = format!("{}{}", self.id(&c.0), q);
+                self.clause(&p.0, Some(Typedef), "domain", v);
+            }
+            Component::ObjectPropertyRange(ObjectPropertyRange {
+                ope: ObjectPropertyExpression::ObjectProperty(p),
+                ce: ClassExpression::Class(c),
+            }) => {
+                let v = format!("{}{}", self.id(&c.0), q);
+                self.clause(&p.0, Some(Typedef), "range", v);
+            }
+            Component::InverseObjectProperties(InverseObjectProperties(p, r)) => {
+                let v = format!("{}{}", self.id(&r.0), q);
+                self.clause(&p.0, Some(Typedef), "inverse_of", v);
+            }
+            Component::TransitiveObjectProperty(TransitiveObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_transitive", q),
+            Component::SymmetricObjectProperty(SymmetricObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_symmetric", q),
+            Component::ReflexiveObjectProperty(ReflexiveObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_reflexive", q),
+            Component::AsymmetricObjectProperty(AsymmetricObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_asymmetric", q),
+            Component::FunctionalObjectProperty(FunctionalObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_functional", q),
+            Component::InverseFunctionalObjectProperty(InverseFunctionalObjectProperty(
+                ObjectPropertyExpression::ObjectProperty(p),
+            )) => self.characteristic(p, "is_inverse_functional", q),
+            Component::SubAnnotationPropertyOf(SubAnnotationPropertyOf { sub, sup })
+                if !(self.subsets.contains(&sub.0) || self.synonym_types.contains(&sub.0)) =>
+            {
+                let v = format!("{}{}", self.id(&sup.0), q);
+                self.clause(&sub.0, Some(Typedef), "is_a", v);
+            }
+            Component::AnnotationAssertion(AnnotationAssertion {
+                subject: AnnotationSubject::IRI(iri),
+                ann,
+            }) => self.annotation_assertion(iri, ann, &ac.ann),
+            _ => {}
+        }
+    }
+
+    fn characteristic(&mut self, p: &ObjectProperty<A>, tag: &str, q: String) {
+        self.clause(&p.0, Some(StanzaKind::Typedef), tag, format!("true{}", q));
+    }
+
+    fn equivalent_class(&mut self, c: &Class<A>, ce: &ClassExpression<A>, q: String) {
+        let term = Some(StanzaKind::Term);
+        let element = |doc: &Self, ce: &ClassExpression<A>| match ce {
+            ClassExpression::Class(d) => Some(doc.id(&d.0)),
+            ClassExpression::ObjectSomeValuesFrom {
+                ope: ObjectPropertyExpression::ObjectProperty(r),
+                bce,
+            } => match bce.as_ref() {
+                ClassExpression::Class(d) => Some(format!("{} {}", doc.id(&r.0), doc.id(&d.0))),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match ce {
+            ClassExpression::Class(d) => {
+                let v = format!("{}{}", self.id(&d.0), q);
+                self.clause(&c.0, term, "equivalent_to", v);
+            }
+            ClassExpression::ObjectIntersectionOf(ces) => {
+                let elements: Option<Vec<_>> = ces.iter().map(|ce| element(self, ce)).collect();
+                for e in elements.into_iter().flatten() {
+                    self.clause(&c.0, term, "intersection_of", e);
+                }
+            }
+            ClassExpression::ObjectUnionOf(ces) => {
+                let elements: Option<Vec<_>> = ces
+                    .iter()
+                    .map(|ce| match ce {
+                        ClassExpression::Class(d) => Some(self.id(&d.0)),
+                        _ => None,
+                    })
+                    .collect();
+                for e in elements.into_iter().flatten() {
+                    self.clause(&c.0, term, "union_of", e);
+                }
+            }
+            ce => {
+                if let Some(e) = element(self, ce) {
+                    self.clause(&c.0, term, "intersection_of", e);
+                }
+            }
+        }
+    }
+
+    fn annotation_assertion(
+        &mut self,
+        iri: &IRI<A>,
+        ann: &Annotation<A>,
+        anns: &BTreeSet<Annotation<A>>,
+    ) {
+        let ap = ann.ap.0.as_ref();
+
+        if self.subsets.contains(iri) || self.synonym_types.contains(iri) {
+            let def = self
+                .header_definitions
+                .entry(iri.clone())
+                .or_insert_with(|| (String::new(), None));
+            if let Some(v) = literal_str(&ann.av) {
+                if ap == RDFS::Comment.as_ref() || ap == RDFS::Label.as_ref() {
+                    def.0 = v.to_string();
+                } else if ap.strip_prefix(OBO_IN_OWL) == Some("hasScope") {
+                    def.1 = Some(v.to_string());
+                }
+            }
+            return;
+        }
+
+        let value = match &ann.av {
+            AnnotationValue::Literal(l) => escape(l.literal()),
+            AnnotationValue::IRI(i) => self.id(i),
+            AnnotationValue::AnonymousIndividual(_) => return,
+        };
+        let q = self.qualifiers(anns.iter());
+
+        let (tag, v) = if ap == RDFS::Label.as_ref() {
+            ("name", format!("{}{}", value, q))
+        } else if ap == RDFS::Comment.as_ref() {
+            ("comment", format!("{}{}", value, q))
+        } else if ap == DEFINITION {
+            let text = literal_str(&ann.av).unwrap_or_default();
+            let other = self.other(anns, &["hasDbXref"]);
+            (
+                "def",
+                format!("{} {}{}", quote(text), self.xrefs(anns), other),
+            )
+        } else if ap == REPLACED_BY {
+            ("replaced_by", format!("{}{}", value, q))
+        } else if ap == AnnotationBuiltIn::Deprecated.as_ref() {
+            if literal_str(&ann.av) != Some("true") {
+                return;
+            }
+            ("is_obsolete", format!("true{}", q))
+        } else if let Some(local) = ap.strip_prefix(OBO_IN_OWL) {
+            if let Some((scope, _)) = SYNONYM_SCOPES.iter().find(|(_, l)| *l == local) {
+                let text = literal_str(&ann.av).unwrap_or_default();
+                let synonym_type = anns
+                    .iter()
+                    .find(|a| self.oio(&a.ap.0) == Some("hasSynonymType"))
+                    .and_then(|a| match &a.av {
+                        AnnotationValue::IRI(i) => Some(format!(" {}", self.id(i))),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                let other = self.other(anns, &["hasDbXref", "hasSynonymType"]);
+                let v = format!(
+                    "{} {}{} {}{}",
+                    quote(text),
+                    scope,
+                    synonym_type,
+                    self.xrefs(anns),
+                    other
+                );
+                ("synonym", v)
+            } else {
+                match local {
+                    "id" | "shorthand" => return,
+                    "hasDbXref" => {
+                        let label = anns
+                            .iter()
+                            .find(|a| a.ap.0.as_ref() == RDFS::Label.as_ref())
+                            .and_then(|a| literal_str(&a.av))
+                            .map(|l| format!(" {}", quote(l)))
+                            .unwrap_or_default();
+                        ("xref", format!("{}{}{}", value, label, q))
+                    }
+                    "inSubset" => ("subset", format!("{}{}", value, q)),
+                    local => {
+                        let tag = LITERAL_TAGS
+                            .iter()
+                            .find(|(_, l)| *l == local)
+                            .map(|(t, _)| *t)
+                            .unwrap_or(local);
+                        (tag, format!("{}{}", value, q))
+                    }
+                }
+            }
+        } else {
+            let value = match &ann.av {
+                AnnotationValue::Literal(Literal::Datatype {
+                    literal,
+                    datatype_iri,
+                }) => format!("{} {}", quote(literal), self.id(datatype_iri)),
+                AnnotationValue::Literal(l) => format!("{} xsd:string", quote(l.literal())),
+                _ => value,
+            };
+            (
+                "property_value",
+                format!("{} {}{}", self.id(&ann.ap.0), value, q),
+            )
+        };
+
+        self.clause(iri, None, tag, v);
+    }
+
+    fn write_to<W: Write>(mut self, write: &mut W) -> Result<(), HornedError> {
+        for (prefix, base) in &self.idspaces {
+            self.header
+                .push(("idspace".to_string(), format!("{} {}", prefix, base)));
+        }
+        for (iri, (desc, scope)) in std::mem::take(&mut self.header_definitions) {
+            let (tag, scope) = if self.subsets.contains(&iri) {
+                ("subsetdef", String::new())
+            } else {
+                (
+                    "synonymtypedef",
+                    scope.map(|s| format!(" {}", s)).unwrap_or_default(),
+                )
+            };
+            let v = format!("{} {}{}", self.id(&iri), quote(&desc), scope);
+            self.header.push((tag.to_string(), v));
+        }
+
+        self.header.sort_by(|a, b| {
+            (position(HEADER_ORDER, &a.0), a).cmp(&(position(HEADER_ORDER, &b.0), b))
+        });
+        for (tag, value) in &self.header {
+            writeln!(write, "{}: {}", tag, value)?;
+        }
+
+        let mut stanzas: Vec<_> = self
+            .stanzas
+            .iter()
+            .filter_map(|(iri, s)| Some((s.kind?, self.id(iri), &s.clauses)))
+            .collect();
+        stanzas.sort();
+
+        for (kind, id, clauses) in stanzas {
+            let mut clauses: Vec<_> = clauses.iter().collect();
+            clauses.sort_by(|a, b| {
+                (position(TAG_ORDER, &a.0), a).cmp(&(position(TAG_ORDER, &b.0), b))
+            });
+
+            writeln!(write)?;
+            writeln!(write, "[{}]", kind.name())?;
+            writeln!(write, "id: {}", id)?;
+            for (tag, value) in clauses {
+                writeln!(write, "{}: {}", tag, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write an Ontology to `write` in the OBO flat file format.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    mut write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<(), HornedError> {
+    // Ensure we have a single OntologyID in the ontology.
+    let optional_id = {
+        let mut components = ont.i().component_for_kind(ComponentKind::OntologyID);
+        let component = components.next();
+        if components.next().is_some() {
+            return Err(HornedError::invalid("multiple ontology IDs found"));
+        }
+        component.map(|c| {
+            if let Component::OntologyID(ontology_id) = &c.component {
+                ontology_id
+            } else {
+                unreachable!()
+            }
+        })
+    };
+
+    let mut doc = Document {
+        ontology: String::new(),
+        shorthands: HashMap::new(),
+        idspaces: Vec::new(),
+        subsets: HashSet::new(),
+        synonym_types: HashSet::new(),
+        header: Vec::new(),
+        header_definitions: BTreeMap::new(),
+        stanzas: BTreeMap::new(),
+    };
+
+    if let Some(iri) = optional_id.and_then(|id| id.iri.as_ref()) {
+        let iri = iri.as_ref();
+        doc.ontology = iri
+            .strip_prefix(OBO)
+            .and_then(|s| s.strip_suffix(".owl"))
+            .unwrap_or(iri)
+            .to_string();
+        doc.header
+            .push(("ontology".to_string(), doc.ontology.clone()));
+
+        if let Some(viri) = optional_id.and_then(|id| id.viri.as_ref()) {
+            let prefix = format!("{}{}/", OBO, doc.ontology);
+            let suffix = format!("/{}.owl", doc.ontology);
+            let viri = viri.as_ref();
+            let version = viri
+                .strip_prefix(prefix.as_str())
+                .and_then(|s| s.strip_suffix(suffix.as_str()))
+                .unwrap_or(viri);
+            doc.header
+                .push(("data-version".to_string(), version.to_string()));
+        }
+    }
+
+    for ac in ont.i().iter() {
+        doc.prepare(ac);
+    }
+
+    if !ont.i().iter().any(|ac| {
+        matches!(&ac.component, Component::OntologyAnnotation(OntologyAnnotation(ann))
+            if ann.ap.0.as_ref().strip_prefix(OBO_IN_OWL) == Some("hasOBOFormatVersion"))
+    }) {
+        doc.header
+            .push(("format-version".to_string(), "1.2".to_string()));
+    }
+
+    for ac in ont.i().iter() {
+        doc.component(ac);
+    }
+
+    doc.write_to(&mut write)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::AnnotatedComponent;
+    use crate::model::RcStr;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    #[test_resources("src/ont/obo/*.obo")]
+    fn roundtrip_resource(resource: &str) {
+        let reader = std::fs::File::open(resource)
+            .map(std::io::BufReader::new)
+            .unwrap();
+        let (ont, _): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::obo::reader::read(reader, Default::default()).unwrap();
+
+        let mut writer = Vec::new();
+        write(&mut writer, &ont).unwrap();
+
+        let (ont2, _): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::obo::reader::read(std::io::Cursor::new(&writer), Default::default())
+                .unwrap();
+
+        assert_eq!(ont, ont2, "ontologies differ");
+    }
+
+    fn to_obo(obo: &str) -> String {
+        let (ont, _): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::obo::reader::read(obo.as_bytes(), Default::default()).unwrap();
+        let mut writer = Vec::new();
+        write(&mut writer, &ont).unwrap();
+        String::from_utf8(writer).unwrap()
+    }
+
+    #[test]
+    fn stanzas() {
+        let obo = to_obo(
+            "ontology: go
+
+[Term]
+id: GO:0000002
+is_a: GO:0000001 {source=\"x\"}
+name: b
+
+[Term]
+id: GO:0000001
+name: a ! a comment
+comment: not \\! a comment
+",
+        );
+        assert_eq!(
+            obo,
+            "format-version: 1.2
+ontology: go
+
+[Term]
+id: GO:0000001
+name: a
+comment: not \\! a comment
+
+[Term]
+id: GO:0000002
+name: b
+is_a: GO:0000001 {source=\"x\"}
+"
+        );
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(quote("a \"b\"\n"), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(super::escape("a ! {b}"), "a \\! \\{b}");
+    }
+}
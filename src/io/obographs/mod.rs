@@ -0,0 +1,200 @@
+//! The [obographs](https://github.com/geneontology/obographs) JSON
+//! model.
+//!
+//! Obographs is a graph oriented representation of the parts of an
+//! ontology commonly used for display and navigation: entities
+//! become nodes with labels and metadata, and simple axioms become
+//! edges between them. Logical definitions of the form `A ≡ B and R
+//! some C`, and equivalences between named classes, are kept
+//! alongside the graph.
+//!
+//! Metadata follows the [OBO to OWL
+//! mapping](crate::io::obo), so definitions, synonyms, xrefs and
+//! subsets are read from and written to the same annotations as for
+//! OBO documents. Identifiers are written as full IRIs.
+use serde::{Deserialize, Serialize};
+
+pub mod reader;
+pub mod writer;
+
+/// The `pred` of edges for `SubClassOf` axioms between named classes.
+pub(crate) const IS_A: &str = "is_a";
+
+/// The `pred` of edges for sub-property axioms.
+pub(crate) const SUB_PROPERTY_OF: &str = "subPropertyOf";
+
+/// The `pred` of edges for `ClassAssertion` axioms.
+pub(crate) const TYPE: &str = "type";
+
+/// The `pred` of edges for `InverseObjectProperties` axioms.
+pub(crate) const INVERSE_OF: &str = "inverseOf";
+
+/// A document containing one or more graphs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphDocument {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(default)]
+    pub graphs: Vec<Graph>,
+}
+
+/// A graph, which corresponds to a single ontology.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Graph {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lbl: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+    #[serde(default)]
+    pub edges: Vec<Edge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equivalent_nodes_sets: Vec<EquivalentNodesSet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logical_definition_axioms: Vec<LogicalDefinitionAxiom>,
+}
+
+/// The kind of entity of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NodeType {
+    Class,
+    Individual,
+    Property,
+}
+
+/// The kind of property of a node with `NodeType::Property`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PropertyType {
+    Annotation,
+    Object,
+    Data,
+}
+
+/// A node, which is an entity of the ontology.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lbl: Option<String>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub node_type: Option<NodeType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_type: Option<PropertyType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+/// An edge between two nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+    pub sub: String,
+    pub pred: String,
+    pub obj: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+/// Metadata of a graph, node or edge.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<DefinitionPropertyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subsets: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synonyms: Vec<SynonymPropertyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xrefs: Vec<XrefPropertyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub basic_property_values: Vec<BasicPropertyValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+}
+
+impl Meta {
+    pub fn is_empty(&self) -> bool {
+        self == &Meta::default()
+    }
+}
+
+/// A textual definition, and the xrefs which support it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionPropertyValue {
+    pub val: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xrefs: Vec<String>,
+}
+
+/// A synonym; `pred` is the local name of the scope property, such
+/// as `hasExactSynonym`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynonymPropertyValue {
+    pub pred: String,
+    pub val: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xrefs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synonym_type: Option<String>,
+}
+
+/// A database cross reference.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XrefPropertyValue {
+    pub val: String,
+}
+
+/// Any other annotation, with the IRI of its property.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicPropertyValue {
+    pub pred: String,
+    pub val: String,
+}
+
+/// A set of named classes which are equivalent to each other.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivalentNodesSet {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub representative_node_id: Option<String>,
+    pub node_ids: Vec<String>,
+}
+
+/// A class defined as the intersection of named classes and
+/// existential restrictions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogicalDefinitionAxiom {
+    pub defined_class_id: String,
+    #[serde(default)]
+    pub genus_ids: Vec<String>,
+    #[serde(default)]
+    pub restrictions: Vec<ExistentialRestriction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+/// The restriction `property some filler`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistentialRestriction {
+    pub property_id: String,
+    pub filler_id: String,
+}
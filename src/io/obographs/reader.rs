@@ -0,0 +1,465 @@
+//! Read an ontology from an obographs JSON document.
+//!
+//! All graphs of the document are read into a single ontology, whose
+//! ID is taken from the first graph with an `id`. Edges are read as
+//! the axioms which the [writer](super::writer) produces for them;
+//! an edge with any other predicate is read as an object property
+//! assertion if its subject is an individual, and otherwise as a
+//! `SubClassOf` axiom with an existential restriction.
+//!
+//! Values of `basicPropertyValues` are read as literals, since
+//! obographs does not distinguish them from IRIs.
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use curie::PrefixMapping;
+
+use crate::error::HornedError;
+use crate::io::obo::{DEFINITION, OBO, OBO_IN_OWL, XSD};
+use crate::io::ParserConfiguration;
+use crate::model::*;
+use crate::vocab::{AnnotationBuiltIn, RDFS};
+
+use super::*;
+
+type Result<T> = std::result::Result<T, HornedError>;
+
+struct Context<'a, A: ForIRI> {
+    build: &'a Build<A>,
+    /// The type and property type of each node
+    nodes: HashMap<&'a str, (Option<NodeType>, Option<PropertyType>)>,
+}
+
+impl<'a, A: ForIRI> Context<'a, A> {
+    fn oio(&self, local: &str) -> AnnotationProperty<A> {
+        self.build
+            .annotation_property(format!("{}{}", OBO_IN_OWL, local))
+    }
+
+    fn annotation(&self, ap: AnnotationProperty<A>, av: AnnotationValue<A>) -> Annotation<A> {
+        Annotation { ap, av }
+    }
+
+    fn literal(&self, ap: AnnotationProperty<A>, val: &str) -> Annotation<A> {
+        self.annotation(
+            ap,
+            Literal::Simple {
+                literal: val.to_string(),
+            }
+            .into(),
+        )
+    }
+
+    fn xrefs(&self, xrefs: &[String]) -> BTreeSet<Annotation<A>> {
+        xrefs
+            .iter()
+            .map(|x| self.literal(self.oio("hasDbXref"), x))
+            .collect()
+    }
+
+    fn node_type(&self, id: &str) -> (Option<NodeType>, Option<PropertyType>) {
+        self.nodes.get(id).copied().unwrap_or((None, None))
+    }
+
+    /// The annotations of `meta`, each with the annotations of the
+    /// axiom which asserts it.
+    fn meta(&self, meta: &Meta) -> Vec<(Annotation<A>, BTreeSet<Annotation<A>>)> {
+        let mut anns = vec![];
+
+        if let Some(def) = &meta.definition {
+            anns.push((
+                self.literal(self.build.annotation_property(DEFINITION), &def.val),
+                self.xrefs(&def.xrefs),
+            ));
+        }
+        for comment in &meta.comments {
+            anns.push((
+                self.literal(self.build.annotation_property(RDFS::Comment), comment),
+                BTreeSet::new(),
+            ));
+        }
+        for subset in &meta.subsets {
+            anns.push((
+                self.annotation(self.oio("inSubset"), self.build.iri(subset.as_str()).into()),
+                BTreeSet::new(),
+            ));
+        }
+        for syn in &meta.synonyms {
+            let pred = syn.pred.strip_prefix(OBO_IN_OWL).unwrap_or(&syn.pred);
+            let mut qualifiers = self.xrefs(&syn.xrefs);
+            if let Some(st) = &syn.synonym_type {
+                qualifiers.insert(self.annotation(
+                    self.oio("hasSynonymType"),
+                    self.build.iri(st.as_str()).into(),
+                ));
+            }
+            anns.push((self.literal(self.oio(pred), &syn.val), qualifiers));
+        }
+        for xref in &meta.xrefs {
+            anns.push((
+                self.literal(self.oio("hasDbXref"), &xref.val),
+                BTreeSet::new(),
+            ));
+        }
+        for bpv in &meta.basic_property_values {
+            anns.push((
+                self.literal(self.build.annotation_property(bpv.pred.as_str()), &bpv.val),
+                BTreeSet::new(),
+            ));
+        }
+        if meta.deprecated {
+            anns.push((
+                self.annotation(
+                    self.build
+                        .annotation_property(AnnotationBuiltIn::Deprecated),
+                    Literal::Datatype {
+                        literal: "true".to_string(),
+                        datatype_iri: self.build.iri(format!("{}boolean", XSD)),
+                    }
+                    .into(),
+                ),
+                BTreeSet::new(),
+            ));
+        }
+
+        anns
+    }
+
+    /// The annotations of an axiom with metadata `meta`.
+    fn axiom_annotations(&self, meta: &Option<Meta>) -> BTreeSet<Annotation<A>> {
+        meta.iter()
+            .flat_map(|m| self.meta(m))
+            .map(|(ann, _)| ann)
+            .collect()
+    }
+
+    fn node(&self, node: &Node, components: &mut Vec<AnnotatedComponent<A>>) {
+        let iri = self.build.iri(node.id.as_str());
+        let declaration: Option<Component<A>> = match (node.node_type, node.property_type) {
+            (Some(NodeType::Class), _) => Some(DeclareClass(iri.clone().into()).into()),
+            (Some(NodeType::Individual), _) => {
+                Some(DeclareNamedIndividual(iri.clone().into()).into())
+            }
+            (Some(NodeType::Property), Some(PropertyType::Annotation)) => {
+                Some(DeclareAnnotationProperty(iri.clone().into()).into())
+            }
+            (Some(NodeType::Property), Some(PropertyType::Data)) => {
+                Some(DeclareDataProperty(iri.clone().into()).into())
+            }
+            (Some(NodeType::Property), _) => Some(DeclareObjectProperty(iri.clone().into()).into()),
+            (None, _) => None,
+        };
+        components.extend(declaration.map(|c| c.into()));
+
+        let label = node.lbl.iter().map(|lbl| {
+            (
+                self.literal(self.build.annotation_property(RDFS::Label), lbl),
+                BTreeSet::new(),
+            )
+        });
+        let meta = node.meta.iter().flat_map(|m| self.meta(m));
+        for (ann, qualifiers) in label.chain(meta) {
+            components.push(AnnotatedComponent::new(
+                AnnotationAssertion {
+                    subject: iri.clone().into(),
+                    ann,
+                },
+                qualifiers,
+            ));
+        }
+    }
+
+    fn edge(&self, edge: &Edge, components: &mut Vec<AnnotatedComponent<A>>) {
+        let b = self.build;
+        let sub = edge.sub.as_str();
+        let obj = edge.obj.as_str();
+
+        let component: Component<A> = match edge.pred.as_str() {
+            IS_A => SubClassOf {
+                sub: b.class(sub).into(),
+                sup: b.class(obj).into(),
+            }
+            .into(),
+            SUB_PROPERTY_OF => match self.node_type(sub).1 {
+                Some(PropertyType::Annotation) => SubAnnotationPropertyOf {
+                    sub: b.annotation_property(sub),
+                    sup: b.annotation_property(obj),
+                }
+                .into(),
+                Some(PropertyType::Data) => SubDataPropertyOf {
+                    sub: b.data_property(sub),
+                    sup: b.data_property(obj),
+                }
+                .into(),
+                _ => SubObjectPropertyOf {
+                    sub: ObjectPropertyExpression::from(b.object_property(sub)).into(),
+                    sup: b.object_property(obj).into(),
+                }
+                .into(),
+            },
+            TYPE => ClassAssertion {
+                ce: b.class(obj).into(),
+                i: b.named_individual(sub).into(),
+            }
+            .into(),
+            INVERSE_OF => {
+                InverseObjectProperties(b.object_property(sub), b.object_property(obj)).into()
+            }
+            pred => match self.node_type(sub).0 {
+                Some(NodeType::Individual) => ObjectPropertyAssertion {
+                    ope: b.object_property(pred).into(),
+                    from: b.named_individual(sub).into(),
+                    to: b.named_individual(obj).into(),
+                }
+                .into(),
+                _ => SubClassOf {
+                    sub: b.class(sub).into(),
+                    sup: ClassExpression::ObjectSomeValuesFrom {
+                        ope: b.object_property(pred).into(),
+                        bce: Box::new(b.class(obj).into()),
+                    },
+                }
+                .into(),
+            },
+        };
+
+        components.push(AnnotatedComponent::new(
+            component,
+            self.axiom_annotations(&edge.meta),
+        ));
+    }
+
+    fn logical_definition(
+        &self,
+        lda: &LogicalDefinitionAxiom,
+        components: &mut Vec<AnnotatedComponent<A>>,
+    ) {
+        let b = self.build;
+        let mut operands: Vec<ClassExpression<A>> = lda
+            .genus_ids
+            .iter()
+            .map(|g| b.class(g.as_str()).into())
+            .collect();
+        operands.extend(
+            lda.restrictions
+                .iter()
+                .map(|r| ClassExpression::ObjectSomeValuesFrom {
+                    ope: b.object_property(r.property_id.as_str()).into(),
+                    bce: Box::new(b.class(r.filler_id.as_str()).into()),
+                }),
+        );
+
+        let definition = if operands.len() == 1 {
+            operands.pop().unwrap()
+        } else {
+            ClassExpression::ObjectIntersectionOf(operands)
+        };
+
+        components.push(AnnotatedComponent::new(
+            EquivalentClasses(vec![
+                b.class(lda.defined_class_id.as_str()).into(),
+                definition,
+            ]),
+            self.axiom_annotations(&lda.meta),
+        ));
+    }
+
+    fn graph(&self, graph: &Graph, with_id: bool, components: &mut Vec<AnnotatedComponent<A>>) {
+        if with_id {
+            components.push(
+                OntologyID {
+                    iri: graph.id.as_ref().map(|id| self.build.iri(id.as_str())),
+                    viri: graph
+                        .meta
+                        .as_ref()
+                        .and_then(|m| m.version.as_ref())
+                        .map(|v| self.build.iri(v.as_str())),
+                }
+                .into(),
+            );
+        }
+
+        let label = graph
+            .lbl
+            .iter()
+            .map(|lbl| self.literal(self.build.annotation_property(RDFS::Label), lbl));
+        let meta = graph
+            .meta
+            .iter()
+            .flat_map(|m| self.meta(m))
+            .map(|(ann, _)| ann);
+        for ann in label.chain(meta) {
+            components.push(OntologyAnnotation(ann).into());
+        }
+
+        for node in &graph.nodes {
+            self.node(node, components);
+        }
+        for edge in &graph.edges {
+            self.edge(edge, components);
+        }
+        for ens in &graph.equivalent_nodes_sets {
+            components.push(
+                EquivalentClasses(
+                    ens.node_ids
+                        .iter()
+                        .map(|id| self.build.class(id.as_str()).into())
+                        .collect(),
+                )
+                .into(),
+            );
+        }
+        for lda in &graph.logical_definition_axioms {
+            self.logical_definition(lda, components);
+        }
+    }
+}
+
+/// Return the components of all graphs of an obographs
+/// `GraphDocument`.
+pub fn components<A: ForIRI>(doc: &GraphDocument, build: &Build<A>) -> Vec<AnnotatedComponent<A>> {
+    let ctx = Context {
+        build,
+        nodes: doc
+            .graphs
+            .iter()
+            .flat_map(|g| &g.nodes)
+            .map(|n| (n.id.as_str(), (n.node_type, n.property_type)))
+            .collect(),
+    };
+
+    let id_graph = doc.graphs.iter().position(|g| g.id.is_some());
+    let mut components = vec![];
+    for (i, graph) in doc.graphs.iter().enumerate() {
+        ctx.graph(graph, id_graph == Some(i), &mut components);
+    }
+    components
+}
+
+pub fn read<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    bufread: R,
+    _config: ParserConfiguration,
+) -> Result<(O, PrefixMapping)> {
+    let b = Build::new();
+    read_with_build(bufread, &b)
+}
+
+pub fn read_with_build<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    bufread: R,
+    build: &Build<A>,
+) -> Result<(O, PrefixMapping)> {
+    let doc: GraphDocument = serde_json::from_reader(bufread)?;
+
+    let mut o = O::default();
+    for c in components(&doc, build) {
+        o.insert(c);
+    }
+
+    let mut mapping = PrefixMapping::default();
+    mapping
+        .add_prefix("obo", OBO)
+        .expect("valid prefix should not fail");
+    mapping
+        .add_prefix("oboInOwl", OBO_IN_OWL)
+        .expect("valid prefix should not fail");
+
+    Ok((o, mapping))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+    use crate::ontology::component_mapped::ComponentMappedOntology;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn read_str(json: &str) -> SetOntology<RcStr> {
+        read(&mut json.as_bytes(), Default::default()).unwrap().0
+    }
+
+    #[test_resources("src/ont/obographs/*.json")]
+    fn roundtrip_resource(resource: &str) {
+        let json = slurp::read_all_to_string(resource).unwrap();
+        let ont = read_str(&json);
+
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = ont.clone().into();
+        let mut writer = Vec::new();
+        crate::io::obographs::writer::write(&mut writer, &amo).unwrap();
+        let json2 = String::from_utf8(writer).unwrap();
+
+        let doc: GraphDocument = serde_json::from_str(&json).unwrap();
+        let doc2: GraphDocument = serde_json::from_str(&json2).unwrap();
+        assert_eq!(doc, doc2);
+        assert_eq!(ont, read_str(&json2));
+    }
+
+    #[test]
+    fn edges() {
+        let ont = read_str(
+            r#"{"graphs": [{
+                "nodes": [
+                    {"id": "http://example.com/i", "type": "INDIVIDUAL"},
+                    {"id": "http://example.com/ap", "type": "PROPERTY", "propertyType": "ANNOTATION"}
+                ],
+                "edges": [
+                    {"sub": "http://example.com/A", "pred": "is_a", "obj": "http://example.com/B"},
+                    {"sub": "http://example.com/A", "pred": "http://example.com/r", "obj": "http://example.com/C"},
+                    {"sub": "http://example.com/i", "pred": "http://example.com/r", "obj": "http://example.com/j"},
+                    {"sub": "http://example.com/i", "pred": "type", "obj": "http://example.com/A"},
+                    {"sub": "http://example.com/ap", "pred": "subPropertyOf", "obj": "http://example.com/aq"}
+                ]
+            }]}"#,
+        );
+
+        let b = Build::new_rc();
+        let a = b.class("http://example.com/A");
+        let r = b.object_property("http://example.com/r");
+        let i = b.named_individual("http://example.com/i");
+        for c in [
+            Component::from(SubClassOf {
+                sub: a.clone().into(),
+                sup: b.class("http://example.com/B").into(),
+            }),
+            SubClassOf {
+                sub: a.clone().into(),
+                sup: ClassExpression::ObjectSomeValuesFrom {
+                    ope: r.clone().into(),
+                    bce: Box::new(b.class("http://example.com/C").into()),
+                },
+            }
+            .into(),
+            ObjectPropertyAssertion {
+                ope: r.into(),
+                from: i.clone().into(),
+                to: b.named_individual("http://example.com/j").into(),
+            }
+            .into(),
+            ClassAssertion {
+                ce: a.into(),
+                i: i.into(),
+            }
+            .into(),
+            SubAnnotationPropertyOf {
+                sub: b.annotation_property("http://example.com/ap"),
+                sup: b.annotation_property("http://example.com/aq"),
+            }
+            .into(),
+        ] {
+            assert!(ont.i().contains(&c.clone().into()), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn syntax_error() {
+        let r: Result<(SetOntology<RcStr>, _)> = read(
+            &mut r#"{"graphs": [{"nodes": 1}]}"#.as_bytes(),
+            Default::default(),
+        );
+        assert!(matches!(r, Err(HornedError::ParserError(..))));
+    }
+}
@@ -0,0 +1,510 @@
+//! Write an ontology as an obographs JSON document.
+//!
+//! Every declared entity, and every IRI which is the subject of an
+//! annotation assertion, becomes a node. Edges are written for
+//! `SubClassOf` axioms between named classes (`is_a`) or from a named
+//! class to an existential restriction of a named class (the
+//! property), for sub-property, class assertion and inverse axioms,
+//! and for object property assertions between named individuals.
+//!
+//! Other components, such as property characteristics, disjointness
+//! axioms and nested class expressions, have no representation in
+//! obographs and are not written. Annotations on annotation
+//! assertions are only kept where obographs has a place for them, as
+//! with the xrefs of definitions and synonyms.
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+use crate::error::HornedError;
+use crate::io::obo::{DEFINITION, OBO_IN_OWL, SYNONYM_SCOPES};
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::{AnnotationBuiltIn, RDFS};
+
+use super::*;
+
+/// The string form of an annotation value.
+fn value_str<A: ForIRI>(av: &AnnotationValue<A>) -> String {
+    match av {
+        AnnotationValue::Literal(l) => l.literal().clone(),
+        AnnotationValue::IRI(iri) => iri.to_string(),
+        AnnotationValue::AnonymousIndividual(ai) => ai.to_string(),
+    }
+}
+
+/// The local name of `iri` in the `oboInOwl` namespace, if it has one.
+fn oio<A: ForIRI>(iri: &IRI<A>) -> Option<&str> {
+    iri.as_ref().strip_prefix(OBO_IN_OWL)
+}
+
+/// The values of the `oboInOwl:hasDbXref` annotations in `anns`.
+fn xrefs<A: ForIRI>(anns: &BTreeSet<Annotation<A>>) -> Vec<String> {
+    anns.iter()
+        .filter(|ann| oio(&ann.ap.0) == Some("hasDbXref"))
+        .map(|ann| value_str(&ann.av))
+        .collect()
+}
+
+/// Add `ann` to `meta`, where `qualifiers` are the annotations of the
+/// axiom which asserts it.
+fn add_to_meta<A: ForIRI>(
+    meta: &mut Meta,
+    ann: &Annotation<A>,
+    qualifiers: &BTreeSet<Annotation<A>>,
+) {
+    let ap = ann.ap.0.as_ref();
+    let val = value_str(&ann.av);
+    match oio(&ann.ap.0) {
+        Some("hasDbXref") => meta.xrefs.push(XrefPropertyValue { val }),
+        Some("inSubset") => meta.subsets.push(val),
+        Some(local) if SYNONYM_SCOPES.iter().any(|(_, l)| *l == local) => {
+            meta.synonyms.push(SynonymPropertyValue {
+                pred: local.to_string(),
+                val,
+                xrefs: xrefs(qualifiers),
+                synonym_type: qualifiers
+                    .iter()
+                    .find(|q| oio(&q.ap.0) == Some("hasSynonymType"))
+                    .map(|q| value_str(&q.av)),
+            })
+        }
+        _ if ap == DEFINITION && meta.definition.is_none() => {
+            meta.definition = Some(DefinitionPropertyValue {
+                val,
+                xrefs: xrefs(qualifiers),
+            })
+        }
+        _ if ap == RDFS::Comment.as_ref() => meta.comments.push(val),
+        _ if ap == AnnotationBuiltIn::Deprecated.as_ref() => {
+            meta.deprecated = meta.deprecated || val == "true"
+        }
+        _ => meta.basic_property_values.push(BasicPropertyValue {
+            pred: ap.to_string(),
+            val,
+        }),
+    }
+}
+
+/// The metadata of an axiom with annotations `anns`.
+fn axiom_meta<A: ForIRI>(anns: &BTreeSet<Annotation<A>>) -> Option<Meta> {
+    let mut meta = Meta::default();
+    for ann in anns {
+        add_to_meta(&mut meta, ann, &BTreeSet::new());
+    }
+    Some(meta).filter(|m| !m.is_empty())
+}
+
+/// The named class and the named classes and existential
+/// restrictions, if `ce` can be written as the genus and
+/// differentiae of a logical definition.
+fn differentia<A: ForIRI>(
+    ce: &ClassExpression<A>,
+) -> Option<(Vec<String>, Vec<ExistentialRestriction>)> {
+    let operands = match ce {
+        ClassExpression::ObjectIntersectionOf(v) => v.as_slice(),
+        ClassExpression::ObjectSomeValuesFrom { .. } => std::slice::from_ref(ce),
+        _ => return None,
+    };
+
+    let mut genus_ids = vec![];
+    let mut restrictions = vec![];
+    for op in operands {
+        match op {
+            ClassExpression::Class(c) => genus_ids.push(c.0.to_string()),
+            ClassExpression::ObjectSomeValuesFrom {
+                ope: ObjectPropertyExpression::ObjectProperty(p),
+                bce,
+            } => match bce.as_ref() {
+                ClassExpression::Class(c) => restrictions.push(ExistentialRestriction {
+                    property_id: p.0.to_string(),
+                    filler_id: c.0.to_string(),
+                }),
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+    Some((genus_ids, restrictions))
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    graph: Graph,
+    meta: Meta,
+    nodes: BTreeMap<String, Node>,
+}
+
+impl GraphBuilder {
+    fn node(&mut self, id: String) -> &mut Node {
+        self.nodes.entry(id.clone()).or_insert_with(|| Node {
+            id,
+            lbl: None,
+            node_type: None,
+            property_type: None,
+            meta: None,
+        })
+    }
+
+    fn declare<A: ForIRI>(&mut self, iri: &IRI<A>, nt: NodeType, pt: Option<PropertyType>) {
+        let node = self.node(iri.to_string());
+        node.node_type = Some(nt);
+        node.property_type = pt;
+    }
+
+    fn edge<A: ForIRI>(
+        &mut self,
+        sub: &IRI<A>,
+        pred: &str,
+        obj: &IRI<A>,
+        anns: &BTreeSet<Annotation<A>>,
+    ) {
+        self.graph.edges.push(Edge {
+            sub: sub.to_string(),
+            pred: pred.to_string(),
+            obj: obj.to_string(),
+            meta: axiom_meta(anns),
+        });
+    }
+
+    fn annotation_assertion<A: ForIRI>(
+        &mut self,
+        iri: &IRI<A>,
+        ann: &Annotation<A>,
+        qualifiers: &BTreeSet<Annotation<A>>,
+    ) {
+        let node = self.node(iri.to_string());
+        match &ann.av {
+            AnnotationValue::Literal(l)
+                if ann.ap.0.as_ref() == RDFS::Label.as_ref() && node.lbl.is_none() =>
+            {
+                node.lbl = Some(l.literal().clone())
+            }
+            _ => add_to_meta(node.meta.get_or_insert_with(Meta::default), ann, qualifiers),
+        }
+    }
+
+    fn equivalent_classes<A: ForIRI>(
+        &mut self,
+        ces: &[ClassExpression<A>],
+        anns: &BTreeSet<Annotation<A>>,
+    ) {
+        let named: Vec<_> = ces
+            .iter()
+            .filter_map(|ce| match ce {
+                ClassExpression::Class(c) => Some(c.0.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        if named.len() == ces.len() && named.len() > 1 {
+            let mut node_ids = named;
+            node_ids.sort();
+            self.graph.equivalent_nodes_sets.push(EquivalentNodesSet {
+                representative_node_id: None,
+                node_ids,
+            });
+        } else if let ([defined_class_id], [ce, other]) = (named.as_slice(), ces) {
+            let expression = if matches!(ce, ClassExpression::Class(_)) {
+                other
+            } else {
+                ce
+            };
+            if let Some((genus_ids, restrictions)) = differentia(expression) {
+                self.graph
+                    .logical_definition_axioms
+                    .push(LogicalDefinitionAxiom {
+                        defined_class_id: defined_class_id.clone(),
+                        genus_ids,
+                        restrictions,
+                        meta: axiom_meta(anns),
+                    });
+            }
+        }
+    }
+
+    fn component<A: ForIRI>(&mut self, ac: &AnnotatedComponent<A>) {
+        let anns = &ac.ann;
+        match &ac.component {
+            Component::OntologyID(OntologyID { iri, viri }) => {
+                self.graph.id = iri.as_ref().map(|iri| iri.to_string());
+                self.meta.version = viri.as_ref().map(|viri| viri.to_string());
+            }
+            Component::OntologyAnnotation(OntologyAnnotation(ann)) => match &ann.av {
+                AnnotationValue::Literal(l)
+                    if ann.ap.0.as_ref() == RDFS::Label.as_ref() && self.graph.lbl.is_none() =>
+                {
+                    self.graph.lbl = Some(l.literal().clone())
+                }
+                _ => add_to_meta(&mut self.meta, ann, anns),
+            },
+            Component::DeclareClass(DeclareClass(c)) => self.declare(&c.0, NodeType::Class, None),
+            Component::DeclareNamedIndividual(DeclareNamedIndividual(i)) => {
+                self.declare(&i.0, NodeType::Individual, None)
+            }
+            Component::DeclareObjectProperty(DeclareObjectProperty(p)) => {
+                self.declare(&p.0, NodeType::Property, Some(PropertyType::Object))
+            }
+            Component::DeclareAnnotationProperty(DeclareAnnotationProperty(p)) => {
+                self.declare(&p.0, NodeType::Property, Some(PropertyType::Annotation))
+            }
+            Component::DeclareDataProperty(DeclareDataProperty(p)) => {
+                self.declare(&p.0, NodeType::Property, Some(PropertyType::Data))
+            }
+            Component::AnnotationAssertion(AnnotationAssertion {
+                subject: AnnotationSubject::IRI(iri),
+                ann,
+            }) => self.annotation_assertion(iri, ann, anns),
+            Component::SubClassOf(SubClassOf {
+                sub: ClassExpression::Class(sub),
+                sup,
+            }) => match sup {
+                ClassExpression::Class(sup) => self.edge(&sub.0, IS_A, &sup.0, anns),
+                ClassExpression::ObjectSomeValuesFrom {
+                    ope: ObjectPropertyExpression::ObjectProperty(p),
+                    bce,
+                } => {
+                    if let ClassExpression::Class(c) = bce.as_ref() {
+                        self.edge(&sub.0, p.0.as_ref(), &c.0, anns)
+                    }
+                }
+                _ => {}
+            },
+            Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                sub:
+                    SubObjectPropertyExpression::ObjectPropertyExpression(
+                        ObjectPropertyExpression::ObjectProperty(sub),
+                    ),
+                sup: ObjectPropertyExpression::ObjectProperty(sup),
+            }) => self.edge(&sub.0, SUB_PROPERTY_OF, &sup.0, anns),
+            Component::SubAnnotationPropertyOf(SubAnnotationPropertyOf { sub, sup }) => {
+                self.edge(&sub.0, SUB_PROPERTY_OF, &sup.0, anns)
+            }
+            Component::SubDataPropertyOf(SubDataPropertyOf { sub, sup }) => {
+                self.edge(&sub.0, SUB_PROPERTY_OF, &sup.0, anns)
+            }
+            Component::InverseObjectProperties(InverseObjectProperties(a, b)) => {
+                self.edge(&a.0, INVERSE_OF, &b.0, anns)
+            }
+            Component::ClassAssertion(ClassAssertion {
+                ce: ClassExpression::Class(c),
+                i: Individual::Named(i),
+            }) => self.edge(&i.0, TYPE, &c.0, anns),
+            Component::ObjectPropertyAssertion(ObjectPropertyAssertion {
+                ope: ObjectPropertyExpression::ObjectProperty(p),
+                from: Individual::Named(from),
+                to: Individual::Named(to),
+            }) => self.edge(&from.0, p.0.as_ref(), &to.0, anns),
+            Component::EquivalentClasses(EquivalentClasses(ces)) => {
+                self.equivalent_classes(ces, anns)
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> Graph {
+        self.graph.meta = Some(self.meta).filter(|m| !m.is_empty());
+        self.graph.nodes = self.nodes.into_values().collect();
+        self.graph
+            .edges
+            .sort_by(|a, b| (&a.sub, &a.pred, &a.obj).cmp(&(&b.sub, &b.pred, &b.obj)));
+        self.graph
+            .equivalent_nodes_sets
+            .sort_by(|a, b| a.node_ids.cmp(&b.node_ids));
+        self.graph
+            .logical_definition_axioms
+            .sort_by(|a, b| a.defined_class_id.cmp(&b.defined_class_id));
+        self.graph
+    }
+}
+
+/// Return the obographs `GraphDocument` of an ontology, which has a
+/// single graph.
+pub fn graph_document<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<GraphDocument, HornedError> {
+    if ont
+        .i()
+        .component_for_kind(ComponentKind::OntologyID)
+        .nth(1)
+        .is_some()
+    {
+        return Err(HornedError::invalid("multiple ontology IDs found"));
+    }
+
+    let mut builder = GraphBuilder::default();
+    for ac in ont.i().iter() {
+        builder.component(ac);
+    }
+
+    Ok(GraphDocument {
+        meta: None,
+        graphs: vec![builder.finish()],
+    })
+}
+
+/// Write an Ontology to `write` as an obographs JSON document.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    mut write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<(), HornedError> {
+    let doc = graph_document(ont)?;
+    serde_json::to_writer_pretty(&mut write, &doc)?;
+    writeln!(write)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+
+    fn to_graph(obo: &str) -> Graph {
+        let (so, _): (SetOntology<RcStr>, _) =
+            crate::io::obo::reader::read(&mut obo.as_bytes(), Default::default()).unwrap();
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = so.into();
+        let mut doc = graph_document(&amo).unwrap();
+        assert_eq!(doc.graphs.len(), 1);
+        doc.graphs.pop().unwrap()
+    }
+
+    fn go(local: &str) -> String {
+        format!("http://purl.obolibrary.org/obo/GO_{}", local)
+    }
+
+    #[test]
+    fn nodes() {
+        let graph = to_graph(
+            r#"format-version: 1.2
+ontology: go
+data-version: 2024-01-01
+
+[Term]
+id: GO:0000001
+name: mitochondrion inheritance
+def: "The distribution of mitochondria." [GOC:mcc, PMID:10873824]
+comment: A comment.
+subset: goslim_yeast
+synonym: "mitochondrial inheritance" EXACT []
+xref: Wikipedia:Mitochondrion
+is_obsolete: true
+"#,
+        );
+
+        assert_eq!(
+            graph.id.as_deref(),
+            Some("http://purl.obolibrary.org/obo/go.owl")
+        );
+        assert_eq!(
+            graph.meta.unwrap().version.as_deref(),
+            Some("http://purl.obolibrary.org/obo/go/2024-01-01/go.owl")
+        );
+
+        let node = graph.nodes.iter().find(|n| n.id == go("0000001")).unwrap();
+        assert_eq!(node.lbl.as_deref(), Some("mitochondrion inheritance"));
+        assert_eq!(node.node_type, Some(NodeType::Class));
+
+        let meta = node.meta.as_ref().unwrap();
+        assert_eq!(
+            meta.definition,
+            Some(DefinitionPropertyValue {
+                val: "The distribution of mitochondria.".to_string(),
+                xrefs: vec!["GOC:mcc".to_string(), "PMID:10873824".to_string()],
+            })
+        );
+        assert_eq!(meta.comments, vec!["A comment.".to_string()]);
+        assert_eq!(
+            meta.subsets,
+            vec!["http://purl.obolibrary.org/obo/go#goslim_yeast".to_string()]
+        );
+        assert_eq!(
+            meta.synonyms,
+            vec![SynonymPropertyValue {
+                pred: "hasExactSynonym".to_string(),
+                val: "mitochondrial inheritance".to_string(),
+                xrefs: vec![],
+                synonym_type: None,
+            }]
+        );
+        assert_eq!(
+            meta.xrefs,
+            vec![XrefPropertyValue {
+                val: "Wikipedia:Mitochondrion".to_string()
+            }]
+        );
+        assert!(meta.deprecated);
+    }
+
+    #[test]
+    fn edges_and_definitions() {
+        let graph = to_graph(
+            r#"format-version: 1.2
+ontology: go
+
+[Term]
+id: GO:0000002
+is_a: GO:0000001
+relationship: part_of GO:0000003
+
+[Term]
+id: GO:0000004
+intersection_of: GO:0000001
+intersection_of: part_of GO:0000003
+
+[Term]
+id: GO:0000005
+equivalent_to: GO:0000006
+
+[Typedef]
+id: part_of
+xref: BFO:0000050
+"#,
+        );
+
+        let part_of = "http://purl.obolibrary.org/obo/BFO_0000050".to_string();
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.sub.clone(), e.pred.clone(), e.obj.clone()))
+            .collect();
+        assert!(edges.contains(&(go("0000002"), IS_A.to_string(), go("0000001"))));
+        assert!(edges.contains(&(go("0000002"), part_of.clone(), go("0000003"))));
+
+        assert_eq!(
+            graph.logical_definition_axioms,
+            vec![LogicalDefinitionAxiom {
+                defined_class_id: go("0000004"),
+                genus_ids: vec![go("0000001")],
+                restrictions: vec![ExistentialRestriction {
+                    property_id: part_of,
+                    filler_id: go("0000003"),
+                }],
+                meta: None,
+            }]
+        );
+        assert_eq!(
+            graph.equivalent_nodes_sets,
+            vec![EquivalentNodesSet {
+                representative_node_id: None,
+                node_ids: vec![go("0000005"), go("0000006")],
+            }]
+        );
+    }
+
+    #[test]
+    fn serialized() {
+        let graph = to_graph("format-version: 1.2\nontology: go\n\n[Term]\nid: GO:0000001\n");
+        let doc = GraphDocument {
+            meta: None,
+            graphs: vec![graph],
+        };
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["graphs"][0]["nodes"][0]["type"], "CLASS");
+        assert!(json["graphs"][0].get("logicalDefinitionAxioms").is_none());
+    }
+}
@@ -11,10 +11,10 @@ use crate::model::Ontology;
 
 mod from_pair;
 mod lexer;
+mod stream;
+
+pub use self::stream::ComponentReader;
 
-use self::from_pair::FromPair;
-use self::from_pair::MutableOntologyWrapper;
-use self::lexer::OwlFunctionalLexer;
 use self::lexer::Rule;
 
 struct Context<'a, A: ForIRI> {
@@ -37,20 +37,33 @@ pub fn read<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead
 }
 
 pub fn read_with_build<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
-    mut bufread: R,
+    bufread: R,
     build: &Build<A>,
 ) -> Result<(O, PrefixMapping), HornedError> {
-    let prefixes = PrefixMapping::default();
-    let ctx = Context::new(build, &prefixes);
-
-    // FIXME: implement iterative parser (this is possible in )
-    let mut doc = String::new();
-    bufread.read_to_string(&mut doc)?;
-    let pair = OwlFunctionalLexer::lex(Rule::OntologyDocument, doc.trim())?
-        .next()
-        .unwrap();
-
-    let wrapper: Result<(MutableOntologyWrapper<A, O>, PrefixMapping), HornedError> =
-        FromPair::from_pair(pair, &ctx);
-    wrapper.map(|r| (r.0 .0, r.1))
+    let mut reader = ComponentReader::new(bufread, build);
+    let mut o = O::default();
+    for ac in &mut reader {
+        o.insert(ac?);
+    }
+    Ok((o, reader.into_mapping()))
+}
+
+/// Read an ontology whose components are `body`, with the `:`
+/// prefix bound to `http://example.com/` and the usual prefixes for
+/// the OWL, RDFS, XSD and SWRL built-in vocabularies.
+#[cfg(test)]
+pub(crate) fn read_example<O>(body: &str) -> O
+where
+    O: MutableOntology<crate::model::RcStr> + Ontology<crate::model::RcStr> + Default,
+{
+    let doc = format!(
+        "Prefix(:=<http://example.com/>)
+Prefix(owl:=<http://www.w3.org/2002/07/owl#>)
+Prefix(rdfs:=<http://www.w3.org/2000/01/rdf-schema#>)
+Prefix(xsd:=<http://www.w3.org/2001/XMLSchema#>)
+Prefix(swrlb:=<http://www.w3.org/2003/11/swrlb#>)
+Ontology({})",
+        body
+    );
+    read(doc.as_bytes(), Default::default()).unwrap().0
 }
@@ -0,0 +1,533 @@
+use std::io::BufRead;
+
+use curie::PrefixMapping;
+
+use crate::error::{HornedError, Location};
+use crate::model::*;
+
+use super::from_pair::FromPair;
+use super::lexer::OwlFunctionalLexer;
+use super::lexer::Rule;
+use super::Context;
+
+type Result<T> = std::result::Result<T, HornedError>;
+
+/// Reads a document one character at a time, a line at a time.
+struct Scanner<R> {
+    read: R,
+    line: String,
+    pos: usize,
+    /// The byte offset of the next character in the document
+    offset: usize,
+}
+
+impl<R: BufRead> Scanner<R> {
+    fn peek(&mut self) -> Result<Option<char>> {
+        if self.pos == self.line.len() {
+            self.line.clear();
+            self.pos = 0;
+            if self.read.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+        }
+        Ok(self.line[self.pos..].chars().next())
+    }
+
+    /// Consume the next character, adding it to `buf`.
+    fn bump(&mut self, buf: &mut String) -> Result<Option<char>> {
+        let c = self.peek()?;
+        if let Some(c) = c {
+            self.pos += c.len_utf8();
+            self.offset += c.len_utf8();
+            buf.push(c);
+        }
+        Ok(c)
+    }
+
+    fn eof(&self, what: &str) -> HornedError {
+        HornedError::invalid_at(
+            format!("unexpected end of document in {}", what),
+            self.offset,
+        )
+    }
+
+    /// Consume whitespace and comments.
+    fn skip_ws(&mut self, buf: &mut String) -> Result<()> {
+        while let Some(c) = self.peek()? {
+            match c {
+                ' ' | '\t' | '\n' | '\r' => {
+                    self.bump(buf)?;
+                }
+                '#' => {
+                    while !matches!(self.peek()?, None | Some('\n') | Some('\r')) {
+                        self.bump(buf)?;
+                    }
+                }
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Consume characters up to and including `end`.
+    fn until(&mut self, end: char, escapes: bool, buf: &mut String) -> Result<()> {
+        loop {
+            match self.bump(buf)? {
+                Some('\\') if escapes => {
+                    self.bump(buf)?;
+                }
+                Some(c) if c == end => return Ok(()),
+                Some(_) => {}
+                None => return Err(self.eof("string or IRI")),
+            }
+        }
+    }
+
+    /// Consume a keyword, full IRI or abbreviated IRI.
+    fn word(&mut self, buf: &mut String) -> Result<()> {
+        if self.peek()? == Some('<') {
+            self.bump(buf)?;
+            return self.until('>', false, buf);
+        }
+        while let Some(c) = self.peek()? {
+            match c {
+                ' ' | '\t' | '\n' | '\r' | '(' | ')' | '#' | '<' | '"' => break,
+                '\\' => {
+                    self.bump(buf)?;
+                    self.bump(buf)?;
+                }
+                _ => {
+                    self.bump(buf)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Consume a bracketed list, starting at the opening bracket,
+    /// up to and including the matching closing bracket.
+    fn balanced(&mut self, buf: &mut String) -> Result<()> {
+        let mut depth = 0;
+        loop {
+            match self.peek()? {
+                Some('"') => {
+                    self.bump(buf)?;
+                    self.until('"', true, buf)?;
+                }
+                Some('<') => {
+                    self.bump(buf)?;
+                    self.until('>', false, buf)?;
+                }
+                Some('#') => self.skip_ws(buf)?,
+                Some('\\') => {
+                    self.bump(buf)?;
+                    self.bump(buf)?;
+                }
+                Some('(') => {
+                    depth += 1;
+                    self.bump(buf)?;
+                }
+                Some(')') => {
+                    depth -= 1;
+                    self.bump(buf)?;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => {
+                    self.bump(buf)?;
+                }
+                None => return Err(self.eof("bracketed expression")),
+            }
+        }
+    }
+}
+
+/// Move the location of an error in an item to its location in the
+/// document.
+fn offset_error(e: HornedError, offset: usize) -> HornedError {
+    let relocate = |l| match l {
+        Location::BytePosition(p) => Location::BytePosition(p + offset),
+        Location::ByteSpan(r) => Location::ByteSpan(r.start + offset..r.end + offset),
+        Location::Unknown => Location::Unknown,
+    };
+    match e {
+        HornedError::ParserError(e, l) => HornedError::ParserError(e, relocate(l)),
+        HornedError::ValidityError(s, l) => HornedError::ValidityError(s, relocate(l)),
+        e => e,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum State {
+    Prefixes,
+    Imports,
+    Annotations,
+    Axioms,
+    Done,
+}
+
+/// An incremental reader for the OWL Functional-style syntax.
+///
+/// Rather than reading the whole document, `ComponentReader` reads
+/// one top-level element, such as a prefix declaration or an axiom,
+/// at a time, and yields the component for it. The `OntologyID` is
+/// yielded first, followed by imports, ontology annotations and
+/// axioms in document order.
+///
+/// Prefix declarations are available from
+/// [`mapping`](ComponentReader::mapping) once the first component
+/// has been read.
+///
+/// # Example
+/// ```
+/// # use horned_owl::model::*;
+/// # use horned_owl::io::ofn::reader::ComponentReader;
+/// # use horned_owl::ontology::set::SetOntology;
+/// let ofn = "Prefix(:=<http://example.com/>) Ontology(Declaration(Class(:A)))";
+///
+/// let b = Build::new_rc();
+/// let mut o = SetOntology::new();
+/// for ac in ComponentReader::new(ofn.as_bytes(), &b) {
+///     o.insert(ac.unwrap());
+/// }
+/// assert!(o.i().contains(&DeclareClass(b.class("http://example.com/A")).into()));
+/// ```
+pub struct ComponentReader<'a, A: ForIRI, R> {
+    build: &'a Build<A>,
+    mapping: PrefixMapping,
+    scanner: Scanner<R>,
+    state: State,
+    /// The offset and keyword of an element which has been partly read
+    pending: Option<(usize, String)>,
+}
+
+impl<'a, A: ForIRI, R: BufRead> ComponentReader<'a, A, R> {
+    pub fn new(bufread: R, build: &'a Build<A>) -> Self {
+        ComponentReader {
+            build,
+            mapping: PrefixMapping::default(),
+            scanner: Scanner {
+                read: bufread,
+                line: String::new(),
+                pos: 0,
+                offset: 0,
+            },
+            state: State::Prefixes,
+            pending: None,
+        }
+    }
+
+    /// The prefix declarations of the document.
+    pub fn mapping(&self) -> &PrefixMapping {
+        &self.mapping
+    }
+
+    /// Consume the reader, returning the prefix declarations.
+    pub fn into_mapping(self) -> PrefixMapping {
+        self.mapping
+    }
+
+    /// Lex a single element with `rule`, and convert it with `f`.
+    fn parse<T>(
+        &self,
+        rule: Rule,
+        item: &str,
+        f: impl FnOnce(pest::iterators::Pair<'_, Rule>, &Context<'_, A>) -> Result<T>,
+    ) -> Result<T> {
+        let ctx = Context::new(self.build, &self.mapping);
+        let pair = OwlFunctionalLexer::lex(rule, item)?.next().unwrap();
+        if pair.as_span().end() != item.len() {
+            return Err(HornedError::invalid_at(
+                format!("unexpected input in {:?}", rule),
+                pair.as_span().end(),
+            ));
+        }
+        f(pair, &ctx)
+    }
+
+    /// Read the prefix declarations and the start of the ontology, and
+    /// return the `OntologyID`.
+    fn header(&mut self) -> Result<Component<A>> {
+        let mut ignored = String::new();
+        loop {
+            self.scanner.skip_ws(&mut ignored)?;
+            let offset = self.scanner.offset;
+            let mut item = String::new();
+            self.scanner.word(&mut item)?;
+            self.scanner.skip_ws(&mut item)?;
+            if self.scanner.peek()? != Some('(') {
+                return Err(match self.scanner.peek()? {
+                    None => self.scanner.eof("document"),
+                    Some(_) => HornedError::invalid_at("expected Prefix or Ontology", offset),
+                });
+            }
+
+            match item.trim_end() {
+                "Prefix" => {
+                    self.scanner.balanced(&mut item)?;
+                    let prefixes: PrefixMapping = self
+                        .parse(Rule::PrefixDeclarations, &item, |p, ctx| {
+                            FromPair::from_pair(p, ctx)
+                        })
+                        .map_err(|e| offset_error(e, offset))?;
+                    for (prefix, ns) in prefixes.mappings() {
+                        self.mapping
+                            .add_prefix(prefix, ns)
+                            .expect("grammar does not allow invalid prefixes");
+                    }
+                }
+                "Ontology" => {
+                    self.scanner.bump(&mut ignored)?;
+                    break;
+                }
+                _ => {
+                    return Err(HornedError::invalid_at(
+                        "expected Prefix or Ontology",
+                        offset,
+                    ))
+                }
+            }
+        }
+
+        // The ontology IRI and version IRI are the words before the
+        // first bracketed element
+        let mut ontology_id = OntologyID::default();
+        let mut iris = vec![];
+        loop {
+            self.scanner.skip_ws(&mut ignored)?;
+            if matches!(self.scanner.peek()?, None | Some(')')) {
+                break;
+            }
+            let offset = self.scanner.offset;
+            let mut word = String::new();
+            self.scanner.word(&mut word)?;
+            if word.is_empty() {
+                return Err(HornedError::invalid_at(
+                    "unexpected input in Ontology",
+                    offset,
+                ));
+            }
+            let mut ws = String::new();
+            self.scanner.skip_ws(&mut ws)?;
+            if self.scanner.peek()? == Some('(') {
+                self.pending = Some((offset, word + &ws));
+                break;
+            }
+            iris.push((offset, word));
+        }
+
+        if iris.len() > 2 {
+            return Err(HornedError::invalid_at(
+                "too many IRIs in Ontology",
+                iris[2].0,
+            ));
+        }
+        let mut iris = iris
+            .into_iter()
+            .map(|(offset, word)| {
+                self.parse(Rule::IRI, &word, |p, ctx| IRI::from_pair(p, ctx))
+                    .map_err(|e| offset_error(e, offset))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        ontology_id.iri = iris.next();
+        ontology_id.viri = iris.next();
+
+        self.state = State::Imports;
+        Ok(ontology_id.into())
+    }
+
+    /// Read the next element of the ontology, or return `None` at the
+    /// end of the ontology.
+    fn element(&mut self) -> Result<Option<AnnotatedComponent<A>>> {
+        let (offset, mut item) = match self.pending.take() {
+            Some(pending) => pending,
+            None => {
+                let mut ignored = String::new();
+                self.scanner.skip_ws(&mut ignored)?;
+                let offset = self.scanner.offset;
+                match self.scanner.peek()? {
+                    Some(')') => {
+                        self.scanner.bump(&mut ignored)?;
+                        self.scanner.skip_ws(&mut ignored)?;
+                        if self.scanner.peek()?.is_some() {
+                            return Err(HornedError::invalid_at(
+                                "unexpected input after Ontology",
+                                self.scanner.offset,
+                            ));
+                        }
+                        self.state = State::Done;
+                        return Ok(None);
+                    }
+                    None => return Err(self.scanner.eof("Ontology")),
+                    Some(_) => {}
+                }
+
+                let mut item = String::new();
+                self.scanner.word(&mut item)?;
+                self.scanner.skip_ws(&mut item)?;
+                if item.is_empty() || self.scanner.peek()? != Some('(') {
+                    return Err(HornedError::invalid_at("expected an axiom", offset));
+                }
+                (offset, item)
+            }
+        };
+
+        let state = match item.trim_end() {
+            "Import" => State::Imports,
+            "Annotation" => State::Annotations,
+            _ => State::Axioms,
+        };
+        if state < self.state {
+            return Err(HornedError::invalid_at(
+                format!("unexpected {} in Ontology", item.trim_end()),
+                offset,
+            ));
+        }
+        self.state = state;
+        self.scanner.balanced(&mut item)?;
+
+        match state {
+            State::Imports => self.parse(Rule::Import, &item, |p, ctx| {
+                Import::from_pair(p, ctx).map(|i| Component::from(i).into())
+            }),
+            State::Annotations => self.parse(Rule::Annotation, &item, |p, ctx| {
+                OntologyAnnotation::from_pair(p, ctx).map(|oa| Component::from(oa).into())
+            }),
+            _ => self.parse(Rule::OntologyAxiom, &item, |p, ctx| {
+                let inner = p.into_inner().next().unwrap();
+                match inner.as_rule() {
+                    Rule::Axiom => AnnotatedComponent::from_pair(inner, ctx),
+                    rule => Err(HornedError::invalid_at(
+                        format!("{:?} is not supported", rule),
+                        inner.as_span(),
+                    )),
+                }
+            }),
+        }
+        .map(Some)
+        .map_err(|e| offset_error(e, offset))
+    }
+}
+
+impl<'a, A: ForIRI, R: BufRead> Iterator for ComponentReader<'a, A, R> {
+    type Item = Result<AnnotatedComponent<A>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let r = match self.state {
+            State::Prefixes => self.header().map(|c| Some(c.into())),
+            State::Done => return None,
+            _ => self.element(),
+        };
+        if r.is_err() {
+            self.state = State::Done;
+        }
+        r.transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::io::ofn::reader::from_pair::MutableOntologyWrapper;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn read_all(doc: &str) -> Result<(SetOntology<RcStr>, PrefixMapping)> {
+        let b = Build::new_rc();
+        let mut reader = ComponentReader::new(doc.as_bytes(), &b);
+        let mut o = SetOntology::new();
+        for ac in &mut reader {
+            o.insert(ac?);
+        }
+        Ok((o, reader.into_mapping()))
+    }
+
+    #[test_resources("src/ont/owl-functional/*.ofn")]
+    fn stream_resource(resource: &str) {
+        let text = slurp::read_all_to_string(resource).unwrap();
+        let (ont, mapping) = read_all(&text).unwrap();
+
+        let b = Build::new_rc();
+        let empty = PrefixMapping::default();
+        let pair = OwlFunctionalLexer::lex(Rule::OntologyDocument, text.trim())
+            .unwrap()
+            .next()
+            .unwrap();
+        let (expected, expected_mapping): (MutableOntologyWrapper<_, SetOntology<RcStr>>, _) =
+            FromPair::from_pair(pair, &Context::new(&b, &empty)).unwrap();
+
+        assert_eq!(ont, expected.0);
+        assert_eq!(
+            mapping.mappings().collect::<Vec<_>>(),
+            expected_mapping.mappings().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn incremental() {
+        let doc = "Prefix(:=<http://example.com/>)
+Ontology(<http://example.com/o>
+  Declaration(Class(:A))
+  SubClassOf(:A :B)
+  Declaration(Class(:C :D))
+)";
+        let b = Build::new_rc();
+        let mut reader = ComponentReader::new(doc.as_bytes(), &b);
+
+        let id = reader.next().unwrap().unwrap();
+        assert_eq!(
+            id.component,
+            OntologyID {
+                iri: Some(b.iri("http://example.com/o")),
+                viri: None
+            }
+            .into()
+        );
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+
+        let error = doc.find("Declaration(Class(:C").unwrap();
+        match reader.next() {
+            Some(Err(HornedError::ParserError(_, Location::BytePosition(p)))) => {
+                assert!(p > error, "{} {}", p, error)
+            }
+            r => panic!("expected a parser error, got {:?}", r),
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn brackets_in_strings_and_comments() {
+        let (ont, _) = read_all(
+            r#"Prefix(rdfs:=<http://www.w3.org/2000/01/rdf-schema#>)
+Ontology( # a comment with a bracket )
+  Annotation(rdfs:comment "A \"quoted\" string ( with
+ a bracket")
+  Declaration(Class(<http://example.com/a(b)>)) # another )
+)
+"#,
+        )
+        .unwrap();
+        assert_eq!(ont.i().iter().count(), 3);
+    }
+
+    #[test]
+    fn invalid() {
+        for doc in [
+            "",
+            "Ontology(",
+            "Ontology() Ontology()",
+            "Prefix(:=<http://example.com/>) Nonsense()",
+            "Ontology(Declaration(Class(<http://example.com/A>)) Import(<http://example.com/>))",
+            "Ontology(<http://example.com/a> <http://example.com/b> <http://example.com/c>)",
+            "Ontology(Declaration(Class(<http://example.com/A>) <http://example.com/B>))",
+        ] {
+            assert!(read_all(doc).is_err(), "{}", doc);
+        }
+    }
+}
@@ -1,11 +1,15 @@
+use std::borrow::Borrow;
 use std::io::Write;
 
 use curie::PrefixMapping;
 
 use crate::error::HornedError;
+use crate::model::AnnotatedComponent;
 use crate::model::Component;
 use crate::model::ComponentKind;
 use crate::model::ForIRI;
+use crate::model::Kinded;
+use crate::model::OntologyID;
 use crate::ontology::component_mapped::ComponentMappedOntology;
 use crate::ontology::indexed::ForIndex;
 
@@ -41,13 +45,56 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
         }
         component.map(|c| {
             if let Component::OntologyID(ontology_id) = &c.component {
-                ontology_id
+                ontology_id.clone()
             } else {
                 unreachable!()
             }
         })
     };
 
+    // Write axioms in order
+    let components = ComponentKind::all_kinds().into_iter().flat_map(|kind| {
+        let mut components = ont.i().component_for_kind(kind).collect::<Vec<_>>();
+        components.sort();
+        components
+    });
+
+    write_components(
+        &mut write,
+        &optional_id.unwrap_or_default(),
+        Some(mapping),
+        components,
+    )
+}
+
+/// Write the components of an ontology to `write`, using the given
+/// `PrefixMapping`.
+///
+/// Components are written as they are taken from `components`, so
+/// the whole ontology need not be held in memory. The output is the
+/// same as that of [`write`] if the components are given grouped by
+/// `ComponentKind`, in the order of `ComponentKind::all_kinds`, and
+/// sorted within each group. `OntologyID` and `DocIRI` components
+/// are not written; the ontology IRI and version IRI are taken from
+/// `ontology_id`.
+pub fn write_components<A, W, I>(
+    mut write: W,
+    ontology_id: &OntologyID<A>,
+    mapping: Option<&PrefixMapping>,
+    components: I,
+) -> Result<(), HornedError>
+where
+    A: ForIRI,
+    W: Write,
+    I: IntoIterator,
+    I::Item: Borrow<AnnotatedComponent<A>>,
+{
+    let default_mapper = PrefixMapping::default();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+
     // Write prefixes
     write!(
         &mut write,
@@ -59,29 +106,26 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
     write!(write, "Ontology(")?;
 
     // Write the IRI and Version IRI if any
-    if let Some(ontology_id) = optional_id {
-        if let Some(iri) = &ontology_id.iri {
-            write!(write, "{}", iri.as_functional_with_prefixes(mapping))?;
-            if let Some(viri) = &ontology_id.viri {
-                writeln!(write, " {}", viri.as_functional_with_prefixes(mapping))?;
-            } else {
-                writeln!(write)?;
-            }
+    if let Some(iri) = &ontology_id.iri {
+        write!(write, "{}", iri.as_functional_with_prefixes(mapping))?;
+        if let Some(viri) = &ontology_id.viri {
+            writeln!(write, " {}", viri.as_functional_with_prefixes(mapping))?;
+        } else {
+            writeln!(write)?;
         }
     }
 
-    // Write axioms in order
-    for kind in ComponentKind::all_kinds() {
-        if kind != ComponentKind::OntologyID && kind != ComponentKind::DocIRI {
-            let mut components = ont.i().component_for_kind(kind).collect::<Vec<_>>();
-            components.sort();
-            for component in components {
-                writeln!(
-                    &mut write,
-                    "    {}",
-                    component.as_functional_with_prefixes(mapping)
-                )?;
-            }
+    for component in components {
+        let component = component.borrow();
+        if !matches!(
+            component.kind(),
+            ComponentKind::OntologyID | ComponentKind::DocIRI
+        ) {
+            writeln!(
+                &mut write,
+                "    {}",
+                component.as_functional_with_prefixes(mapping)
+            )?;
         }
     }
 
@@ -117,4 +161,35 @@ mod test {
         assert_eq!(prefixes, prefixes2, "prefix mapping differ");
         assert_eq!(ont, ont2, "ontologies differ");
     }
+
+    #[test_resources("src/ont/owl-functional/*.ofn")]
+    fn write_components_resource(resource: &str) {
+        let reader = std::fs::File::open(resource)
+            .map(std::io::BufReader::new)
+            .unwrap();
+        let (ont, prefixes): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::ofn::reader::read(reader, Default::default()).unwrap();
+        let mut expected = Vec::new();
+        write(&mut expected, &ont, Some(&prefixes)).unwrap();
+
+        let b = crate::model::Build::new_rc();
+        let mut reader = crate::io::ofn::reader::ComponentReader::new(expected.as_slice(), &b);
+        let mut components = (&mut reader).collect::<Result<Vec<_>, _>>().unwrap();
+        let kinds = ComponentKind::all_kinds();
+        components.sort_by_key(|ac| kinds.iter().position(|k| *k == ac.kind()));
+
+        let mut writer = Vec::new();
+        write_components(
+            &mut writer,
+            &ont.i().the_ontology_id_or_default(),
+            Some(reader.mapping()),
+            &components,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected).unwrap(),
+            String::from_utf8(writer).unwrap()
+        );
+    }
 }
@@ -0,0 +1,3 @@
+//! [Manchester Syntax](https://www.w3.org/TR/owl2-manchester-syntax/) for OWL.
+pub mod reader;
+pub mod writer;
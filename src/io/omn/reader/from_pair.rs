@@ -0,0 +1,1282 @@
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use curie::Curie;
+use curie::PrefixMapping;
+use pest::iterators::{Pair, Pairs};
+
+use crate::error::HornedError;
+use crate::model::*;
+use crate::vocab::{Facet, Namespace, OWL2Datatype, OWL};
+
+use super::Context;
+use super::Rule;
+
+// ---------------------------------------------------------------------------
+
+type Result<T> = std::result::Result<T, HornedError>;
+
+/// A trait for OWL elements that can be obtained from OWL Manchester tokens.
+///
+/// `Pair<Rule>` values can be obtained from the `OwlManchesterLexer` struct
+/// after parsing a document.
+pub trait FromPair<A: ForIRI>: Sized {
+    /// The valid production rule for the implementor.
+    const RULE: Rule;
+
+    /// Create a new instance from a `Pair`.
+    #[inline]
+    fn from_pair(pair: Pair<Rule>, context: &Context<'_, A>) -> Result<Self> {
+        if cfg!(debug_assertions) && pair.as_rule() != Self::RULE {
+            return Err(HornedError::from(pest::error::Error::new_from_span(
+                pest::error::ErrorVariant::ParsingError {
+                    positives: vec![pair.as_rule()],
+                    negatives: vec![Self::RULE],
+                },
+                pair.as_span(),
+            )));
+        }
+        Self::from_pair_unchecked(pair, context)
+    }
+
+    /// Create a new instance from a `Pair` without checking the PEG rule.
+    fn from_pair_unchecked(pair: Pair<Rule>, context: &Context<'_, A>) -> Result<Self>;
+}
+
+// ---------------------------------------------------------------------------
+
+macro_rules! impl_wrapper {
+    ($ty:ident, $rule:path) => {
+        impl<A: ForIRI> FromPair<A> for $ty<A> {
+            const RULE: Rule = $rule;
+            fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+                FromPair::from_pair(pair.into_inner().next().unwrap(), ctx).map($ty)
+            }
+        }
+    };
+}
+
+impl_wrapper!(Class, Rule::Class);
+impl_wrapper!(Import, Rule::Import);
+impl_wrapper!(ObjectProperty, Rule::ObjectProperty);
+impl_wrapper!(DataProperty, Rule::DataProperty);
+impl_wrapper!(AnnotationProperty, Rule::AnnotationProperty);
+impl_wrapper!(NamedIndividual, Rule::NamedIndividual);
+
+// ---------------------------------------------------------------------------
+
+/// Split an annotated list item into its annotations and the item.
+fn annotated<'i, A: ForIRI>(
+    pair: Pair<'i, Rule>,
+    ctx: &Context<'_, A>,
+) -> Result<(BTreeSet<Annotation<A>>, Pair<'i, Rule>)> {
+    let mut inner = pair.into_inner();
+    let anns = optional_annotations(&mut inner, ctx)?;
+    Ok((anns, inner.next().unwrap()))
+}
+
+/// Consume the annotations at the start of `pairs` if there are any.
+fn optional_annotations<A: ForIRI>(
+    pairs: &mut Pairs<Rule>,
+    ctx: &Context<'_, A>,
+) -> Result<BTreeSet<Annotation<A>>> {
+    match pairs.peek() {
+        Some(pair) if pair.as_rule() == Rule::Annotations => {
+            FromPair::from_pair(pairs.next().unwrap(), ctx)
+        }
+        _ => Ok(BTreeSet::new()),
+    }
+}
+
+fn xsd<A: ForIRI>(ctx: &Context<'_, A>, local: &str) -> IRI<A> {
+    ctx.build
+        .iri(format!("{}{}", Namespace::XSD.as_ref(), local))
+}
+
+/// Is `pair` a simple IRI which is also a datatype keyword?
+fn datatype_keyword(pair: &Pair<Rule>) -> Option<&'static str> {
+    let inner = pair.clone().into_inner().next()?;
+    if inner.as_rule() != Rule::SimpleIRI {
+        return None;
+    }
+    ["integer", "decimal", "float", "string"]
+        .iter()
+        .copied()
+        .find(|k| *k == inner.as_str())
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Vec<AnnotatedComponent<A>> {
+    const RULE: Rule = Rule::Frame;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let pair = pair.into_inner().next().unwrap();
+        let mut v = Vec::new();
+        match pair.as_rule() {
+            Rule::DatatypeFrame => datatype_frame(pair, ctx, &mut v)?,
+            Rule::ClassFrame => class_frame(pair, ctx, &mut v)?,
+            Rule::ObjectPropertyFrame => object_property_frame(pair, ctx, &mut v)?,
+            Rule::DataPropertyFrame => data_property_frame(pair, ctx, &mut v)?,
+            Rule::AnnotationPropertyFrame => annotation_property_frame(pair, ctx, &mut v)?,
+            Rule::IndividualFrame => individual_frame(pair, ctx, &mut v)?,
+            Rule::Misc => v.push(misc(pair.into_inner().next().unwrap(), ctx)?),
+            rule => unreachable!("unexpected rule in Frame::from_pair: {:?}", rule),
+        }
+        Ok(v)
+    }
+}
+
+fn frame_annotations<A: ForIRI>(
+    pair: Pair<Rule>,
+    subject: &AnnotationSubject<A>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    for item in pair.into_inner().next().unwrap().into_inner() {
+        let (anns, ann) = annotated(item, ctx)?;
+        v.push(AnnotatedComponent::new(
+            AnnotationAssertion::new(subject.clone(), Annotation::from_pair(ann, ctx)?),
+            anns,
+        ));
+    }
+    Ok(())
+}
+
+/// Call `f` with each item of an annotated list, and add the
+/// components it returns with the item's annotations.
+fn annotated_list<A: ForIRI, C: Into<Component<A>>>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+    mut f: impl FnMut(Pair<Rule>) -> Result<C>,
+) -> Result<()> {
+    for item in pair.into_inner().next().unwrap().into_inner() {
+        let (anns, item) = annotated(item, ctx)?;
+        v.push(AnnotatedComponent::new(f(item)?, anns));
+    }
+    Ok(())
+}
+
+fn datatype_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let dt = Datatype::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = AnnotationSubject::IRI(dt.0.clone());
+    v.push(DeclareDatatype(dt.clone()).into());
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::DatatypeEquivalentTo => {
+                let (anns, dr) = annotated(section.into_inner().next().unwrap(), ctx)?;
+                v.push(AnnotatedComponent::new(
+                    DatatypeDefinition::new(dt.clone(), DataRange::from_pair(dr, ctx)?),
+                    anns,
+                ));
+            }
+            rule => unreachable!("unexpected rule in datatype frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn class_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let cls = Class::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = AnnotationSubject::IRI(cls.0.clone());
+    let ce: ClassExpression<A> = cls.clone().into();
+    v.push(DeclareClass(cls.clone()).into());
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::SubClassOf => annotated_list(section, ctx, v, |p| {
+                Ok(SubClassOf::new(
+                    ClassExpression::from_pair(p, ctx)?,
+                    ce.clone(),
+                ))
+            })?,
+            Rule::EquivalentTo => annotated_list(section, ctx, v, |p| {
+                Ok(EquivalentClasses(vec![
+                    ce.clone(),
+                    ClassExpression::from_pair(p, ctx)?,
+                ]))
+            })?,
+            Rule::DisjointWith => annotated_list(section, ctx, v, |p| {
+                Ok(DisjointClasses(vec![
+                    ce.clone(),
+                    ClassExpression::from_pair(p, ctx)?,
+                ]))
+            })?,
+            Rule::DisjointUnionOf => {
+                let mut inner = section.into_inner();
+                let anns = optional_annotations(&mut inner, ctx)?;
+                let ces = inner
+                    .map(|p| ClassExpression::from_pair(p, ctx))
+                    .collect::<Result<_>>()?;
+                v.push(AnnotatedComponent::new(
+                    DisjointUnion(cls.clone(), ces),
+                    anns,
+                ));
+            }
+            Rule::HasKey => {
+                let mut inner = section.into_inner();
+                let anns = optional_annotations(&mut inner, ctx)?;
+                let pes = inner
+                    .map(|p| PropertyExpression::from_pair(p, ctx))
+                    .collect::<Result<_>>()?;
+                v.push(AnnotatedComponent::new(HasKey::new(ce.clone(), pes), anns));
+            }
+            rule => unreachable!("unexpected rule in class frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn object_property_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let op = ObjectProperty::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = AnnotationSubject::IRI(op.0.clone());
+    let ope: ObjectPropertyExpression<A> = op.clone().into();
+    v.push(DeclareObjectProperty(op.clone()).into());
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::Domain => annotated_list(section, ctx, v, |p| {
+                Ok(ObjectPropertyDomain::new(
+                    ope.clone(),
+                    ClassExpression::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::ObjectPropertyRange => annotated_list(section, ctx, v, |p| {
+                Ok(ObjectPropertyRange::new(
+                    ope.clone(),
+                    ClassExpression::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::Characteristics => annotated_list(section, ctx, v, |p| {
+                let ope = ope.clone();
+                Ok::<Component<A>, _>(match p.as_str() {
+                    "Functional" => FunctionalObjectProperty(ope).into(),
+                    "InverseFunctional" => InverseFunctionalObjectProperty(ope).into(),
+                    "Reflexive" => ReflexiveObjectProperty(ope).into(),
+                    "Irreflexive" => IrreflexiveObjectProperty(ope).into(),
+                    "Symmetric" => SymmetricObjectProperty(ope).into(),
+                    "Asymmetric" => AsymmetricObjectProperty(ope).into(),
+                    "Transitive" => TransitiveObjectProperty(ope).into(),
+                    c => unreachable!("unexpected characteristic: {}", c),
+                })
+            })?,
+            Rule::SubPropertyOf => annotated_list(section, ctx, v, |p| {
+                Ok(SubObjectPropertyOf {
+                    sub: ope.clone().into(),
+                    sup: ObjectPropertyExpression::from_pair(p, ctx)?,
+                })
+            })?,
+            Rule::PropertyEquivalentTo => annotated_list(section, ctx, v, |p| {
+                Ok(EquivalentObjectProperties(vec![
+                    ope.clone(),
+                    ObjectPropertyExpression::from_pair(p, ctx)?,
+                ]))
+            })?,
+            Rule::PropertyDisjointWith => annotated_list(section, ctx, v, |p| {
+                Ok(DisjointObjectProperties(vec![
+                    ope.clone(),
+                    ObjectPropertyExpression::from_pair(p, ctx)?,
+                ]))
+            })?,
+            Rule::InverseOf => {
+                annotated_list(
+                    section,
+                    ctx,
+                    v,
+                    |p| match ObjectPropertyExpression::from_pair(p.clone(), ctx)? {
+                        ObjectPropertyExpression::ObjectProperty(other) => {
+                            Ok(InverseObjectProperties(op.clone(), other))
+                        }
+                        ObjectPropertyExpression::InverseObjectProperty(_) => {
+                            Err(HornedError::invalid_at(
+                                "horned-owl does not support inverse properties in `InverseOf`",
+                                p.as_span(),
+                            ))
+                        }
+                    },
+                )?
+            }
+            Rule::SubPropertyChain => {
+                let mut inner = section.into_inner();
+                let anns = optional_annotations(&mut inner, ctx)?;
+                let chain = inner
+                    .next()
+                    .unwrap()
+                    .into_inner()
+                    .map(|p| ObjectPropertyExpression::from_pair(p, ctx))
+                    .collect::<Result<_>>()?;
+                v.push(AnnotatedComponent::new(
+                    SubObjectPropertyOf {
+                        sub: SubObjectPropertyExpression::ObjectPropertyChain(chain),
+                        sup: ope.clone(),
+                    },
+                    anns,
+                ));
+            }
+            rule => unreachable!("unexpected rule in object property frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn data_property_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let dp = DataProperty::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = AnnotationSubject::IRI(dp.0.clone());
+    v.push(DeclareDataProperty(dp.clone()).into());
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::Domain => annotated_list(section, ctx, v, |p| {
+                Ok(DataPropertyDomain::new(
+                    dp.clone(),
+                    ClassExpression::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::DataPropertyRange => annotated_list(section, ctx, v, |p| {
+                Ok(DataPropertyRange::new(
+                    dp.clone(),
+                    DataRange::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::Characteristics => annotated_list(section, ctx, v, |p| {
+                if p.as_str() == "Functional" {
+                    Ok(FunctionalDataProperty(dp.clone()))
+                } else {
+                    Err(HornedError::invalid_at(
+                        "data properties can only be functional",
+                        p.as_span(),
+                    ))
+                }
+            })?,
+            Rule::SubPropertyOf => annotated_list(section, ctx, v, |p| {
+                Ok(SubDataPropertyOf {
+                    sub: dp.clone(),
+                    sup: data_property(p, ctx)?,
+                })
+            })?,
+            Rule::PropertyEquivalentTo => annotated_list(section, ctx, v, |p| {
+                Ok(EquivalentDataProperties(vec![
+                    dp.clone(),
+                    data_property(p, ctx)?,
+                ]))
+            })?,
+            Rule::PropertyDisjointWith => annotated_list(section, ctx, v, |p| {
+                Ok(DisjointDataProperties(vec![
+                    dp.clone(),
+                    data_property(p, ctx)?,
+                ]))
+            })?,
+            rule => unreachable!("unexpected rule in data property frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn annotation_property_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let ap = AnnotationProperty::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = AnnotationSubject::IRI(ap.0.clone());
+    v.push(DeclareAnnotationProperty(ap.clone()).into());
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::AnnotationPropertyDomain => annotated_list(section, ctx, v, |p| {
+                Ok(AnnotationPropertyDomain::new(
+                    ap.clone(),
+                    IRI::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::AnnotationPropertyRange => annotated_list(section, ctx, v, |p| {
+                Ok(AnnotationPropertyRange::new(
+                    ap.clone(),
+                    IRI::from_pair(p, ctx)?,
+                ))
+            })?,
+            Rule::SubPropertyOf => annotated_list(section, ctx, v, |p| {
+                Ok(SubAnnotationPropertyOf {
+                    sub: ap.clone(),
+                    sup: AnnotationProperty(data_property(p, ctx)?.0),
+                })
+            })?,
+            rule => unreachable!("unexpected rule in annotation property frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn individual_frame<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+    v: &mut Vec<AnnotatedComponent<A>>,
+) -> Result<()> {
+    let mut inner = pair.into_inner();
+    let i = Individual::from_pair(inner.next().unwrap(), ctx)?;
+    let subject = match &i {
+        Individual::Named(ni) => {
+            v.push(DeclareNamedIndividual(ni.clone()).into());
+            AnnotationSubject::IRI(ni.0.clone())
+        }
+        Individual::Anonymous(ai) => AnnotationSubject::AnonymousIndividual(ai.clone()),
+    };
+
+    for section in inner {
+        match section.as_rule() {
+            Rule::FrameAnnotations => frame_annotations(section, &subject, ctx, v)?,
+            Rule::Types => annotated_list(section, ctx, v, |p| {
+                Ok(ClassAssertion::new(
+                    ClassExpression::from_pair(p, ctx)?,
+                    i.clone(),
+                ))
+            })?,
+            Rule::Facts => annotated_list(section, ctx, v, |p| fact(p, &i, ctx))?,
+            Rule::SameAs => annotated_list(section, ctx, v, |p| {
+                Ok(SameIndividual(vec![
+                    i.clone(),
+                    Individual::from_pair(p, ctx)?,
+                ]))
+            })?,
+            Rule::DifferentFrom => annotated_list(section, ctx, v, |p| {
+                Ok(DifferentIndividuals(vec![
+                    i.clone(),
+                    Individual::from_pair(p, ctx)?,
+                ]))
+            })?,
+            rule => unreachable!("unexpected rule in individual frame: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn fact<A: ForIRI>(
+    pair: Pair<Rule>,
+    from: &Individual<A>,
+    ctx: &Context<'_, A>,
+) -> Result<Component<A>> {
+    let mut inner = pair.into_inner();
+    let mut next = inner.next().unwrap();
+    let negative = next.as_rule() == Rule::Negation;
+    if negative {
+        next = inner.next().unwrap();
+    }
+
+    let iri = IRI::from_pair(next, ctx)?;
+    let from = from.clone();
+    let value = inner.next().unwrap();
+    Ok(match (value.as_rule(), negative) {
+        (Rule::Literal, false) => {
+            DataPropertyAssertion::new(DataProperty(iri), from, Literal::from_pair(value, ctx)?)
+                .into()
+        }
+        (Rule::Literal, true) => NegativeDataPropertyAssertion::new(
+            DataProperty(iri),
+            from,
+            Literal::from_pair(value, ctx)?,
+        )
+        .into(),
+        (_, false) => ObjectPropertyAssertion {
+            ope: ObjectProperty(iri).into(),
+            from,
+            to: Individual::from_pair(value, ctx)?,
+        }
+        .into(),
+        (_, true) => NegativeObjectPropertyAssertion::new(
+            ObjectProperty(iri).into(),
+            from,
+            Individual::from_pair(value, ctx)?,
+        )
+        .into(),
+    })
+}
+
+fn misc<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<AnnotatedComponent<A>> {
+    let rule = pair.as_rule();
+    let mut inner = pair.into_inner();
+    let anns = optional_annotations(&mut inner, ctx)?;
+
+    let component: Component<A> = match rule {
+        Rule::EquivalentClasses => EquivalentClasses(
+            inner
+                .map(|p| ClassExpression::from_pair(p, ctx))
+                .collect::<Result<_>>()?,
+        )
+        .into(),
+        Rule::DisjointClasses => DisjointClasses(
+            inner
+                .map(|p| ClassExpression::from_pair(p, ctx))
+                .collect::<Result<_>>()?,
+        )
+        .into(),
+        Rule::EquivalentProperties | Rule::DisjointProperties => {
+            let pes = inner
+                .map(|p| PropertyExpression::from_pair(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            let dps: Vec<_> = pes
+                .iter()
+                .filter_map(|pe| match pe {
+                    PropertyExpression::DataProperty(dp) => Some(dp.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            if dps.len() == pes.len() {
+                if rule == Rule::EquivalentProperties {
+                    EquivalentDataProperties(dps).into()
+                } else {
+                    DisjointDataProperties(dps).into()
+                }
+            } else {
+                let opes = pes
+                    .into_iter()
+                    .map(|pe| match pe {
+                        PropertyExpression::ObjectPropertyExpression(ope) => ope,
+                        PropertyExpression::DataProperty(dp) => ObjectProperty(dp.0).into(),
+                        PropertyExpression::AnnotationProperty(ap) => ObjectProperty(ap.0).into(),
+                    })
+                    .collect();
+                if rule == Rule::EquivalentProperties {
+                    EquivalentObjectProperties(opes).into()
+                } else {
+                    DisjointObjectProperties(opes).into()
+                }
+            }
+        }
+        Rule::SameIndividual => SameIndividual(
+            inner
+                .map(|p| Individual::from_pair(p, ctx))
+                .collect::<Result<_>>()?,
+        )
+        .into(),
+        Rule::DifferentIndividuals => DifferentIndividuals(
+            inner
+                .map(|p| Individual::from_pair(p, ctx))
+                .collect::<Result<_>>()?,
+        )
+        .into(),
+        rule => unreachable!("unexpected rule in Misc::from_pair: {:?}", rule),
+    };
+
+    Ok(AnnotatedComponent::new(component, anns))
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Annotation<A> {
+    const RULE: Rule = Rule::Annotation;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let mut inner = pair.into_inner();
+        Ok(Annotation {
+            ap: FromPair::from_pair(inner.next().unwrap(), ctx)?,
+            av: FromPair::from_pair(inner.next().unwrap(), ctx)?,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for BTreeSet<Annotation<A>> {
+    const RULE: Rule = Rule::Annotations;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        // Annotations on annotations are dropped, as for the
+        // functional syntax
+        pair.into_inner()
+            .next()
+            .unwrap()
+            .into_inner()
+            .map(|pair| {
+                let (_, ann) = annotated(pair, ctx)?;
+                Annotation::from_pair(ann, ctx)
+            })
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for AnnotationValue<A> {
+    const RULE: Rule = Rule::AnnotationValue;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::IRI => IRI::from_pair(inner, ctx).map(AnnotationValue::IRI),
+            Rule::Literal => Literal::from_pair(inner, ctx).map(AnnotationValue::Literal),
+            Rule::AnonymousIndividual => {
+                AnonymousIndividual::from_pair(inner, ctx).map(AnnotationValue::AnonymousIndividual)
+            }
+            rule => unreachable!("unexpected rule in AnnotationValue::from_pair: {:?}", rule),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for AnonymousIndividual<A> {
+    const RULE: Rule = Rule::AnonymousIndividual;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        let iri = ctx.build.iri(inner.as_str());
+        Ok(AnonymousIndividual(iri.underlying()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Individual<A> {
+    const RULE: Rule = Rule::Individual;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::NamedIndividual => NamedIndividual::from_pair(inner, ctx).map(Individual::Named),
+            Rule::AnonymousIndividual => {
+                AnonymousIndividual::from_pair(inner, ctx).map(Individual::Anonymous)
+            }
+            rule => unreachable!("unexpected rule in Individual::from_pair: {:?}", rule),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for IRI<A> {
+    const RULE: Rule = Rule::IRI;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        let span = inner.as_span();
+        let curie = match inner.as_rule() {
+            Rule::AbbreviatedIRI => {
+                let mut pname = inner.into_inner().next().unwrap().into_inner();
+                let prefix = pname.next().unwrap().into_inner().next();
+                let local = pname.next().unwrap();
+                Curie::new(
+                    Some(prefix.map(|p| p.as_str()).unwrap_or_default()),
+                    local.as_str(),
+                )
+            }
+            Rule::SimpleIRI => Curie::new(Some(""), inner.as_str()),
+            Rule::FullIRI => {
+                let iri = inner.into_inner().next().unwrap();
+                return Ok(ctx.build.iri(iri.as_str()));
+            }
+            rule => unreachable!("unexpected rule in IRI::from_pair: {:?}", rule),
+        };
+
+        match ctx.mapping.expand_curie(&curie) {
+            Ok(s) => Ok(ctx.build.iri(s)),
+            Err(curie::ExpansionError::Invalid) => {
+                Err(HornedError::invalid_at("undefined prefix", span))
+            }
+            Err(curie::ExpansionError::MissingDefault) => {
+                Err(HornedError::invalid_at("missing default prefix", span))
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Datatype<A> {
+    const RULE: Rule = Rule::Datatype;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::DatatypeKeyword => Ok(Datatype(xsd(ctx, inner.as_str()))),
+            Rule::IRI => IRI::from_pair(inner, ctx).map(Datatype),
+            rule => unreachable!("unexpected rule in Datatype::from_pair: {:?}", rule),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Literal<A> {
+    const RULE: Rule = Rule::Literal;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let pair = pair.into_inner().next().unwrap();
+        let typed = |literal: &str, local: &str| Literal::Datatype {
+            literal: literal.to_string(),
+            datatype_iri: xsd(ctx, local),
+        };
+        match pair.as_rule() {
+            Rule::TypedLiteral => {
+                let mut inner = pair.into_inner();
+                let literal = String::from_pair(inner.next().unwrap(), ctx)?;
+                let dty = Datatype::from_pair(inner.next().unwrap(), ctx)?;
+                Ok(Literal::Datatype {
+                    literal,
+                    datatype_iri: dty.0,
+                })
+            }
+            Rule::StringLiteralWithLanguage => {
+                let mut inner = pair.into_inner();
+                let literal = String::from_pair(inner.next().unwrap(), ctx)?;
+                let lang = inner.next().unwrap().as_str()[1..].trim().to_string();
+                Ok(Literal::Language { literal, lang })
+            }
+            Rule::StringLiteralNoLanguage => {
+                let mut inner = pair.into_inner();
+                let literal = String::from_pair(inner.next().unwrap(), ctx)?;
+                Ok(Literal::Simple { literal })
+            }
+            Rule::IntegerLiteral => Ok(typed(pair.as_str(), "integer")),
+            Rule::DecimalLiteral => Ok(typed(pair.as_str(), "decimal")),
+            Rule::FloatingPointLiteral => {
+                let s = pair.as_str();
+                Ok(typed(&s[..s.len() - 1], "float"))
+            }
+            rule => unreachable!("unexpected rule in Literal::from_pair: {:?}", rule),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for String {
+    const RULE: Rule = Rule::QuotedString;
+    fn from_pair_unchecked(pair: Pair<Rule>, _ctx: &Context<'_, A>) -> Result<Self> {
+        let l = pair.as_str().len();
+        let s = &pair.as_str()[1..l - 1];
+        if s.contains(r"\\") || s.contains(r#"\""#) {
+            Ok(s.replace(r"\\", r"\").replace(r#"\""#, r#"""#))
+        } else {
+            Ok(s.to_string())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for u32 {
+    const RULE: Rule = Rule::NonNegativeInteger;
+    fn from_pair_unchecked(pair: Pair<Rule>, _ctx: &Context<'_, A>) -> Result<Self> {
+        Self::from_str(pair.as_str())
+            .map_err(|_| HornedError::invalid_at("cardinality is too large", pair.as_span()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for ObjectPropertyExpression<A> {
+    const RULE: Rule = Rule::PropertyExpression;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let inner = pair.into_inner().next().unwrap();
+        match inner.as_rule() {
+            Rule::IRI => IRI::from_pair(inner, ctx)
+                .map(ObjectProperty)
+                .map(ObjectPropertyExpression::ObjectProperty),
+            Rule::InverseObjectProperty => {
+                ObjectProperty::from_pair(inner.into_inner().next().unwrap(), ctx)
+                    .map(ObjectPropertyExpression::InverseObjectProperty)
+            }
+            rule => unreachable!(
+                "unexpected rule in ObjectPropertyExpression::from_pair: {:?}",
+                rule
+            ),
+        }
+    }
+}
+
+/// Read a `PropertyExpression` pair which must be a named property.
+fn data_property<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<DataProperty<A>> {
+    let inner = pair.into_inner().next().unwrap();
+    match inner.as_rule() {
+        Rule::IRI => IRI::from_pair(inner, ctx).map(DataProperty),
+        _ => Err(HornedError::invalid_at(
+            "only object properties can be inverted",
+            inner.as_span(),
+        )),
+    }
+}
+
+impl<A: ForIRI> FromPair<A> for PropertyExpression<A> {
+    const RULE: Rule = Rule::PropertyExpression;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let ope = ObjectPropertyExpression::from_pair_unchecked(pair, ctx)?;
+        match ope {
+            ObjectPropertyExpression::ObjectProperty(op) if ctx.is_data_property(&op.0) => {
+                Ok(PropertyExpression::DataProperty(DataProperty(op.0)))
+            }
+            ope => Ok(PropertyExpression::ObjectPropertyExpression(ope)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for ClassExpression<A> {
+    const RULE: Rule = Rule::Description;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        class_expression(pair, ctx)
+    }
+}
+
+/// Read any of the rules which make up a description.
+fn class_expression<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+) -> Result<ClassExpression<A>> {
+    match pair.as_rule() {
+        Rule::Description => {
+            let mut ces = pair
+                .into_inner()
+                .map(|p| class_expression(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if ces.len() == 1 {
+                Ok(ces.pop().unwrap())
+            } else {
+                Ok(ClassExpression::ObjectUnionOf(ces))
+            }
+        }
+        Rule::Conjunction => {
+            let mut ces = pair
+                .into_inner()
+                .map(|p| class_expression(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if ces.len() == 1 {
+                Ok(ces.pop().unwrap())
+            } else {
+                Ok(ClassExpression::ObjectIntersectionOf(ces))
+            }
+        }
+        Rule::Primary | Rule::Atomic | Rule::NestedDescription => {
+            class_expression(pair.into_inner().next().unwrap(), ctx)
+        }
+        Rule::Complement => class_expression(pair.into_inner().next().unwrap(), ctx)
+            .map(Box::new)
+            .map(ClassExpression::ObjectComplementOf),
+        Rule::ObjectOneOf => pair
+            .into_inner()
+            .map(|p| Individual::from_pair(p, ctx))
+            .collect::<Result<_>>()
+            .map(ClassExpression::ObjectOneOf),
+        Rule::ClassIRI => IRI::from_pair(pair.into_inner().next().unwrap(), ctx)
+            .map(Class)
+            .map(ClassExpression::Class),
+        Rule::Restriction => restriction(pair.into_inner().next().unwrap(), ctx),
+        rule => unreachable!("unexpected rule in ClassExpression::from_pair: {:?}", rule),
+    }
+}
+
+/// Is the restriction on the property `pair` with the given filler a
+/// data property restriction?
+fn is_data_restriction<A: ForIRI>(
+    pair: &Pair<Rule>,
+    filler: Option<&Pair<Rule>>,
+    ctx: &Context<'_, A>,
+) -> Result<bool> {
+    let inner = pair.clone().into_inner().next().unwrap();
+    if inner.as_rule() != Rule::IRI {
+        return Ok(false);
+    }
+
+    let iri = IRI::from_pair(inner, ctx)?;
+    Ok(ctx.is_data_property(&iri)
+        || filler
+            .map(|f| f.clone().into_inner().next().unwrap().as_rule() == Rule::DataPrimary)
+            .unwrap_or(false))
+}
+
+fn class_filler<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<ClassExpression<A>> {
+    let inner = pair.into_inner().next().unwrap();
+    match inner.as_rule() {
+        Rule::Primary => class_expression(inner, ctx),
+        _ => Err(HornedError::invalid_at(
+            "expected a class expression",
+            inner.as_span(),
+        )),
+    }
+}
+
+fn data_filler<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<DataRange<A>> {
+    let inner = pair.into_inner().next().unwrap();
+    match inner.as_rule() {
+        Rule::DataPrimary => data_range(inner, ctx),
+        _ => description_data_range(inner, ctx),
+    }
+}
+
+fn restriction<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<ClassExpression<A>> {
+    let rule = pair.as_rule();
+    let mut pairs = pair.into_inner();
+    let prop = pairs.next().unwrap();
+
+    match rule {
+        Rule::SomeRestriction | Rule::OnlyRestriction => {
+            let filler = pairs.next().unwrap();
+            if is_data_restriction(&prop, Some(&filler), ctx)? {
+                let dp = data_property(prop, ctx)?;
+                let dr = data_filler(filler, ctx)?;
+                Ok(if rule == Rule::SomeRestriction {
+                    ClassExpression::DataSomeValuesFrom { dp, dr }
+                } else {
+                    ClassExpression::DataAllValuesFrom { dp, dr }
+                })
+            } else {
+                let ope = ObjectPropertyExpression::from_pair(prop, ctx)?;
+                let bce = Box::new(class_filler(filler, ctx)?);
+                Ok(if rule == Rule::SomeRestriction {
+                    ClassExpression::ObjectSomeValuesFrom { ope, bce }
+                } else {
+                    ClassExpression::ObjectAllValuesFrom { ope, bce }
+                })
+            }
+        }
+        Rule::ValueRestriction => {
+            let value = pairs.next().unwrap();
+            if value.as_rule() == Rule::Literal {
+                Ok(ClassExpression::DataHasValue {
+                    dp: data_property(prop, ctx)?,
+                    l: Literal::from_pair(value, ctx)?,
+                })
+            } else {
+                Ok(ClassExpression::ObjectHasValue {
+                    ope: ObjectPropertyExpression::from_pair(prop, ctx)?,
+                    i: Individual::from_pair(value, ctx)?,
+                })
+            }
+        }
+        Rule::SelfRestriction => Ok(ClassExpression::ObjectHasSelf(
+            ObjectPropertyExpression::from_pair(prop, ctx)?,
+        )),
+        Rule::MinRestriction | Rule::MaxRestriction | Rule::ExactlyRestriction => {
+            let n = u32::from_pair(pairs.next().unwrap(), ctx)?;
+            let filler = pairs.next();
+            if is_data_restriction(&prop, filler.as_ref(), ctx)? {
+                let dp = data_property(prop, ctx)?;
+                let dr = match filler {
+                    Some(filler) => data_filler(filler, ctx)?,
+                    // No data range is equivalent to `rdfs:Literal` as a data range.
+                    None => Datatype(ctx.build.iri(OWL2Datatype::Literal)).into(),
+                };
+                Ok(match rule {
+                    Rule::MinRestriction => ClassExpression::DataMinCardinality { n, dp, dr },
+                    Rule::MaxRestriction => ClassExpression::DataMaxCardinality { n, dp, dr },
+                    _ => ClassExpression::DataExactCardinality { n, dp, dr },
+                })
+            } else {
+                let ope = ObjectPropertyExpression::from_pair(prop, ctx)?;
+                let bce = Box::new(match filler {
+                    Some(filler) => class_filler(filler, ctx)?,
+                    // Missing class expression is equivalent to `owl:Thing` as class expression.
+                    None => ClassExpression::Class(Class(ctx.build.iri(OWL::Thing))),
+                });
+                Ok(match rule {
+                    Rule::MinRestriction => ClassExpression::ObjectMinCardinality { n, ope, bce },
+                    Rule::MaxRestriction => ClassExpression::ObjectMaxCardinality { n, ope, bce },
+                    _ => ClassExpression::ObjectExactCardinality { n, ope, bce },
+                })
+            }
+        }
+        rule => unreachable!("unexpected rule in restriction: {:?}", rule),
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for DataRange<A> {
+    const RULE: Rule = Rule::DataRange;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        data_range(pair, ctx)
+    }
+}
+
+/// Read any of the rules which make up a data range.
+fn data_range<A: ForIRI>(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<DataRange<A>> {
+    match pair.as_rule() {
+        Rule::DataRange => {
+            let mut drs = pair
+                .into_inner()
+                .map(|p| data_range(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if drs.len() == 1 {
+                Ok(drs.pop().unwrap())
+            } else {
+                Ok(DataRange::DataUnionOf(drs))
+            }
+        }
+        Rule::DataConjunction => {
+            let mut drs = pair
+                .into_inner()
+                .map(|p| data_range(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if drs.len() == 1 {
+                Ok(drs.pop().unwrap())
+            } else {
+                Ok(DataRange::DataIntersectionOf(drs))
+            }
+        }
+        Rule::DataPrimary | Rule::DataAtomic | Rule::NestedDataRange => {
+            data_range(pair.into_inner().next().unwrap(), ctx)
+        }
+        Rule::DataComplement => data_range(pair.into_inner().next().unwrap(), ctx)
+            .map(Box::new)
+            .map(DataRange::DataComplementOf),
+        Rule::DataOneOf => pair
+            .into_inner()
+            .map(|p| Literal::from_pair(p, ctx))
+            .collect::<Result<_>>()
+            .map(DataRange::DataOneOf),
+        Rule::DatatypeRestriction => {
+            let mut pairs = pair.into_inner();
+            Ok(DataRange::DatatypeRestriction(
+                Datatype::from_pair(pairs.next().unwrap(), ctx)?,
+                pairs
+                    .map(|pair| FacetRestriction::from_pair(pair, ctx))
+                    .collect::<Result<_>>()?,
+            ))
+        }
+        Rule::Datatype => Datatype::from_pair(pair, ctx).map(DataRange::Datatype),
+        rule => unreachable!("unexpected rule in DataRange::from_pair: {:?}", rule),
+    }
+}
+
+/// Read a description which has been used as the filler of a data
+/// property restriction as a data range.
+fn description_data_range<A: ForIRI>(
+    pair: Pair<Rule>,
+    ctx: &Context<'_, A>,
+) -> Result<DataRange<A>> {
+    match pair.as_rule() {
+        Rule::Description => {
+            let mut drs = pair
+                .into_inner()
+                .map(|p| description_data_range(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if drs.len() == 1 {
+                Ok(drs.pop().unwrap())
+            } else {
+                Ok(DataRange::DataUnionOf(drs))
+            }
+        }
+        Rule::Conjunction => {
+            let mut drs = pair
+                .into_inner()
+                .map(|p| description_data_range(p, ctx))
+                .collect::<Result<Vec<_>>>()?;
+            if drs.len() == 1 {
+                Ok(drs.pop().unwrap())
+            } else {
+                Ok(DataRange::DataIntersectionOf(drs))
+            }
+        }
+        Rule::Primary | Rule::Atomic | Rule::NestedDescription => {
+            description_data_range(pair.into_inner().next().unwrap(), ctx)
+        }
+        Rule::Complement => description_data_range(pair.into_inner().next().unwrap(), ctx)
+            .map(Box::new)
+            .map(DataRange::DataComplementOf),
+        Rule::ClassIRI => {
+            let iri = pair.into_inner().next().unwrap();
+            match datatype_keyword(&iri) {
+                Some(k) => Ok(Datatype(xsd(ctx, k)).into()),
+                None => IRI::from_pair(iri, ctx)
+                    .map(Datatype)
+                    .map(DataRange::Datatype),
+            }
+        }
+        _ => Err(HornedError::invalid_at(
+            "expected a data range",
+            pair.as_span(),
+        )),
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for Facet {
+    const RULE: Rule = Rule::Facet;
+    fn from_pair_unchecked(pair: Pair<Rule>, _ctx: &Context<'_, A>) -> Result<Self> {
+        Ok(match pair.as_str() {
+            "length" => Facet::Length,
+            "minLength" => Facet::MinLength,
+            "maxLength" => Facet::MaxLength,
+            "pattern" => Facet::Pattern,
+            "langRange" => Facet::LangRange,
+            "totalDigits" => Facet::TotalDigits,
+            "fractionDigits" => Facet::FractionDigits,
+            "<=" => Facet::MaxInclusive,
+            "<" => Facet::MaxExclusive,
+            ">=" => Facet::MinInclusive,
+            ">" => Facet::MinExclusive,
+            f => unreachable!("unexpected facet: {}", f),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for FacetRestriction<A> {
+    const RULE: Rule = Rule::FacetRestriction;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let mut inner = pair.into_inner();
+        let f = Facet::from_pair(inner.next().unwrap(), ctx)?;
+        let l = Literal::from_pair(inner.next().unwrap(), ctx)?;
+        Ok(FacetRestriction { f, l })
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for OntologyAnnotation<A> {
+    const RULE: Rule = Rule::Annotation;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        Annotation::from_pair(pair, ctx).map(OntologyAnnotation)
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+pub(crate) struct MutableOntologyWrapper<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default>(
+    pub(crate) O,
+    PhantomData<A>,
+);
+
+impl<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default> FromPair<A>
+    for MutableOntologyWrapper<A, O>
+{
+    const RULE: Rule = Rule::Ontology;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let mut pairs = pair.into_inner();
+        let mut pair = pairs.next().unwrap();
+
+        let mut ontology: O = Default::default();
+        let mut ontology_id = OntologyID::default();
+
+        // Parse ontology IRI and Version IRI if any
+        if pair.as_rule() == Rule::OntologyIRI {
+            let inner = pair.into_inner().next().unwrap();
+            ontology_id.iri = Some(IRI::from_pair(inner, ctx)?);
+            pair = pairs.next().unwrap();
+            if pair.as_rule() == Rule::VersionIRI {
+                let inner = pair.into_inner().next().unwrap();
+                ontology_id.viri = Some(IRI::from_pair(inner, ctx)?);
+                pair = pairs.next().unwrap();
+            }
+        }
+        ontology.insert(ontology_id);
+
+        // Process imports
+        for p in pair.into_inner() {
+            ontology.insert(Import::from_pair(p, ctx)?);
+        }
+
+        // Process ontology annotations
+        for pair in pairs.next().unwrap().into_inner() {
+            for ann in BTreeSet::<Annotation<A>>::from_pair(pair, ctx)? {
+                ontology.insert(OntologyAnnotation(ann));
+            }
+        }
+
+        // Process frames
+        for pair in pairs.next().unwrap().into_inner() {
+            for ac in Vec::<AnnotatedComponent<A>>::from_pair(pair, ctx)? {
+                ontology.insert(ac);
+            }
+        }
+
+        Ok(MutableOntologyWrapper(ontology, Default::default()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+impl<A: ForIRI> FromPair<A> for PrefixMapping {
+    const RULE: Rule = Rule::PrefixDeclarations;
+    fn from_pair_unchecked(pair: Pair<Rule>, _ctx: &Context<'_, A>) -> Result<Self> {
+        // Manchester Syntax predefines these prefixes, although the
+        // document may redeclare them
+        let mut prefixes = Self::default();
+        for (prefix, ns) in [
+            ("rdf", Namespace::RDF),
+            ("rdfs", Namespace::RDFS),
+            ("xsd", Namespace::XSD),
+            ("owl", Namespace::OWL),
+        ] {
+            prefixes
+                .add_prefix(prefix, ns.as_ref())
+                .expect("prefix should be valid");
+        }
+        for inner in pair.into_inner() {
+            let mut decl = inner.into_inner();
+            let mut pname = decl.next().unwrap().into_inner();
+            let iri = decl.next().unwrap().into_inner().next().unwrap();
+            if let Some(prefix) = pname.next().unwrap().into_inner().next() {
+                prefixes
+                    .add_prefix(prefix.as_str(), iri.as_str())
+                    .expect("grammar does not allow invalid prefixes");
+            } else {
+                prefixes
+                    .add_prefix("", iri.as_str())
+                    .expect("empty prefix shouldn't fail")
+            }
+        }
+        Ok(prefixes)
+    }
+}
+
+// ---------------------------------------------------------------------------
+
+/// Find the data properties which have a frame in the ontology.
+fn data_properties<A: ForIRI>(pair: &Pair<Rule>, ctx: &Context<'_, A>) -> Result<Vec<IRI<A>>> {
+    let frames = pair
+        .clone()
+        .into_inner()
+        .find(|p| p.as_rule() == Rule::Frames)
+        .unwrap();
+
+    frames
+        .into_inner()
+        .map(|frame| frame.into_inner().next().unwrap())
+        .filter(|frame| frame.as_rule() == Rule::DataPropertyFrame)
+        .map(|frame| {
+            DataProperty::from_pair(frame.into_inner().next().unwrap(), ctx).map(|dp| dp.0)
+        })
+        .collect()
+}
+
+impl<A, O> FromPair<A> for (MutableOntologyWrapper<A, O>, PrefixMapping)
+where
+    A: ForIRI,
+    O: Default + MutableOntology<A> + Ontology<A>,
+{
+    const RULE: Rule = Rule::OntologyDocument;
+    fn from_pair_unchecked(pair: Pair<Rule>, ctx: &Context<'_, A>) -> Result<Self> {
+        let mut pairs = pair.into_inner();
+        let prefixes = PrefixMapping::from_pair(pairs.next().unwrap(), ctx)?;
+        let ontology = pairs.next().unwrap();
+
+        let mut context = Context::new(ctx.build, &prefixes);
+        context.data_properties = data_properties(&ontology, &context)?.into_iter().collect();
+
+        MutableOntologyWrapper::from_pair(ontology, &context).map(|ont| (ont, prefixes))
+    }
+}
@@ -0,0 +1,205 @@
+//! Read an ontology in Manchester Syntax.
+//!
+//! Each frame declares its subject entity, as well as providing the
+//! axioms about it. As the syntax does not distinguish between object
+//! and data property restrictions, a restriction is read as a data
+//! property restriction when its property has a `DataProperty:` frame
+//! in the document, or its filler can only be a data range.
+//!
+//! SWRL rules are not supported.
+use std::collections::HashSet;
+use std::io::BufRead;
+
+use curie::PrefixMapping;
+
+use crate::error::HornedError;
+use crate::io::ParserConfiguration;
+use crate::model::Build;
+use crate::model::ForIRI;
+use crate::model::MutableOntology;
+use crate::model::Ontology;
+use crate::model::IRI;
+
+mod from_pair;
+mod lexer;
+
+use self::from_pair::FromPair;
+use self::from_pair::MutableOntologyWrapper;
+use self::lexer::OwlManchesterLexer;
+use self::lexer::Rule;
+
+struct Context<'a, A: ForIRI> {
+    build: &'a Build<A>,
+    mapping: &'a PrefixMapping,
+    data_properties: HashSet<IRI<A>>,
+}
+
+impl<'a, A: ForIRI> Context<'a, A> {
+    fn new(build: &'a Build<A>, mapping: &'a PrefixMapping) -> Self {
+        Self {
+            build,
+            mapping,
+            data_properties: HashSet::new(),
+        }
+    }
+
+    fn is_data_property(&self, iri: &IRI<A>) -> bool {
+        self.data_properties.contains(iri)
+    }
+}
+
+pub fn read<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    bufread: R,
+    _config: ParserConfiguration,
+) -> Result<(O, PrefixMapping), HornedError> {
+    let b = Build::new();
+    read_with_build(bufread, &b)
+}
+
+pub fn read_with_build<A: ForIRI, O: MutableOntology<A> + Ontology<A> + Default, R: BufRead>(
+    mut bufread: R,
+    build: &Build<A>,
+) -> Result<(O, PrefixMapping), HornedError> {
+    let prefixes = PrefixMapping::default();
+    let ctx = Context::new(build, &prefixes);
+
+    let mut doc = String::new();
+    bufread.read_to_string(&mut doc)?;
+    let pair = OwlManchesterLexer::lex(Rule::OntologyDocument, doc.trim())?
+        .next()
+        .unwrap();
+
+    let wrapper: Result<(MutableOntologyWrapper<A, O>, PrefixMapping), HornedError> =
+        FromPair::from_pair(pair, &ctx);
+    wrapper.map(|r| (r.0 .0, r.1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::*;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    #[test_resources("src/ont/owl-manchester/*.omn")]
+    fn compare_to_ofn(resource: &str) {
+        let ofn_resource = resource
+            .replace("owl-manchester", "owl-functional")
+            .replace(".omn", ".ofn");
+
+        let (omnont, _): (SetOntology<RcStr>, _) = read(
+            &mut slurp::read_all_to_string(resource).unwrap().as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        let (ofnont, _): (SetOntology<RcStr>, _) = crate::io::ofn::reader::read(
+            &mut slurp::read_all_to_string(ofn_resource).unwrap().as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+
+        assert_eq!(omnont, ofnont);
+    }
+
+    #[test]
+    fn frame_declares_entity() {
+        let omn = "Prefix: : <http://example.com/>
+Ontology: <http://example.com/o>
+Class: A
+    SubClassOf: B
+";
+        let (ont, _): (SetOntology<RcStr>, _) =
+            read(&mut omn.as_bytes(), Default::default()).unwrap();
+        let b = Build::new_rc();
+
+        assert!(ont.i().contains(&AnnotatedComponent::from(DeclareClass(
+            b.class("http://example.com/A")
+        ))));
+        assert!(ont.i().contains(&AnnotatedComponent::from(SubClassOf::new(
+            b.class("http://example.com/B").into(),
+            b.class("http://example.com/A").into(),
+        ))));
+        // B has no frame, so is not declared
+        assert!(!ont.i().contains(&AnnotatedComponent::from(DeclareClass(
+            b.class("http://example.com/B")
+        ))));
+    }
+
+    #[test]
+    fn data_property_restriction() {
+        let omn = "Prefix: : <http://example.com/>
+Ontology:
+DataProperty: d
+Class: A
+    SubClassOf: d some integer, p some B, d min 1
+";
+        let (ont, _): (SetOntology<RcStr>, _) =
+            read(&mut omn.as_bytes(), Default::default()).unwrap();
+        let b = Build::new_rc();
+        let a: ClassExpression<_> = b.class("http://example.com/A").into();
+
+        assert!(ont.i().contains(&AnnotatedComponent::from(SubClassOf::new(
+            ClassExpression::DataSomeValuesFrom {
+                dp: b.data_property("http://example.com/d"),
+                dr: b
+                    .datatype("http://www.w3.org/2001/XMLSchema#integer")
+                    .into(),
+            },
+            a.clone(),
+        ))));
+        assert!(ont.i().contains(&AnnotatedComponent::from(SubClassOf::new(
+            ClassExpression::ObjectSomeValuesFrom {
+                ope: b.object_property("http://example.com/p").into(),
+                bce: Box::new(b.class("http://example.com/B").into()),
+            },
+            a.clone(),
+        ))));
+        assert!(ont.i().contains(&AnnotatedComponent::from(SubClassOf::new(
+            ClassExpression::DataMinCardinality {
+                n: 1,
+                dp: b.data_property("http://example.com/d"),
+                dr: b
+                    .datatype("http://www.w3.org/2000/01/rdf-schema#Literal")
+                    .into(),
+            },
+            a,
+        ))));
+    }
+
+    #[test]
+    fn predefined_prefixes() {
+        let omn = "Prefix: : <http://example.com/>
+Ontology:
+DataProperty: d
+    Range: xsd:integer
+Class: A
+    SubClassOf: owl:Thing
+";
+        let (ont, _): (SetOntology<RcStr>, _) =
+            read(&mut omn.as_bytes(), Default::default()).unwrap();
+        let b = Build::new_rc();
+
+        assert!(ont
+            .i()
+            .contains(&AnnotatedComponent::from(DataPropertyRange {
+                dp: b.data_property("http://example.com/d"),
+                dr: b
+                    .datatype("http://www.w3.org/2001/XMLSchema#integer")
+                    .into(),
+            })));
+        assert!(ont.i().contains(&AnnotatedComponent::from(SubClassOf::new(
+            b.class("http://www.w3.org/2002/07/owl#Thing").into(),
+            b.class("http://example.com/A").into(),
+        ))));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let r: Result<(SetOntology<RcStr>, _), _> =
+            read(&mut "Ontology: Class:".as_bytes(), Default::default());
+        assert!(matches!(r, Err(HornedError::ParserError(_, _))));
+    }
+}
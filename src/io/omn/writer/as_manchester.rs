@@ -0,0 +1,512 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+use curie::PrefixMapping;
+
+use crate::model::*;
+use crate::vocab::Facet;
+
+/// Write a string literal while escaping `"` and `\` characters.
+fn quote(mut s: &str, f: &mut Formatter<'_>) -> Result<(), Error> {
+    f.write_str("\"")?;
+    while let Some((i, c)) = s.char_indices().find(|(_, c)| *c == '\\' || *c == '"') {
+        f.write_str(&s[..i])?;
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '"' => f.write_str("\\\"")?,
+            _ => unreachable!(),
+        }
+        s = &s[i + 1..];
+    }
+    f.write_str(s)?;
+    f.write_str("\"")
+}
+
+/// A trait for OWL elements that can be rendered in OWL Manchester syntax.
+///
+/// Only the elements which appear inside a frame can be rendered on
+/// their own; frames themselves are built by
+/// [`write`](crate::io::omn::writer::write).
+pub trait AsManchester<A: ForIRI> {
+    /// Get a handle for displaying the element in Manchester syntax.
+    fn as_manchester(&self) -> Manchester<'_, Self, A> {
+        Manchester(self, None, PhantomData)
+    }
+
+    /// Get a handle for displaying the element, using the given
+    /// `PrefixMapping` to abbreviate IRIs when possible.
+    fn as_manchester_with_prefixes<'t>(
+        &'t self,
+        prefix: &'t PrefixMapping,
+    ) -> Manchester<'t, Self, A> {
+        Manchester(self, Some(prefix), PhantomData)
+    }
+}
+
+/// A wrapper for displaying an OWL2 element in Manchester syntax.
+#[derive(Debug)]
+pub struct Manchester<'t, T: ?Sized, A: ForIRI>(
+    /// The element to display
+    &'t T,
+    /// An eventual context to use (for IRI prefixes)
+    Option<&'t PrefixMapping>,
+    PhantomData<A>,
+);
+
+impl<'t, T: ?Sized, A: ForIRI> Manchester<'t, T, A> {
+    fn with<U: ?Sized>(&self, u: &'t U) -> Manchester<'t, U, A> {
+        Manchester(u, self.1, PhantomData)
+    }
+}
+
+/// Write `items` separated by `sep`.
+fn join<'t, T, A: ForIRI>(
+    f: &mut Formatter<'_>,
+    items: impl IntoIterator<Item = Manchester<'t, T, A>>,
+    sep: &str,
+) -> Result<(), Error>
+where
+    T: 't,
+    Manchester<'t, T, A>: Display,
+{
+    for (i, x) in items.into_iter().enumerate() {
+        if i != 0 {
+            f.write_str(sep)?;
+        }
+        x.fmt(f)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, IRI<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        if let Some(prefixes) = self.1.as_ref() {
+            match prefixes.shrink_iri(self.0) {
+                Err(_) => write!(f, "<{}>", self.0),
+                Ok(curie) => write!(f, "{}", curie),
+            }
+        } else {
+            write!(f, "<{}>", self.0)
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for IRI<A> {}
+
+// ---------------------------------------------------------------------------
+
+macro_rules! derive_wrapper {
+    ($A:ident, $ty:ty) => {
+        impl<'a, $A: ForIRI> Display for Manchester<'a, $ty, $A> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+                self.with(&self.0 .0).fmt(f)
+            }
+        }
+
+        impl<$A: ForIRI> AsManchester<$A> for $ty {}
+    };
+}
+
+derive_wrapper!(A, AnnotationProperty<A>);
+derive_wrapper!(A, Class<A>);
+derive_wrapper!(A, DataProperty<A>);
+derive_wrapper!(A, Datatype<A>);
+derive_wrapper!(A, NamedIndividual<A>);
+derive_wrapper!(A, ObjectProperty<A>);
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, AnonymousIndividual<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.0 .0.borrow())
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for AnonymousIndividual<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, Individual<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0 {
+            Individual::Named(i) => self.with(i).fmt(f),
+            Individual::Anonymous(i) => self.with(i).fmt(f),
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for Individual<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, Literal<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0 {
+            Literal::Simple { literal } => quote(literal, f),
+            Literal::Language { literal, lang } => {
+                quote(literal, f)?;
+                write!(f, "@{}", lang)
+            }
+            Literal::Datatype {
+                literal,
+                datatype_iri,
+            } => {
+                // Integers have a shorthand which is easier to read
+                let digits = literal.strip_prefix(&['+', '-'][..]).unwrap_or(literal);
+                let local = datatype_iri
+                    .as_ref()
+                    .strip_prefix(crate::vocab::Namespace::XSD.as_ref());
+                if local == Some("integer")
+                    && !digits.is_empty()
+                    && digits.chars().all(|c| c.is_ascii_digit())
+                {
+                    f.write_str(literal)
+                } else {
+                    quote(literal, f)?;
+                    write!(f, "^^{}", self.with(datatype_iri))
+                }
+            }
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for Literal<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, ObjectPropertyExpression<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0 {
+            ObjectPropertyExpression::ObjectProperty(op) => self.with(op).fmt(f),
+            ObjectPropertyExpression::InverseObjectProperty(op) => {
+                write!(f, "inverse ({})", self.with(op))
+            }
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for ObjectPropertyExpression<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, PropertyExpression<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0 {
+            PropertyExpression::ObjectPropertyExpression(ope) => self.with(ope).fmt(f),
+            PropertyExpression::DataProperty(dp) => self.with(dp).fmt(f),
+            PropertyExpression::AnnotationProperty(ap) => self.with(ap).fmt(f),
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for PropertyExpression<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, AnnotationValue<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self.0 {
+            AnnotationValue::Literal(lit) => self.with(lit).fmt(f),
+            AnnotationValue::IRI(iri) => self.with(iri).fmt(f),
+            AnnotationValue::AnonymousIndividual(ai) => self.with(ai).fmt(f),
+        }
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for AnnotationValue<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, Annotation<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{} {}", self.with(&self.0.ap), self.with(&self.0.av))
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for Annotation<A> {}
+
+// ---------------------------------------------------------------------------
+
+/// The precedence of the position an expression is written in;
+/// expressions binding more loosely than their position are
+/// parenthesized.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Precedence {
+    /// A top level expression, which may be a union.
+    Description,
+    /// An operand of `or`.
+    Conjunction,
+    /// An operand of `and`, or the filler of a restriction.
+    Primary,
+    /// The operand of `not`.
+    Atomic,
+}
+
+fn class_expression<A: ForIRI>(
+    m: &Manchester<'_, ClassExpression<A>, A>,
+    prec: Precedence,
+    f: &mut Formatter<'_>,
+) -> Result<(), Error> {
+    use ClassExpression::*;
+
+    let nested = |ce, prec, f: &mut Formatter<'_>| class_expression(&m.with(ce), prec, f);
+    let parens = match m.0 {
+        ObjectUnionOf(_) => prec > Precedence::Description,
+        ObjectIntersectionOf(_) => prec > Precedence::Conjunction,
+        ObjectComplementOf(_) => prec > Precedence::Primary,
+        _ => false,
+    };
+    if parens {
+        f.write_str("(")?;
+    }
+
+    macro_rules! object_cardinality {
+        ($kw:literal, $n:ident, $ope:ident, $bce:ident) => {{
+            write!(f, concat!("{} ", $kw, " {}"), m.with($ope), $n)?;
+            match $bce.as_ref() {
+                Class(cls) if cls.0.as_ref() == crate::vocab::OWL::Thing.as_ref() => {}
+                bce => {
+                    f.write_str(" ")?;
+                    nested(bce, Precedence::Primary, f)?;
+                }
+            }
+        }};
+    }
+    macro_rules! data_cardinality {
+        ($kw:literal, $n:ident, $dp:ident, $dr:ident) => {{
+            write!(f, concat!("{} ", $kw, " {}"), m.with($dp), $n)?;
+            match $dr {
+                DataRange::Datatype(dt)
+                    if dt.0.as_ref() == crate::vocab::OWL2Datatype::Literal.as_ref() => {}
+                dr => {
+                    f.write_str(" ")?;
+                    data_range(&m.with(dr), Precedence::Primary, f)?;
+                }
+            }
+        }};
+    }
+
+    match m.0 {
+        Class(cls) => m.with(cls).fmt(f)?,
+        ObjectIntersectionOf(ces) => {
+            for (i, ce) in ces.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(" and ")?;
+                }
+                nested(ce, Precedence::Primary, f)?;
+            }
+        }
+        ObjectUnionOf(ces) => {
+            for (i, ce) in ces.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(" or ")?;
+                }
+                nested(ce, Precedence::Conjunction, f)?;
+            }
+        }
+        ObjectComplementOf(ce) => {
+            f.write_str("not ")?;
+            nested(ce, Precedence::Atomic, f)?;
+        }
+        ObjectOneOf(is) => {
+            f.write_str("{")?;
+            join(f, is.iter().map(|i| m.with(i)), ", ")?;
+            f.write_str("}")?;
+        }
+        ObjectSomeValuesFrom { ope, bce } => {
+            write!(f, "{} some ", m.with(ope))?;
+            nested(bce, Precedence::Primary, f)?;
+        }
+        ObjectAllValuesFrom { ope, bce } => {
+            write!(f, "{} only ", m.with(ope))?;
+            nested(bce, Precedence::Primary, f)?;
+        }
+        ObjectHasValue { ope, i } => write!(f, "{} value {}", m.with(ope), m.with(i))?,
+        ObjectHasSelf(ope) => write!(f, "{} Self", m.with(ope))?,
+        ObjectMinCardinality { n, ope, bce } => object_cardinality!("min", n, ope, bce),
+        ObjectMaxCardinality { n, ope, bce } => object_cardinality!("max", n, ope, bce),
+        ObjectExactCardinality { n, ope, bce } => object_cardinality!("exactly", n, ope, bce),
+        DataSomeValuesFrom { dp, dr } => {
+            write!(f, "{} some ", m.with(dp))?;
+            data_range(&m.with(dr), Precedence::Primary, f)?;
+        }
+        DataAllValuesFrom { dp, dr } => {
+            write!(f, "{} only ", m.with(dp))?;
+            data_range(&m.with(dr), Precedence::Primary, f)?;
+        }
+        DataHasValue { dp, l } => write!(f, "{} value {}", m.with(dp), m.with(l))?,
+        DataMinCardinality { n, dp, dr } => data_cardinality!("min", n, dp, dr),
+        DataMaxCardinality { n, dp, dr } => data_cardinality!("max", n, dp, dr),
+        DataExactCardinality { n, dp, dr } => data_cardinality!("exactly", n, dp, dr),
+    }
+
+    if parens {
+        f.write_str(")")?;
+    }
+    Ok(())
+}
+
+impl<'a, A: ForIRI> Display for Manchester<'a, ClassExpression<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        class_expression(self, Precedence::Description, f)
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for ClassExpression<A> {}
+
+// ---------------------------------------------------------------------------
+
+fn data_range<A: ForIRI>(
+    m: &Manchester<'_, DataRange<A>, A>,
+    prec: Precedence,
+    f: &mut Formatter<'_>,
+) -> Result<(), Error> {
+    use DataRange::*;
+
+    let nested = |dr, prec, f: &mut Formatter<'_>| data_range(&m.with(dr), prec, f);
+    let parens = match m.0 {
+        DataUnionOf(_) => prec > Precedence::Description,
+        DataIntersectionOf(_) => prec > Precedence::Conjunction,
+        DataComplementOf(_) => prec > Precedence::Primary,
+        _ => false,
+    };
+    if parens {
+        f.write_str("(")?;
+    }
+
+    match m.0 {
+        Datatype(dt) => m.with(dt).fmt(f)?,
+        DataIntersectionOf(drs) => {
+            for (i, dr) in drs.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(" and ")?;
+                }
+                nested(dr, Precedence::Primary, f)?;
+            }
+        }
+        DataUnionOf(drs) => {
+            for (i, dr) in drs.iter().enumerate() {
+                if i != 0 {
+                    f.write_str(" or ")?;
+                }
+                nested(dr, Precedence::Conjunction, f)?;
+            }
+        }
+        DataComplementOf(dr) => {
+            f.write_str("not ")?;
+            nested(dr, Precedence::Atomic, f)?;
+        }
+        DataOneOf(lits) => {
+            f.write_str("{")?;
+            join(f, lits.iter().map(|l| m.with(l)), ", ")?;
+            f.write_str("}")?;
+        }
+        DatatypeRestriction(dt, frs) => {
+            write!(f, "{}[", m.with(dt))?;
+            join(f, frs.iter().map(|fr| m.with(fr)), ", ")?;
+            f.write_str("]")?;
+        }
+    }
+
+    if parens {
+        f.write_str(")")?;
+    }
+    Ok(())
+}
+
+impl<'a, A: ForIRI> Display for Manchester<'a, DataRange<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        data_range(self, Precedence::Description, f)
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for DataRange<A> {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, Facet, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(match self.0 {
+            Facet::Length => "length",
+            Facet::MinLength => "minLength",
+            Facet::MaxLength => "maxLength",
+            Facet::Pattern => "pattern",
+            Facet::MinInclusive => ">=",
+            Facet::MinExclusive => ">",
+            Facet::MaxInclusive => "<=",
+            Facet::MaxExclusive => "<",
+            Facet::TotalDigits => "totalDigits",
+            Facet::FractionDigits => "fractionDigits",
+            Facet::LangRange => "langRange",
+        })
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for Facet {}
+
+// ---------------------------------------------------------------------------
+
+impl<'a, A: ForIRI> Display for Manchester<'a, FacetRestriction<A>, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{} {}", self.with(&self.0.f), self.with(&self.0.l))
+    }
+}
+
+impl<A: ForIRI> AsManchester<A> for FacetRestriction<A> {}
+
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        let b = Build::new_rc();
+        let a: ClassExpression<_> = b.class("http://example.com/A").into();
+        let c: ClassExpression<_> = b.class("http://example.com/C").into();
+        let r = b.object_property("http://example.com/r");
+        let mut prefixes = PrefixMapping::default();
+        prefixes.add_prefix("ex", "http://example.com/").unwrap();
+
+        let ce = ClassExpression::ObjectIntersectionOf(vec![
+            ClassExpression::ObjectUnionOf(vec![a.clone(), c.clone()]),
+            ClassExpression::ObjectComplementOf(Box::new(a.clone())),
+            ClassExpression::ObjectSomeValuesFrom {
+                ope: r.into(),
+                bce: Box::new(ClassExpression::ObjectIntersectionOf(vec![a, c])),
+            },
+        ]);
+        assert_eq!(
+            ce.as_manchester_with_prefixes(&prefixes).to_string(),
+            "(ex:A or ex:C) and not ex:A and ex:r some (ex:A and ex:C)"
+        );
+    }
+
+    #[test]
+    fn literal() {
+        let b = Build::new_rc();
+        let integer = |literal: &str| Literal::Datatype {
+            literal: literal.to_string(),
+            datatype_iri: b.iri("http://www.w3.org/2001/XMLSchema#integer"),
+        };
+
+        assert_eq!(integer("-10").as_manchester().to_string(), "-10");
+        assert_eq!(
+            integer("ten").as_manchester().to_string(),
+            r#""ten"^^<http://www.w3.org/2001/XMLSchema#integer>"#
+        );
+        assert_eq!(
+            Literal::<RcStr>::Simple {
+                literal: r#"a "quote""#.to_string()
+            }
+            .as_manchester()
+            .to_string(),
+            r#""a \"quote\"""#
+        );
+    }
+}
@@ -0,0 +1,848 @@
+//! Write an ontology in Manchester Syntax.
+//!
+//! Axioms are grouped into frames keyed by the entity they describe,
+//! such as the sub-class of a `SubClassOf` axiom. Axioms between
+//! several entities with no natural subject are written in the
+//! trailing `EquivalentClasses:`, `DisjointClasses:` and similar
+//! sections. Every entity which is the subject of an axiom has a
+//! frame, whether or not it is declared.
+//!
+//! Some components, such as general class inclusions and SWRL rules,
+//! cannot be written in Manchester Syntax; these are written in
+//! functional syntax in a comment at the end of the document.
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::Write;
+
+use curie::PrefixMapping;
+
+use crate::error::HornedError;
+use crate::io::ofn::writer::AsFunctional;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+
+mod as_manchester;
+
+pub use self::as_manchester::AsManchester;
+pub use self::as_manchester::Manchester;
+
+/// The entity described by a frame.
+///
+/// Frames are written in the order of the variants.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Subject<A: ForIRI> {
+    AnnotationProperty(AnnotationProperty<A>),
+    Datatype(Datatype<A>),
+    ObjectProperty(ObjectProperty<A>),
+    DataProperty(DataProperty<A>),
+    Class(Class<A>),
+    Individual(Individual<A>),
+}
+
+impl<A: ForIRI> Subject<A> {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Subject::AnnotationProperty(_) => "AnnotationProperty:",
+            Subject::Datatype(_) => "Datatype:",
+            Subject::ObjectProperty(_) => "ObjectProperty:",
+            Subject::DataProperty(_) => "DataProperty:",
+            Subject::Class(_) => "Class:",
+            Subject::Individual(_) => "Individual:",
+        }
+    }
+
+    fn iri(&self) -> Option<&IRI<A>> {
+        match self {
+            Subject::AnnotationProperty(ap) => Some(&ap.0),
+            Subject::Datatype(dt) => Some(&dt.0),
+            Subject::ObjectProperty(op) => Some(&op.0),
+            Subject::DataProperty(dp) => Some(&dp.0),
+            Subject::Class(c) => Some(&c.0),
+            Subject::Individual(Individual::Named(ni)) => Some(&ni.0),
+            Subject::Individual(Individual::Anonymous(_)) => None,
+        }
+    }
+}
+
+/// A section of a frame.
+///
+/// Sections are written in the order of the variants.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Section {
+    Annotations,
+    SubClassOf,
+    EquivalentTo,
+    DisjointWith,
+    DisjointUnionOf,
+    HasKey,
+    Domain,
+    Range,
+    Characteristics,
+    SubPropertyOf,
+    InverseOf,
+    SubPropertyChain,
+    Types,
+    Facts,
+    SameAs,
+    DifferentFrom,
+}
+
+impl Section {
+    fn keyword(self) -> &'static str {
+        match self {
+            Section::Annotations => "Annotations:",
+            Section::SubClassOf => "SubClassOf:",
+            Section::EquivalentTo => "EquivalentTo:",
+            Section::DisjointWith => "DisjointWith:",
+            Section::DisjointUnionOf => "DisjointUnionOf:",
+            Section::HasKey => "HasKey:",
+            Section::Domain => "Domain:",
+            Section::Range => "Range:",
+            Section::Characteristics => "Characteristics:",
+            Section::SubPropertyOf => "SubPropertyOf:",
+            Section::InverseOf => "InverseOf:",
+            Section::SubPropertyChain => "SubPropertyChain:",
+            Section::Types => "Types:",
+            Section::Facts => "Facts:",
+            Section::SameAs => "SameAs:",
+            Section::DifferentFrom => "DifferentFrom:",
+        }
+    }
+
+    /// Does the section take a single item, so that it must be
+    /// repeated for each one?
+    fn is_single<A: ForIRI>(self, subject: &Subject<A>) -> bool {
+        match self {
+            Section::DisjointUnionOf | Section::HasKey | Section::SubPropertyChain => true,
+            Section::EquivalentTo => matches!(subject, Subject::Datatype(_)),
+            _ => false,
+        }
+    }
+}
+
+type Frame = BTreeMap<Section, Vec<String>>;
+
+/// The frames and trailing sections of a document.
+struct Document<'a, A: ForIRI> {
+    mapping: &'a PrefixMapping,
+    frames: BTreeMap<Subject<A>, Frame>,
+    misc: Vec<String>,
+    inexpressible: Vec<String>,
+}
+
+impl<'a, A: ForIRI> Document<'a, A> {
+    fn new(mapping: &'a PrefixMapping) -> Self {
+        Document {
+            mapping,
+            frames: BTreeMap::new(),
+            misc: Vec::new(),
+            inexpressible: Vec::new(),
+        }
+    }
+
+    fn m<'t, T: AsManchester<A>>(&self, t: &'t T) -> Manchester<'t, T, A>
+    where
+        'a: 't,
+    {
+        t.as_manchester_with_prefixes(self.mapping)
+    }
+
+    fn list<T: AsManchester<A>>(&self, items: &[T], sep: &str) -> String
+    where
+        for<'t> Manchester<'t, T, A>: Display,
+    {
+        items
+            .iter()
+            .map(|i| self.m(i).to_string())
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    /// Prefix `item` with its annotations, if there are any.
+    fn annotated(&self, anns: &BTreeSet<Annotation<A>>, item: impl Display) -> String {
+        if anns.is_empty() {
+            item.to_string()
+        } else {
+            let anns: Vec<_> = anns.iter().map(|a| self.m(a).to_string()).collect();
+            format!("Annotations: {} {}", anns.join(", "), item)
+        }
+    }
+
+    fn declare(&mut self, subject: Subject<A>, ac: &AnnotatedComponent<A>) {
+        self.frames.entry(subject).or_default();
+        // Frames cannot carry annotations on the declaration itself
+        if !ac.ann.is_empty() {
+            self.inexpressible(ac);
+        }
+    }
+
+    fn add(
+        &mut self,
+        subject: Subject<A>,
+        section: Section,
+        anns: &BTreeSet<Annotation<A>>,
+        item: impl Display,
+    ) {
+        let item = self.annotated(anns, item);
+        self.frames
+            .entry(subject)
+            .or_default()
+            .entry(section)
+            .or_default()
+            .push(item);
+    }
+
+    fn misc(&mut self, keyword: &str, anns: &BTreeSet<Annotation<A>>, items: String) {
+        let item = self.annotated(anns, items);
+        self.misc.push(format!("{} {}", keyword, item));
+    }
+
+    fn inexpressible(&mut self, ac: &AnnotatedComponent<A>) {
+        let ofn = ac.as_functional_with_prefixes(self.mapping).to_string();
+        self.inexpressible.push(ofn);
+    }
+
+    /// Add an n-ary axiom to the frame of its first member when it
+    /// has two members, or to the trailing sections otherwise.
+    fn nary<T: AsManchester<A>>(
+        &mut self,
+        items: &[T],
+        subject: impl Fn(&T) -> Option<Subject<A>>,
+        section: Section,
+        keyword: &str,
+        ac: &AnnotatedComponent<A>,
+    ) where
+        for<'t> Manchester<'t, T, A>: Display,
+    {
+        if items.len() == 2 {
+            if let Some(s) = subject(&items[0]) {
+                let item = self.m(&items[1]).to_string();
+                return self.add(s, section, &ac.ann, item);
+            }
+        }
+        if items.len() >= 2 {
+            let items = self.list(items, ", ");
+            self.misc(keyword, &ac.ann, items);
+        } else {
+            self.inexpressible(ac);
+        }
+    }
+
+    /// Add an object property axiom to the frame of its property,
+    /// unless it is an inverse property.
+    fn characteristic(
+        &mut self,
+        ope: &ObjectPropertyExpression<A>,
+        item: &str,
+        ac: &AnnotatedComponent<A>,
+    ) {
+        match ope {
+            ObjectPropertyExpression::ObjectProperty(op) => self.add(
+                Subject::ObjectProperty(op.clone()),
+                Section::Characteristics,
+                &ac.ann,
+                item,
+            ),
+            ObjectPropertyExpression::InverseObjectProperty(_) => self.inexpressible(ac),
+        }
+    }
+
+    /// Add a component to its frame; annotation assertions are
+    /// returned, as they can only be placed once all frames are known.
+    fn component<'c>(
+        &mut self,
+        ac: &'c AnnotatedComponent<A>,
+    ) -> Option<&'c AnnotationAssertion<A>> {
+        use Component::*;
+        let anns = &ac.ann;
+        let named_class = |ce: &ClassExpression<A>| match ce {
+            ClassExpression::Class(c) => Some(Subject::Class(c.clone())),
+            _ => None,
+        };
+        let named_property = |ope: &ObjectPropertyExpression<A>| match ope {
+            ObjectPropertyExpression::ObjectProperty(op) => {
+                Some(Subject::ObjectProperty(op.clone()))
+            }
+            _ => None,
+        };
+
+        match &ac.component {
+            // These are written in the ontology header
+            OntologyID(_) | DocIRI(_) | OntologyAnnotation(_) | Import(_) => {}
+            DeclareClass(dc) => self.declare(Subject::Class(dc.0.clone()), ac),
+            DeclareObjectProperty(dop) => self.declare(Subject::ObjectProperty(dop.0.clone()), ac),
+            DeclareAnnotationProperty(dap) => {
+                self.declare(Subject::AnnotationProperty(dap.0.clone()), ac)
+            }
+            DeclareDataProperty(ddp) => self.declare(Subject::DataProperty(ddp.0.clone()), ac),
+            DeclareNamedIndividual(dni) => {
+                self.declare(Subject::Individual(dni.0.clone().into()), ac)
+            }
+            DeclareDatatype(dd) => self.declare(Subject::Datatype(dd.0.clone()), ac),
+            SubClassOf(sc) => match named_class(&sc.sub) {
+                Some(s) => {
+                    let item = self.m(&sc.sup).to_string();
+                    self.add(s, Section::SubClassOf, anns, item)
+                }
+                None => self.inexpressible(ac),
+            },
+            EquivalentClasses(ec) => self.nary(
+                &ec.0,
+                named_class,
+                Section::EquivalentTo,
+                "EquivalentClasses:",
+                ac,
+            ),
+            DisjointClasses(dc) => self.nary(
+                &dc.0,
+                named_class,
+                Section::DisjointWith,
+                "DisjointClasses:",
+                ac,
+            ),
+            DisjointUnion(du) if du.1.len() >= 2 => {
+                let item = self.list(&du.1, ", ");
+                self.add(
+                    Subject::Class(du.0.clone()),
+                    Section::DisjointUnionOf,
+                    anns,
+                    item,
+                )
+            }
+            HasKey(hk) if !hk.vpe.is_empty() => match named_class(&hk.ce) {
+                Some(s) => {
+                    let item = self.list(&hk.vpe, ", ");
+                    self.add(s, Section::HasKey, anns, item)
+                }
+                None => self.inexpressible(ac),
+            },
+            SubObjectPropertyOf(sop) => match (&sop.sub, named_property(&sop.sup)) {
+                (
+                    SubObjectPropertyExpression::ObjectPropertyExpression(
+                        ObjectPropertyExpression::ObjectProperty(op),
+                    ),
+                    _,
+                ) => {
+                    let item = self.m(&sop.sup).to_string();
+                    self.add(
+                        Subject::ObjectProperty(op.clone()),
+                        Section::SubPropertyOf,
+                        anns,
+                        item,
+                    )
+                }
+                (SubObjectPropertyExpression::ObjectPropertyChain(chain), Some(s))
+                    if chain.len() >= 2 =>
+                {
+                    let item = self.list(chain, " o ");
+                    self.add(s, Section::SubPropertyChain, anns, item)
+                }
+                _ => self.inexpressible(ac),
+            },
+            EquivalentObjectProperties(eop) => self.nary(
+                &eop.0,
+                named_property,
+                Section::EquivalentTo,
+                "EquivalentProperties:",
+                ac,
+            ),
+            DisjointObjectProperties(dop) => self.nary(
+                &dop.0,
+                named_property,
+                Section::DisjointWith,
+                "DisjointProperties:",
+                ac,
+            ),
+            InverseObjectProperties(iop) => {
+                let item = self.m(&iop.1).to_string();
+                self.add(
+                    Subject::ObjectProperty(iop.0.clone()),
+                    Section::InverseOf,
+                    anns,
+                    item,
+                )
+            }
+            ObjectPropertyDomain(opd) => match named_property(&opd.ope) {
+                Some(s) => {
+                    let item = self.m(&opd.ce).to_string();
+                    self.add(s, Section::Domain, anns, item)
+                }
+                None => self.inexpressible(ac),
+            },
+            ObjectPropertyRange(opr) => match named_property(&opr.ope) {
+                Some(s) => {
+                    let item = self.m(&opr.ce).to_string();
+                    self.add(s, Section::Range, anns, item)
+                }
+                None => self.inexpressible(ac),
+            },
+            FunctionalObjectProperty(fop) => self.characteristic(&fop.0, "Functional", ac),
+            InverseFunctionalObjectProperty(ifop) => {
+                self.characteristic(&ifop.0, "InverseFunctional", ac)
+            }
+            ReflexiveObjectProperty(rop) => self.characteristic(&rop.0, "Reflexive", ac),
+            IrreflexiveObjectProperty(iop) => self.characteristic(&iop.0, "Irreflexive", ac),
+            SymmetricObjectProperty(sop) => self.characteristic(&sop.0, "Symmetric", ac),
+            AsymmetricObjectProperty(aop) => self.characteristic(&aop.0, "Asymmetric", ac),
+            TransitiveObjectProperty(top) => self.characteristic(&top.0, "Transitive", ac),
+            SubDataPropertyOf(sdp) => {
+                let item = self.m(&sdp.sup).to_string();
+                self.add(
+                    Subject::DataProperty(sdp.sub.clone()),
+                    Section::SubPropertyOf,
+                    anns,
+                    item,
+                )
+            }
+            EquivalentDataProperties(edp) => self.nary(
+                &edp.0,
+                |dp| Some(Subject::DataProperty(dp.clone())),
+                Section::EquivalentTo,
+                "EquivalentProperties:",
+                ac,
+            ),
+            DisjointDataProperties(ddp) => self.nary(
+                &ddp.0,
+                |dp| Some(Subject::DataProperty(dp.clone())),
+                Section::DisjointWith,
+                "DisjointProperties:",
+                ac,
+            ),
+            DataPropertyDomain(dpd) => {
+                let item = self.m(&dpd.ce).to_string();
+                self.add(
+                    Subject::DataProperty(dpd.dp.clone()),
+                    Section::Domain,
+                    anns,
+                    item,
+                )
+            }
+            DataPropertyRange(dpr) => {
+                let item = self.m(&dpr.dr).to_string();
+                self.add(
+                    Subject::DataProperty(dpr.dp.clone()),
+                    Section::Range,
+                    anns,
+                    item,
+                )
+            }
+            FunctionalDataProperty(fdp) => self.add(
+                Subject::DataProperty(fdp.0.clone()),
+                Section::Characteristics,
+                anns,
+                "Functional",
+            ),
+            DatatypeDefinition(dd) => {
+                let item = self.m(&dd.range).to_string();
+                self.add(
+                    Subject::Datatype(dd.kind.clone()),
+                    Section::EquivalentTo,
+                    anns,
+                    item,
+                )
+            }
+            SameIndividual(si) => self.nary(
+                &si.0,
+                |i| Some(Subject::Individual(i.clone())),
+                Section::SameAs,
+                "SameIndividual:",
+                ac,
+            ),
+            DifferentIndividuals(di) => self.nary(
+                &di.0,
+                |i| Some(Subject::Individual(i.clone())),
+                Section::DifferentFrom,
+                "DifferentIndividuals:",
+                ac,
+            ),
+            ClassAssertion(ca) => {
+                let item = self.m(&ca.ce).to_string();
+                self.add(
+                    Subject::Individual(ca.i.clone()),
+                    Section::Types,
+                    anns,
+                    item,
+                )
+            }
+            ObjectPropertyAssertion(opa) => match &opa.ope {
+                ObjectPropertyExpression::ObjectProperty(op) => {
+                    let item = format!("{} {}", self.m(op), self.m(&opa.to));
+                    self.add(
+                        Subject::Individual(opa.from.clone()),
+                        Section::Facts,
+                        anns,
+                        item,
+                    )
+                }
+                _ => self.inexpressible(ac),
+            },
+            NegativeObjectPropertyAssertion(nopa) => match &nopa.ope {
+                ObjectPropertyExpression::ObjectProperty(op) => {
+                    let item = format!("not {} {}", self.m(op), self.m(&nopa.to));
+                    self.add(
+                        Subject::Individual(nopa.from.clone()),
+                        Section::Facts,
+                        anns,
+                        item,
+                    )
+                }
+                _ => self.inexpressible(ac),
+            },
+            DataPropertyAssertion(dpa) => {
+                let item = format!("{} {}", self.m(&dpa.dp), self.m(&dpa.to));
+                self.add(
+                    Subject::Individual(dpa.from.clone()),
+                    Section::Facts,
+                    anns,
+                    item,
+                )
+            }
+            NegativeDataPropertyAssertion(ndpa) => {
+                let item = format!("not {} {}", self.m(&ndpa.dp), self.m(&ndpa.to));
+                self.add(
+                    Subject::Individual(ndpa.from.clone()),
+                    Section::Facts,
+                    anns,
+                    item,
+                )
+            }
+            AnnotationAssertion(aa) => return Some(aa),
+            SubAnnotationPropertyOf(sap) => {
+                let item = self.m(&sap.sup).to_string();
+                self.add(
+                    Subject::AnnotationProperty(sap.sub.clone()),
+                    Section::SubPropertyOf,
+                    anns,
+                    item,
+                )
+            }
+            AnnotationPropertyDomain(apd) => {
+                let item = self.m(&apd.iri).to_string();
+                self.add(
+                    Subject::AnnotationProperty(apd.ap.clone()),
+                    Section::Domain,
+                    anns,
+                    item,
+                )
+            }
+            AnnotationPropertyRange(apr) => {
+                let item = self.m(&apr.iri).to_string();
+                self.add(
+                    Subject::AnnotationProperty(apr.ap.clone()),
+                    Section::Range,
+                    anns,
+                    item,
+                )
+            }
+            DisjointUnion(_) | HasKey(_) | Rule(_) => self.inexpressible(ac),
+        }
+        None
+    }
+
+    /// Add annotation assertions to the frame of their subject; an
+    /// IRI which has several frames is annotated in the first.
+    fn annotation_assertions(
+        &mut self,
+        aas: Vec<(&AnnotationAssertion<A>, &AnnotatedComponent<A>)>,
+    ) {
+        let mut by_iri: BTreeMap<IRI<A>, Subject<A>> = BTreeMap::new();
+        for subject in self.frames.keys() {
+            if let Some(iri) = subject.iri() {
+                by_iri.entry(iri.clone()).or_insert_with(|| subject.clone());
+            }
+        }
+
+        for (aa, ac) in aas {
+            let subject = match &aa.subject {
+                AnnotationSubject::IRI(iri) => by_iri.get(iri).cloned(),
+                AnnotationSubject::AnonymousIndividual(ai) => {
+                    Some(Subject::Individual(ai.clone().into()))
+                }
+            };
+            match subject {
+                Some(s) => {
+                    let item = self.m(&aa.ann).to_string();
+                    self.add(s, Section::Annotations, &ac.ann, item)
+                }
+                None => self.inexpressible(ac),
+            }
+        }
+    }
+
+    fn write_frames<W: Write>(&self, mut write: W) -> Result<(), HornedError> {
+        for (subject, frame) in &self.frames {
+            let name = match subject {
+                Subject::AnnotationProperty(ap) => self.m(ap).to_string(),
+                Subject::Datatype(dt) => self.m(dt).to_string(),
+                Subject::ObjectProperty(op) => self.m(op).to_string(),
+                Subject::DataProperty(dp) => self.m(dp).to_string(),
+                Subject::Class(c) => self.m(c).to_string(),
+                Subject::Individual(i) => self.m(i).to_string(),
+            };
+            writeln!(write, "{} {}", subject.keyword(), name)?;
+
+            for (section, items) in frame {
+                if section.is_single(subject) {
+                    for item in items {
+                        writeln!(write)?;
+                        writeln!(write, "    {} {}", section.keyword(), item)?;
+                    }
+                } else {
+                    writeln!(write)?;
+                    writeln!(write, "    {}", section.keyword())?;
+                    writeln!(write, "        {}", items.join(",\n        "))?;
+                }
+            }
+            writeln!(write)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write an Ontology to `write`, using the given `PrefixMapping`.
+///
+/// The ontology is written in OWL
+/// [Manchester](https://www.w3.org/TR/owl2-manchester-syntax/)
+/// syntax.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    mut write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+    mapping: Option<&PrefixMapping>,
+) -> Result<(), HornedError> {
+    // Ensure we have a prefix mapping; the default is a no-op and
+    // it's easier than checking every time.
+    let default_mapper = PrefixMapping::default();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+    let mut doc = Document::new(mapping);
+
+    // Ensure we have a single OntologyID in the ontology.
+    let optional_id = {
+        let mut components = ont.i().component_for_kind(ComponentKind::OntologyID);
+        let component = components.next();
+        if components.next().is_some() {
+            return Err(HornedError::invalid("multiple ontology IDs found"));
+        }
+        component.map(|c| {
+            if let Component::OntologyID(ontology_id) = &c.component {
+                ontology_id
+            } else {
+                unreachable!()
+            }
+        })
+    };
+
+    // Write prefixes
+    for (name, value) in mapping.mappings() {
+        writeln!(write, "Prefix: {}: <{}>", name, value)?;
+    }
+    writeln!(write)?;
+
+    // Write the IRI and Version IRI if any
+    write!(write, "Ontology:")?;
+    if let Some(iri) = optional_id.and_then(|id| id.iri.as_ref()) {
+        write!(write, " {}", doc.m(iri))?;
+        if let Some(viri) = optional_id.and_then(|id| id.viri.as_ref()) {
+            write!(write, " {}", doc.m(viri))?;
+        }
+    }
+    writeln!(write)?;
+    writeln!(write)?;
+
+    let mut imports: Vec<_> = ont.i().component_for_kind(ComponentKind::Import).collect();
+    imports.sort();
+    for ac in &imports {
+        if let Component::Import(import) = &ac.component {
+            writeln!(write, "Import: {}", doc.m(&import.0))?;
+        }
+    }
+    if !imports.is_empty() {
+        writeln!(write)?;
+    }
+
+    let mut annotations: Vec<_> = ont
+        .i()
+        .component_for_kind(ComponentKind::OntologyAnnotation)
+        .collect();
+    annotations.sort();
+    if !annotations.is_empty() {
+        let items: Vec<_> = annotations
+            .iter()
+            .filter_map(|ac| match &ac.component {
+                Component::OntologyAnnotation(oa) => Some(doc.annotated(&ac.ann, doc.m(&oa.0))),
+                _ => None,
+            })
+            .collect();
+        writeln!(write, "Annotations:")?;
+        writeln!(write, "    {}", items.join(",\n    "))?;
+        writeln!(write)?;
+    }
+
+    // Group the remaining components into frames
+    let mut aas = Vec::new();
+    for kind in ComponentKind::all_kinds() {
+        let mut components = ont.i().component_for_kind(kind).collect::<Vec<_>>();
+        components.sort();
+        for ac in components {
+            if let Some(aa) = doc.component(ac) {
+                aas.push((aa, ac));
+            }
+        }
+    }
+    doc.annotation_assertions(aas);
+
+    doc.write_frames(&mut write)?;
+
+    for misc in &doc.misc {
+        writeln!(write, "{}", misc)?;
+        writeln!(write)?;
+    }
+
+    if !doc.inexpressible.is_empty() {
+        writeln!(
+            write,
+            "# The following components cannot be written in Manchester Syntax"
+        )?;
+        for ofn in &doc.inexpressible {
+            for line in ofn.lines() {
+                writeln!(write, "# {}", line)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::AnnotatedComponent;
+    use crate::model::RcStr;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    #[test_resources("src/ont/owl-manchester/*.omn")]
+    fn roundtrip_resource(resource: &str) {
+        let reader = std::fs::File::open(resource)
+            .map(std::io::BufReader::new)
+            .unwrap();
+        let (ont, prefixes): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::omn::reader::read(reader, Default::default()).unwrap();
+
+        let mut writer = Vec::new();
+        crate::io::omn::writer::write(&mut writer, &ont, Some(&prefixes)).unwrap();
+
+        let (ont2, prefixes2) =
+            crate::io::omn::reader::read(std::io::Cursor::new(&writer), Default::default())
+                .unwrap();
+
+        assert_eq!(prefixes, prefixes2, "prefix mapping differ");
+        assert_eq!(ont, ont2, "ontologies differ");
+    }
+
+    fn to_omn(ofn: &str) -> String {
+        let (ont, prefixes): (ComponentMappedOntology<RcStr, AnnotatedComponent<RcStr>>, _) =
+            crate::io::ofn::reader::read(ofn.as_bytes(), Default::default()).unwrap();
+        let mut writer = Vec::new();
+        write(&mut writer, &ont, Some(&prefixes)).unwrap();
+        String::from_utf8(writer).unwrap()
+    }
+
+    #[test]
+    fn frames() {
+        let omn = to_omn(
+            "Prefix(:=<http://example.com/>)
+Ontology(<http://example.com/o>
+Declaration(Class(:A))
+Declaration(Class(:B))
+Declaration(NamedIndividual(:i))
+SubClassOf(:A :B)
+SubClassOf(:A ObjectSomeValuesFrom(:r :B))
+ClassAssertion(:A :i)
+AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> :A \"A\")
+)",
+        );
+
+        assert_eq!(
+            omn,
+            "Prefix: : <http://example.com/>
+
+Ontology: :o
+
+Class: :A
+
+    Annotations:
+        <http://www.w3.org/2000/01/rdf-schema#label> \"A\"
+
+    SubClassOf:
+        :B,
+        :r some :B
+
+Class: :B
+
+Individual: :i
+
+    Types:
+        :A
+
+"
+        );
+    }
+
+    #[test]
+    fn trailing_sections() {
+        let omn = to_omn(
+            "Prefix(:=<http://example.com/>)
+Ontology(
+DisjointClasses(:A :B :C)
+SubClassOf(ObjectSomeValuesFrom(:r :A) :B)
+)",
+        );
+
+        assert!(omn.contains("\nDisjointClasses: :A, :B, :C\n"));
+        assert!(omn.contains("\n# SubClassOf(ObjectSomeValuesFrom(:r :A) :B)\n"));
+
+        // Both trailing sections are ignored or understood when reading
+        let (ont, _): (SetOntology<RcStr>, _) =
+            crate::io::omn::reader::read(omn.as_bytes(), Default::default()).unwrap();
+        assert_eq!(ont.iter().count(), 2);
+    }
+
+    #[test]
+    fn undeclared_frames() {
+        let ofn = "Prefix(:=<http://example.com/>)
+Ontology(<http://example.com/o>
+SubClassOf(:C :D)
+ObjectPropertyDomain(:r :A)
+ClassAssertion(:A :i)
+)";
+        let omn = to_omn(ofn);
+        assert!(omn.contains("\nClass: :C\n"));
+        assert!(omn.contains("\nObjectProperty: :r\n"));
+        assert!(omn.contains("\nIndividual: :i\n"));
+        assert!(!omn.contains("\n# "));
+
+        // Each frame declares its entity, but nothing else is added
+        let (ont, _): (SetOntology<RcStr>, _) =
+            crate::io::omn::reader::read(omn.as_bytes(), Default::default()).unwrap();
+        let (expected, _): (SetOntology<RcStr>, _) =
+            crate::io::ofn::reader::read(ofn.as_bytes(), Default::default()).unwrap();
+        assert!(expected.iter().all(|ac| ont.i().contains(ac)));
+        assert!(ont
+            .iter()
+            .filter(|ac| !expected.i().contains(ac))
+            .all(|ac| matches!(
+                ac.component,
+                Component::DeclareClass(_)
+                    | Component::DeclareObjectProperty(_)
+                    | Component::DeclareNamedIndividual(_)
+            )));
+    }
+}
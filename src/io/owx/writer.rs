@@ -14,6 +14,7 @@ use quick_xml::events::BytesText;
 use quick_xml::events::Event;
 use quick_xml::Writer;
 
+use std::borrow::Borrow;
 use std::collections::BTreeSet;
 use std::io::Write as StdWrite;
 
@@ -41,6 +42,38 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: StdWrite>(
     Ok(())
 }
 
+/// Write the components of an ontology to `write`, using the given
+/// PrefixMapping
+///
+/// Components are written as they are taken from `components`, so
+/// the whole ontology need not be held in memory. The output is the
+/// same as that of [`write`] if the components are given grouped by
+/// `ComponentKind`, in the order of `ComponentKind::all_kinds`.
+/// `OntologyID` and `DocIRI` components are not written; the
+/// ontology IRI and version IRI are taken from `ontology_id`.
+pub fn write_components<A, W, I>(
+    write: W,
+    ontology_id: &OntologyID<A>,
+    mapping: Option<&PrefixMapping>,
+    components: I,
+) -> Result<(), HornedError>
+where
+    A: ForIRI,
+    W: StdWrite,
+    I: IntoIterator,
+    I::Item: Borrow<AnnotatedComponent<A>>,
+{
+    let mut writer = Writer::new_with_indent(write, b' ', 4);
+
+    let default_mapper = PrefixMapping::default();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+
+    render_components(ontology_id, components, &mut writer, mapping)
+}
+
 /// Add an IRI to BytesStart as a element if necessary
 ///
 /// `key` is the attribute name to use.
@@ -235,6 +268,25 @@ fn render_ont<A: ForIRI, AA: ForIndex<A>, W>(
 ) -> Result<(), HornedError>
 where
     W: StdWrite,
+{
+    let id = o.i().the_ontology_id_or_default();
+    let components = ComponentKind::all_kinds()
+        .into_iter()
+        .flat_map(|axk| o.i().component_for_kind(axk));
+
+    render_components(&id, components, w, m)
+}
+
+fn render_components<A: ForIRI, I, W>(
+    id: &OntologyID<A>,
+    components: I,
+    w: &mut Writer<W>,
+    m: &PrefixMapping,
+) -> Result<(), HornedError>
+where
+    I: IntoIterator,
+    I::Item: Borrow<AnnotatedComponent<A>>,
+    W: StdWrite,
 {
     // w.write_event(Event::Decl(BytesDecl::new(&b"1.0"[..], None, None)))?;
     w.write_event(Event::Decl(BytesDecl::new("1.0", None, None)))?;
@@ -243,7 +295,6 @@ where
     let mut elem = BytesStart::new("Ontology");
     elem.push_attribute((b"xmlns" as &[u8], OWL.as_bytes()));
 
-    let id = o.i().the_ontology_id_or_default();
     iri_maybe(&mut elem, "xml:base", &id.iri);
 
     // Render XML Namespaces.
@@ -261,10 +312,8 @@ where
     // let elem = BytesEnd::owned(b"Ontology".to_vec());
     m.render(w, m)?;
 
-    for axk in ComponentKind::all_kinds() {
-        for ax in o.i().component_for_kind(axk) {
-            ax.render(w, m)?;
-        }
+    for ax in components {
+        ax.borrow().render(w, m)?;
     }
 
     w.write_event(ev_end)?;
@@ -1145,4 +1194,29 @@ mod test {
     fn family() {
         assert_round(include_str!("../../ont/owl-xml/manual/family.owx"));
     }
+
+    #[test_resources("src/ont/owl-xml/*.owx")]
+    fn write_components_resource(resource: &str) {
+        let (ont, prefixes) = read_ok(&mut BufReader::new(File::open(resource).unwrap()));
+        let mut expected = Vec::new();
+        write(&mut expected, &ont, Some(&prefixes)).unwrap();
+
+        // Components of each kind in order, as they would arrive from a
+        // sorted stream
+        let kinds = ComponentKind::all_kinds();
+        let mut components: Vec<_> = ont.i().iter().collect();
+        components.sort_by_key(|ac| kinds.iter().position(|k| *k == ac.kind()));
+
+        let mut writer = Vec::new();
+        write_components(
+            &mut writer,
+            &ont.i().the_ontology_id_or_default(),
+            Some(&prefixes),
+            components,
+        )
+        .unwrap();
+
+        let (ont2, _) = read_ok(&mut writer.as_slice());
+        assert_eq!(ont, ont2);
+    }
 }
@@ -0,0 +1,9 @@
+//! [JSON-LD](https://www.w3.org/TR/json-ld11/) syntax for OWL.
+//!
+//! JSON-LD documents are expanded into triples, which are mapped to
+//! OWL using the same triple-to-axiom machinery as
+//! [RDF/XML](crate::io::rdf). Both expanded and compacted documents
+//! can be read, but only with inline contexts; remote contexts are
+//! not retrieved. Documents are written in compacted form.
+pub mod reader;
+pub mod writer;
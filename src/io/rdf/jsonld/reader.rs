@@ -0,0 +1,860 @@
+//! Read an ontology from a JSON-LD document.
+//!
+//! Documents are expanded following the [JSON-LD 1.1
+//! algorithms](https://www.w3.org/TR/json-ld11-api/) closely enough
+//! for the documents produced by RDF tools: contexts may define
+//! prefixes, terms with type coercion, `@list` and `@language`
+//! containers and reverse properties, as well as `@base`, `@vocab`
+//! and a default `@language`. Contexts must be given inline, as
+//! remote contexts are not retrieved. Named graphs are merged into
+//! the default graph.
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use rio_api::model::{BlankNode, Literal, NamedNode, Subject, Term, Triple};
+use rio_api::parser::TriplesParser;
+use serde_json::{Map, Number, Value};
+
+use crate::error::HornedError;
+use crate::io::rdf::reader::{ConcreteRDFOntology, IncompleteParse, OntologyParser, RDFOntology};
+use crate::io::ParserConfiguration;
+use crate::model::{Build, ForIRI, RcAnnotatedComponent, RcStr};
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::{Namespace, RDF};
+
+type Result<T> = std::result::Result<T, HornedError>;
+
+/// The maximum depth of terms defined using other terms.
+const MAX_TERM_DEPTH: usize = 16;
+
+#[derive(Clone, Debug, Default)]
+struct TermDefinition {
+    /// The IRI of the term, or `None` if it is explicitly unmapped
+    id: Option<String>,
+    /// `@id`, `@vocab` or the IRI of a datatype
+    type_mapping: Option<String>,
+    /// The language of string values; `Some(None)` removes the
+    /// default language
+    language: Option<Option<String>>,
+    list: bool,
+    language_map: bool,
+    reverse: bool,
+}
+
+impl TermDefinition {
+    fn parse(key: &str, value: &Value) -> Result<TermDefinition> {
+        let mut def = TermDefinition {
+            id: Some(key.to_string()),
+            ..Default::default()
+        };
+
+        match value {
+            Value::Null => def.id = None,
+            Value::String(s) => def.id = Some(s.clone()),
+            Value::Object(o) => {
+                if let Some(r) = o.get("@reverse") {
+                    def.reverse = true;
+                    def.id = Some(string(r, "@reverse")?.to_string());
+                } else {
+                    match o.get("@id") {
+                        Some(Value::Null) => def.id = None,
+                        Some(id) => def.id = Some(string(id, "@id")?.to_string()),
+                        None => {}
+                    }
+                }
+                if let Some(t) = o.get("@type") {
+                    def.type_mapping = Some(string(t, "@type")?.to_string());
+                }
+                if let Some(c) = o.get("@container") {
+                    for c in as_array(c) {
+                        match string(c, "@container")? {
+                            "@list" => def.list = true,
+                            "@language" => def.language_map = true,
+                            _ => {}
+                        }
+                    }
+                }
+                match o.get("@language") {
+                    Some(Value::Null) => def.language = Some(None),
+                    Some(l) => def.language = Some(Some(string(l, "@language")?.to_string())),
+                    None => {}
+                }
+            }
+            _ => {
+                return Err(HornedError::invalid(format!(
+                    "invalid definition of term: {}",
+                    key
+                )))
+            }
+        }
+        Ok(def)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Context {
+    base: Option<String>,
+    vocab: Option<String>,
+    language: Option<String>,
+    terms: HashMap<String, TermDefinition>,
+}
+
+impl Context {
+    /// Expand a term, compact IRI or relative IRI.
+    ///
+    /// Terms and `@vocab` are only used for `vocab` values, such as
+    /// properties and types; other relative IRIs are resolved against
+    /// `@base`. `None` is returned for `vocab` values which are not
+    /// mapped to an IRI.
+    fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        self.expand_iri_at(value, vocab, 0)
+    }
+
+    fn expand_iri_at(&self, value: &str, vocab: bool, depth: usize) -> Option<String> {
+        if value.starts_with('@') || depth > MAX_TERM_DEPTH {
+            return Some(value.to_string());
+        }
+
+        if vocab {
+            if let Some(def) = self.terms.get(value) {
+                return match &def.id {
+                    Some(id) if id != value => self.expand_iri_at(id, true, depth + 1),
+                    Some(_) => self.expand_prefixed(value, depth),
+                    None => None,
+                };
+            }
+        }
+
+        if value.contains(':') {
+            return self.expand_prefixed(value, depth);
+        }
+
+        if vocab {
+            self.vocab.as_ref().map(|v| format!("{}{}", v, value))
+        } else {
+            Some(self.resolve(value))
+        }
+    }
+
+    fn expand_prefixed(&self, value: &str, depth: usize) -> Option<String> {
+        match value.split_once(':') {
+            Some((prefix, suffix)) if prefix != "_" && !suffix.starts_with("//") => {
+                match self.terms.get(prefix).and_then(|def| def.id.as_ref()) {
+                    Some(id) if id != prefix => self
+                        .expand_iri_at(id, true, depth + 1)
+                        .map(|ns| format!("{}{}", ns, suffix)),
+                    _ => Some(value.to_string()),
+                }
+            }
+            _ => Some(value.to_string()),
+        }
+    }
+
+    fn resolve(&self, value: &str) -> String {
+        self.base
+            .as_ref()
+            .and_then(|base| oxiri::Iri::parse(base.as_str()).ok())
+            .and_then(|base| base.resolve(value).ok())
+            .map(|iri| iri.into_inner())
+            .unwrap_or_else(|| value.to_string())
+    }
+
+    /// Return the context which results from processing a local
+    /// context.
+    fn update(&self, local: &Value) -> Result<Context> {
+        let o = match local {
+            Value::Null => {
+                return Ok(Context {
+                    base: self.base.clone(),
+                    ..Default::default()
+                })
+            }
+            Value::Array(a) => return a.iter().try_fold(self.clone(), |c, l| c.update(l)),
+            Value::String(s) => {
+                return Err(HornedError::invalid(format!(
+                    "remote contexts are not supported: {}",
+                    s
+                )))
+            }
+            Value::Object(o) => o,
+            _ => return Err(HornedError::invalid("invalid local context")),
+        };
+
+        let mut ctx = self.clone();
+        match o.get("@base") {
+            Some(Value::Null) => ctx.base = None,
+            Some(b) => ctx.base = Some(ctx.resolve(string(b, "@base")?)),
+            None => {}
+        }
+        match o.get("@vocab") {
+            Some(Value::Null) => ctx.vocab = None,
+            Some(v) => {
+                let v = string(v, "@vocab")?;
+                ctx.vocab = Some(ctx.expand_iri(v, true).unwrap_or_else(|| ctx.resolve(v)));
+            }
+            None => {}
+        }
+        match o.get("@language") {
+            Some(Value::Null) => ctx.language = None,
+            Some(l) => ctx.language = Some(string(l, "@language")?.to_string()),
+            None => {}
+        }
+
+        let keys: Vec<_> = o.keys().filter(|k| !k.starts_with('@')).collect();
+        for key in &keys {
+            ctx.terms.insert(
+                key.to_string(),
+                TermDefinition::parse(key, &o[key.as_str()])?,
+            );
+        }
+
+        // Terms may be defined using others from the same context, so
+        // are only expanded once all have been added
+        let expanded: Vec<_> = keys
+            .iter()
+            .map(|key| {
+                let def = &ctx.terms[key.as_str()];
+                let id = def.id.as_ref().and_then(|id| {
+                    if id == *key {
+                        ctx.expand_prefixed(id, 0)
+                    } else {
+                        ctx.expand_iri(id, true)
+                    }
+                });
+                let type_mapping = def
+                    .type_mapping
+                    .as_ref()
+                    .and_then(|t| ctx.expand_iri(t, true));
+                (key.to_string(), id, type_mapping)
+            })
+            .collect();
+        for (key, id, type_mapping) in expanded {
+            let def = ctx.terms.get_mut(&key).unwrap();
+            def.id = id;
+            def.type_mapping = type_mapping;
+        }
+
+        Ok(ctx)
+    }
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(a) => a,
+        v => std::slice::from_ref(v),
+    }
+}
+
+fn string<'a>(value: &'a Value, keyword: &str) -> Result<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| HornedError::invalid(format!("the value of {} must be a string", keyword)))
+}
+
+/// The canonical lexical form of an `xsd:double`, as used by JSON-LD.
+fn canonical_double(f: f64) -> String {
+    let s = format!("{:E}", f);
+    match s.split_once('E') {
+        Some((mantissa, exponent)) if !mantissa.contains('.') => {
+            format!("{}.0E{}", mantissa, exponent)
+        }
+        _ => s,
+    }
+}
+
+#[derive(Clone, Debug)]
+enum OwnedNode {
+    Named(String),
+    Blank(String),
+}
+
+impl OwnedNode {
+    fn subject(&self) -> Subject<'_> {
+        match self {
+            OwnedNode::Named(iri) => NamedNode { iri }.into(),
+            OwnedNode::Blank(id) => BlankNode { id }.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum OwnedTerm {
+    Node(OwnedNode),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+impl OwnedTerm {
+    fn typed(value: String, datatype: String) -> OwnedTerm {
+        OwnedTerm::Literal {
+            value,
+            datatype: Some(datatype),
+            language: None,
+        }
+    }
+
+    fn term(&self) -> Term<'_> {
+        match self {
+            OwnedTerm::Node(OwnedNode::Named(iri)) => NamedNode { iri }.into(),
+            OwnedTerm::Node(OwnedNode::Blank(id)) => BlankNode { id }.into(),
+            OwnedTerm::Literal {
+                value,
+                datatype: Some(datatype),
+                ..
+            } => Literal::Typed {
+                value,
+                datatype: NamedNode { iri: datatype },
+            }
+            .into(),
+            OwnedTerm::Literal {
+                value,
+                language: Some(language),
+                ..
+            } => Literal::LanguageTaggedString { value, language }.into(),
+            OwnedTerm::Literal { value, .. } => Literal::Simple { value }.into(),
+        }
+    }
+}
+
+struct OwnedTriple(OwnedNode, String, OwnedTerm);
+
+/// Expands a JSON-LD document into triples.
+#[derive(Default)]
+struct Expander {
+    triples: Vec<OwnedTriple>,
+    bnode: usize,
+    /// The blank nodes given for the labels of the document
+    labels: HashMap<String, OwnedNode>,
+}
+
+impl Expander {
+    fn fresh(&mut self) -> OwnedNode {
+        self.bnode += 1;
+        OwnedNode::Blank(format!("genid{}", self.bnode))
+    }
+
+    /// Return the node for `id`. Blank node labels of the document
+    /// are relabelled, so that they cannot clash with fresh nodes.
+    fn id(&mut self, id: String) -> OwnedNode {
+        match id.strip_prefix("_:") {
+            Some(label) => {
+                if let Some(node) = self.labels.get(label) {
+                    return node.clone();
+                }
+                let node = self.fresh();
+                self.labels.insert(label.to_string(), node.clone());
+                node
+            }
+            None => OwnedNode::Named(id),
+        }
+    }
+
+    fn push(&mut self, subject: &OwnedNode, predicate: &str, object: OwnedTerm) {
+        self.triples
+            .push(OwnedTriple(subject.clone(), predicate.to_string(), object));
+    }
+
+    fn document(&mut self, ctx: &Context, value: &Value) -> Result<()> {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.document(ctx, item)?;
+                }
+            }
+            Value::Object(o) => {
+                self.node(ctx, o)?;
+            }
+            _ => {
+                return Err(HornedError::invalid(
+                    "a JSON-LD document must be an object or an array",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn node(&mut self, ctx: &Context, o: &Map<String, Value>) -> Result<OwnedNode> {
+        let local;
+        let ctx = match o.get("@context") {
+            Some(c) => {
+                local = ctx.update(c)?;
+                &local
+            }
+            None => ctx,
+        };
+
+        let subject = match o.get("@id") {
+            Some(id) => self.id(ctx.expand_iri(string(id, "@id")?, false).unwrap()),
+            None => self.fresh(),
+        };
+        self.properties(ctx, &subject, o)?;
+        Ok(subject)
+    }
+
+    fn properties(
+        &mut self,
+        ctx: &Context,
+        subject: &OwnedNode,
+        o: &Map<String, Value>,
+    ) -> Result<()> {
+        for (key, value) in o {
+            match key.as_str() {
+                "@type" => {
+                    for t in as_array(value) {
+                        if let Some(t) = ctx.expand_iri(string(t, "@type")?, true) {
+                            let t = OwnedTerm::Node(self.id(t));
+                            self.push(subject, RDF::Type.as_ref(), t);
+                        }
+                    }
+                }
+                "@graph" | "@included" => {
+                    for item in as_array(value) {
+                        if let Value::Object(o) = item {
+                            self.node(ctx, o)?;
+                        }
+                    }
+                }
+                "@nest" => {
+                    for item in as_array(value) {
+                        if let Value::Object(o) = item {
+                            self.properties(ctx, subject, o)?;
+                        }
+                    }
+                }
+                "@reverse" => {
+                    if let Value::Object(o) = value {
+                        for (key, value) in o {
+                            if let Some(iri) = ctx.expand_iri(key, true) {
+                                for object in self.values(ctx, key, value)? {
+                                    if let OwnedTerm::Node(n) = object {
+                                        self.push(&n, &iri, OwnedTerm::Node(subject.clone()));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                k if k.starts_with('@') => {}
+                _ => {
+                    // Properties which do not expand to an absolute
+                    // IRI are dropped
+                    let iri = match ctx.expand_iri(key, true) {
+                        Some(iri) if iri.contains(':') && !iri.starts_with("_:") => iri,
+                        _ => continue,
+                    };
+                    let reverse = ctx.terms.get(key).is_some_and(|def| def.reverse);
+                    for object in self.values(ctx, key, value)? {
+                        if !reverse {
+                            self.push(subject, &iri, object);
+                        } else if let OwnedTerm::Node(n) = object {
+                            self.push(&n, &iri, OwnedTerm::Node(subject.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Expand the value of the property `key`.
+    fn values(&mut self, ctx: &Context, key: &str, value: &Value) -> Result<Vec<OwnedTerm>> {
+        let def = ctx.terms.get(key).cloned().unwrap_or_default();
+        match value {
+            Value::Array(items) if def.list => Ok(vec![self.list(ctx, &def, items)?]),
+            Value::Object(o) if def.language_map && !o.contains_key("@value") => {
+                let mut v = vec![];
+                for (language, values) in o {
+                    for s in as_array(values) {
+                        v.push(OwnedTerm::Literal {
+                            value: string(s, "a language map")?.to_string(),
+                            datatype: None,
+                            language: Some(language.clone()),
+                        });
+                    }
+                }
+                Ok(v)
+            }
+            _ => self.value(ctx, &def, value),
+        }
+    }
+
+    fn value(
+        &mut self,
+        ctx: &Context,
+        def: &TermDefinition,
+        value: &Value,
+    ) -> Result<Vec<OwnedTerm>> {
+        Ok(match value {
+            Value::Null => vec![],
+            Value::Array(items) => {
+                let mut v = vec![];
+                for item in items {
+                    v.extend(self.value(ctx, def, item)?);
+                }
+                v
+            }
+            Value::String(s) => vec![self.string(ctx, def, s)],
+            Value::Number(_) | Value::Bool(_) => {
+                let datatype = def.type_mapping.clone().filter(|t| !t.starts_with('@'));
+                vec![native(value, datatype)]
+            }
+            Value::Object(o) => {
+                if let Some(v) = o.get("@value") {
+                    self.value_object(ctx, o, v)?.into_iter().collect()
+                } else if let Some(l) = o.get("@list") {
+                    vec![self.list(ctx, def, as_array(l))?]
+                } else if let Some(s) = o.get("@set") {
+                    self.value(ctx, def, s)?
+                } else {
+                    vec![OwnedTerm::Node(self.node(ctx, o)?)]
+                }
+            }
+        })
+    }
+
+    fn string(&mut self, ctx: &Context, def: &TermDefinition, s: &str) -> OwnedTerm {
+        match def.type_mapping.as_deref() {
+            Some("@id") => OwnedTerm::Node(self.id(ctx.expand_iri(s, false).unwrap())),
+            Some("@vocab") => {
+                OwnedTerm::Node(self.id(ctx.expand_iri(s, true).unwrap_or_else(|| ctx.resolve(s))))
+            }
+            Some(dt) if !dt.starts_with('@') => OwnedTerm::typed(s.to_string(), dt.to_string()),
+            _ => OwnedTerm::Literal {
+                value: s.to_string(),
+                datatype: None,
+                language: match &def.language {
+                    Some(l) => l.clone(),
+                    None => ctx.language.clone(),
+                },
+            },
+        }
+    }
+
+    fn value_object(
+        &mut self,
+        ctx: &Context,
+        o: &Map<String, Value>,
+        value: &Value,
+    ) -> Result<Option<OwnedTerm>> {
+        let datatype = match o.get("@type") {
+            Some(t) => ctx.expand_iri(string(t, "@type")?, true),
+            None => None,
+        };
+        Ok(match value {
+            Value::Null => None,
+            Value::String(s) => Some(OwnedTerm::Literal {
+                value: s.clone(),
+                language: match (&datatype, o.get("@language")) {
+                    (None, Some(l)) => Some(string(l, "@language")?.to_string()),
+                    _ => None,
+                },
+                datatype,
+            }),
+            Value::Number(_) | Value::Bool(_) => Some(native(value, datatype)),
+            _ => return Err(HornedError::invalid("invalid @value")),
+        })
+    }
+
+    fn list(&mut self, ctx: &Context, def: &TermDefinition, items: &[Value]) -> Result<OwnedTerm> {
+        let item_def = TermDefinition {
+            list: false,
+            ..def.clone()
+        };
+        let mut values = vec![];
+        for item in items {
+            values.extend(self.value(ctx, &item_def, item)?);
+        }
+
+        let mut rest = OwnedNode::Named(RDF::Nil.as_ref().to_string());
+        for value in values.into_iter().rev() {
+            let node = self.fresh();
+            self.push(&node, RDF::First.as_ref(), value);
+            self.push(&node, RDF::Rest.as_ref(), OwnedTerm::Node(rest));
+            rest = node;
+        }
+        Ok(OwnedTerm::Node(rest))
+    }
+}
+
+/// Convert a JSON number or boolean into a literal.
+fn native(value: &Value, datatype: Option<String>) -> OwnedTerm {
+    let xsd = |local: &str| format!("{}{}", Namespace::XSD.as_ref(), local);
+    let (lexical, default) = match value {
+        Value::Bool(b) => (b.to_string(), xsd("boolean")),
+        Value::Number(n) => number(n, xsd),
+        _ => unreachable!(),
+    };
+    OwnedTerm::typed(lexical, datatype.unwrap_or(default))
+}
+
+fn number(n: &Number, xsd: impl Fn(&str) -> String) -> (String, String) {
+    if n.is_i64() || n.is_u64() {
+        (n.to_string(), xsd("integer"))
+    } else {
+        (canonical_double(n.as_f64().unwrap()), xsd("double"))
+    }
+}
+
+/// A `rio` triples parser for JSON-LD documents.
+///
+/// The whole document is read and expanded by the first call to
+/// `parse_step`.
+pub struct JsonLdParser<R: BufRead> {
+    read: R,
+    base_iri: Option<String>,
+    end: bool,
+}
+
+impl<R: BufRead> JsonLdParser<R> {
+    /// Create a parser, resolving relative IRIs against `base_iri`
+    /// unless the document sets `@base`.
+    pub fn new(read: R, base_iri: Option<&str>) -> JsonLdParser<R> {
+        JsonLdParser {
+            read,
+            base_iri: base_iri.map(String::from),
+            end: false,
+        }
+    }
+
+    fn expand(&mut self) -> Result<Vec<OwnedTriple>> {
+        self.end = true;
+        let value: Value = serde_json::from_reader(&mut self.read)?;
+        let ctx = Context {
+            base: self.base_iri.clone(),
+            ..Default::default()
+        };
+
+        let mut expander = Expander::default();
+        expander.document(&ctx, &value)?;
+        Ok(expander.triples)
+    }
+}
+
+impl<R: BufRead> TriplesParser for JsonLdParser<R> {
+    type Error = HornedError;
+
+    fn parse_step<E: From<HornedError>>(
+        &mut self,
+        on_triple: &mut impl FnMut(Triple<'_>) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        for OwnedTriple(subject, predicate, object) in self.expand()? {
+            on_triple(Triple {
+                subject: subject.subject(),
+                predicate: NamedNode { iri: &predicate },
+                object: object.term(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn is_end(&self) -> bool {
+        self.end
+    }
+}
+
+/// Return an `OntologyParser` which reads triples from a JSON-LD
+/// document.
+pub fn parser_with_build<'b, A: ForIRI, AA: ForIndex<A>, O: RDFOntology<A, AA>, R: BufRead>(
+    bufread: &mut R,
+    build: &'b Build<A>,
+    config: ParserConfiguration,
+) -> OntologyParser<'b, A, AA, O> {
+    OntologyParser::from_triples_parser(build, JsonLdParser::new(bufread, None), config)
+}
+
+pub fn read_with_build<A: ForIRI, AA: ForIndex<A>, R: BufRead>(
+    bufread: &mut R,
+    build: &Build<A>,
+    config: ParserConfiguration,
+) -> Result<(ConcreteRDFOntology<A, AA>, IncompleteParse<A>)> {
+    parser_with_build(bufread, build, config).parse()
+}
+
+pub fn read<R: BufRead>(
+    bufread: &mut R,
+    config: ParserConfiguration,
+) -> Result<(
+    ConcreteRDFOntology<RcStr, RcAnnotatedComponent>,
+    IncompleteParse<RcStr>,
+)> {
+    let b = Build::new_rc();
+    read_with_build(bufread, &b, config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::*;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+
+    fn read_ok(jsonld: &str) -> SetOntology<RcStr> {
+        let (ont, incomp) = read(&mut jsonld.as_bytes(), Default::default()).unwrap();
+        assert!(incomp.is_complete());
+        ont.into()
+    }
+
+    fn triples(jsonld: &str) -> Vec<String> {
+        let mut parser = JsonLdParser::new(jsonld.as_bytes(), Some("http://example.com/doc"));
+        let mut v = vec![];
+        parser
+            .parse_all(&mut |t| -> Result<()> {
+                v.push(t.to_string());
+                Ok(())
+            })
+            .unwrap();
+        v.sort();
+        v
+    }
+
+    const COMPACTED: &str = r#"{
+  "@context": {
+    "owl": "http://www.w3.org/2002/07/owl#",
+    "rdfs": "http://www.w3.org/2000/01/rdf-schema#",
+    "ex": "http://example.com/",
+    "subClassOf": {"@id": "rdfs:subClassOf", "@type": "@id"},
+    "label": {"@id": "rdfs:label", "@language": "en"}
+  },
+  "@graph": [
+    {"@id": "ex:o", "@type": "owl:Ontology"},
+    {"@id": "ex:A", "@type": "owl:Class", "label": "A"},
+    {"@id": "ex:B", "@type": "owl:Class", "subClassOf": "ex:A"}
+  ]
+}"#;
+
+    const EXPANDED: &str = r#"[
+  {"@id": "http://example.com/o",
+   "@type": ["http://www.w3.org/2002/07/owl#Ontology"]},
+  {"@id": "http://example.com/A",
+   "@type": ["http://www.w3.org/2002/07/owl#Class"],
+   "http://www.w3.org/2000/01/rdf-schema#label": [{"@value": "A", "@language": "en"}]},
+  {"@id": "http://example.com/B",
+   "@type": ["http://www.w3.org/2002/07/owl#Class"],
+   "http://www.w3.org/2000/01/rdf-schema#subClassOf": [{"@id": "http://example.com/A"}]}
+]"#;
+
+    #[test]
+    fn compacted_and_expanded() {
+        let compacted = read_ok(COMPACTED);
+        let expanded = read_ok(EXPANDED);
+        assert_eq!(compacted, expanded);
+
+        let b = Build::new_rc();
+        assert!(compacted.i().contains(
+            &SubClassOf::new(
+                b.class("http://example.com/A").into(),
+                b.class("http://example.com/B").into()
+            )
+            .into()
+        ));
+    }
+
+    #[test]
+    fn lists() {
+        let ont = read_ok(
+            r#"{
+  "@context": {"owl": "http://www.w3.org/2002/07/owl#", "@vocab": "http://example.com/"},
+  "@graph": [
+    {"@id": "http://example.com/o", "@type": "owl:Ontology"},
+    {"@id": "http://example.com/A", "@type": "owl:Class"},
+    {"@id": "http://example.com/B", "@type": "owl:Class"},
+    {"@id": "http://example.com/C", "@type": "owl:Class",
+     "owl:equivalentClass": {
+       "@type": "owl:Class",
+       "owl:intersectionOf": {"@list": [{"@id": "http://example.com/A"},
+                                        {"@id": "http://example.com/B"}]}
+     }}
+  ]
+}"#,
+        );
+
+        let b = Build::new_rc();
+        assert!(ont.i().contains(
+            &EquivalentClasses(vec![
+                b.class("http://example.com/C").into(),
+                ClassExpression::ObjectIntersectionOf(vec![
+                    b.class("http://example.com/A").into(),
+                    b.class("http://example.com/B").into()
+                ])
+            ])
+            .into()
+        ));
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(
+            triples(
+                r#"{
+  "@context": {
+    "@vocab": "http://example.com/",
+    "@base": "http://example.com/base/",
+    "@language": "en",
+    "n": {"@id": "http://example.com/n", "@type": "http://www.w3.org/2001/XMLSchema#decimal"},
+    "r": {"@reverse": "http://example.com/p"},
+    "plain": {"@id": "http://example.com/plain", "@language": null}
+  },
+  "@id": "a",
+  "s": "hello",
+  "plain": "hi",
+  "i": 1,
+  "d": 1.5,
+  "n": 2,
+  "b": true,
+  "r": {"@id": "c"},
+  "t": {"@value": "x", "@type": "http://example.com/dt"}
+}"#
+            ),
+            vec![
+                r#"<http://example.com/base/a> <http://example.com/b> "true"^^<http://www.w3.org/2001/XMLSchema#boolean>"#,
+                r#"<http://example.com/base/a> <http://example.com/d> "1.5E0"^^<http://www.w3.org/2001/XMLSchema#double>"#,
+                r#"<http://example.com/base/a> <http://example.com/i> "1"^^<http://www.w3.org/2001/XMLSchema#integer>"#,
+                r#"<http://example.com/base/a> <http://example.com/n> "2"^^<http://www.w3.org/2001/XMLSchema#decimal>"#,
+                r#"<http://example.com/base/a> <http://example.com/plain> "hi""#,
+                r#"<http://example.com/base/a> <http://example.com/s> "hello"@en"#,
+                r#"<http://example.com/base/a> <http://example.com/t> "x"^^<http://example.com/dt>"#,
+                r#"<http://example.com/base/c> <http://example.com/p> <http://example.com/base/a>"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_node_labels() {
+        // The document's labels do not clash with fresh blank nodes
+        assert_eq!(
+            triples(
+                r#"{
+  "@context": {"@vocab": "http://example.com/"},
+  "@id": "_:genid2",
+  "p": {"q": "x"},
+  "r": {"@id": "_:genid2"}
+}"#
+            ),
+            vec![
+                r#"_:genid1 <http://example.com/p> _:genid2"#,
+                r#"_:genid1 <http://example.com/r> _:genid1"#,
+                r#"_:genid2 <http://example.com/q> "x""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn remote_context() {
+        let r = read(
+            &mut r#"{"@context": "http://example.com/context.jsonld"}"#.as_bytes(),
+            Default::default(),
+        );
+        assert!(matches!(r, Err(HornedError::ValidityError(_, _))));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let r = read(&mut r#"{"@id": "#.as_bytes(), Default::default());
+        assert!(matches!(r, Err(HornedError::ParserError(_, _))));
+    }
+}
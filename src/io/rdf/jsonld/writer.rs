@@ -0,0 +1,367 @@
+//! Write an ontology as a compacted JSON-LD document.
+//!
+//! Triples are generated in the same way as for the RDF/XML writer.
+//! The `@context` of the document defines one term for each prefix of
+//! the `PrefixMapping`, which are used to compact IRIs. Each subject
+//! is written as a node object in the `@graph`, and blank nodes are
+//! referenced by their label rather than nested.
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use curie::PrefixMapping;
+use pretty_rdf::{PLiteral, PNamedNode, PSubject, PTerm, PTriple, RdfXmlFormatter};
+use serde_json::{Map, Value};
+
+use crate::error::HornedError;
+use crate::io::rdf::turtle::writer::default_mapping;
+use crate::io::rdf::writer::{blank_node_label, render_ontology};
+use crate::model::ForIRI;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::{Namespace, RDF};
+
+/// Write a component mapped ontology as JSON-LD, using the given
+/// `PrefixMapping`.
+///
+/// If no mapping is given, prefixes for the OWL, RDF, RDFS and XSD
+/// namespaces are used.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+    mapping: Option<&PrefixMapping>,
+) -> Result<(), HornedError> {
+    let default_mapper = default_mapping();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+
+    let mapping = terms(mapping);
+    let mut f = JsonLdFormatter::new(write, &mapping);
+    render_ontology(&mut f, ont)?;
+    f.finish()?;
+
+    Ok(())
+}
+
+/// Return `mapping` with the empty prefix, which is not a valid
+/// JSON-LD term, renamed to an unused term.
+fn terms(mapping: &PrefixMapping) -> PrefixMapping {
+    let mut terms = PrefixMapping::default();
+    for (prefix, ns) in mapping.mappings() {
+        let prefix = if prefix.is_empty() {
+            std::iter::once("ns".to_string())
+                .chain((1..).map(|n| format!("ns{}", n)))
+                .find(|t| mapping.mappings().all(|(p, _)| p != t))
+                .unwrap()
+        } else {
+            prefix.clone()
+        };
+        terms
+            .add_prefix(&prefix, ns)
+            .expect("prefix should be valid");
+    }
+    terms
+}
+
+/// Return a JSON-LD context which defines a term for each prefix of
+/// `mapping`. The empty prefix is given a non-empty term.
+pub fn context(mapping: &PrefixMapping) -> Value {
+    Value::Object(
+        terms(mapping)
+            .mappings()
+            .map(|(prefix, ns)| (prefix.clone(), Value::String(ns.clone())))
+            .collect(),
+    )
+}
+
+type PredicateObjects<A> = Vec<(PNamedNode<A>, PTerm<A>)>;
+
+/// Collects triples and writes them as JSON-LD when finished.
+struct JsonLdFormatter<'a, A: ForIRI, W: Write> {
+    write: W,
+    mapping: &'a PrefixMapping,
+    seen: HashSet<PTriple<A>>,
+    subjects: Vec<PSubject<A>>,
+    by_subject: HashMap<PSubject<A>, PredicateObjects<A>>,
+}
+
+impl<'a, A: ForIRI, W: Write> JsonLdFormatter<'a, A, W> {
+    fn new(write: W, mapping: &'a PrefixMapping) -> Self {
+        JsonLdFormatter {
+            write,
+            mapping,
+            seen: HashSet::new(),
+            subjects: vec![],
+            by_subject: HashMap::new(),
+        }
+    }
+
+    /// Compact an IRI using the longest matching prefix.
+    fn compact(&self, iri: &str) -> String {
+        self.mapping
+            .mappings()
+            .filter(|(_, ns)| iri.starts_with(ns.as_str()))
+            // A suffix starting with `//` would be read as an IRI
+            .filter(|(_, ns)| !iri[ns.len()..].starts_with("//"))
+            .max_by_key(|(_, ns)| ns.len())
+            .map(|(prefix, ns)| format!("{}:{}", prefix, &iri[ns.len()..]))
+            .unwrap_or_else(|| iri.to_string())
+    }
+
+    fn subject(&self, s: &PSubject<A>) -> String {
+        match s {
+            PSubject::NamedNode(nn) => self.compact(nn.iri.as_ref()),
+            PSubject::BlankNode(bn) => format!("_:{}", blank_node_label(bn.id.as_ref())),
+        }
+    }
+
+    fn object(&self, o: &PTerm<A>) -> Value {
+        let mut m = Map::new();
+        match o {
+            PTerm::NamedNode(nn) => {
+                m.insert("@id".into(), self.compact(nn.iri.as_ref()).into());
+            }
+            PTerm::BlankNode(bn) => {
+                m.insert(
+                    "@id".into(),
+                    format!("_:{}", blank_node_label(bn.id.as_ref())).into(),
+                );
+            }
+            PTerm::Literal(PLiteral::Simple { value }) => return value.as_ref().into(),
+            PTerm::Literal(PLiteral::LanguageTaggedString { value, language }) => {
+                m.insert("@value".into(), value.as_ref().into());
+                m.insert("@language".into(), language.as_ref().into());
+            }
+            PTerm::Literal(PLiteral::Typed { value, datatype }) => {
+                let datatype = datatype.iri.as_ref();
+                if datatype.strip_prefix(Namespace::XSD.as_ref()) == Some("string") {
+                    return value.as_ref().into();
+                }
+                m.insert("@value".into(), value.as_ref().into());
+                m.insert("@type".into(), self.compact(datatype).into());
+            }
+        }
+        Value::Object(m)
+    }
+
+    fn node(&self, s: &PSubject<A>) -> Value {
+        let mut node = Map::new();
+        node.insert("@id".into(), self.subject(s).into());
+
+        let mut types = vec![];
+        let mut properties: Map<String, Value> = Map::new();
+        for (p, o) in &self.by_subject[s] {
+            match o {
+                PTerm::NamedNode(nn) if p.iri.as_ref() == RDF::Type.as_ref() => {
+                    types.push(Value::String(self.compact(nn.iri.as_ref())))
+                }
+                _ => {
+                    let values = properties
+                        .entry(self.compact(p.iri.as_ref()))
+                        .or_insert_with(|| Value::Array(vec![]));
+                    if let Value::Array(v) = values {
+                        v.push(self.object(o));
+                    }
+                }
+            }
+        }
+
+        if !types.is_empty() {
+            node.insert("@type".into(), single(types));
+        }
+        for (p, values) in properties {
+            if let Value::Array(v) = values {
+                node.insert(p, single(v));
+            }
+        }
+        Value::Object(node)
+    }
+
+    fn document(&self) -> Value {
+        let mut doc = Map::new();
+        doc.insert("@context".into(), context(self.mapping));
+        doc.insert(
+            "@graph".into(),
+            Value::Array(self.subjects.iter().map(|s| self.node(s)).collect()),
+        );
+        Value::Object(doc)
+    }
+}
+
+/// Compact an array with a single value to the value.
+fn single(mut v: Vec<Value>) -> Value {
+    if v.len() == 1 {
+        v.pop().unwrap()
+    } else {
+        Value::Array(v)
+    }
+}
+
+impl<'a, A: ForIRI, W: Write> RdfXmlFormatter<A, W> for JsonLdFormatter<'a, A, W> {
+    fn format(&mut self, triple: PTriple<A>) -> Result<(), std::io::Error> {
+        if !self.seen.insert(triple.clone()) {
+            return Ok(());
+        }
+
+        let PTriple {
+            subject,
+            predicate,
+            object,
+        } = triple;
+        if !self.by_subject.contains_key(&subject) {
+            self.subjects.push(subject.clone());
+        }
+        self.by_subject
+            .entry(subject)
+            .or_default()
+            .push((predicate, object));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W, std::io::Error> {
+        let doc = self.document();
+        serde_json::to_writer_pretty(&mut self.write, &doc)?;
+        writeln!(self.write)?;
+        Ok(self.write)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::io::rdf::reader::ConcreteRDFOntology;
+    use crate::model::{MutableOntology, RcAnnotatedComponent, RcStr};
+    use crate::normalize::normalize;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn roundtrip(resource: &str) -> (SetOntology<RcStr>, SetOntology<RcStr>, String) {
+        let (ont, _): (ConcreteRDFOntology<RcStr, RcAnnotatedComponent>, _) =
+            crate::io::rdf::turtle::reader::read(
+                &mut slurp::read_all_to_string(resource).unwrap().as_bytes(),
+                Default::default(),
+            )
+            .unwrap();
+        let so: SetOntology<_> = ont.into();
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = so.clone().into();
+
+        let mut writer = Vec::new();
+        write(&mut writer, &amo, None).unwrap();
+        let jsonld = String::from_utf8(writer).unwrap();
+
+        let (ont2, incomp) =
+            crate::io::rdf::jsonld::reader::read(&mut jsonld.as_bytes(), Default::default())
+                .unwrap();
+        assert!(incomp.is_complete(), "{}", jsonld);
+        (so, ont2.into(), jsonld)
+    }
+
+    #[test_resources("src/ont/owl-ttl/*.ttl")]
+    fn roundtrip_jsonld(resource: &str) {
+        let (ont, ont2, _) = roundtrip(resource);
+        let ont = normalize(ont.into_iter().collect());
+        let ont2 = normalize(ont2.into_iter().collect());
+        assert_eq!(ont, ont2);
+    }
+
+    #[test]
+    fn compacted() {
+        let (_, _, jsonld) = roundtrip("src/ont/owl-ttl/subclass.ttl");
+        let doc: Value = serde_json::from_str(&jsonld).unwrap();
+
+        assert_eq!(
+            doc["@context"]["owl"],
+            Value::String("http://www.w3.org/2002/07/owl#".to_string())
+        );
+        let graph = doc["@graph"].as_array().unwrap();
+        assert!(graph
+            .iter()
+            .any(|n| n["@type"] == Value::String("owl:Class".to_string())));
+    }
+
+    #[test]
+    fn prefix_mapping() {
+        let mut mapping = PrefixMapping::default();
+        mapping.add_prefix("ex", "http://example.com/").unwrap();
+        assert_eq!(
+            context(&mapping),
+            serde_json::json!({"ex": "http://example.com/"})
+        );
+
+        // The empty prefix is not a valid term
+        mapping.add_prefix("", "http://example.com/o#").unwrap();
+        mapping.add_prefix("ns", "http://example.com/ns#").unwrap();
+        assert_eq!(
+            context(&mapping),
+            serde_json::json!({
+                "ex": "http://example.com/",
+                "ns": "http://example.com/ns#",
+                "ns1": "http://example.com/o#"
+            })
+        );
+    }
+
+    #[test]
+    fn empty_prefix() {
+        let b = crate::model::Build::new_rc();
+        let mut so: SetOntology<RcStr> = SetOntology::new();
+        so.insert(crate::model::DeclareClass(
+            b.class("http://example.com/o#A"),
+        ));
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = so.clone().into();
+
+        let mut mapping = PrefixMapping::default();
+        mapping.add_prefix("", "http://example.com/o#").unwrap();
+        let mut writer = Vec::new();
+        write(&mut writer, &amo, Some(&mapping)).unwrap();
+        let jsonld = String::from_utf8(writer).unwrap();
+        let doc: Value = serde_json::from_str(&jsonld).unwrap();
+
+        assert!(doc["@context"].get("").is_none());
+        assert!(doc["@graph"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|n| n["@id"] == Value::String("ns:A".to_string())));
+
+        let (ont2, _) =
+            crate::io::rdf::jsonld::reader::read(&mut jsonld.as_bytes(), Default::default())
+                .unwrap();
+        let ont2: SetOntology<RcStr> = ont2.into();
+        assert!(so.iter().all(|ac| ont2.i().contains(ac)));
+    }
+
+    #[test]
+    fn anonymous_individual() {
+        let (so, _): (SetOntology<RcStr>, _) = crate::io::ofn::reader::read(
+            "Prefix(:=<http://example.com/>)
+Ontology(<http://example.com/o>
+Declaration(Class(:A))
+AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#seeAlso> :A _:x)
+)"
+            .as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = so.clone().into();
+        let mut writer = Vec::new();
+        write(&mut writer, &amo, None).unwrap();
+        let jsonld = String::from_utf8(writer).unwrap();
+        assert!(jsonld.contains("\"_:x\""), "{}", jsonld);
+        assert!(!jsonld.contains("_:_:"), "{}", jsonld);
+
+        let (ont2, incomp) =
+            crate::io::rdf::jsonld::reader::read(&mut jsonld.as_bytes(), Default::default())
+                .unwrap();
+        assert!(incomp.is_complete());
+        let ont2: SetOntology<RcStr> = ont2.into();
+        assert_eq!(
+            normalize(so.into_iter().collect()),
+            normalize(ont2.into_iter().collect())
+        );
+    }
+}
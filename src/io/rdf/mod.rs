@@ -1,4 +1,10 @@
-//! [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/) syntax for OWL.
+//! RDF syntaxes for OWL: [RDF/XML](https://www.w3.org/TR/rdf-syntax-grammar/),
+//! [Turtle](https://www.w3.org/TR/turtle/),
+//! [N-Triples](https://www.w3.org/TR/n-triples/) and
+//! [JSON-LD](https://www.w3.org/TR/json-ld11/).
 pub mod closure_reader;
+pub mod jsonld;
+pub mod ntriples;
 pub mod reader;
+pub mod turtle;
 pub mod writer;
@@ -0,0 +1,9 @@
+//! [N-Triples](https://www.w3.org/TR/n-triples/) and
+//! [N-Quads](https://www.w3.org/TR/n-quads/) syntaxes for OWL.
+//!
+//! These line-based syntaxes are mapped to OWL using the same
+//! triple-to-axiom machinery as [RDF/XML](crate::io::rdf). N-Quads
+//! can only be read; the graph of each quad is either ignored or
+//! used to select the triples of a single graph.
+pub mod reader;
+pub mod writer;
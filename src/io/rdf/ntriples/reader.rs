@@ -0,0 +1,191 @@
+//! Read an ontology from an N-Triples or N-Quads document.
+use std::io::BufRead;
+
+use rio_api::model::GraphName;
+use rio_turtle::{NQuadsParser, NTriplesParser};
+
+use crate::error::HornedError;
+use crate::io::rdf::reader::{ConcreteRDFOntology, IncompleteParse, OntologyParser, RDFOntology};
+use crate::io::ParserConfiguration;
+use crate::model::{Build, ForIRI, RcAnnotatedComponent, RcStr};
+use crate::ontology::indexed::ForIndex;
+
+/// The graph of an N-Quads document from which triples are read.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum GraphSelection {
+    /// Read the triples of all graphs, ignoring the graph name.
+    #[default]
+    All,
+    /// Read only the triples of the default graph.
+    Default,
+    /// Read only the triples of the graph with the given IRI.
+    Named(String),
+}
+
+impl GraphSelection {
+    fn selects(&self, graph_name: Option<&GraphName>) -> bool {
+        match (self, graph_name) {
+            (GraphSelection::All, _) => true,
+            (GraphSelection::Default, None) => true,
+            (GraphSelection::Named(iri), Some(GraphName::NamedNode(nn))) => nn.iri == iri,
+            _ => false,
+        }
+    }
+}
+
+/// Return an `OntologyParser` which reads triples from an N-Triples
+/// document.
+pub fn parser_with_build<'b, A: ForIRI, AA: ForIndex<A>, O: RDFOntology<A, AA>, R: BufRead>(
+    bufread: &mut R,
+    build: &'b Build<A>,
+    config: ParserConfiguration,
+) -> OntologyParser<'b, A, AA, O> {
+    OntologyParser::from_triples_parser(build, NTriplesParser::new(bufread), config)
+}
+
+pub fn read_with_build<A: ForIRI, AA: ForIndex<A>, R: BufRead>(
+    bufread: &mut R,
+    build: &Build<A>,
+    config: ParserConfiguration,
+) -> Result<(ConcreteRDFOntology<A, AA>, IncompleteParse<A>), HornedError> {
+    parser_with_build(bufread, build, config).parse()
+}
+
+pub fn read<R: BufRead>(
+    bufread: &mut R,
+    config: ParserConfiguration,
+) -> Result<
+    (
+        ConcreteRDFOntology<RcStr, RcAnnotatedComponent>,
+        IncompleteParse<RcStr>,
+    ),
+    HornedError,
+> {
+    let b = Build::new_rc();
+    read_with_build(bufread, &b, config)
+}
+
+/// Return an `OntologyParser` which reads the triples of the
+/// selected graph from an N-Quads document.
+pub fn quads_parser_with_build<
+    'b,
+    A: ForIRI,
+    AA: ForIndex<A>,
+    O: RDFOntology<A, AA>,
+    R: BufRead,
+>(
+    bufread: &mut R,
+    build: &'b Build<A>,
+    graph: &GraphSelection,
+    config: ParserConfiguration,
+) -> OntologyParser<'b, A, AA, O> {
+    OntologyParser::from_quads_parser(
+        build,
+        NQuadsParser::new(bufread),
+        |g| graph.selects(g),
+        config,
+    )
+}
+
+pub fn read_quads_with_build<A: ForIRI, AA: ForIndex<A>, R: BufRead>(
+    bufread: &mut R,
+    build: &Build<A>,
+    graph: &GraphSelection,
+    config: ParserConfiguration,
+) -> Result<(ConcreteRDFOntology<A, AA>, IncompleteParse<A>), HornedError> {
+    quads_parser_with_build(bufread, build, graph, config).parse()
+}
+
+pub fn read_quads<R: BufRead>(
+    bufread: &mut R,
+    graph: &GraphSelection,
+    config: ParserConfiguration,
+) -> Result<
+    (
+        ConcreteRDFOntology<RcStr, RcAnnotatedComponent>,
+        IncompleteParse<RcStr>,
+    ),
+    HornedError,
+> {
+    let b = Build::new_rc();
+    read_quads_with_build(bufread, &b, graph, config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{Class, Component, DeclareClass};
+    use crate::ontology::set::SetOntology;
+
+    const QUADS: &str = r#"<http://example.com/o> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Ontology> .
+<http://example.com/A> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> .
+<http://example.com/B> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> <http://example.com/g> .
+<http://example.com/C> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://www.w3.org/2002/07/owl#Class> <http://example.com/h> .
+"#;
+
+    fn classes(graph: GraphSelection) -> Vec<String> {
+        let (ont, incomp) = read_quads(&mut QUADS.as_bytes(), &graph, Default::default()).unwrap();
+        assert!(incomp.is_complete());
+
+        let ont: SetOntology<_> = ont.into();
+        let mut v: Vec<String> = ont
+            .into_iter()
+            .filter_map(|ac| match ac.component {
+                Component::DeclareClass(DeclareClass(Class(iri))) => Some(iri.to_string()),
+                _ => None,
+            })
+            .collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn read_ntriples() {
+        // The first two quads are in the default graph, so are also
+        // valid N-Triples
+        let nt: String = QUADS.lines().take(2).map(|l| format!("{}\n", l)).collect();
+        let (ont, incomp) = read(&mut nt.as_bytes(), Default::default()).unwrap();
+        assert!(incomp.is_complete());
+
+        let ont: SetOntology<_> = ont.into();
+        assert_eq!(ont.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn quads_all_graphs() {
+        assert_eq!(
+            classes(GraphSelection::All),
+            vec![
+                "http://example.com/A",
+                "http://example.com/B",
+                "http://example.com/C"
+            ]
+        );
+    }
+
+    #[test]
+    fn quads_default_graph() {
+        assert_eq!(
+            classes(GraphSelection::Default),
+            vec!["http://example.com/A"]
+        );
+    }
+
+    #[test]
+    fn quads_named_graph() {
+        assert_eq!(
+            classes(GraphSelection::Named("http://example.com/g".to_string())),
+            vec!["http://example.com/B"]
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        let r = read(
+            &mut "<http://example.com/A> .".as_bytes(),
+            Default::default(),
+        );
+        assert!(matches!(r, Err(HornedError::ParserError(_, _))));
+    }
+}
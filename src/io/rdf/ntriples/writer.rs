@@ -0,0 +1,171 @@
+//! Write an ontology as an N-Triples document.
+//!
+//! Triples are generated in the same way as for the RDF/XML writer,
+//! one per line. Blank nodes are labelled in the order in which they
+//! are generated, so writing the same ontology always produces the
+//! same document.
+use std::io::Write;
+
+use pretty_rdf::{PLiteral, PNamedNode, PSubject, PTerm, PTriple, RdfXmlFormatter};
+use rio_api::formatter::TriplesFormatter;
+use rio_api::model::{BlankNode, Literal, NamedNode, Subject, Term, Triple};
+use rio_turtle::NTriplesFormatter;
+
+use crate::error::HornedError;
+use crate::io::rdf::writer::{blank_node_label, render_ontology};
+use crate::model::ForIRI;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+
+/// Write a component mapped ontology as N-Triples.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<(), HornedError> {
+    let mut f = NTriplesWriter(NTriplesFormatter::new(write));
+    render_ontology(&mut f, ont)?;
+    RdfXmlFormatter::<A, W>::finish(f)?;
+
+    Ok(())
+}
+
+/// Adapts a `rio` N-Triples formatter to the triples generated by
+/// the RDF writer.
+struct NTriplesWriter<W: Write>(NTriplesFormatter<W>);
+
+impl<A: ForIRI, W: Write> RdfXmlFormatter<A, W> for NTriplesWriter<W> {
+    fn format(&mut self, triple: PTriple<A>) -> Result<(), std::io::Error> {
+        let subject_label = match &triple.subject {
+            PSubject::BlankNode(bn) => blank_node_label(bn.id.as_ref()),
+            _ => String::new(),
+        };
+        let object_label = match &triple.object {
+            PTerm::BlankNode(bn) => blank_node_label(bn.id.as_ref()),
+            _ => String::new(),
+        };
+        self.0.format(&Triple {
+            subject: subject(&triple.subject, &subject_label),
+            predicate: named_node(&triple.predicate),
+            object: term(&triple.object, &object_label),
+        })
+    }
+
+    fn finish(self) -> Result<W, std::io::Error> {
+        self.0.finish()
+    }
+}
+
+fn named_node<A: ForIRI>(nn: &PNamedNode<A>) -> NamedNode<'_> {
+    NamedNode {
+        iri: nn.iri.as_ref(),
+    }
+}
+
+/// Convert `s`, using `label` if it is a blank node.
+fn subject<'a, A: ForIRI>(s: &'a PSubject<A>, label: &'a str) -> Subject<'a> {
+    match s {
+        PSubject::NamedNode(nn) => named_node(nn).into(),
+        PSubject::BlankNode(_) => BlankNode { id: label }.into(),
+    }
+}
+
+/// Convert `t`, using `label` if it is a blank node.
+fn term<'a, A: ForIRI>(t: &'a PTerm<A>, label: &'a str) -> Term<'a> {
+    match t {
+        PTerm::NamedNode(nn) => named_node(nn).into(),
+        PTerm::BlankNode(_) => BlankNode { id: label }.into(),
+        PTerm::Literal(PLiteral::Simple { value }) => Literal::Simple {
+            value: value.as_ref(),
+        }
+        .into(),
+        PTerm::Literal(PLiteral::LanguageTaggedString { value, language }) => {
+            Literal::LanguageTaggedString {
+                value: value.as_ref(),
+                language: language.as_ref(),
+            }
+            .into()
+        }
+        PTerm::Literal(PLiteral::Typed { value, datatype }) => Literal::Typed {
+            value: value.as_ref(),
+            datatype: named_node(datatype),
+        }
+        .into(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::io::rdf::ntriples::reader::read;
+    use crate::model::{RcAnnotatedComponent, RcStr};
+    use crate::normalize::normalize;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn write_nt(resource: &str) -> (SetOntology<RcStr>, String) {
+        let (ont, _) = crate::io::rdf::reader::read(
+            &mut slurp::read_all_to_string(resource).unwrap().as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        let ont: SetOntology<_> = ont.into();
+        let cmo: ComponentMappedOntology<_, RcAnnotatedComponent> = ont.clone().into();
+
+        let mut buf = Vec::new();
+        write(&mut buf, &cmo).unwrap();
+        (ont, String::from_utf8(buf).unwrap())
+    }
+
+    #[test_resources("src/ont/owl-rdf/*.owl")]
+    fn roundtrip_nt(resource: &str) {
+        let (ont, s) = write_nt(resource);
+
+        let (rt, incomp) = read(&mut s.as_bytes(), Default::default()).unwrap();
+        assert!(incomp.is_complete());
+        let rt: SetOntology<_> = rt.into();
+
+        assert_eq!(
+            normalize(ont.into_iter().collect()),
+            normalize(rt.into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let (_, first) = write_nt("src/ont/owl-rdf/and.owl");
+        let (_, second) = write_nt("src/ont/owl-rdf/and.owl");
+
+        assert_eq!(first, second);
+        assert!(first.lines().all(|l| l.ends_with(" .")));
+    }
+
+    #[test]
+    fn anonymous_individual() {
+        let (ont, _): (SetOntology<RcStr>, _) = crate::io::ofn::reader::read(
+            "Prefix(:=<http://example.com/>)
+Ontology(<http://example.com/o>
+Declaration(Class(:A))
+AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#seeAlso> :A _:x)
+)"
+            .as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        let cmo: ComponentMappedOntology<_, RcAnnotatedComponent> = ont.clone().into();
+        let mut buf = Vec::new();
+        write(&mut buf, &cmo).unwrap();
+        let s = String::from_utf8(buf).unwrap();
+        assert!(!s.contains("_:_:"), "{}", s);
+
+        let (rt, incomp) = read(&mut s.as_bytes(), Default::default()).unwrap();
+        assert!(incomp.is_complete());
+        let rt: SetOntology<_> = rt.into();
+        assert_eq!(
+            normalize(ont.into_iter().collect()),
+            normalize(rt.into_iter().collect())
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use rio_api::{
-    model::{BlankNode, NamedNode, Subject, Triple},
-    parser::TriplesParser,
+    model::{BlankNode, GraphName, NamedNode, Subject, Triple},
+    parser::{QuadsParser, TriplesParser},
 };
 use Term::*;
 
@@ -489,6 +489,77 @@ impl<'a, A: ForIRI, AA: ForIndex<A>, O: RDFOntology<A, AA>> OntologyParser<'a, A
         OntologyParser::new(b, triples, config)
     }
 
+    /// Create a parser from the triples of any `rio` triple parser.
+    ///
+    /// This allows RDF syntaxes other than RDF/XML to share the
+    /// triple-to-axiom mapping. Errors from the underlying parser are
+    /// returned when the `OntologyParser` is parsed.
+    pub fn from_triples_parser<P: TriplesParser>(
+        b: &'a Build<A>,
+        mut parser: P,
+        config: ParserConfiguration,
+    ) -> OntologyParser<'a, A, AA, O>
+    where
+        HornedError: From<P::Error>,
+    {
+        let mut triples = vec![];
+        let mut on_triple = |rio_triple: rio_api::model::Triple| -> Result<_, HornedError> {
+            triples.push(b.to_pos_triple(rio_triple, 0));
+            Ok(())
+        };
+
+        let mut error = Ok(());
+        while !parser.is_end() {
+            if let Err(e) = parser.parse_step(&mut on_triple) {
+                error = Err(e);
+                break;
+            }
+        }
+
+        let mut op = OntologyParser::new(b, triples, config);
+        op.error = error;
+        op
+    }
+
+    /// Create a parser from the quads of any `rio` quad parser.
+    ///
+    /// Only quads for which `select` returns true, given their graph
+    /// name, are used; the graph name is otherwise ignored.
+    pub fn from_quads_parser<P: QuadsParser, F: FnMut(Option<&GraphName>) -> bool>(
+        b: &'a Build<A>,
+        mut parser: P,
+        mut select: F,
+        config: ParserConfiguration,
+    ) -> OntologyParser<'a, A, AA, O>
+    where
+        HornedError: From<P::Error>,
+    {
+        let mut triples = vec![];
+        let mut on_quad = |rio_quad: rio_api::model::Quad| -> Result<_, HornedError> {
+            if select(rio_quad.graph_name.as_ref()) {
+                let rio_triple = Triple {
+                    subject: rio_quad.subject,
+                    predicate: rio_quad.predicate,
+                    object: rio_quad.object,
+                };
+                triples.push(b.to_pos_triple(rio_triple, 0));
+            }
+            Ok(())
+        };
+
+        let mut error = Ok(());
+        while !parser.is_end() {
+            if let Err(e) = parser.parse_step(&mut on_quad) {
+                error = Err(e);
+                break;
+            }
+        }
+
+        let mut op = OntologyParser::new(b, triples, config);
+        op.error = error;
+        op
+    }
+
     pub fn from_doc_iri(
         b: &'a Build<A>,
         iri: &IRI<A>,
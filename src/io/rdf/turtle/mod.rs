@@ -0,0 +1,6 @@
+//! [Turtle](https://www.w3.org/TR/turtle/) syntax for OWL.
+//!
+//! Turtle documents are mapped to OWL using the same triple-to-axiom
+//! machinery as [RDF/XML](crate::io::rdf).
+pub mod reader;
+pub mod writer;
@@ -0,0 +1,96 @@
+//! Read an ontology from a Turtle document.
+use std::io::BufRead;
+
+use rio_turtle::TurtleParser;
+
+use crate::error::HornedError;
+use crate::io::rdf::reader::{ConcreteRDFOntology, IncompleteParse, OntologyParser, RDFOntology};
+use crate::io::ParserConfiguration;
+use crate::model::{Build, ForIRI, RcAnnotatedComponent, RcStr};
+use crate::ontology::indexed::ForIndex;
+
+/// Return an `OntologyParser` which reads triples from a Turtle
+/// document.
+///
+/// Relative IRIs in the document are resolved against any `@base`
+/// directive that it contains.
+pub fn parser_with_build<'b, A: ForIRI, AA: ForIndex<A>, O: RDFOntology<A, AA>, R: BufRead>(
+    bufread: &mut R,
+    build: &'b Build<A>,
+    config: ParserConfiguration,
+) -> OntologyParser<'b, A, AA, O> {
+    OntologyParser::from_triples_parser(build, TurtleParser::new(bufread, None), config)
+}
+
+pub fn read_with_build<A: ForIRI, AA: ForIndex<A>, R: BufRead>(
+    bufread: &mut R,
+    build: &Build<A>,
+    config: ParserConfiguration,
+) -> Result<(ConcreteRDFOntology<A, AA>, IncompleteParse<A>), HornedError> {
+    parser_with_build(bufread, build, config).parse()
+}
+
+pub fn read<R: BufRead>(
+    bufread: &mut R,
+    config: ParserConfiguration,
+) -> Result<
+    (
+        ConcreteRDFOntology<RcStr, RcAnnotatedComponent>,
+        IncompleteParse<RcStr>,
+    ),
+    HornedError,
+> {
+    let b = Build::new_rc();
+    read_with_build(bufread, &b, config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::normalize::normalize;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn read_ok<R: BufRead>(bufread: &mut R) -> ConcreteRDFOntology<RcStr, RcAnnotatedComponent> {
+        let (ont, incomp) = read(bufread, Default::default()).unwrap();
+        assert!(incomp.is_complete());
+        ont
+    }
+
+    #[test_resources("src/ont/owl-ttl/*.ttl")]
+    fn compare_to_rdf(resource: &str) {
+        let rdf_resource = resource
+            .replace("owl-ttl", "owl-rdf")
+            .replace(".ttl", ".owl");
+
+        let ttlont: SetOntology<_> =
+            read_ok(&mut slurp::read_all_to_string(resource).unwrap().as_bytes()).into();
+        let (rdfont, _) = crate::io::rdf::reader::read(
+            &mut slurp::read_all_to_string(rdf_resource).unwrap().as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        let rdfont: SetOntology<_> = rdfont.into();
+
+        let ttlont = normalize(ttlont.into_iter().collect());
+        let rdfont = normalize(rdfont.into_iter().collect());
+        assert_eq!(ttlont, rdfont);
+    }
+
+    #[test_resources("src/ont/owl-ttl/ambiguous/*.ttl")]
+    fn test_read_ok(resource: &str) {
+        read_ok(&mut slurp::read_all_to_string(resource).unwrap().as_bytes());
+    }
+
+    #[test]
+    fn syntax_error() {
+        let r = read(
+            &mut "<http://www.example.com/iri> a".as_bytes(),
+            Default::default(),
+        );
+        assert!(matches!(r, Err(HornedError::ParserError(_, _))));
+    }
+}
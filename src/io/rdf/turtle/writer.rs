@@ -0,0 +1,490 @@
+//! Write an ontology as a Turtle document.
+//!
+//! Triples are generated in the same way as for the RDF/XML
+//! writer. Blank nodes which are only used once, such as those of
+//! class expressions, are nested using `[...]`, and `rdf:List`s are
+//! written with the collection syntax.
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use curie::PrefixMapping;
+use pretty_rdf::{PBlankNode, PLiteral, PNamedNode, PSubject, PTerm, PTriple, RdfXmlFormatter};
+
+use crate::error::HornedError;
+use crate::io::rdf::writer::{blank_node_label, render_ontology};
+use crate::model::{AnonymousIndividual, ForIRI};
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::{Namespace, OWL, RDF};
+
+/// Write a component mapped ontology as Turtle, using the given
+/// `PrefixMapping`.
+///
+/// If no mapping is given, prefixes for the OWL, RDF, RDFS and XSD
+/// namespaces are used.
+pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
+    write: W,
+    ont: &ComponentMappedOntology<A, AA>,
+    mapping: Option<&PrefixMapping>,
+) -> Result<(), HornedError> {
+    let default_mapper = default_mapping();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+
+    // Anonymous individuals must keep their labels, so are never
+    // nested.
+    let mut walk = Walk::new(AnonymousLabels::default());
+    for cmp in ont.i().iter() {
+        walk.annotated_component(cmp);
+    }
+
+    let mut f = TurtleFormatter::new(write, mapping, walk.into_visit().0);
+    render_ontology(&mut f, ont)?;
+    f.finish()?;
+
+    Ok(())
+}
+
+pub(crate) fn default_mapping() -> PrefixMapping {
+    let mut mapping = PrefixMapping::default();
+    for (prefix, ns) in [
+        ("owl", Namespace::OWL),
+        ("rdf", Namespace::RDF),
+        ("rdfs", Namespace::RDFS),
+        ("swrl", Namespace::SWRL),
+        ("xsd", Namespace::XSD),
+    ] {
+        mapping
+            .add_prefix(prefix, ns.as_ref())
+            .expect("prefix should be valid");
+    }
+    mapping
+}
+
+struct AnonymousLabels<A>(HashSet<A>);
+
+impl<A> Default for AnonymousLabels<A> {
+    fn default() -> Self {
+        AnonymousLabels(HashSet::new())
+    }
+}
+
+impl<A: ForIRI> Visit<A> for AnonymousLabels<A> {
+    fn visit_anonymous_individual(&mut self, anon: &AnonymousIndividual<A>) {
+        self.0.insert(anon.underlying());
+    }
+}
+
+type PredicateObjects<A> = Vec<(PNamedNode<A>, PTerm<A>)>;
+
+/// Collects triples and writes them as Turtle when finished.
+struct TurtleFormatter<'a, A: ForIRI, W: Write> {
+    write: W,
+    mapping: &'a PrefixMapping,
+    anonymous: HashSet<A>,
+    seen: HashSet<PTriple<A>>,
+    subjects: Vec<PSubject<A>>,
+    by_subject: HashMap<PSubject<A>, PredicateObjects<A>>,
+    object_count: HashMap<A, usize>,
+    written: HashSet<A>,
+}
+
+impl<'a, A: ForIRI, W: Write> TurtleFormatter<'a, A, W> {
+    fn new(write: W, mapping: &'a PrefixMapping, anonymous: HashSet<A>) -> Self {
+        TurtleFormatter {
+            write,
+            mapping,
+            anonymous,
+            seen: HashSet::new(),
+            subjects: vec![],
+            by_subject: HashMap::new(),
+            object_count: HashMap::new(),
+            written: HashSet::new(),
+        }
+    }
+
+    /// Can the blank node be written inline where it is used?
+    fn is_nestable(&self, id: &A) -> bool {
+        !self.anonymous.contains(id)
+            && !self.written.contains(id)
+            && self.object_count.get(id) == Some(&1)
+    }
+
+    /// Return the items of the `rdf:List` starting at `id`, if it is
+    /// a well-formed list which can be written with the collection
+    /// syntax.
+    fn list_items(&self, id: &A) -> Option<Vec<PTerm<A>>> {
+        let mut items = vec![];
+        let mut visited = HashSet::new();
+        let mut current = id.clone();
+        loop {
+            if !self.is_nestable(&current) || !visited.insert(current.clone()) {
+                return None;
+            }
+
+            let po = self
+                .by_subject
+                .get(&PSubject::BlankNode(PBlankNode::new(current.clone())))?;
+
+            let (first, rest) = match po.as_slice() {
+                [(p1, first), (p2, rest)] | [(p2, rest), (p1, first)]
+                    if p1.iri.as_ref() == RDF::First.as_ref()
+                        && p2.iri.as_ref() == RDF::Rest.as_ref() =>
+                {
+                    (first, rest)
+                }
+                _ => return None,
+            };
+
+            items.push(first.clone());
+            match rest {
+                PTerm::NamedNode(nn) if nn.iri.as_ref() == RDF::Nil.as_ref() => {
+                    return Some(items);
+                }
+                PTerm::BlankNode(bn) => current = bn.id.clone(),
+                _ => return None,
+            }
+        }
+    }
+
+    fn mark_list_written(&mut self, id: &A) {
+        let mut current = id.clone();
+        while self.written.insert(current.clone()) {
+            let rest = self
+                .by_subject
+                .get(&PSubject::BlankNode(PBlankNode::new(current.clone())))
+                .and_then(|po| {
+                    po.iter().find_map(|(p, o)| match o {
+                        PTerm::BlankNode(bn) if p.iri.as_ref() == RDF::Rest.as_ref() => {
+                            Some(bn.id.clone())
+                        }
+                        _ => None,
+                    })
+                });
+            match rest {
+                Some(rest) => current = rest,
+                None => break,
+            }
+        }
+    }
+
+    fn named_node(&self, nn: &PNamedNode<A>) -> String {
+        let iri = nn.iri.as_ref();
+        self.mapping
+            .mappings()
+            .filter(|(_, ns)| iri.starts_with(ns.as_str()))
+            .filter(|(_, ns)| is_local_name(&iri[ns.len()..]))
+            .max_by_key(|(_, ns)| ns.len())
+            .map(|(prefix, ns)| format!("{}:{}", prefix, &iri[ns.len()..]))
+            .unwrap_or_else(|| format!("<{}>", iri))
+    }
+
+    fn predicate(&self, nn: &PNamedNode<A>) -> String {
+        if nn.iri.as_ref() == RDF::Type.as_ref() {
+            "a".to_string()
+        } else {
+            self.named_node(nn)
+        }
+    }
+
+    fn literal(&self, l: &PLiteral<A>) -> String {
+        match l {
+            PLiteral::Simple { value } => quote(value.as_ref()),
+            PLiteral::LanguageTaggedString { value, language } => {
+                format!("{}@{}", quote(value.as_ref()), language.as_ref())
+            }
+            PLiteral::Typed { value, datatype } => {
+                format!("{}^^{}", quote(value.as_ref()), self.named_node(datatype))
+            }
+        }
+    }
+
+    fn write_object(&mut self, o: &PTerm<A>, depth: usize) -> Result<(), HornedError> {
+        match o {
+            PTerm::NamedNode(nn) => {
+                let s = self.named_node(nn);
+                write!(self.write, "{}", s)?;
+            }
+            PTerm::Literal(l) => {
+                let s = self.literal(l);
+                write!(self.write, "{}", s)?;
+            }
+            PTerm::BlankNode(bn) => {
+                if let Some(items) = self.list_items(&bn.id) {
+                    self.mark_list_written(&bn.id);
+                    write!(self.write, "(")?;
+                    for item in items {
+                        write!(self.write, " ")?;
+                        self.write_object(&item, depth)?;
+                    }
+                    write!(self.write, " )")?;
+                } else if self.is_nestable(&bn.id) {
+                    self.written.insert(bn.id.clone());
+                    let sub = PSubject::BlankNode(bn.clone());
+                    if self.by_subject.contains_key(&sub) {
+                        writeln!(self.write, "[")?;
+                        self.write_predicate_objects(&sub, depth + 1, false)?;
+                        write!(self.write, "\n{}]", indent(depth))?;
+                    } else {
+                        write!(self.write, "[]")?;
+                    }
+                } else {
+                    write!(self.write, "_:{}", blank_node_label(bn.id.as_ref()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the predicates and objects of `sub`, one predicate per
+    /// line. If `inline` is true, the first predicate follows on from
+    /// the subject.
+    fn write_predicate_objects(
+        &mut self,
+        sub: &PSubject<A>,
+        depth: usize,
+        inline: bool,
+    ) -> Result<(), HornedError> {
+        let po = self.by_subject.get(sub).cloned().unwrap_or_default();
+
+        // Group the objects by predicate, with `rdf:type` first
+        let mut predicates: Vec<(PNamedNode<A>, Vec<PTerm<A>>)> = vec![];
+        for (p, o) in po {
+            match predicates.iter_mut().find(|(ep, _)| ep == &p) {
+                Some((_, objects)) => objects.push(o),
+                None => predicates.push((p, vec![o])),
+            }
+        }
+        predicates.sort_by_key(|(p, _)| p.iri.as_ref() != RDF::Type.as_ref());
+
+        for (i, (p, objects)) in predicates.iter().enumerate() {
+            if i != 0 {
+                writeln!(self.write, " ;")?;
+            }
+            let p = self.predicate(p);
+            if i == 0 && inline {
+                write!(self.write, " {} ", p)?;
+            } else {
+                write!(self.write, "{}{} ", indent(depth), p)?;
+            }
+            for (j, o) in objects.iter().enumerate() {
+                if j != 0 {
+                    write!(self.write, ", ")?;
+                }
+                self.write_object(o, depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_statement(&mut self, sub: &PSubject<A>) -> Result<(), HornedError> {
+        writeln!(self.write)?;
+        match sub {
+            PSubject::NamedNode(nn) => {
+                let s = self.named_node(nn);
+                write!(self.write, "{}", s)?;
+            }
+            PSubject::BlankNode(bn) => {
+                self.written.insert(bn.id.clone());
+                if self.object_count.contains_key(&bn.id) || self.anonymous.contains(&bn.id) {
+                    write!(self.write, "_:{}", blank_node_label(bn.id.as_ref()))?;
+                } else {
+                    // Never used as an object, so can be nested at the
+                    // top level.
+                    writeln!(self.write, "[")?;
+                    self.write_predicate_objects(sub, 1, false)?;
+                    writeln!(self.write, "\n] .")?;
+                    return Ok(());
+                }
+            }
+        }
+        self.write_predicate_objects(sub, 1, true)?;
+        writeln!(self.write, " .")?;
+        Ok(())
+    }
+
+    fn write_document(&mut self) -> Result<(), HornedError> {
+        for (prefix, ns) in self.mapping.mappings() {
+            writeln!(self.write, "@prefix {}: <{}> .", prefix, ns)?;
+        }
+
+        // The ontology header comes first, then named subjects in
+        // order, then any blank nodes which have not been written
+        // inline.
+        let ontology = PNamedNode::new(A::from(OWL::Ontology.as_ref().to_string()));
+        let rdf_type = PNamedNode::new(A::from(RDF::Type.as_ref().to_string()));
+        let mut named: Vec<_> = self
+            .subjects
+            .iter()
+            .filter(|s| matches!(s, PSubject::NamedNode(_)))
+            .cloned()
+            .collect();
+        named.sort_by_key(|s| {
+            let is_ontology = self.by_subject[s]
+                .iter()
+                .any(|(p, o)| p == &rdf_type && o == &PTerm::NamedNode(ontology.clone()));
+            (!is_ontology, s.clone())
+        });
+
+        for sub in named {
+            self.write_statement(&sub)?;
+        }
+
+        for sub in self.subjects.clone() {
+            if let PSubject::BlankNode(bn) = &sub {
+                if !self.written.contains(&bn.id) && !self.is_nestable(&bn.id) {
+                    self.write_statement(&sub)?;
+                }
+            }
+        }
+
+        // Anything left must be part of a cycle of blank nodes.
+        for sub in self.subjects.clone() {
+            if let PSubject::BlankNode(bn) = &sub {
+                if !self.written.contains(&bn.id) {
+                    self.anonymous.insert(bn.id.clone());
+                    self.write_statement(&sub)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, A: ForIRI, W: Write> RdfXmlFormatter<A, W> for TurtleFormatter<'a, A, W> {
+    fn format(&mut self, triple: PTriple<A>) -> Result<(), std::io::Error> {
+        if !self.seen.insert(triple.clone()) {
+            return Ok(());
+        }
+
+        if let PTerm::BlankNode(bn) = &triple.object {
+            *self.object_count.entry(bn.id.clone()).or_insert(0) += 1;
+        }
+
+        let PTriple {
+            subject,
+            predicate,
+            object,
+        } = triple;
+        if !self.by_subject.contains_key(&subject) {
+            self.subjects.push(subject.clone());
+        }
+        self.by_subject
+            .entry(subject)
+            .or_default()
+            .push((predicate, object));
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W, std::io::Error> {
+        self.write_document().map_err(|e| match e {
+            HornedError::IOError(e) => e,
+            e => std::io::Error::other(e.to_string()),
+        })?;
+        Ok(self.write)
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+/// Is `s` safe to use as the local part of a prefixed name?
+fn is_local_name(s: &str) -> bool {
+    s.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !s.starts_with('-')
+}
+
+/// Write a string literal, escaping characters as necessary.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::io::rdf::turtle::reader::read;
+    use crate::model::{Build, DeclareClass, MutableOntology, RcAnnotatedComponent, RcStr};
+    use crate::normalize::normalize;
+    use crate::ontology::set::SetOntology;
+
+    use pretty_assertions::assert_eq;
+    use test_generator::test_resources;
+
+    fn roundtrip(resource: &str) -> (SetOntology<RcStr>, SetOntology<RcStr>, String) {
+        let (ont, incomplete) = read(
+            &mut slurp::read_all_to_string(resource).unwrap().as_bytes(),
+            Default::default(),
+        )
+        .unwrap();
+        assert!(incomplete.is_complete());
+        let ont: SetOntology<_> = ont.into();
+        let amo: ComponentMappedOntology<RcStr, RcAnnotatedComponent> = ont.clone().into();
+
+        let mut buf = Vec::new();
+        write(&mut buf, &amo, None).unwrap();
+        let s = String::from_utf8(buf).unwrap();
+
+        let (ont_round, incomplete) = read(&mut s.as_bytes(), Default::default()).unwrap();
+        assert!(incomplete.is_complete(), "Read Not Complete: {}", s);
+        (ont, ont_round.into(), s)
+    }
+
+    #[test_resources("src/ont/owl-ttl/*.ttl")]
+    #[test_resources("src/ont/owl-ttl/ambiguous/*.ttl")]
+    fn roundtrip_ttl(resource: &str) {
+        let (ont, ont_round, s) = roundtrip(resource);
+
+        // Anonymous individuals may be relabelled by the parser
+        let ont = normalize(ont.into_iter().collect());
+        let ont_round = normalize(ont_round.into_iter().collect());
+        assert_eq!(ont, ont_round, "{}", s);
+    }
+
+    #[test]
+    fn nested_class_expression() {
+        let (_, _, s) = roundtrip("src/ont/owl-ttl/and.ttl");
+        assert!(s.contains("rdfs:subClassOf ["));
+        assert!(s.contains("owl:intersectionOf ( "));
+        assert!(!s.contains("rdf:first"));
+    }
+
+    #[test]
+    fn prefix_mapping() {
+        let b = Build::new_rc();
+        let mut ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            ComponentMappedOntology::new_rc();
+        ont.insert(DeclareClass(b.class("http://www.example.com/iri#A")));
+
+        let mut mapping = default_mapping();
+        mapping
+            .add_prefix("ex", "http://www.example.com/iri#")
+            .unwrap();
+
+        let mut buf = Vec::new();
+        write(&mut buf, &ont, Some(&mapping)).unwrap();
+        let s = String::from_utf8(buf).unwrap();
+
+        assert!(s.contains("@prefix ex: <http://www.example.com/iri#> ."));
+        assert!(s.contains("ex:A a owl:Class"));
+    }
+}
@@ -51,6 +51,35 @@ pub fn write<A: ForIRI, AA: ForIndex<A>, W: Write>(
     Ok(())
 }
 
+/// Render the triples of a component mapped ontology to any formatter.
+///
+/// This allows other RDF syntaxes to share the triple generation of
+/// the RDF/XML writer.
+pub(crate) fn render_ontology<A: ForIRI, AA: ForIndex<A>, F: RdfXmlFormatter<A, W>, W: Write>(
+    f: &mut F,
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<(), HornedError> {
+    let mut bng = NodeGenerator::default();
+    ont.render(f, &mut bng)
+}
+
+/// Return a blank node label which is valid in Turtle and N-Triples.
+///
+/// Anonymous individuals keep the `_:` of their name, which is not
+/// part of the label.
+pub(crate) fn blank_node_label(id: &str) -> String {
+    id.trim_start_matches("_:")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
 /// Generates Nodes for RDF output
 struct NodeGenerator<A: ForIRI> {
     i: u64,
@@ -35,11 +35,18 @@ extern crate quick_xml;
 
 pub mod adaptor;
 pub mod curie;
+pub mod datatypes;
+pub mod diff;
 pub mod error;
 pub mod io;
+pub mod merge;
 pub mod model;
+pub mod modularity;
 pub mod normalize;
 pub mod ontology;
+pub mod patch;
+pub mod profiles;
+pub mod reasoner;
 pub mod resolve;
 pub mod visitor;
 pub mod vocab;
@@ -0,0 +1,273 @@
+//! Merge concurrent changes to an ontology.
+//!
+//! # Overview
+//!
+//! [`merge3`] combines two ontologies, "ours" and "theirs", which
+//! have each been changed from a common "base", as a version control
+//! system would combine the lines of a text file. The merge works on
+//! the components of the ontologies, rather than their serialization,
+//! so it does not depend on the order in which they are written.
+//!
+//! Components are matched as they are by [`diff`](crate::diff::diff):
+//! a component whose annotations are edited is the same component,
+//! and anonymous individuals of each side are matched to those of
+//! the base, by name and then up to renaming. Ontologies have at most
+//! one `OntologyID`, so all of these are treated as versions of the
+//! same component.
+//!
+//! Where only one side has changed a component, that change is taken.
+//! Where both sides have changed it differently, such as when one
+//! side edits the annotations of an axiom which the other side
+//! removes, or when they give different version IRIs, the change is
+//! a [`Conflict`].
+use std::collections::BTreeMap;
+
+use crate::diff::{correspondence, rename};
+use crate::model::*;
+use crate::ontology::set::SetOntology;
+
+/// A component which has been changed differently in two ontologies.
+///
+/// Each field contains the versions of the component in one of the
+/// ontologies, and is empty if it is absent from that ontology.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Conflict<A> {
+    pub base: Vec<AnnotatedComponent<A>>,
+    pub ours: Vec<AnnotatedComponent<A>>,
+    pub theirs: Vec<AnnotatedComponent<A>>,
+}
+
+/// The versions of a component in an ontology, from their form with
+/// the anonymous individuals of the base to the original.
+type Versions<A> = BTreeMap<AnnotatedComponent<A>, AnnotatedComponent<A>>;
+
+/// Group the components of `o` by the component which they are a
+/// version of, renaming its anonymous individuals to those of `base`.
+fn versions<A: ForIRI>(
+    base: &SetOntology<A>,
+    o: &SetOntology<A>,
+) -> BTreeMap<(ComponentKind, Option<Component<A>>), Versions<A>> {
+    let components: Vec<_> = o.iter().collect();
+    let names = correspondence(&base.iter().collect::<Vec<_>>(), &components);
+    let mut versions: BTreeMap<_, Versions<A>> = BTreeMap::new();
+    for ac in components {
+        let mut c = ac.clone();
+        rename(&mut c, &names, None);
+        let key = match c.component {
+            Component::OntologyID(_) | Component::DocIRI(_) => (c.kind(), None),
+            _ => (c.kind(), Some(c.component.clone())),
+        };
+        versions.entry(key).or_default().insert(c, ac.clone());
+    }
+    versions
+}
+
+/// Merge the changes made from `base` in `ours` and `theirs`.
+///
+/// Returns the merged ontology and the conflicts, for each of which
+/// the merged ontology contains our version. Anonymous individuals in
+/// the merged ontology have their names in `base`, where they are in
+/// it, while the conflicts contain the original components.
+pub fn merge3<A: ForIRI>(
+    base: &SetOntology<A>,
+    ours: &SetOntology<A>,
+    theirs: &SetOntology<A>,
+) -> (SetOntology<A>, Vec<Conflict<A>>) {
+    let mut ours = versions(base, ours);
+    let mut theirs = versions(base, theirs);
+    let mut base = versions(base, base);
+
+    let mut keys: Vec<_> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    let same = |a: &Versions<A>, b: &Versions<A>| a.keys().eq(b.keys());
+    let originals = |v: &Versions<A>| -> Vec<_> { v.values().cloned().collect() };
+
+    let mut merged = SetOntology::new();
+    let mut conflicts = vec![];
+    for key in keys {
+        let b = base.remove(&key).unwrap_or_default();
+        let o = ours.remove(&key).unwrap_or_default();
+        let t = theirs.remove(&key).unwrap_or_default();
+
+        let take = if same(&o, &t) || same(&b, &t) {
+            o
+        } else if same(&b, &o) {
+            t
+        } else {
+            conflicts.push(Conflict {
+                base: originals(&b),
+                ours: originals(&o),
+                theirs: originals(&t),
+            });
+            o
+        };
+        for ac in take.into_keys() {
+            merged.insert(ac);
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::RcStr;
+
+    use pretty_assertions::assert_eq;
+
+    fn read(ofn: &str) -> SetOntology<RcStr> {
+        crate::io::ofn::reader::read_example(ofn)
+    }
+
+    fn components(o: &SetOntology<RcStr>) -> Vec<AnnotatedComponent<RcStr>> {
+        let mut v: Vec<_> = o.iter().cloned().collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn clean() {
+        let base = read(
+            "<http://example.com/o>
+             Declaration(Class(:A))
+             SubClassOf(:A :B)
+             SubClassOf(:A :C)",
+        );
+        let ours = read(
+            "<http://example.com/o>
+             Declaration(Class(:A))
+             SubClassOf(Annotation(rdfs:comment \"edited\") :A :B)
+             SubClassOf(:A :C)
+             SubClassOf(:A :D)",
+        );
+        let theirs = read(
+            "<http://example.com/o>
+             Declaration(Class(:A))
+             SubClassOf(:A :B)
+             SubClassOf(:A :E)",
+        );
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(
+            components(&merged),
+            components(&read(
+                "<http://example.com/o>
+                 Declaration(Class(:A))
+                 SubClassOf(Annotation(rdfs:comment \"edited\") :A :B)
+                 SubClassOf(:A :D)
+                 SubClassOf(:A :E)"
+            ))
+        );
+    }
+
+    #[test]
+    fn edit_remove_conflict() {
+        let base = read("SubClassOf(:A :B)");
+        let ours = read("SubClassOf(Annotation(rdfs:comment \"edited\") :A :B)");
+        let theirs = read("");
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].base.len(), 1);
+        assert_eq!(conflicts[0].ours.len(), 1);
+        assert!(conflicts[0].theirs.is_empty());
+        assert_eq!(components(&merged), components(&ours));
+    }
+
+    #[test]
+    fn version_conflict() {
+        let base = read("<http://example.com/o> <http://example.com/o/1>");
+        let ours = read("<http://example.com/o> <http://example.com/o/2>");
+        let theirs = read("<http://example.com/o> <http://example.com/o/3>");
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts.len(), 1);
+        assert!(matches!(
+            conflicts[0].theirs[0].component,
+            Component::OntologyID(_)
+        ));
+        assert_eq!(components(&merged), components(&ours));
+
+        // The same change on both sides does not conflict
+        let (merged, conflicts) = merge3(&base, &ours, &ours);
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(components(&merged), components(&ours));
+    }
+
+    #[test]
+    fn anonymous() {
+        let base = read("ClassAssertion(:A _:x) ObjectPropertyAssertion(:r _:x :i)");
+        let ours = read(
+            "ClassAssertion(:A _:y) ObjectPropertyAssertion(:r _:y :i) \
+             ClassAssertion(:B :i)",
+        );
+        let theirs = read("ClassAssertion(:A _:z) ObjectPropertyAssertion(:r _:z :i)");
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(
+            components(&merged),
+            components(&read(
+                "ClassAssertion(:A _:x) ObjectPropertyAssertion(:r _:x :i) \
+                 ClassAssertion(:B :i)"
+            ))
+        );
+    }
+
+    #[test]
+    fn anonymous_unrelated() {
+        // Individuals are matched however much else one side adds
+        let base = read("ObjectPropertyAssertion(:r _:a _:b)");
+        let ours = read(
+            "ObjectPropertyAssertion(:r _:x _:y)
+             ObjectPropertyAssertion(:s _:c1 _:c2)
+             ObjectPropertyAssertion(:s _:c2 _:c3)
+             ObjectPropertyAssertion(:s _:c3 _:c4)",
+        );
+        let theirs =
+            read("ObjectPropertyAssertion(Annotation(rdfs:comment \"edited\") :r _:a _:b)");
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(
+            components(&merged),
+            components(&read(
+                "ObjectPropertyAssertion(Annotation(rdfs:comment \"edited\") :r _:a _:b)
+                 ObjectPropertyAssertion(:s _:c1 _:c2)
+                 ObjectPropertyAssertion(:s _:c2 _:c3)
+                 ObjectPropertyAssertion(:s _:c3 _:c4)"
+            ))
+        );
+    }
+
+    #[test]
+    fn anonymous_edited() {
+        // Editing the components of an individual does not change
+        // which it is
+        let base = read("ClassAssertion(:A _:x) ObjectPropertyAssertion(:r _:x :i)");
+        let ours = read("ObjectPropertyAssertion(:r _:x :i)");
+        let theirs = read(
+            "ClassAssertion(:A _:x) ObjectPropertyAssertion(:r _:x :i) \
+             ClassAssertion(:C _:x)",
+        );
+
+        let (merged, conflicts) = merge3(&base, &ours, &theirs);
+        assert_eq!(conflicts, vec![]);
+        assert_eq!(
+            components(&merged),
+            components(&read(
+                "ObjectPropertyAssertion(:r _:x :i) ClassAssertion(:C _:x)"
+            ))
+        );
+    }
+}
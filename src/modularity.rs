@@ -0,0 +1,751 @@
+//! Extract modules from ontologies with syntactic locality.
+//!
+//! # Overview
+//!
+//! A module of an ontology for a signature is a subset of its axioms
+//! which preserves every entailment over that signature, so that it
+//! can be used in place of the ontology by anyone interested only in
+//! those entities.
+//!
+//! The modules here are based on [syntactic
+//! locality](https://doi.org/10.1613/jair.2375). An axiom is
+//! ⊥-local for a signature if it is a tautology when every class and
+//! property outside of the signature is replaced with the empty class
+//! or property, and ⊤-local if it is when they are replaced with the
+//! universal class or property. The module contains every axiom which
+//! is not local for the seed signature extended with the signature of
+//! the module.
+//!
+//! A ⊥-module contains the axioms needed for the superclasses of the
+//! seed, a ⊤-module those needed for its subclasses. The ⊥⊤* module,
+//! [`ModuleType::Star`], alternates the two until it reaches a fixed
+//! point, and is usually the smallest.
+//!
+//! Declarations, and annotation assertions on the entities of the
+//! final signature, are also copied to the module.
+//!
+//! For a lighter extraction, which is not a module, [`mireot`] copies
+//! terms with their told superclasses, as MIREOT does.
+use std::collections::{HashMap, HashSet};
+
+use crate::model::*;
+use crate::ontology::indexed::ForIndex;
+use crate::ontology::iri_mapped::IRIMappedIndex;
+use crate::ontology::set::SetOntology;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::{Namespace, OWL};
+
+/// The kinds of locality-based module.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ModuleType {
+    /// The ⊥-module
+    Bottom,
+    /// The ⊤-module
+    Top,
+    /// The ⊥⊤* module, which is the fixed point of alternately
+    /// extracting ⊥-modules and ⊤-modules
+    Star,
+}
+
+type Signature<A> = HashSet<NamedOWLEntity<A>>;
+
+/// Collects the named entities of a component.
+struct Collect<A>(Signature<A>);
+
+impl<A: ForIRI> Visit<A> for Collect<A> {
+    fn visit_class(&mut self, c: &Class<A>) {
+        self.0.insert(c.clone().into());
+    }
+
+    fn visit_datatype(&mut self, d: &Datatype<A>) {
+        self.0.insert(d.clone().into());
+    }
+
+    fn visit_object_property(&mut self, op: &ObjectProperty<A>) {
+        self.0.insert(op.clone().into());
+    }
+
+    fn visit_data_property(&mut self, dp: &DataProperty<A>) {
+        self.0.insert(dp.clone().into());
+    }
+
+    fn visit_annotation_property(&mut self, ap: &AnnotationProperty<A>) {
+        self.0.insert(ap.clone().into());
+    }
+
+    fn visit_named_individual(&mut self, ni: &NamedIndividual<A>) {
+        self.0.insert(ni.clone().into());
+    }
+}
+
+fn signature<A: ForIRI>(c: &Component<A>) -> Signature<A> {
+    let mut walk = Walk::new(Collect(HashSet::new()));
+    walk.component(c);
+    walk.into_visit().0
+}
+
+fn iri<A: ForIRI>(e: &NamedOWLEntity<A>) -> &IRI<A> {
+    match e {
+        NamedOWLEntity::Class(c) => c,
+        NamedOWLEntity::Datatype(d) => d,
+        NamedOWLEntity::ObjectProperty(op) => op,
+        NamedOWLEntity::DataProperty(dp) => dp,
+        NamedOWLEntity::AnnotationProperty(ap) => ap,
+        NamedOWLEntity::NamedIndividual(ni) => ni,
+    }
+}
+
+/// Return the entity of a declaration.
+fn declared<A: ForIRI>(c: &Component<A>) -> Option<NamedOWLEntity<A>> {
+    match c {
+        Component::DeclareClass(DeclareClass(c)) => Some(c.clone().into()),
+        Component::DeclareObjectProperty(DeclareObjectProperty(op)) => Some(op.clone().into()),
+        Component::DeclareDataProperty(DeclareDataProperty(dp)) => Some(dp.clone().into()),
+        Component::DeclareAnnotationProperty(DeclareAnnotationProperty(ap)) => {
+            Some(ap.clone().into())
+        }
+        Component::DeclareNamedIndividual(DeclareNamedIndividual(ni)) => Some(ni.clone().into()),
+        Component::DeclareDatatype(DeclareDatatype(d)) => Some(d.clone().into()),
+        _ => None,
+    }
+}
+
+/// Return true if `c` has no logical content, and is copied to a
+/// module only for its entities.
+fn is_extra<A: ForIRI>(c: &Component<A>) -> bool {
+    declared(c).is_some()
+        || matches!(
+            c,
+            Component::OntologyID(_)
+                | Component::DocIRI(_)
+                | Component::Import(_)
+                | Component::OntologyAnnotation(_)
+                | Component::AnnotationAssertion(_)
+                | Component::SubAnnotationPropertyOf(_)
+                | Component::AnnotationPropertyDomain(_)
+                | Component::AnnotationPropertyRange(_)
+        )
+}
+
+/// Decides the locality of axioms for a signature.
+struct Locality<'a, A> {
+    signature: &'a Signature<A>,
+    /// True for ⊤-locality, false for ⊥-locality
+    top: bool,
+}
+
+impl<'a, A: ForIRI> Locality<'a, A> {
+    fn contains<E: Clone + Into<NamedOWLEntity<A>>>(&self, e: &E) -> bool {
+        self.signature.contains(&e.clone().into())
+    }
+
+    /// Return true if `ope` is equivalent to the empty property.
+    fn is_bottom_op(&self, ope: &ObjectPropertyExpression<A>) -> bool {
+        let op = match ope {
+            ObjectPropertyExpression::ObjectProperty(op)
+            | ObjectPropertyExpression::InverseObjectProperty(op) => op,
+        };
+        op.is_as(format!("{}bottomObjectProperty", Namespace::OWL.as_ref()))
+            || !self.top && !op.is(&OWL::TopObjectProperty) && !self.contains(op)
+    }
+
+    /// Return true if `ope` is equivalent to the universal property.
+    fn is_top_op(&self, ope: &ObjectPropertyExpression<A>) -> bool {
+        let op = match ope {
+            ObjectPropertyExpression::ObjectProperty(op)
+            | ObjectPropertyExpression::InverseObjectProperty(op) => op,
+        };
+        op.is(&OWL::TopObjectProperty)
+            || self.top
+                && !op.is_as(format!("{}bottomObjectProperty", Namespace::OWL.as_ref()))
+                && !self.contains(op)
+    }
+
+    fn is_bottom_dp(&self, dp: &DataProperty<A>) -> bool {
+        dp.is_as(format!("{}bottomDataProperty", Namespace::OWL.as_ref()))
+            || !self.top && !dp.is(&OWL::TopDataProperty) && !self.contains(dp)
+    }
+
+    fn is_top_dp(&self, dp: &DataProperty<A>) -> bool {
+        dp.is(&OWL::TopDataProperty)
+            || self.top
+                && !dp.is_as(format!("{}bottomDataProperty", Namespace::OWL.as_ref()))
+                && !self.contains(dp)
+    }
+
+    /// Return true if `ce` is syntactically equivalent to `owl:Nothing`.
+    fn is_bottom(&self, ce: &ClassExpression<A>) -> bool {
+        use ClassExpression::*;
+        match ce {
+            Class(c) => c.is_nothing() || !self.top && !c.is_thing() && !self.contains(c),
+            ObjectComplementOf(ce) => self.is_top(ce),
+            ObjectIntersectionOf(v) => v.iter().any(|ce| self.is_bottom(ce)),
+            ObjectUnionOf(v) => v.iter().all(|ce| self.is_bottom(ce)),
+            ObjectOneOf(v) => v.is_empty(),
+            ObjectSomeValuesFrom { ope, bce } => self.is_bottom_op(ope) || self.is_bottom(bce),
+            ObjectAllValuesFrom { ope, bce } => self.is_top_op(ope) && self.is_bottom(bce),
+            ObjectHasValue { ope, .. } | ObjectHasSelf(ope) => self.is_bottom_op(ope),
+            ObjectMinCardinality { n, ope, bce } | ObjectExactCardinality { n, ope, bce } => {
+                *n > 0 && (self.is_bottom_op(ope) || self.is_bottom(bce))
+            }
+            ObjectMaxCardinality { .. } => false,
+            DataSomeValuesFrom { dp, .. } | DataHasValue { dp, .. } => self.is_bottom_dp(dp),
+            DataMinCardinality { n, dp, .. } | DataExactCardinality { n, dp, .. } => {
+                *n > 0 && self.is_bottom_dp(dp)
+            }
+            DataAllValuesFrom { .. } | DataMaxCardinality { .. } => false,
+        }
+    }
+
+    /// Return true if `ce` is syntactically equivalent to `owl:Thing`.
+    fn is_top(&self, ce: &ClassExpression<A>) -> bool {
+        use ClassExpression::*;
+        match ce {
+            Class(c) => c.is_thing() || self.top && !c.is_nothing() && !self.contains(c),
+            ObjectComplementOf(ce) => self.is_bottom(ce),
+            ObjectIntersectionOf(v) => v.iter().all(|ce| self.is_top(ce)),
+            ObjectUnionOf(v) => v.iter().any(|ce| self.is_top(ce)),
+            ObjectOneOf(_) => false,
+            ObjectSomeValuesFrom { ope, bce } => self.is_top_op(ope) && self.is_top(bce),
+            ObjectAllValuesFrom { ope, bce } => self.is_bottom_op(ope) || self.is_top(bce),
+            ObjectHasValue { ope, .. } => self.is_top_op(ope),
+            ObjectHasSelf(_) => false,
+            ObjectMinCardinality { n, ope, bce } => {
+                *n == 0 || *n == 1 && self.is_top_op(ope) && self.is_top(bce)
+            }
+            ObjectMaxCardinality { ope, bce, .. } => self.is_bottom_op(ope) || self.is_bottom(bce),
+            ObjectExactCardinality { n, ope, bce } => {
+                *n == 0 && (self.is_bottom_op(ope) || self.is_bottom(bce))
+            }
+            DataAllValuesFrom { dp, .. } | DataMaxCardinality { dp, .. } => self.is_bottom_dp(dp),
+            DataMinCardinality { n, .. } => *n == 0,
+            DataExactCardinality { n, dp, .. } => *n == 0 && self.is_bottom_dp(dp),
+            DataSomeValuesFrom { .. } | DataHasValue { .. } => false,
+        }
+    }
+
+    fn is_bottom_atom(&self, atom: &Atom<A>) -> bool {
+        match atom {
+            Atom::ClassAtom { pred, .. } => self.is_bottom(pred),
+            Atom::ObjectPropertyAtom { pred, .. } => self.is_bottom_op(pred),
+            Atom::DataPropertyAtom { pred, .. } => self.is_bottom_dp(pred),
+            _ => false,
+        }
+    }
+
+    fn is_top_atom(&self, atom: &Atom<A>) -> bool {
+        match atom {
+            Atom::ClassAtom { pred, .. } => self.is_top(pred),
+            Atom::ObjectPropertyAtom { pred, .. } => self.is_top_op(pred),
+            Atom::DataPropertyAtom { pred, .. } => self.is_top_dp(pred),
+            _ => false,
+        }
+    }
+
+    /// Return true if the logical axiom `c` is local.
+    fn is_local(&self, c: &Component<A>) -> bool {
+        let bottoms = |v: &[ClassExpression<A>]| v.iter().filter(|ce| self.is_bottom(ce)).count();
+        match c {
+            Component::SubClassOf(SubClassOf { sub, sup }) => {
+                self.is_bottom(sub) || self.is_top(sup)
+            }
+            Component::EquivalentClasses(EquivalentClasses(v)) => {
+                v.iter().all(|ce| self.is_bottom(ce)) || v.iter().all(|ce| self.is_top(ce))
+            }
+            Component::DisjointClasses(DisjointClasses(v)) => bottoms(v) + 1 >= v.len(),
+            Component::DisjointUnion(DisjointUnion(c, v)) => {
+                let c = ClassExpression::Class(c.clone());
+                self.is_bottom(&c) && bottoms(v) == v.len()
+                    || self.is_top(&c)
+                        && bottoms(v) + 1 == v.len()
+                        && v.iter().any(|ce| self.is_top(ce))
+            }
+            Component::SubObjectPropertyOf(SubObjectPropertyOf { sub, sup }) => {
+                self.is_top_op(sup)
+                    || match sub {
+                        SubObjectPropertyExpression::ObjectPropertyChain(v) => {
+                            v.iter().any(|ope| self.is_bottom_op(ope))
+                        }
+                        SubObjectPropertyExpression::ObjectPropertyExpression(ope) => {
+                            self.is_bottom_op(ope)
+                        }
+                    }
+            }
+            Component::EquivalentObjectProperties(EquivalentObjectProperties(v)) => {
+                v.iter().all(|ope| self.is_bottom_op(ope))
+                    || v.iter().all(|ope| self.is_top_op(ope))
+            }
+            Component::DisjointObjectProperties(DisjointObjectProperties(v)) => {
+                v.iter().filter(|ope| self.is_bottom_op(ope)).count() + 1 >= v.len()
+            }
+            Component::InverseObjectProperties(InverseObjectProperties(a, b)) => {
+                let (a, b) = (a.clone().into(), b.clone().into());
+                self.is_bottom_op(&a) && self.is_bottom_op(&b)
+                    || self.is_top_op(&a) && self.is_top_op(&b)
+            }
+            Component::ObjectPropertyDomain(ObjectPropertyDomain { ope, ce })
+            | Component::ObjectPropertyRange(ObjectPropertyRange { ope, ce }) => {
+                self.is_bottom_op(ope) || self.is_top(ce)
+            }
+            Component::FunctionalObjectProperty(FunctionalObjectProperty(ope))
+            | Component::InverseFunctionalObjectProperty(InverseFunctionalObjectProperty(ope))
+            | Component::IrreflexiveObjectProperty(IrreflexiveObjectProperty(ope))
+            | Component::AsymmetricObjectProperty(AsymmetricObjectProperty(ope)) => {
+                self.is_bottom_op(ope)
+            }
+            Component::SymmetricObjectProperty(SymmetricObjectProperty(ope))
+            | Component::TransitiveObjectProperty(TransitiveObjectProperty(ope)) => {
+                self.is_bottom_op(ope) || self.is_top_op(ope)
+            }
+            Component::ReflexiveObjectProperty(ReflexiveObjectProperty(ope)) => self.is_top_op(ope),
+            Component::SubDataPropertyOf(SubDataPropertyOf { sub, sup }) => {
+                self.is_bottom_dp(sub) || self.is_top_dp(sup)
+            }
+            Component::EquivalentDataProperties(EquivalentDataProperties(v)) => {
+                v.iter().all(|dp| self.is_bottom_dp(dp)) || v.iter().all(|dp| self.is_top_dp(dp))
+            }
+            Component::DisjointDataProperties(DisjointDataProperties(v)) => {
+                v.iter().filter(|dp| self.is_bottom_dp(dp)).count() + 1 >= v.len()
+            }
+            Component::DataPropertyDomain(DataPropertyDomain { dp, ce }) => {
+                self.is_bottom_dp(dp) || self.is_top(ce)
+            }
+            Component::DataPropertyRange(DataPropertyRange { dp, .. })
+            | Component::FunctionalDataProperty(FunctionalDataProperty(dp)) => {
+                self.is_bottom_dp(dp)
+            }
+            Component::DatatypeDefinition(DatatypeDefinition { kind, .. }) => !self.contains(kind),
+            Component::HasKey(HasKey { ce, .. }) => self.is_bottom(ce),
+            Component::ClassAssertion(ClassAssertion { ce, .. }) => self.is_top(ce),
+            Component::ObjectPropertyAssertion(ObjectPropertyAssertion { ope, .. }) => {
+                self.is_top_op(ope)
+            }
+            Component::NegativeObjectPropertyAssertion(NegativeObjectPropertyAssertion {
+                ope,
+                ..
+            }) => self.is_bottom_op(ope),
+            Component::DataPropertyAssertion(DataPropertyAssertion { dp, .. }) => {
+                self.is_top_dp(dp)
+            }
+            Component::NegativeDataPropertyAssertion(NegativeDataPropertyAssertion {
+                dp, ..
+            }) => self.is_bottom_dp(dp),
+            Component::Rule(Rule { head, body }) => {
+                body.iter().any(|atom| self.is_bottom_atom(atom))
+                    || !head.is_empty() && head.iter().all(|atom| self.is_top_atom(atom))
+            }
+            Component::SameIndividual(_) | Component::DifferentIndividuals(_) => false,
+            _ => true,
+        }
+    }
+}
+
+/// Return the indexes of the axioms of `candidates` which form a
+/// module of them for `seed`.
+fn module<A: ForIRI>(
+    axioms: &[(&Component<A>, Signature<A>)],
+    candidates: &[usize],
+    seed: &Signature<A>,
+    top: bool,
+) -> Vec<usize> {
+    let mut index: HashMap<_, Vec<_>> = HashMap::new();
+    for n in candidates {
+        for e in &axioms[*n].1 {
+            index.entry(e).or_default().push(*n);
+        }
+    }
+
+    // The locality of an axiom changes only when an entity of its
+    // signature is added
+    let mut signature = seed.clone();
+    let mut included = HashSet::new();
+    let mut todo = candidates.to_vec();
+    while let Some(n) = todo.pop() {
+        if included.contains(&n) {
+            continue;
+        }
+        let locality = Locality {
+            signature: &signature,
+            top,
+        };
+        if locality.is_local(axioms[n].0) {
+            continue;
+        }
+        included.insert(n);
+        for e in &axioms[n].1 {
+            if signature.insert(e.clone()) {
+                todo.extend(index.get(e).into_iter().flatten());
+            }
+        }
+    }
+
+    let mut included: Vec<_> = included.into_iter().collect();
+    included.sort_unstable();
+    included
+}
+
+/// Extract the module of `ont` of kind `module_type` for the `seed`
+/// entities.
+///
+/// Return the module, and its signature, which includes the seed.
+pub fn extract<A: ForIRI, I: IntoIterator<Item = NamedOWLEntity<A>>>(
+    ont: &SetOntology<A>,
+    seed: I,
+    module_type: ModuleType,
+) -> (SetOntology<A>, HashSet<NamedOWLEntity<A>>) {
+    let seed: Signature<A> = seed.into_iter().collect();
+    let (extra, logical): (Vec<_>, Vec<_>) = ont.iter().partition(|ac| is_extra(&ac.component));
+    let axioms: Vec<_> = logical
+        .iter()
+        .map(|ac| (&ac.component, signature(&ac.component)))
+        .collect();
+
+    let all: Vec<_> = (0..axioms.len()).collect();
+    let included = match module_type {
+        ModuleType::Bottom => module(&axioms, &all, &seed, false),
+        ModuleType::Top => module(&axioms, &all, &seed, true),
+        ModuleType::Star => {
+            let mut included = all;
+            let mut top = false;
+            let mut unchanged = 0;
+            // Stop once both kinds of extraction leave the module unchanged
+            while unchanged < 2 {
+                let next = module(&axioms, &included, &seed, top);
+                if next.len() == included.len() {
+                    unchanged += 1;
+                } else {
+                    unchanged = 0;
+                }
+                included = next;
+                top = !top;
+            }
+            included
+        }
+    };
+
+    let mut signature = seed;
+    let mut module: SetOntology<A> = SetOntology::new();
+    for n in included {
+        signature.extend(axioms[n].1.iter().cloned());
+        module.insert(logical[n].clone());
+    }
+
+    let iris: HashSet<&IRI<A>> = signature.iter().map(iri).collect();
+    for ac in extra {
+        let copy = match &ac.component {
+            Component::AnnotationAssertion(AnnotationAssertion {
+                subject: AnnotationSubject::IRI(iri),
+                ..
+            }) => iris.contains(iri),
+            c => declared(c).is_some_and(|e| signature.contains(&e)),
+        };
+        if copy {
+            module.insert(ac.clone());
+        }
+    }
+
+    (module, signature)
+}
+
+/// How [`mireot`] extracts the classes between the lower and upper
+/// terms.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Intermediates {
+    /// Keep every class on the paths, with its told superclasses
+    Keep,
+    /// Keep only the lower and upper terms, each a subclass of its
+    /// nearest kept superclasses
+    Collapse,
+}
+
+/// Return the told named superclasses of `c`, with their axioms.
+fn told_supers<'a, A: ForIRI, AA: ForIndex<A>>(
+    index: &'a IRIMappedIndex<A, AA>,
+    c: &'a IRI<A>,
+) -> impl Iterator<Item = (&'a AnnotatedComponent<A>, &'a IRI<A>)> {
+    index
+        .component_for_iri(c)
+        .filter_map(move |ac| match &ac.component {
+            Component::SubClassOf(SubClassOf {
+                sub: ClassExpression::Class(sub),
+                sup: ClassExpression::Class(sup),
+            }) if sub.0 == *c => Some((ac, &sup.0)),
+            _ => None,
+        })
+}
+
+/// Extract the `lower` terms from the ontology of `index`, with their
+/// told superclasses up to the `upper` terms, in the style of
+/// [MIREOT](https://doi.org/10.3233/AO-2011-0087).
+///
+/// Superclasses are followed from each lower term to an upper term,
+/// or to the root of the hierarchy where there is none. The
+/// declarations and annotation assertions of every extracted term are
+/// copied, with the `SubClassOf` axioms between them.
+pub fn mireot<A: ForIRI, AA: ForIndex<A>>(
+    index: &IRIMappedIndex<A, AA>,
+    lower: &[IRI<A>],
+    upper: &[IRI<A>],
+    intermediates: Intermediates,
+) -> SetOntology<A> {
+    let upper: HashSet<_> = upper.iter().collect();
+    let mut module: SetOntology<A> = SetOntology::new();
+
+    let mut terms = HashSet::new();
+    let mut todo: Vec<_> = lower.iter().collect();
+    while let Some(t) = todo.pop() {
+        if !terms.insert(t) || upper.contains(t) {
+            continue;
+        }
+        for (ac, sup) in told_supers(index, t) {
+            if intermediates == Intermediates::Keep {
+                module.insert(ac.clone());
+            }
+            todo.push(sup);
+        }
+    }
+
+    if intermediates == Intermediates::Collapse {
+        terms.retain(|t| lower.contains(t) || upper.contains(t));
+        for t in terms.iter().filter(|t| !upper.contains(*t)) {
+            let mut seen = HashSet::new();
+            let mut todo: Vec<_> = told_supers(index, t).map(|(_, sup)| sup).collect();
+            while let Some(s) = todo.pop() {
+                if !seen.insert(s) {
+                    continue;
+                }
+                if terms.contains(s) {
+                    module.insert(SubClassOf {
+                        sub: Class(IRI::clone(t)).into(),
+                        sup: Class(s.clone()).into(),
+                    });
+                } else {
+                    todo.extend(told_supers(index, s).map(|(_, sup)| sup));
+                }
+            }
+        }
+    }
+
+    for t in terms {
+        for ac in index.component_for_iri(t) {
+            let copy = match &ac.component {
+                Component::AnnotationAssertion(AnnotationAssertion {
+                    subject: AnnotationSubject::IRI(subject),
+                    ..
+                }) => subject == t,
+                c => declared(c).is_some_and(|e| iri(&e) == t),
+            };
+            if copy {
+                module.insert(ac.clone());
+            }
+        }
+    }
+    module
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::RcStr;
+
+    use pretty_assertions::assert_eq;
+
+    fn extract_ofn(
+        ofn: &str,
+        seed: &[&str],
+        module_type: ModuleType,
+    ) -> (Vec<Component<RcStr>>, Vec<String>) {
+        let ont: SetOntology<RcStr> = crate::io::ofn::reader::read_example(ofn);
+
+        let b = Build::new_rc();
+        let seed = seed.iter().map(|s| {
+            let iri = format!("http://example.com/{}", &s[1..]);
+            match &s[..1] {
+                "C" => b.class(iri).into(),
+                "O" => b.object_property(iri).into(),
+                _ => b.named_individual(iri).into(),
+            }
+        });
+        let (module, signature) = extract(&ont, seed, module_type);
+
+        let mut components: Vec<_> = module.into_iter().map(|ac| ac.component).collect();
+        components.sort();
+        let mut signature: Vec<_> = signature
+            .into_iter()
+            .map(|e| iri(&e).as_ref()["http://example.com/".len()..].to_string())
+            .collect();
+        signature.sort();
+        (components, signature)
+    }
+
+    fn class(s: &str) -> ClassExpression<RcStr> {
+        Build::new_rc()
+            .class(format!("http://example.com/{}", s))
+            .into()
+    }
+
+    fn sub(a: &str, b: &str) -> Component<RcStr> {
+        SubClassOf {
+            sub: class(a),
+            sup: class(b),
+        }
+        .into()
+    }
+
+    const HIERARCHY: &str = "Declaration(Class(:A))
+        Declaration(Class(:E))
+        AnnotationAssertion(rdfs:label :A \"a\")
+        AnnotationAssertion(rdfs:label :E \"e\")
+        SubClassOf(:A :B)
+        SubClassOf(:B :C)
+        SubClassOf(:D :A)
+        SubClassOf(:E :F)";
+
+    #[test]
+    fn bottom() {
+        let (module, signature) = extract_ofn(HIERARCHY, &["CA"], ModuleType::Bottom);
+        assert_eq!(signature, vec!["A", "B", "C"]);
+        assert_eq!(module.len(), 4);
+        assert!(module.contains(&sub("A", "B")));
+        assert!(module.contains(&sub("B", "C")));
+        assert!(
+            module.contains(&DeclareClass(Build::new_rc().class("http://example.com/A")).into())
+        );
+    }
+
+    #[test]
+    fn top() {
+        let (module, signature) = extract_ofn(HIERARCHY, &["CA"], ModuleType::Top);
+        assert_eq!(signature, vec!["A", "D"]);
+        assert_eq!(module.len(), 3);
+        assert!(module.contains(&sub("D", "A")));
+    }
+
+    #[test]
+    fn star() {
+        let (module, signature) = extract_ofn(HIERARCHY, &["CA", "CB"], ModuleType::Star);
+        assert_eq!(signature, vec!["A", "B"]);
+        assert_eq!(module.len(), 3);
+        assert!(module.contains(&sub("A", "B")));
+
+        let (module, _) = extract_ofn(HIERARCHY, &["CE"], ModuleType::Star);
+        assert_eq!(module.len(), 2);
+    }
+
+    #[test]
+    fn properties() {
+        let ofn = "SubClassOf(:A ObjectSomeValuesFrom(:r :B))
+                   ObjectPropertyDomain(:r :C)
+                   SubObjectPropertyOf(:r :s)
+                   ObjectPropertyRange(:t :D)
+                   SubClassOf(:X ObjectAllValuesFrom(:t :Y))
+                   DisjointClasses(:A :Z)
+                   DisjointClasses(:P :Q)";
+        let (module, signature) = extract_ofn(ofn, &["CA"], ModuleType::Bottom);
+        assert_eq!(signature, vec!["A", "B", "C", "r", "s"]);
+        assert_eq!(module.len(), 3);
+
+        let (module, signature) = extract_ofn(ofn, &["Ot"], ModuleType::Bottom);
+        assert_eq!(signature, vec!["D", "t"]);
+        assert_eq!(module.len(), 1);
+    }
+
+    #[test]
+    fn assertions_and_rules() {
+        let ofn = "ClassAssertion(:A :i)
+                   ClassAssertion(:B :j)
+                   ObjectPropertyAssertion(:r :i :j)
+                   DLSafeRule(Body(ClassAtom(:X Variable(:x))) Head(ClassAtom(:A Variable(:x))))
+                   DLSafeRule(Body(ClassAtom(:A Variable(:x))) Head(ClassAtom(:Y Variable(:x))))";
+
+        // Assertions are never ⊥-local, unless the class is ⊤
+        let (module, signature) = extract_ofn(ofn, &["CA"], ModuleType::Bottom);
+        assert_eq!(signature, vec!["A", "B", "Y", "i", "j", "r"]);
+        assert_eq!(module.len(), 4);
+
+        let (module, signature) = extract_ofn(ofn, &["CA"], ModuleType::Top);
+        assert_eq!(signature, vec!["A", "X", "i"]);
+        assert_eq!(module.len(), 2);
+    }
+
+    const TERMS: &str = "Declaration(Class(:A))
+        Declaration(Class(:C))
+        AnnotationAssertion(rdfs:label :A \"a\")
+        AnnotationAssertion(rdfs:label :B \"b\")
+        AnnotationAssertion(rdfs:label :F \"f\")
+        AnnotationAssertion(rdfs:comment :X \"x\")
+        SubClassOf(:A :B)
+        SubClassOf(:B :C)
+        SubClassOf(:C :D)
+        SubClassOf(:D :E)
+        SubClassOf(:A :X)
+        SubClassOf(:F :B)
+        SubClassOf(:A ObjectSomeValuesFrom(:r :F))";
+
+    fn mireot_ofn(lower: &[&str], upper: &[&str], intermediates: Intermediates) -> Vec<String> {
+        let ont: SetOntology<RcStr> = crate::io::ofn::reader::read_example(TERMS);
+        let ont = crate::ontology::iri_mapped::RcIRIMappedOntology::from(ont);
+
+        let b = Build::new_rc();
+        let iris = |v: &[&str]| -> Vec<_> {
+            v.iter()
+                .map(|s| b.iri(format!("http://example.com/{}", s)))
+                .collect()
+        };
+        let module = mireot(ont.j(), &iris(lower), &iris(upper), intermediates);
+
+        let mut ofn: Vec<_> = module
+            .iter()
+            .map(|ac| {
+                crate::io::ofn::writer::AsFunctional::as_functional(&ac.component)
+                    .to_string()
+                    .replace("http://example.com/", "")
+            })
+            .collect();
+        ofn.sort();
+        ofn
+    }
+
+    #[test]
+    fn mireot_keep() {
+        assert_eq!(
+            mireot_ofn(&["A"], &["C"], Intermediates::Keep),
+            vec![
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#comment> <X> \"x\")",
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> <A> \"a\")",
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> <B> \"b\")",
+                "Declaration(Class(<A>))",
+                "Declaration(Class(<C>))",
+                "SubClassOf(<A> <B>)",
+                "SubClassOf(<A> <X>)",
+                "SubClassOf(<B> <C>)",
+            ]
+        );
+        assert_eq!(mireot_ofn(&["A"], &[], Intermediates::Keep).len(), 10);
+    }
+
+    #[test]
+    fn mireot_collapse() {
+        assert_eq!(
+            mireot_ofn(&["A"], &["C"], Intermediates::Collapse),
+            vec![
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> <A> \"a\")",
+                "Declaration(Class(<A>))",
+                "Declaration(Class(<C>))",
+                "SubClassOf(<A> <C>)",
+            ]
+        );
+        assert_eq!(
+            mireot_ofn(&["A", "F"], &["C", "E"], Intermediates::Collapse),
+            vec![
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> <A> \"a\")",
+                "AnnotationAssertion(<http://www.w3.org/2000/01/rdf-schema#label> <F> \"f\")",
+                "Declaration(Class(<A>))",
+                "Declaration(Class(<C>))",
+                "SubClassOf(<A> <C>)",
+                "SubClassOf(<F> <C>)",
+            ]
+        );
+    }
+}
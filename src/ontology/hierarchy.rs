@@ -0,0 +1,569 @@
+//! Told hierarchies of classes and properties.
+
+//! # Overview
+//!
+//! This module provides a `HierarchyIndex`, which maintains the
+//! hierarchies of named classes, object properties, data properties
+//! and annotation properties, as they are asserted in `SubClassOf`,
+//! `EquivalentClasses` and the equivalent property axioms. No
+//! reasoning is performed, and axioms involving anonymous class or
+//! property expressions are ignored.
+//!
+//! The index is updated incrementally as axioms are inserted and
+//! removed. As it ignores most axioms passed to it, it does not
+//! provide iteration.
+use super::indexed::{ForIndex, OntologyIndex};
+use crate::model::*;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+type Edges<E> = HashMap<E, HashMap<E, usize>>;
+
+fn add_edge<E: Clone + Eq + Hash>(edges: &mut Edges<E>, from: &E, to: &E) {
+    *edges
+        .entry(from.clone())
+        .or_default()
+        .entry(to.clone())
+        .or_default() += 1;
+}
+
+fn remove_edge<E: Clone + Eq + Hash>(edges: &mut Edges<E>, from: &E, to: &E) {
+    if let Some(tos) = edges.get_mut(from) {
+        if let Some(count) = tos.get_mut(to) {
+            *count -= 1;
+            if *count == 0 {
+                tos.remove(to);
+            }
+        }
+        if tos.is_empty() {
+            edges.remove(from);
+        }
+    }
+}
+
+fn targets<'a, E: Eq + Hash>(edges: &'a Edges<E>, from: &E) -> impl Iterator<Item = &'a E> {
+    edges.get(from).into_iter().flat_map(|tos| tos.keys())
+}
+
+/// A told hierarchy of entities of one kind.
+///
+/// Each relationship is counted, so that it remains until every
+/// axiom asserting it has been removed.
+#[derive(Debug)]
+pub struct Hierarchy<E> {
+    sup: Edges<E>,
+    sub: Edges<E>,
+    equivalent: Edges<E>,
+}
+
+impl<E> Default for Hierarchy<E> {
+    fn default() -> Self {
+        Hierarchy {
+            sup: HashMap::new(),
+            sub: HashMap::new(),
+            equivalent: HashMap::new(),
+        }
+    }
+}
+
+impl<E: Clone + Eq + Hash + Ord> Hierarchy<E> {
+    pub fn new() -> Hierarchy<E> {
+        Default::default()
+    }
+
+    /// Assert that `sub` is a subentity of `sup`.
+    pub fn insert_sub(&mut self, sub: &E, sup: &E) {
+        add_edge(&mut self.sup, sub, sup);
+        add_edge(&mut self.sub, sup, sub);
+    }
+
+    /// Remove one assertion that `sub` is a subentity of `sup`.
+    pub fn remove_sub(&mut self, sub: &E, sup: &E) {
+        remove_edge(&mut self.sup, sub, sup);
+        remove_edge(&mut self.sub, sup, sub);
+    }
+
+    /// Assert that `a` and `b` are equivalent.
+    pub fn insert_equivalent(&mut self, a: &E, b: &E) {
+        add_edge(&mut self.equivalent, a, b);
+        add_edge(&mut self.equivalent, b, a);
+    }
+
+    /// Remove one assertion that `a` and `b` are equivalent.
+    pub fn remove_equivalent(&mut self, a: &E, b: &E) {
+        remove_edge(&mut self.equivalent, a, b);
+        remove_edge(&mut self.equivalent, b, a);
+    }
+
+    /// Return every entity in the hierarchy.
+    pub fn entities(&self) -> BTreeSet<E> {
+        self.sup
+            .keys()
+            .chain(self.sub.keys())
+            .chain(self.equivalent.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Return the entities which are equivalent to `e`, directly or
+    /// through other equivalences, not including `e`.
+    pub fn equivalents(&self, e: &E) -> BTreeSet<E> {
+        let mut equivalents = self.reachable(e, |e| targets(&self.equivalent, e).collect());
+        equivalents.remove(e);
+        equivalents
+    }
+
+    /// Return `e` with its equivalents.
+    fn equivalence_set(&self, e: &E) -> BTreeSet<E> {
+        let mut set = self.equivalents(e);
+        set.insert(e.clone());
+        set
+    }
+
+    /// Return the asserted superentities of `e` and its equivalents.
+    pub fn direct_supers(&self, e: &E) -> BTreeSet<E> {
+        self.direct(e, &self.sup)
+    }
+
+    /// Return the asserted subentities of `e` and its equivalents.
+    pub fn direct_subs(&self, e: &E) -> BTreeSet<E> {
+        self.direct(e, &self.sub)
+    }
+
+    fn direct(&self, e: &E, edges: &Edges<E>) -> BTreeSet<E> {
+        let set = self.equivalence_set(e);
+        set.iter()
+            .flat_map(|e| targets(edges, e))
+            .filter(|e| !set.contains(e))
+            .cloned()
+            .collect()
+    }
+
+    /// Return every superentity of `e`, not including `e` or its
+    /// equivalents.
+    pub fn ancestors(&self, e: &E) -> BTreeSet<E> {
+        self.closure(e, &self.sup)
+    }
+
+    /// Return every subentity of `e`, not including `e` or its
+    /// equivalents.
+    pub fn descendants(&self, e: &E) -> BTreeSet<E> {
+        self.closure(e, &self.sub)
+    }
+
+    fn closure(&self, e: &E, edges: &Edges<E>) -> BTreeSet<E> {
+        let mut closure = self.reachable(e, |e| {
+            targets(edges, e)
+                .chain(targets(&self.equivalent, e))
+                .collect()
+        });
+        for e in self.equivalence_set(e) {
+            closure.remove(&e);
+        }
+        closure
+    }
+
+    /// Return every entity reachable from `e` with `next`, including
+    /// `e`.
+    fn reachable<'a, F>(&'a self, e: &'a E, next: F) -> BTreeSet<E>
+    where
+        F: Fn(&'a E) -> Vec<&'a E>,
+    {
+        let mut seen = HashSet::new();
+        let mut stack = vec![e];
+        while let Some(e) = stack.pop() {
+            if seen.insert(e) {
+                stack.extend(next(e));
+            }
+        }
+        seen.into_iter().cloned().collect()
+    }
+
+    /// Return the entities which have no superentities.
+    pub fn roots(&self) -> BTreeSet<E> {
+        self.entities()
+            .into_iter()
+            .filter(|e| self.direct_supers(e).is_empty())
+            .collect()
+    }
+
+    /// Return the entities which have no subentities.
+    pub fn leaves(&self) -> BTreeSet<E> {
+        self.entities()
+            .into_iter()
+            .filter(|e| self.direct_subs(e).is_empty())
+            .collect()
+    }
+
+    /// Return the cycles in the hierarchy.
+    ///
+    /// Each cycle is a set of entities which are subentities of each
+    /// other, but are not all asserted to be equivalent.
+    pub fn cycles(&self) -> Vec<BTreeSet<E>> {
+        let entities = self.entities();
+        let both = |edges: &'_ Edges<E>, e: &E| -> Vec<E> {
+            targets(edges, e)
+                .chain(targets(&self.equivalent, e))
+                .cloned()
+                .collect()
+        };
+
+        // Kosaraju's algorithm: order entities by the time at which
+        // their depth first search finishes
+        let mut seen = HashSet::new();
+        let mut order = vec![];
+        for e in &entities {
+            if !seen.insert(e.clone()) {
+                continue;
+            }
+            let mut stack = vec![(e.clone(), both(&self.sup, e))];
+            while let Some((e, next)) = stack.last_mut() {
+                match next.pop() {
+                    Some(n) => {
+                        if seen.insert(n.clone()) {
+                            let nn = both(&self.sup, &n);
+                            stack.push((n, nn));
+                        }
+                    }
+                    None => {
+                        order.push(e.clone());
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        // Then collect the components along reversed edges, in
+        // reverse order
+        let mut assigned = HashSet::new();
+        let mut cycles = vec![];
+        for e in order.iter().rev() {
+            if assigned.contains(e) {
+                continue;
+            }
+            let mut component = BTreeSet::new();
+            let mut stack = vec![e.clone()];
+            while let Some(e) = stack.pop() {
+                if assigned.insert(e.clone()) {
+                    stack.extend(both(&self.sub, &e));
+                    component.insert(e);
+                }
+            }
+
+            let cyclic = component
+                .iter()
+                .any(|e| targets(&self.sup, e).any(|s| component.contains(s)))
+                && component
+                    .iter()
+                    .next()
+                    .is_some_and(|e| !component.is_subset(&self.equivalence_set(e)));
+            if cyclic {
+                cycles.push(component);
+            }
+        }
+        cycles.sort();
+        cycles
+    }
+}
+
+/// An `OntologyIndex` of the told hierarchies of classes and
+/// properties.
+#[derive(Debug)]
+pub struct HierarchyIndex<A, AA> {
+    components: HashSet<AA>,
+    classes: Hierarchy<Class<A>>,
+    object_properties: Hierarchy<ObjectProperty<A>>,
+    data_properties: Hierarchy<DataProperty<A>>,
+    annotation_properties: Hierarchy<AnnotationProperty<A>>,
+}
+
+impl<A, AA> Default for HierarchyIndex<A, AA> {
+    fn default() -> Self {
+        HierarchyIndex {
+            components: HashSet::new(),
+            classes: Default::default(),
+            object_properties: Default::default(),
+            data_properties: Default::default(),
+            annotation_properties: Default::default(),
+        }
+    }
+}
+
+/// Return the named classes of `v`.
+fn named_classes<A: ForIRI>(v: &[ClassExpression<A>]) -> Vec<&Class<A>> {
+    v.iter()
+        .filter_map(|ce| match ce {
+            ClassExpression::Class(c) => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Return the named properties of `v`.
+fn named_properties<A: ForIRI>(v: &[ObjectPropertyExpression<A>]) -> Vec<&ObjectProperty<A>> {
+    v.iter()
+        .filter_map(|ope| match ope {
+            ObjectPropertyExpression::ObjectProperty(op) => Some(op),
+            _ => None,
+        })
+        .collect()
+}
+
+impl<A: ForIRI, AA: ForIndex<A>> HierarchyIndex<A, AA> {
+    pub fn new() -> HierarchyIndex<A, AA> {
+        Default::default()
+    }
+
+    pub fn classes(&self) -> &Hierarchy<Class<A>> {
+        &self.classes
+    }
+
+    pub fn object_properties(&self) -> &Hierarchy<ObjectProperty<A>> {
+        &self.object_properties
+    }
+
+    pub fn data_properties(&self) -> &Hierarchy<DataProperty<A>> {
+        &self.data_properties
+    }
+
+    pub fn annotation_properties(&self) -> &Hierarchy<AnnotationProperty<A>> {
+        &self.annotation_properties
+    }
+
+    /// Add (or remove if `insert` is false) the relationships asserted
+    /// by `cmp`, returning false if it asserts none.
+    fn update(&mut self, cmp: &Component<A>, insert: bool) -> bool {
+        fn sub<E: Clone + Eq + Hash + Ord>(h: &mut Hierarchy<E>, sub: &E, sup: &E, insert: bool) {
+            if insert {
+                h.insert_sub(sub, sup)
+            } else {
+                h.remove_sub(sub, sup)
+            }
+        }
+
+        fn equivalent<E: Clone + Eq + Hash + Ord>(h: &mut Hierarchy<E>, v: &[&E], insert: bool) {
+            for w in v.windows(2) {
+                if insert {
+                    h.insert_equivalent(w[0], w[1])
+                } else {
+                    h.remove_equivalent(w[0], w[1])
+                }
+            }
+        }
+
+        match cmp {
+            Component::SubClassOf(SubClassOf {
+                sub: ClassExpression::Class(sb),
+                sup: ClassExpression::Class(sp),
+            }) => sub(&mut self.classes, sb, sp, insert),
+            Component::EquivalentClasses(EquivalentClasses(v)) => {
+                let named = named_classes(v);
+                if named.len() < 2 {
+                    return false;
+                }
+                equivalent(&mut self.classes, &named, insert)
+            }
+            Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                sub:
+                    SubObjectPropertyExpression::ObjectPropertyExpression(
+                        ObjectPropertyExpression::ObjectProperty(sb),
+                    ),
+                sup: ObjectPropertyExpression::ObjectProperty(sp),
+            }) => sub(&mut self.object_properties, sb, sp, insert),
+            Component::EquivalentObjectProperties(EquivalentObjectProperties(v)) => {
+                let named = named_properties(v);
+                if named.len() < 2 {
+                    return false;
+                }
+                equivalent(&mut self.object_properties, &named, insert)
+            }
+            Component::SubDataPropertyOf(SubDataPropertyOf { sub: sb, sup: sp }) => {
+                sub(&mut self.data_properties, sb, sp, insert)
+            }
+            Component::EquivalentDataProperties(EquivalentDataProperties(v)) => {
+                let v: Vec<_> = v.iter().collect();
+                equivalent(&mut self.data_properties, &v, insert)
+            }
+            Component::SubAnnotationPropertyOf(SubAnnotationPropertyOf { sub: sb, sup: sp }) => {
+                sub(&mut self.annotation_properties, sb, sp, insert)
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<A: ForIRI, AA: ForIndex<A>> OntologyIndex<A, AA> for HierarchyIndex<A, AA> {
+    fn index_insert(&mut self, cmp: AA) -> bool {
+        if self.components.contains(cmp.borrow()) || !self.update(&cmp.borrow().component, true) {
+            return false;
+        }
+        self.components.insert(cmp)
+    }
+
+    fn index_remove(&mut self, cmp: &AnnotatedComponent<A>) -> bool {
+        if self.components.remove(cmp) {
+            self.update(&cmp.component, false)
+        } else {
+            false
+        }
+    }
+}
+
+impl HierarchyIndex<RcStr, RcAnnotatedComponent> {
+    pub fn new_rc() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::ontology::indexed::OneIndexedOntology;
+
+    fn classes(v: &[&str]) -> BTreeSet<Class<RcStr>> {
+        let b = Build::new_rc();
+        v.iter()
+            .map(|s| b.class(format!("http://example.com/{}", s)))
+            .collect()
+    }
+
+    fn class(s: &str) -> Class<RcStr> {
+        classes(&[s]).into_iter().next().unwrap()
+    }
+
+    fn sub_class_of(sub: &str, sup: &str) -> Component<RcStr> {
+        SubClassOf {
+            sub: class(sub).into(),
+            sup: class(sup).into(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_hierarchy() {
+        let mut o = OneIndexedOntology::new(HierarchyIndex::new_rc());
+        o.insert(sub_class_of("B", "A"));
+        o.insert(sub_class_of("C", "B"));
+        o.insert(sub_class_of("D", "B"));
+        o.insert(EquivalentClasses(vec![
+            class("D").into(),
+            class("E").into(),
+            ClassExpression::ObjectComplementOf(Box::new(class("A").into())),
+        ]));
+
+        let h = o.i().classes();
+        assert_eq!(h.direct_supers(&class("B")), classes(&["A"]));
+        assert_eq!(h.direct_subs(&class("B")), classes(&["C", "D"]));
+        assert_eq!(h.direct_supers(&class("E")), classes(&["B"]));
+        assert_eq!(h.equivalents(&class("E")), classes(&["D"]));
+        assert_eq!(h.ancestors(&class("C")), classes(&["A", "B"]));
+        assert_eq!(h.descendants(&class("A")), classes(&["B", "C", "D", "E"]));
+        assert_eq!(h.roots(), classes(&["A"]));
+        assert_eq!(h.leaves(), classes(&["C", "D", "E"]));
+        assert!(h.cycles().is_empty());
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut o = OneIndexedOntology::new(HierarchyIndex::new_rc());
+        let ax: AnnotatedComponent<_> = sub_class_of("B", "A").into();
+        let annotated = AnnotatedComponent::new(
+            sub_class_of("B", "A"),
+            vec![Annotation {
+                ap: Build::new_rc().annotation_property("http://example.com/ap"),
+                av: Literal::Simple {
+                    literal: "x".to_string(),
+                }
+                .into(),
+            }]
+            .into_iter()
+            .collect(),
+        );
+
+        assert!(o.insert(ax.clone()));
+        assert!(!o.insert(ax.clone()));
+        assert!(o.insert(annotated.clone()));
+        assert!(!o.insert(DeclareClass(class("A"))));
+
+        // Still asserted by the annotated axiom
+        assert!(o.take(&ax).is_some());
+        assert_eq!(o.i().classes().direct_supers(&class("B")), classes(&["A"]));
+
+        assert!(o.take(&ax).is_none());
+        assert!(o.take(&annotated).is_some());
+        assert!(o.i().classes().direct_supers(&class("B")).is_empty());
+        assert!(o.i().classes().entities().is_empty());
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut o = OneIndexedOntology::new(HierarchyIndex::new_rc());
+        o.insert(sub_class_of("A", "B"));
+        o.insert(sub_class_of("B", "C"));
+        o.insert(sub_class_of("C", "A"));
+        o.insert(sub_class_of("D", "A"));
+        o.insert(EquivalentClasses(vec![
+            class("E").into(),
+            class("F").into(),
+        ]));
+
+        assert_eq!(o.i().classes().cycles(), vec![classes(&["A", "B", "C"])]);
+
+        // Subclass axioms between equivalent classes are not a cycle
+        o.insert(sub_class_of("E", "F"));
+        assert_eq!(o.i().classes().cycles(), vec![classes(&["A", "B", "C"])]);
+
+        // Unless they are only partly asserted to be equivalent
+        o.insert(sub_class_of("F", "G"));
+        o.insert(sub_class_of("G", "E"));
+        assert_eq!(
+            o.i().classes().cycles(),
+            vec![classes(&["A", "B", "C"]), classes(&["E", "F", "G"])]
+        );
+    }
+
+    #[test]
+    fn test_properties() {
+        let b = Build::new_rc();
+        let mut o = OneIndexedOntology::new(HierarchyIndex::new_rc());
+        let r = b.object_property("http://example.com/r");
+        let s = b.object_property("http://example.com/s");
+        o.insert(SubObjectPropertyOf {
+            sub: ObjectPropertyExpression::from(r.clone()).into(),
+            sup: s.clone().into(),
+        });
+        o.insert(SubObjectPropertyOf {
+            sub: ObjectPropertyExpression::InverseObjectProperty(s.clone()).into(),
+            sup: r.clone().into(),
+        });
+        let d = b.data_property("http://example.com/d");
+        let e = b.data_property("http://example.com/e");
+        o.insert(SubDataPropertyOf {
+            sub: d.clone(),
+            sup: e.clone(),
+        });
+        let ap = b.annotation_property("http://example.com/ap");
+        let aq = b.annotation_property("http://example.com/aq");
+        o.insert(SubAnnotationPropertyOf {
+            sub: ap.clone(),
+            sup: aq.clone(),
+        });
+
+        assert_eq!(
+            o.i().object_properties().ancestors(&r),
+            vec![s].into_iter().collect()
+        );
+        assert_eq!(o.i().object_properties().entities().len(), 2);
+        assert_eq!(
+            o.i().data_properties().direct_subs(&e),
+            vec![d].into_iter().collect()
+        );
+        assert_eq!(
+            o.i().annotation_properties().roots(),
+            vec![aq].into_iter().collect()
+        );
+    }
+}
@@ -292,6 +292,10 @@ impl<A: ForIRI, AA: ForIndex<A>> IRIMappedOntology<A, AA> {
     pub fn iter(&self) -> std::vec::IntoIter<&AnnotatedComponent<A>> {
         self.0.i().into_iter()
     }
+
+    pub fn j(&self) -> &IRIMappedIndex<A, AA> {
+        self.0.j()
+    }
 }
 impl RcIRIMappedOntology {
     pub fn new_rc() -> Self {
@@ -31,10 +31,12 @@
 //! [`declaration_mapped`](declaration_mapped.html) indexes only
 //! declaration axioms, allowing rapid look up of the declared type
 //! of an IRI. As it ignores most axioms passed to it, it does not
-//! provide iteration.
+//! provide iteration. Similarly, [`hierarchy`](hierarchy.html)
+//! maintains the told hierarchies of classes and properties.
 
 pub mod component_mapped;
 pub mod declaration_mapped;
+pub mod hierarchy;
 pub mod indexed;
 pub mod iri_mapped;
 pub mod logically_equal;
@@ -0,0 +1,591 @@
+//! Record and replay changes to an ontology.
+//!
+//! # Overview
+//!
+//! A [`Patch`] is an ordered list of [`Operation`]s, each of which
+//! adds or removes a single component, together with the
+//! `OntologyID` which an ontology must have for the patch to apply.
+//! A patch can be made from a [`Diff`], and applied to or reverted
+//! from any `MutableOntology`.
+//!
+//! Patches are written in a syntax like OWL Functional-Style, with
+//! the component of each operation written as it would be in an
+//! ontology, and the `OntologyID` written as an `Ontology` element
+//! with no components.
+//!
+//! ```text
+//! Prefix(:=<http://example.com/>)
+//! Patch(
+//! Require(Ontology(<http://example.com/o>))
+//! Remove(SubClassOf(:A :B))
+//! Add(SubClassOf(:A :C))
+//! )
+//! ```
+use std::io::{BufRead, Write};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use curie::PrefixMapping;
+
+use crate::diff::Diff;
+use crate::error::{invalid, HornedError};
+use crate::io::ofn::reader::ComponentReader;
+use crate::io::ofn::writer::AsFunctional;
+use crate::model::*;
+
+/// A single change to an ontology.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Operation<A> {
+    /// Add a component which is not in the ontology
+    Add(AnnotatedComponent<A>),
+    /// Remove a component which is in the ontology
+    Remove(AnnotatedComponent<A>),
+}
+
+impl<A: ForIRI> Operation<A> {
+    /// Return the component which is added or removed.
+    pub fn component(&self) -> &AnnotatedComponent<A> {
+        match self {
+            Operation::Add(ac) | Operation::Remove(ac) => ac,
+        }
+    }
+
+    /// Return the operation which undoes this one.
+    pub fn inverse(&self) -> Operation<A> {
+        match self {
+            Operation::Add(ac) => Operation::Remove(ac.clone()),
+            Operation::Remove(ac) => Operation::Add(ac.clone()),
+        }
+    }
+
+    /// Perform the operation on `o`, returning false if it conflicts
+    /// with the ontology, in which case `o` is unchanged.
+    fn perform<O: MutableOntology<A>>(&self, o: &mut O) -> bool {
+        match self {
+            Operation::Add(ac) => o.insert(ac.clone()),
+            Operation::Remove(ac) => o.take(ac).is_some(),
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            Operation::Add(_) => "Add",
+            Operation::Remove(_) => "Remove",
+        }
+    }
+}
+
+/// An ordered list of changes to an ontology.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Patch<A> {
+    /// The `OntologyID` which the ontology must have, if any
+    pub require: Option<OntologyID<A>>,
+    /// The operations, in the order in which they are applied
+    pub operations: Vec<Operation<A>>,
+}
+
+impl<A: ForIRI> Patch<A> {
+    pub fn new() -> Patch<A> {
+        Patch {
+            require: None,
+            operations: vec![],
+        }
+    }
+
+    /// Return a patch which makes the changes in `diff`.
+    ///
+    /// Components which have changed are removed and then added
+    /// again. As anonymous individuals are compared up to renaming by
+    /// [`diff`](crate::diff::diff), the added components may name
+    /// them differently from those which are unchanged.
+    pub fn from_diff(diff: &Diff<A>) -> Patch<A> {
+        let mut operations: Vec<_> = diff
+            .removed
+            .iter()
+            .cloned()
+            .map(Operation::Remove)
+            .collect();
+        for (old, new) in &diff.changed {
+            operations.push(Operation::Remove(old.clone()));
+            operations.push(Operation::Add(new.clone()));
+        }
+        operations.extend(diff.added.iter().cloned().map(Operation::Add));
+
+        Patch {
+            require: None,
+            operations,
+        }
+    }
+
+    /// Return the `OntologyID` which the ontology will have once the
+    /// patch is applied, as far as is known.
+    fn result(&self) -> Option<OntologyID<A>> {
+        let mut id = self.require.clone();
+        for op in &self.operations {
+            match (op, &op.component().component) {
+                (Operation::Add(_), Component::OntologyID(added)) => {
+                    id = Some(added.clone());
+                }
+                (Operation::Remove(_), Component::OntologyID(removed))
+                    if id.as_ref() == Some(removed) =>
+                {
+                    id = None;
+                }
+                _ => {}
+            }
+        }
+        id
+    }
+
+    /// Return the patch which undoes this one.
+    pub fn inverse(&self) -> Patch<A> {
+        Patch {
+            require: self.result(),
+            operations: self
+                .operations
+                .iter()
+                .rev()
+                .map(|op| op.inverse())
+                .collect(),
+        }
+    }
+
+    /// Apply the patch to `o`.
+    ///
+    /// Returns an error if `o` does not have the required
+    /// `OntologyID`, or if an operation conflicts with the ontology
+    /// by adding a component which is already present or removing
+    /// one which is absent. In either case, `o` is left unchanged.
+    pub fn apply<O: MutableOntology<A>>(&self, o: &mut O) -> Result<(), HornedError> {
+        if let Some(id) = &self.require {
+            let ac: AnnotatedComponent<A> = Component::OntologyID(id.clone()).into();
+            match o.take(&ac) {
+                Some(ac) => {
+                    o.insert(ac);
+                }
+                None => {
+                    return Err(invalid!(
+                        "Ontology does not have the required ID: Ontology({})",
+                        id.as_functional()
+                    ));
+                }
+            }
+        }
+
+        for (i, op) in self.operations.iter().enumerate() {
+            if !op.perform(o) {
+                for done in self.operations[..i].iter().rev() {
+                    done.inverse().perform(o);
+                }
+                return Err(invalid!(
+                    "Operation {} conflicts with the ontology: {}({}) {}",
+                    i,
+                    op.keyword(),
+                    op.component().as_functional(),
+                    match op {
+                        Operation::Add(_) => "is already present",
+                        Operation::Remove(_) => "is not present",
+                    }
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Revert the patch from `o`, which it must previously have been
+    /// applied to.
+    ///
+    /// Conflicts are reported as for [`apply`](Patch::apply).
+    pub fn revert<O: MutableOntology<A>>(&self, o: &mut O) -> Result<(), HornedError> {
+        self.inverse().apply(o)
+    }
+}
+
+impl<A: ForIRI> Default for Patch<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Write the `OntologyID` or component `ac` as it appears in a patch.
+fn component<A: ForIRI>(
+    ac: &AnnotatedComponent<A>,
+    mapping: &PrefixMapping,
+) -> Result<String, HornedError> {
+    match &ac.component {
+        Component::OntologyID(id) => Ok(format!(
+            "Ontology({})",
+            id.as_functional_with_prefixes(mapping)
+        )),
+        Component::DocIRI(_) => Err(invalid!("DocIRI cannot be written to a patch")),
+        _ => Ok(ac.as_functional_with_prefixes(mapping).to_string()),
+    }
+}
+
+/// Write a patch to `write`, using the given `PrefixMapping`.
+pub fn write<A: ForIRI, W: Write>(
+    mut write: W,
+    patch: &Patch<A>,
+    mapping: Option<&PrefixMapping>,
+) -> Result<(), HornedError> {
+    let default_mapper = PrefixMapping::default();
+    let mapping = match mapping {
+        Some(m) => m,
+        None => &default_mapper,
+    };
+
+    write!(
+        &mut write,
+        "{}",
+        <PrefixMapping as AsFunctional<A>>::as_functional(mapping)
+    )?;
+    writeln!(write, "Patch(")?;
+
+    if let Some(id) = &patch.require {
+        let ac = Component::OntologyID(id.clone()).into();
+        writeln!(write, "Require({})", component(&ac, mapping)?)?;
+    }
+    for op in &patch.operations {
+        writeln!(
+            write,
+            "{}({})",
+            op.keyword(),
+            component(op.component(), mapping)?
+        )?;
+    }
+
+    writeln!(write, ")")?;
+    Ok(())
+}
+
+/// Skip whitespace and comments.
+fn skip(chars: &mut Peekable<CharIndices<'_>>) {
+    while let Some(&(_, c)) = chars.peek() {
+        if c == '#' {
+            chars.find(|&(_, c)| c == '\n');
+        } else if c.is_whitespace() {
+            chars.next();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Split `s`, which starts at `offset` in the document, into a
+/// sequence of elements, returning the offset, keyword and contents
+/// of each.
+fn elements(s: &str, offset: usize) -> Result<Vec<(usize, &str, &str)>, HornedError> {
+    let mut elements = vec![];
+    let mut chars = s.char_indices().peekable();
+    let at = |msg: &str, i: usize| HornedError::invalid_at(msg, offset + i);
+
+    loop {
+        skip(&mut chars);
+        let start = match chars.peek() {
+            Some(&(i, _)) => i,
+            None => return Ok(elements),
+        };
+        while matches!(chars.peek(), Some(&(_, c)) if c.is_alphanumeric()) {
+            chars.next();
+        }
+        let keyword = &s[start..chars.peek().map_or(s.len(), |&(i, _)| i)];
+        skip(&mut chars);
+        if keyword.is_empty() || chars.next().map(|(_, c)| c) != Some('(') {
+            return Err(at("Expected an element", start));
+        }
+
+        let body = chars.peek().map_or(s.len(), |&(i, _)| i);
+        let mut depth = 1;
+        let end = loop {
+            match chars.next() {
+                Some((_, '"')) => loop {
+                    match chars.next() {
+                        Some((_, '\\')) => {
+                            chars.next();
+                        }
+                        Some((_, '"')) => break,
+                        Some(_) => {}
+                        None => return Err(at("Unterminated string", start)),
+                    }
+                },
+                Some((_, '<')) => {
+                    if chars.find(|&(_, c)| c == '>').is_none() {
+                        return Err(at("Unterminated IRI", start));
+                    }
+                }
+                Some((_, '#')) => {
+                    chars.find(|&(_, c)| c == '\n');
+                }
+                Some((_, '(')) => depth += 1,
+                Some((i, ')')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break i;
+                    }
+                }
+                Some(_) => {}
+                None => return Err(at("Unterminated element", start)),
+            }
+        };
+        elements.push((offset + start, keyword, &s[body..end]));
+    }
+}
+
+/// Read the `OntologyID` or component `body`, in the context of the
+/// prefix declarations `prefixes`.
+fn read_component<A: ForIRI>(
+    build: &Build<A>,
+    prefixes: &str,
+    body: &str,
+    offset: usize,
+) -> Result<AnnotatedComponent<A>, HornedError> {
+    let is_id = body
+        .trim_start()
+        .strip_prefix("Ontology")
+        .is_some_and(|rest| rest.trim_start().starts_with('('));
+    let doc = if is_id {
+        format!("{}\n{}", prefixes, body)
+    } else {
+        format!("{}\nOntology({})", prefixes, body)
+    };
+
+    let mut components = ComponentReader::new(doc.as_bytes(), build)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+    if !is_id {
+        components.next();
+    }
+    match (components.next(), components.next()) {
+        (Some(ac), None) => Ok(ac),
+        _ => Err(HornedError::invalid_at(
+            "Expected a single component",
+            offset,
+        )),
+    }
+}
+
+/// Read a patch from `bufread`.
+///
+/// Returns the patch, and the prefix declarations of the document.
+pub fn read<A: ForIRI, R: BufRead>(
+    mut bufread: R,
+    build: &Build<A>,
+) -> Result<(Patch<A>, PrefixMapping), HornedError> {
+    let mut text = String::new();
+    bufread.read_to_string(&mut text)?;
+
+    let mut prefixes = String::new();
+    let mut body = None;
+    for (offset, keyword, contents) in elements(&text, 0)? {
+        match keyword {
+            "Prefix" if body.is_none() => {
+                prefixes.push_str(&format!("Prefix({})\n", contents));
+            }
+            "Patch" if body.is_none() => {
+                body = Some((offset, contents));
+            }
+            _ => {
+                return Err(HornedError::invalid_at(
+                    format!("Unexpected element: {}", keyword),
+                    offset,
+                ))
+            }
+        }
+    }
+    let (offset, body) = body.ok_or_else(|| invalid!("No Patch element found"))?;
+    let body_offset = offset + text[offset..].find('(').unwrap() + 1;
+
+    let header = format!("{}Ontology()", prefixes);
+    let mut reader = ComponentReader::new(header.as_bytes(), build);
+    reader.next().transpose()?;
+    let mapping = reader.into_mapping();
+
+    let mut patch = Patch::new();
+    for (offset, keyword, contents) in elements(body, body_offset)? {
+        let ac = read_component(build, &prefixes, contents, offset)?;
+        match keyword {
+            "Require" if patch.require.is_none() && patch.operations.is_empty() => {
+                match ac.component {
+                    Component::OntologyID(id) => patch.require = Some(id),
+                    _ => {
+                        return Err(HornedError::invalid_at(
+                            "Require must be given an Ontology element",
+                            offset,
+                        ))
+                    }
+                }
+            }
+            "Add" => patch.operations.push(Operation::Add(ac)),
+            "Remove" => patch.operations.push(Operation::Remove(ac)),
+            _ => {
+                return Err(HornedError::invalid_at(
+                    format!("Unexpected element: {}", keyword),
+                    offset,
+                ))
+            }
+        }
+    }
+
+    Ok((patch, mapping))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    use crate::diff::diff;
+    use crate::ontology::set::SetOntology;
+
+    fn build() -> Build<RcStr> {
+        Build::new_rc()
+    }
+
+    fn ontology(b: &Build<RcStr>, sup: &str) -> SetOntology<RcStr> {
+        let mut o = SetOntology::new();
+        o.insert(OntologyID {
+            iri: Some(b.iri("http://example.com/o")),
+            viri: None,
+        });
+        o.insert(DeclareClass(b.class("http://example.com/A")));
+        o.insert(SubClassOf {
+            sub: b.class("http://example.com/A").into(),
+            sup: b.class(sup).into(),
+        });
+        o
+    }
+
+    fn components(o: &SetOntology<RcStr>) -> BTreeSet<AnnotatedComponent<RcStr>> {
+        o.iter().cloned().collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let b = build();
+        let mut mapping = PrefixMapping::default();
+        mapping.add_prefix("", "http://example.com/").unwrap();
+
+        let patch = Patch {
+            require: Some(OntologyID {
+                iri: Some(b.iri("http://example.com/o")),
+                viri: Some(b.iri("http://example.com/o1")),
+            }),
+            operations: vec![
+                Operation::Remove(
+                    Component::OntologyID(OntologyID {
+                        iri: Some(b.iri("http://example.com/o")),
+                        viri: Some(b.iri("http://example.com/o1")),
+                    })
+                    .into(),
+                ),
+                Operation::Add(DeclareClass(b.class("http://example.com/A")).into()),
+                Operation::Remove(AnnotatedComponent {
+                    component: Component::AnnotationAssertion(AnnotationAssertion {
+                        subject: b.iri("http://example.com/A").into(),
+                        ann: Annotation {
+                            ap: b.annotation_property("http://example.com/label"),
+                            av: Literal::Simple {
+                                literal: "a (\"quoted\") # label".into(),
+                            }
+                            .into(),
+                        },
+                    }),
+                    ann: vec![Annotation {
+                        ap: b.annotation_property("http://example.com/comment"),
+                        av: b.iri("http://example.com/B").into(),
+                    }]
+                    .into_iter()
+                    .collect(),
+                }),
+                Operation::Add(Import(b.iri("http://example.com/i")).into()),
+            ],
+        };
+
+        let mut bytes = vec![];
+        write(&mut bytes, &patch, Some(&mapping)).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.contains("Add(Declaration(Class(:A)))"), "{}", text);
+
+        let (read, read_mapping) = read(text.as_bytes(), &b).expect(&text);
+        assert_eq!(read, patch);
+        assert_eq!(
+            read_mapping.expand_curie_string(":A").unwrap(),
+            "http://example.com/A"
+        );
+    }
+
+    #[test]
+    fn apply_and_revert() {
+        let b = build();
+        let old = ontology(&b, "http://example.com/B");
+        let new = ontology(&b, "http://example.com/C");
+
+        let mut patch = Patch::from_diff(&diff(&old, &new));
+        patch.require = Some(OntologyID {
+            iri: Some(b.iri("http://example.com/o")),
+            viri: None,
+        });
+
+        let mut o = old.clone();
+        patch.apply(&mut o).unwrap();
+        assert_eq!(components(&o), components(&new));
+
+        patch.revert(&mut o).unwrap();
+        assert_eq!(components(&o), components(&old));
+    }
+
+    #[test]
+    fn conflict() {
+        let b = build();
+        let old = ontology(&b, "http://example.com/B");
+
+        let patch = Patch {
+            require: None,
+            operations: vec![
+                Operation::Add(DeclareClass(b.class("http://example.com/C")).into()),
+                Operation::Remove(DeclareClass(b.class("http://example.com/D")).into()),
+            ],
+        };
+
+        let mut o = old.clone();
+        assert!(patch.apply(&mut o).is_err());
+        assert_eq!(components(&o), components(&old));
+
+        // Reverting a patch which was never applied also conflicts
+        assert!(patch.revert(&mut o).is_err());
+        assert_eq!(components(&o), components(&old));
+    }
+
+    #[test]
+    fn precondition() {
+        let b = build();
+        let old = ontology(&b, "http://example.com/B");
+
+        let mut patch = Patch::new();
+        patch.require = Some(OntologyID {
+            iri: Some(b.iri("http://example.com/other")),
+            viri: None,
+        });
+        patch.operations.push(Operation::Add(
+            DeclareClass(b.class("http://example.com/C")).into(),
+        ));
+
+        let mut o = old.clone();
+        assert!(patch.apply(&mut o).is_err());
+        assert_eq!(components(&o), components(&old));
+    }
+
+    #[test]
+    fn read_errors() {
+        let b = build();
+        let read = |s: &str| read::<RcStr, _>(s.as_bytes(), &b);
+
+        assert!(read("Patch(Add(Declaration(Class(<http://example.com/A>))").is_err());
+        assert!(read("Patch(Change(Declaration(Class(<http://example.com/A>))))").is_err());
+        assert!(read("Patch(Require(Declaration(Class(<http://example.com/A>))))").is_err());
+        assert!(read("Prefix(:=<http://example.com/>)").is_err());
+        assert!(read("Patch(Add(\"unterminated))").is_err());
+        assert!(read("Patch()").unwrap().0.operations.is_empty());
+    }
+}
@@ -0,0 +1,620 @@
+//! The [global
+//! restrictions](https://www.w3.org/TR/owl2-syntax/#Global_Restrictions_on_Axioms_in_OWL_2_DL)
+//! of OWL 2 DL.
+//!
+//! An ontology which breaks these restrictions can still be
+//! represented, but it is in OWL 2 Full, for which reasoning is
+//! undecidable. Unlike the rules of the profiles, each restriction
+//! involves several axioms at once, so a [`GlobalViolation`] reports
+//! every component which contributes to it.
+//!
+//! [`PropertyHierarchy`] computes the partition of object properties
+//! into simple and non-simple properties, and the order in which
+//! property chains must be defined. Legal punning, where an IRI is
+//! used as, for example, both a class and an individual, is allowed.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::{is_annotation_builtin, Namespace};
+
+use super::{is_iri_in, rl};
+
+/// A global restriction of OWL 2 DL.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum GlobalRestriction {
+    /// Cardinality restrictions, `ObjectHasSelf`, and some property
+    /// axioms may only use simple properties.
+    NonSimpleProperty,
+    /// Property chains must not define a property in terms of itself,
+    /// directly or through the property hierarchy.
+    IrregularHierarchy,
+    /// An IRI may not be used for more than one kind of property, or
+    /// for both a class and a datatype.
+    IllegalPunning,
+    /// IRIs in the reserved namespaces may only be used for the
+    /// built-in entities.
+    ReservedVocabulary,
+}
+
+impl Display for GlobalRestriction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::NonSimpleProperty => "non-simple property is used where a simple one is required",
+            Self::IrregularHierarchy => "property hierarchy is not regular",
+            Self::IllegalPunning => "IRI is used for incompatible kinds of entity",
+            Self::ReservedVocabulary => "reserved vocabulary is used as an entity",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// An entity, and the components which together break `restriction`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GlobalViolation<A> {
+    pub restriction: GlobalRestriction,
+    pub iri: IRI<A>,
+    pub components: Vec<AnnotatedComponent<A>>,
+}
+
+type Edges<A> = HashMap<ObjectProperty<A>, Vec<(ObjectProperty<A>, usize)>>;
+
+fn add_edge<A: ForIRI>(
+    edges: &mut Edges<A>,
+    from: &ObjectPropertyExpression<A>,
+    to: &ObjectPropertyExpression<A>,
+    index: usize,
+) {
+    edges
+        .entry(property(from).clone())
+        .or_default()
+        .push((property(to).clone(), index));
+}
+
+/// The named property of an object property expression.
+///
+/// A property and its inverse are always both simple or both
+/// non-simple, and have the same place in the regularity order, so
+/// they need not be distinguished.
+fn property<A: ForIRI>(ope: &ObjectPropertyExpression<A>) -> &ObjectProperty<A> {
+    match ope {
+        ObjectPropertyExpression::ObjectProperty(op)
+        | ObjectPropertyExpression::InverseObjectProperty(op) => op,
+    }
+}
+
+/// Breadth first search along `edges` from each of `start`, returning
+/// the index of the components on the shortest path to every
+/// property which is reached.
+fn reach<A: ForIRI>(
+    edges: &Edges<A>,
+    start: Vec<(ObjectProperty<A>, Vec<usize>)>,
+) -> BTreeMap<ObjectProperty<A>, Vec<usize>> {
+    let mut paths = BTreeMap::new();
+    let mut queue = VecDeque::new();
+    for (op, path) in start {
+        if !paths.contains_key(&op) {
+            paths.insert(op.clone(), path);
+            queue.push_back(op);
+        }
+    }
+
+    while let Some(op) = queue.pop_front() {
+        for (to, index) in edges.get(&op).into_iter().flatten() {
+            if !paths.contains_key(to) {
+                let mut path = paths[&op].clone();
+                path.push(*index);
+                paths.insert(to.clone(), path);
+                queue.push_back(to.clone());
+            }
+        }
+    }
+    paths
+}
+
+/// Collects the entities used in each component.
+struct Uses<A> {
+    index: usize,
+    properties: BTreeSet<ObjectProperty<A>>,
+    /// Properties which must be simple
+    restricted: BTreeSet<(ObjectProperty<A>, usize)>,
+    entities: BTreeMap<IRI<A>, BTreeMap<NamedOWLEntityKind, BTreeSet<usize>>>,
+}
+
+impl<A: ForIRI> Uses<A> {
+    fn entity(&mut self, iri: &IRI<A>, kind: NamedOWLEntityKind) {
+        self.entities
+            .entry(iri.clone())
+            .or_default()
+            .entry(kind)
+            .or_default()
+            .insert(self.index);
+    }
+
+    fn restrict(&mut self, ope: &ObjectPropertyExpression<A>) {
+        self.restricted.insert((property(ope).clone(), self.index));
+    }
+}
+
+impl<A: ForIRI> Visit<A> for Uses<A> {
+    fn visit_class(&mut self, c: &Class<A>) {
+        self.entity(&c.0, NamedOWLEntityKind::Class);
+    }
+
+    fn visit_datatype(&mut self, dt: &Datatype<A>) {
+        self.entity(&dt.0, NamedOWLEntityKind::Datatype);
+    }
+
+    fn visit_object_property(&mut self, op: &ObjectProperty<A>) {
+        self.properties.insert(op.clone());
+        self.entity(&op.0, NamedOWLEntityKind::ObjectProperty);
+    }
+
+    fn visit_data_property(&mut self, dp: &DataProperty<A>) {
+        self.entity(&dp.0, NamedOWLEntityKind::DataProperty);
+    }
+
+    fn visit_annotation_property(&mut self, ap: &AnnotationProperty<A>) {
+        self.entity(&ap.0, NamedOWLEntityKind::AnnotationProperty);
+    }
+
+    fn visit_named_individual(&mut self, ni: &NamedIndividual<A>) {
+        self.entity(&ni.0, NamedOWLEntityKind::NamedIndividual);
+    }
+
+    fn visit_component(&mut self, c: &Component<A>) {
+        match c {
+            Component::FunctionalObjectProperty(FunctionalObjectProperty(ope))
+            | Component::InverseFunctionalObjectProperty(InverseFunctionalObjectProperty(ope))
+            | Component::IrreflexiveObjectProperty(IrreflexiveObjectProperty(ope))
+            | Component::AsymmetricObjectProperty(AsymmetricObjectProperty(ope)) => {
+                self.restrict(ope)
+            }
+            Component::DisjointObjectProperties(DisjointObjectProperties(v)) => {
+                for ope in v {
+                    self.restrict(ope);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_class_expression(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::ObjectMinCardinality { ope, .. }
+            | ClassExpression::ObjectMaxCardinality { ope, .. }
+            | ClassExpression::ObjectExactCardinality { ope, .. }
+            | ClassExpression::ObjectHasSelf(ope) => self.restrict(ope),
+            _ => {}
+        }
+    }
+}
+
+/// The object property hierarchy of an ontology.
+///
+/// A property is composite if it is the superproperty of a property
+/// chain, is transitive or is one of `owl:topObjectProperty` and
+/// `owl:bottomObjectProperty`. It is non-simple if it is composite,
+/// or has a non-simple subproperty.
+pub struct PropertyHierarchy<'a, A> {
+    components: Vec<&'a AnnotatedComponent<A>>,
+    uses: Uses<A>,
+    /// From subproperties to superproperties
+    hierarchy: Edges<A>,
+    /// From properties in a chain to its superproperty, which must be
+    /// greater in the regularity order
+    chains: Edges<A>,
+    /// Non-simple properties with the path from a composite property
+    non_simple: BTreeMap<ObjectProperty<A>, Vec<usize>>,
+}
+
+impl<'a, A: ForIRI> PropertyHierarchy<'a, A> {
+    pub fn new<AA: ForIndex<A>>(ont: &'a ComponentMappedOntology<A, AA>) -> Self {
+        let components: Vec<_> = ont.i().iter().collect();
+        let mut walk = Walk::new(Uses {
+            index: 0,
+            properties: BTreeSet::new(),
+            restricted: BTreeSet::new(),
+            entities: BTreeMap::new(),
+        });
+        for (index, ac) in components.iter().enumerate() {
+            walk.as_mut_visit().index = index;
+            walk.annotated_component(ac);
+        }
+        let uses = walk.into_visit();
+
+        let mut hierarchy = HashMap::new();
+        let mut chains = HashMap::new();
+        let mut composite = BTreeMap::new();
+        for (index, ac) in components.iter().enumerate() {
+            match &ac.component {
+                Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                    sub: SubObjectPropertyExpression::ObjectPropertyExpression(sub),
+                    sup,
+                }) => add_edge(&mut hierarchy, sub, sup, index),
+                Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                    sub: SubObjectPropertyExpression::ObjectPropertyChain(chain),
+                    sup,
+                }) => {
+                    if chain.len() == 1 {
+                        add_edge(&mut hierarchy, &chain[0], sup, index);
+                        continue;
+                    }
+                    composite
+                        .entry(property(sup).clone())
+                        .or_insert(vec![index]);
+                    // The superproperty may be the first or last
+                    // element of its own chain
+                    for (i, ope) in chain.iter().enumerate() {
+                        if !((i == 0 || i == chain.len() - 1) && ope == sup) {
+                            add_edge(&mut chains, ope, sup, index);
+                        }
+                    }
+                }
+                Component::EquivalentObjectProperties(EquivalentObjectProperties(v)) => {
+                    for w in v.windows(2) {
+                        add_edge(&mut hierarchy, &w[0], &w[1], index);
+                        add_edge(&mut hierarchy, &w[1], &w[0], index);
+                    }
+                }
+                Component::InverseObjectProperties(InverseObjectProperties(p, q)) => {
+                    let (p, q) = (p.clone().into(), q.clone().into());
+                    add_edge(&mut hierarchy, &p, &q, index);
+                    add_edge(&mut hierarchy, &q, &p, index);
+                }
+                Component::TransitiveObjectProperty(TransitiveObjectProperty(ope)) => {
+                    composite
+                        .entry(property(ope).clone())
+                        .or_insert(vec![index]);
+                }
+                _ => {}
+            }
+        }
+
+        for op in &uses.properties {
+            if is_iri_in(
+                &op.0,
+                &["owl:topObjectProperty", "owl:bottomObjectProperty"],
+            ) {
+                composite.entry(op.clone()).or_insert(vec![]);
+            }
+        }
+        let non_simple = reach(&hierarchy, composite.into_iter().collect());
+
+        PropertyHierarchy {
+            components,
+            uses,
+            hierarchy,
+            chains,
+            non_simple,
+        }
+    }
+
+    /// Return true if `op` is simple.
+    pub fn is_simple(&self, op: &ObjectProperty<A>) -> bool {
+        !self.non_simple.contains_key(op)
+    }
+
+    /// Return the object properties of the ontology, partitioned into
+    /// simple and non-simple properties.
+    pub fn partition(&self) -> (BTreeSet<ObjectProperty<A>>, BTreeSet<ObjectProperty<A>>) {
+        self.uses
+            .properties
+            .iter()
+            .cloned()
+            .partition(|op| self.is_simple(op))
+    }
+
+    /// Return the regularity order of the object properties, as sets of
+    /// properties, each of which is greater than the properties in
+    /// the sets before it, or `None` if the hierarchy is not regular.
+    pub fn regularity_order(&self) -> Option<Vec<BTreeSet<ObjectProperty<A>>>> {
+        if !self.irregular().is_empty() {
+            return None;
+        }
+
+        // The order is acyclic, so every property is eventually
+        // reached by the longest path to it
+        let mut incoming: HashMap<&ObjectProperty<A>, usize> = HashMap::new();
+        for (to, _) in self.chains.values().flatten() {
+            *incoming.entry(to).or_default() += 1;
+        }
+        let mut current: BTreeSet<_> = self
+            .uses
+            .properties
+            .iter()
+            .filter(|op| !incoming.contains_key(op))
+            .cloned()
+            .collect();
+
+        let mut order = vec![];
+        while !current.is_empty() {
+            let mut next = BTreeSet::new();
+            for op in &current {
+                for (to, _) in self.chains.get(op).into_iter().flatten() {
+                    let count = incoming.get_mut(to).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        next.insert(to.clone());
+                    }
+                }
+            }
+            order.push(current);
+            current = next;
+        }
+        Some(order)
+    }
+
+    /// Return each property which must be less than itself in the
+    /// regularity order, with the components which require it.
+    fn irregular(&self) -> Vec<(ObjectProperty<A>, Vec<usize>)> {
+        let mut seen = HashSet::new();
+        let mut irregular = vec![];
+        let sources: BTreeSet<_> = self.chains.keys().collect();
+        for op in sources {
+            let start = self.chains[op]
+                .iter()
+                .map(|(to, index)| (to.clone(), vec![*index]))
+                .collect();
+
+            // `op` is less than every property reached through
+            // chains, so none of them may be a subproperty of it
+            for (greater, path) in reach(&self.chains, start) {
+                let sub = reach(&self.hierarchy, vec![(greater, path)]);
+                if let Some(path) = sub.get(op) {
+                    if seen.insert(path.iter().cloned().collect::<BTreeSet<_>>()) {
+                        irregular.push((op.clone(), path.clone()));
+                    }
+                }
+            }
+        }
+        irregular
+    }
+
+    fn violation(
+        &self,
+        restriction: GlobalRestriction,
+        iri: &IRI<A>,
+        indexes: impl IntoIterator<Item = usize>,
+    ) -> GlobalViolation<A> {
+        let mut seen = HashSet::new();
+        GlobalViolation {
+            restriction,
+            iri: iri.clone(),
+            components: indexes
+                .into_iter()
+                .filter(|i| seen.insert(*i))
+                .map(|i| self.components[i].clone())
+                .collect(),
+        }
+    }
+}
+
+/// Return true if `iri` is in one of the reserved namespaces.
+fn is_reserved<A: ForIRI>(iri: &IRI<A>) -> bool {
+    [
+        Namespace::RDF,
+        Namespace::RDFS,
+        Namespace::OWL,
+        Namespace::XSD,
+    ]
+    .iter()
+    .any(|ns| iri.starts_with(ns.as_ref()))
+}
+
+/// Return true if `iri` is a built-in entity of kind `kind`.
+fn is_built_in<A: ForIRI>(iri: &IRI<A>, kind: NamedOWLEntityKind) -> bool {
+    match kind {
+        NamedOWLEntityKind::Class => is_iri_in(iri, &["owl:Thing", "owl:Nothing"]),
+        NamedOWLEntityKind::ObjectProperty => {
+            is_iri_in(iri, &["owl:topObjectProperty", "owl:bottomObjectProperty"])
+        }
+        NamedOWLEntityKind::DataProperty => {
+            is_iri_in(iri, &["owl:topDataProperty", "owl:bottomDataProperty"])
+        }
+        NamedOWLEntityKind::Datatype => {
+            is_iri_in(iri, rl::DATATYPES) || is_iri_in(iri, &["owl:real", "owl:rational"])
+        }
+        NamedOWLEntityKind::AnnotationProperty => is_annotation_builtin(iri),
+        NamedOWLEntityKind::NamedIndividual => false,
+    }
+}
+
+/// Return every violation of the global restrictions of OWL 2 DL in
+/// `ont`.
+pub fn violations<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<GlobalViolation<A>> {
+    let h = PropertyHierarchy::new(ont);
+    let mut violations = vec![];
+
+    for (op, index) in &h.uses.restricted {
+        if let Some(path) = h.non_simple.get(op) {
+            violations.push(h.violation(
+                GlobalRestriction::NonSimpleProperty,
+                &op.0,
+                std::iter::once(*index).chain(path.iter().cloned()),
+            ));
+        }
+    }
+
+    for (op, path) in h.irregular() {
+        violations.push(h.violation(GlobalRestriction::IrregularHierarchy, &op.0, path));
+    }
+
+    for (iri, kinds) in &h.uses.entities {
+        for incompatible in [
+            &[
+                NamedOWLEntityKind::ObjectProperty,
+                NamedOWLEntityKind::DataProperty,
+                NamedOWLEntityKind::AnnotationProperty,
+            ][..],
+            &[NamedOWLEntityKind::Class, NamedOWLEntityKind::Datatype][..],
+        ] {
+            let used: Vec<_> = incompatible.iter().filter_map(|k| kinds.get(k)).collect();
+            if used.len() > 1 {
+                let indexes: BTreeSet<_> = used.into_iter().flatten().cloned().collect();
+                violations.push(h.violation(GlobalRestriction::IllegalPunning, iri, indexes));
+            }
+        }
+
+        if is_reserved(iri) {
+            for (kind, indexes) in kinds {
+                if !is_built_in(iri, *kind) {
+                    violations.push(h.violation(
+                        GlobalRestriction::ReservedVocabulary,
+                        iri,
+                        indexes.iter().cloned(),
+                    ));
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Return true if `ont` meets the global restrictions of OWL 2 DL.
+pub fn is_dl<A: ForIRI, AA: ForIndex<A>>(ont: &ComponentMappedOntology<A, AA>) -> bool {
+    violations(ont).is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn read(ofn: &str) -> ComponentMappedOntology<RcStr, RcAnnotatedComponent> {
+        crate::io::ofn::reader::read_example(ofn)
+    }
+
+    fn check(ofn: &str) -> Vec<(GlobalRestriction, String, usize)> {
+        violations(&read(ofn))
+            .into_iter()
+            .map(|v| (v.restriction, v.iri.to_string(), v.components.len()))
+            .collect()
+    }
+
+    fn op(s: &str) -> ObjectProperty<RcStr> {
+        Build::new_rc().object_property(format!("http://example.com/{}", s))
+    }
+
+    #[test]
+    fn partition() {
+        let ont = read(
+            "TransitiveObjectProperty(:r)
+             SubObjectPropertyOf(:r :s)
+             SubObjectPropertyOf(:t :r)
+             InverseObjectProperties(:s :u)",
+        );
+        let h = PropertyHierarchy::new(&ont);
+        let (simple, non_simple) = h.partition();
+        assert_eq!(simple, vec![op("t")].into_iter().collect());
+        assert_eq!(
+            non_simple,
+            vec![op("r"), op("s"), op("u")].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn non_simple() {
+        assert_eq!(
+            check(
+                "TransitiveObjectProperty(:r)
+                 SubObjectPropertyOf(:r :s)
+                 FunctionalObjectProperty(:s)
+                 SubClassOf(:A ObjectMinCardinality(1 :t))"
+            ),
+            vec![(
+                GlobalRestriction::NonSimpleProperty,
+                "http://example.com/s".to_string(),
+                3
+            )]
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectHasSelf(owl:topObjectProperty))")[0].0,
+            GlobalRestriction::NonSimpleProperty
+        );
+    }
+
+    #[test]
+    fn regular() {
+        let ont = read(
+            "SubObjectPropertyOf(ObjectPropertyChain(:r :s) :t)
+             SubObjectPropertyOf(ObjectPropertyChain(:t :t) :t)
+             SubObjectPropertyOf(ObjectPropertyChain(:t :u) :v)",
+        );
+        assert!(is_dl(&ont));
+        assert_eq!(
+            PropertyHierarchy::new(&ont).regularity_order(),
+            Some(vec![
+                vec![op("r"), op("s"), op("u")].into_iter().collect(),
+                vec![op("t")].into_iter().collect(),
+                vec![op("v")].into_iter().collect(),
+            ])
+        );
+    }
+
+    #[test]
+    fn irregular() {
+        let ont = read(
+            "SubObjectPropertyOf(ObjectPropertyChain(:r :s) :t)
+             SubObjectPropertyOf(:t :r)",
+        );
+        assert_eq!(PropertyHierarchy::new(&ont).regularity_order(), None);
+        assert_eq!(
+            check(
+                "SubObjectPropertyOf(ObjectPropertyChain(:r :s) :t)
+                 SubObjectPropertyOf(:t :r)"
+            ),
+            vec![(
+                GlobalRestriction::IrregularHierarchy,
+                "http://example.com/r".to_string(),
+                2
+            )]
+        );
+        assert_eq!(
+            check("SubObjectPropertyOf(ObjectPropertyChain(:r :r :r) :r)")[0].0,
+            GlobalRestriction::IrregularHierarchy
+        );
+    }
+
+    #[test]
+    fn punning() {
+        assert_eq!(
+            check(
+                "Declaration(Class(:a))
+                 Declaration(NamedIndividual(:a))
+                 Declaration(ObjectProperty(:p))
+                 DataPropertyAssertion(:p :a \"x\")"
+            ),
+            vec![(
+                GlobalRestriction::IllegalPunning,
+                "http://example.com/p".to_string(),
+                2
+            )]
+        );
+    }
+
+    #[test]
+    fn reserved() {
+        assert_eq!(
+            check(
+                "SubClassOf(owl:Thing owl:Nothing)
+                 AnnotationAssertion(rdfs:label :A \"A\")
+                 SubClassOf(:A owl:real)"
+            ),
+            vec![(
+                GlobalRestriction::ReservedVocabulary,
+                "http://www.w3.org/2002/07/owl#real".to_string(),
+                1
+            )]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! The [OWL 2 EL](https://www.w3.org/TR/owl2-profiles/#OWL_2_EL)
+//! profile.
+//!
+//! OWL 2 EL allows existential restrictions and intersections, but
+//! not universal restrictions, unions, complements, cardinality
+//! restrictions or inverse properties. Reasoning with it is
+//! polynomial.
+//!
+//! The global restriction on ranges of properties used in property
+//! chains requires entailment checking, and is not checked.
+use std::collections::HashSet;
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::Visit;
+
+use super::*;
+
+/// The datatypes which are allowed in OWL 2 EL.
+pub(crate) const DATATYPES: &[&str] = &[
+    "rdf:PlainLiteral",
+    "rdf:XMLLiteral",
+    "rdfs:Literal",
+    "owl:real",
+    "owl:rational",
+    "xsd:decimal",
+    "xsd:integer",
+    "xsd:nonNegativeInteger",
+    "xsd:string",
+    "xsd:normalizedString",
+    "xsd:token",
+    "xsd:Name",
+    "xsd:NCName",
+    "xsd:NMTOKEN",
+    "xsd:hexBinary",
+    "xsd:base64Binary",
+    "xsd:anyURI",
+    "xsd:dateTime",
+    "xsd:dateTimeStamp",
+];
+
+/// Return true if components of kind `kind` are allowed in OWL 2 EL.
+fn is_allowed_kind(kind: ComponentKind) -> bool {
+    !matches!(
+        kind,
+        ComponentKind::DisjointUnion
+            | ComponentKind::DisjointObjectProperties
+            | ComponentKind::InverseObjectProperties
+            | ComponentKind::FunctionalObjectProperty
+            | ComponentKind::InverseFunctionalObjectProperty
+            | ComponentKind::IrreflexiveObjectProperty
+            | ComponentKind::SymmetricObjectProperty
+            | ComponentKind::AsymmetricObjectProperty
+            | ComponentKind::DisjointDataProperties
+            | ComponentKind::Rule
+    )
+}
+
+/// Collects the violations within a single component.
+struct Checker<A> {
+    /// Datatypes defined with `DatatypeDefinition` axioms
+    defined: HashSet<IRI<A>>,
+    /// True if literals in the current component are annotation values
+    annotation: bool,
+    found: Vec<(Construct<A>, ProfileRule)>,
+}
+
+impl<A: ForIRI> ProfileVisit<A> for Checker<A> {
+    fn set_annotation(&mut self, annotation: bool) {
+        self.annotation = annotation;
+    }
+
+    fn found(&mut self) -> &mut Vec<(Construct<A>, ProfileRule)> {
+        &mut self.found
+    }
+}
+
+impl<A: ForIRI> Visit<A> for Checker<A> {
+    fn visit_component(&mut self, c: &Component<A>) {
+        if !is_allowed_kind(c.kind()) {
+            self.found.push((Construct::Axiom, ProfileRule::Axiom));
+        }
+    }
+
+    fn visit_class_expression(&mut self, ce: &ClassExpression<A>) {
+        let allowed = match ce {
+            ClassExpression::Class(_)
+            | ClassExpression::ObjectIntersectionOf(_)
+            | ClassExpression::ObjectSomeValuesFrom { .. }
+            | ClassExpression::ObjectHasValue { .. }
+            | ClassExpression::ObjectHasSelf(_)
+            | ClassExpression::DataSomeValuesFrom { .. }
+            | ClassExpression::DataHasValue { .. } => true,
+            ClassExpression::ObjectOneOf(v) => v.len() == 1,
+            _ => false,
+        };
+        if !allowed {
+            self.found.push((
+                Construct::ClassExpression(ce.clone()),
+                ProfileRule::ClassExpression,
+            ));
+        }
+    }
+
+    fn visit_object_property_expression(&mut self, ope: &ObjectPropertyExpression<A>) {
+        if let ObjectPropertyExpression::InverseObjectProperty(_) = ope {
+            self.found.push((
+                Construct::ObjectPropertyExpression(ope.clone()),
+                ProfileRule::ObjectPropertyExpression,
+            ));
+        }
+    }
+
+    fn visit_data_range(&mut self, dr: &DataRange<A>) {
+        match dr {
+            DataRange::Datatype(dt) => {
+                if !is_iri_in(&dt.0, DATATYPES) && !self.defined.contains(&dt.0) {
+                    self.found
+                        .push((Construct::DataRange(dr.clone()), ProfileRule::Datatype));
+                }
+            }
+            DataRange::DataIntersectionOf(_) => {}
+            DataRange::DataOneOf(v) if v.len() == 1 => {}
+            _ => self
+                .found
+                .push((Construct::DataRange(dr.clone()), ProfileRule::DataRange)),
+        }
+    }
+
+    fn visit_literal(&mut self, l: &Literal<A>) {
+        if !self.annotation && !is_iri_in(&literal_datatype(l), DATATYPES) {
+            self.found
+                .push((Construct::Literal(l.clone()), ProfileRule::Literal));
+        }
+    }
+
+    fn visit_individual(&mut self, i: &Individual<A>) {
+        if let Individual::Anonymous(_) = i {
+            self.found.push((
+                Construct::Individual(i.clone()),
+                ProfileRule::AnonymousIndividual,
+            ));
+        }
+    }
+}
+
+/// Return every violation of the OWL 2 EL profile in `ont`.
+///
+/// A component is reported once for each construct within it which
+/// breaks a rule, so nested constructs which are not allowed are
+/// each reported.
+pub fn violations<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<Violation<A>> {
+    violations_with(
+        ont,
+        Checker {
+            defined: defined_datatypes(ont),
+            annotation: false,
+            found: vec![],
+        },
+    )
+}
+
+/// Return true if `ont` is in the OWL 2 EL profile.
+pub fn is_el<A: ForIRI, AA: ForIndex<A>>(ont: &ComponentMappedOntology<A, AA>) -> bool {
+    violations(ont).is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn check(ofn: &str) -> Vec<(Construct<RcStr>, ProfileRule)> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        violations(&ont)
+            .into_iter()
+            .map(|v| (v.construct, v.rule))
+            .collect()
+    }
+
+    #[test]
+    fn allowed() {
+        assert_eq!(
+            check(
+                "SubClassOf(:A ObjectIntersectionOf(:B ObjectSomeValuesFrom(:r :C)))
+                 SubClassOf(:A ObjectHasValue(:r :i))
+                 SubClassOf(:A DataSomeValuesFrom(:d xsd:integer))
+                 SubClassOf(:A DataHasValue(:d \"1\"^^xsd:integer))
+                 SubObjectPropertyOf(ObjectPropertyChain(:r :s) :r)
+                 TransitiveObjectProperty(:r)
+                 DataPropertyRange(:d :t)
+                 DatatypeDefinition(:t xsd:integer)
+                 AnnotationAssertion(rdfs:comment :A \"1.0\"^^xsd:double)"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn class_expressions() {
+        let b = Build::new_rc();
+        let violations = check(
+            "SubClassOf(:A ObjectAllValuesFrom(:r ObjectUnionOf(:B :C)))
+             SubClassOf(:A ObjectMinCardinality(1 :r))",
+        );
+        for ce in [
+            ClassExpression::ObjectUnionOf(vec![
+                b.class("http://example.com/B").into(),
+                b.class("http://example.com/C").into(),
+            ]),
+            ClassExpression::ObjectMinCardinality {
+                n: 1,
+                ope: b.object_property("http://example.com/r").into(),
+                bce: Box::new(b.class("http://www.w3.org/2002/07/owl#Thing").into()),
+            },
+        ] {
+            assert!(
+                violations
+                    .contains(&(Construct::ClassExpression(ce), ProfileRule::ClassExpression)),
+                "{:?}",
+                violations
+            );
+        }
+        assert_eq!(violations.len(), 3);
+    }
+
+    #[test]
+    fn inverse_property() {
+        let b = Build::new_rc();
+        assert_eq!(
+            check("SubClassOf(:A ObjectSomeValuesFrom(ObjectInverseOf(:r) :B))"),
+            vec![(
+                Construct::ObjectPropertyExpression(
+                    ObjectPropertyExpression::InverseObjectProperty(
+                        b.object_property("http://example.com/r")
+                    )
+                ),
+                ProfileRule::ObjectPropertyExpression
+            )]
+        );
+    }
+
+    #[test]
+    fn datatypes() {
+        let b = Build::new_rc();
+        assert_eq!(
+            check("SubClassOf(:A DataSomeValuesFrom(:d xsd:double))"),
+            vec![(
+                Construct::DataRange(b.datatype("http://www.w3.org/2001/XMLSchema#double").into()),
+                ProfileRule::Datatype
+            )]
+        );
+        assert_eq!(
+            check("DataPropertyAssertion(:d :i \"true\"^^xsd:boolean)")[0].1,
+            ProfileRule::Literal
+        );
+        assert_eq!(
+            check("DataPropertyRange(:d DataUnionOf(xsd:integer xsd:string))")[0].1,
+            ProfileRule::DataRange
+        );
+    }
+
+    #[test]
+    fn axioms() {
+        assert_eq!(
+            check("FunctionalObjectProperty(:r) ClassAssertion(:A _:x)"),
+            vec![
+                (Construct::Axiom, ProfileRule::Axiom),
+                (
+                    Construct::Individual(Build::new_rc().anon("_:x").into()),
+                    ProfileRule::AnonymousIndividual
+                )
+            ]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Checkers for the [OWL 2 Profiles](https://www.w3.org/TR/owl2-profiles/).
+//!
+//! A profile is a syntactic subset of OWL 2 for which reasoning is
+//! cheaper. Each checker walks the components of an ontology and
+//! returns a [`Violation`] for every construct which falls outside of
+//! its profile, so that an ontology is in the profile exactly when
+//! there are none.
+//!
+//! Annotations have no effect on reasoning, and are not checked.
+//!
+//! The global restrictions of OWL 2 DL, which every profile also
+//! requires, involve several axioms at once, and are checked
+//! separately by [`dl`].
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::Namespace;
+
+pub mod dl;
+pub mod el;
+pub mod ql;
+pub mod rl;
+
+/// The part of a component which breaks a profile rule.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Construct<A> {
+    /// The component itself is not allowed.
+    Axiom,
+    ClassExpression(ClassExpression<A>),
+    ObjectPropertyExpression(ObjectPropertyExpression<A>),
+    DataRange(DataRange<A>),
+    Literal(Literal<A>),
+    Individual(Individual<A>),
+}
+
+/// A rule of a profile.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ProfileRule {
+    /// Only some kinds of axiom are allowed.
+    Axiom,
+    /// Only some class expressions are allowed.
+    ClassExpression,
+    /// Only some class expressions are allowed as a subclass.
+    SubClassExpression,
+    /// Only some class expressions are allowed as a superclass.
+    SuperClassExpression,
+    /// Only some class expressions are allowed in an equivalence.
+    EquivalentClassExpression,
+    /// Only some object property expressions are allowed.
+    ObjectPropertyExpression,
+    /// Only some data ranges are allowed.
+    DataRange,
+    /// Only datatypes of the profile, or those defined in the
+    /// ontology, may be used.
+    Datatype,
+    /// Only literals with a datatype of the profile may be used.
+    Literal,
+    /// Anonymous individuals may not be used.
+    AnonymousIndividual,
+}
+
+impl Display for ProfileRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Axiom => "axiom is not allowed",
+            Self::ClassExpression => "class expression is not allowed",
+            Self::SubClassExpression => "class expression is not allowed as a subclass",
+            Self::SuperClassExpression => "class expression is not allowed as a superclass",
+            Self::EquivalentClassExpression => "class expression is not allowed in an equivalence",
+            Self::ObjectPropertyExpression => "object property expression is not allowed",
+            Self::DataRange => "data range is not allowed",
+            Self::Datatype => "datatype is not allowed",
+            Self::Literal => "literal has a datatype which is not allowed",
+            Self::AnonymousIndividual => "anonymous individuals are not allowed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A component, and the construct within it which breaks `rule`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation<A> {
+    pub component: AnnotatedComponent<A>,
+    pub construct: Construct<A>,
+    pub rule: ProfileRule,
+}
+
+/// Return true if `iri` is one of `names`, given as prefixed names
+/// in the `rdf`, `rdfs`, `owl` or `xsd` namespaces.
+pub(crate) fn is_iri_in(iri: &str, names: &[&str]) -> bool {
+    names.iter().any(|name| {
+        let (prefix, local) = name.split_once(':').unwrap();
+        let ns = match prefix {
+            "rdf" => Namespace::RDF,
+            "rdfs" => Namespace::RDFS,
+            "owl" => Namespace::OWL,
+            _ => Namespace::XSD,
+        };
+        iri.strip_prefix(ns.as_ref()) == Some(local)
+    })
+}
+
+/// The datatype of a literal.
+pub(crate) fn literal_datatype<A: ForIRI>(l: &Literal<A>) -> String {
+    match l {
+        Literal::Simple { .. } => format!("{}string", Namespace::XSD.as_ref()),
+        Literal::Language { .. } => format!("{}PlainLiteral", Namespace::RDF.as_ref()),
+        Literal::Datatype { datatype_iri, .. } => datatype_iri.to_string(),
+    }
+}
+
+/// The position of a class expression within an axiom.
+///
+/// The QL and RL profiles restrict class expressions differently
+/// depending on whether they are used as a subclass or superclass.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Position {
+    /// A subclass, or a class in a `DisjointClasses` or `HasKey` axiom.
+    Sub,
+    /// A superclass, or a domain or range.
+    Super,
+    /// A class in an `EquivalentClasses` axiom.
+    Equivalent,
+    /// The class of a `ClassAssertion`.
+    Assertion,
+}
+
+/// Return the class expressions at the top level of `c`, with their
+/// positions.
+///
+/// `DisjointUnion` and SWRL rules are not in any profile with
+/// positional restrictions, so their class expressions are not
+/// returned.
+pub(crate) fn class_expressions<A: ForIRI>(
+    c: &Component<A>,
+) -> Vec<(&ClassExpression<A>, Position)> {
+    match c {
+        Component::SubClassOf(SubClassOf { sub, sup }) => {
+            vec![(sub, Position::Sub), (sup, Position::Super)]
+        }
+        Component::EquivalentClasses(EquivalentClasses(v)) => {
+            v.iter().map(|ce| (ce, Position::Equivalent)).collect()
+        }
+        Component::DisjointClasses(DisjointClasses(v)) => {
+            v.iter().map(|ce| (ce, Position::Sub)).collect()
+        }
+        Component::HasKey(HasKey { ce, .. }) => vec![(ce, Position::Sub)],
+        Component::ObjectPropertyDomain(ObjectPropertyDomain { ce, .. })
+        | Component::ObjectPropertyRange(ObjectPropertyRange { ce, .. })
+        | Component::DataPropertyDomain(DataPropertyDomain { ce, .. }) => {
+            vec![(ce, Position::Super)]
+        }
+        Component::ClassAssertion(ClassAssertion { ce, .. }) => vec![(ce, Position::Assertion)],
+        _ => vec![],
+    }
+}
+
+/// A visitor which collects the violations of a profile within a
+/// single component.
+pub(crate) trait ProfileVisit<A: ForIRI>: Visit<A> {
+    /// Called before each component is walked, with true if the
+    /// component is an annotation.
+    fn set_annotation(&mut self, annotation: bool);
+
+    fn found(&mut self) -> &mut Vec<(Construct<A>, ProfileRule)>;
+}
+
+/// Walk every component of `ont` with `v`, and return the violations
+/// which it finds.
+///
+/// Axiom annotations are not walked.
+pub(crate) fn violations_with<A: ForIRI, AA: ForIndex<A>, V: ProfileVisit<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+    v: V,
+) -> Vec<Violation<A>> {
+    let mut walk = Walk::new(v);
+    let mut violations = vec![];
+    for ac in ont.i().iter() {
+        walk.as_mut_visit().set_annotation(matches!(
+            ac.kind(),
+            ComponentKind::AnnotationAssertion | ComponentKind::OntologyAnnotation
+        ));
+        walk.component(&ac.component);
+        violations.extend(
+            walk.as_mut_visit()
+                .found()
+                .drain(..)
+                .map(|(construct, rule)| Violation {
+                    component: ac.clone(),
+                    construct,
+                    rule,
+                }),
+        );
+    }
+    violations
+}
+
+/// Return the datatypes which are defined with a `DatatypeDefinition`
+/// in `ont`.
+pub(crate) fn defined_datatypes<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> HashSet<IRI<A>> {
+    ont.i()
+        .component_for_kind(ComponentKind::DatatypeDefinition)
+        .filter_map(|ac| match &ac.component {
+            Component::DatatypeDefinition(DatatypeDefinition { kind, .. }) => Some(kind.0.clone()),
+            _ => None,
+        })
+        .collect()
+}
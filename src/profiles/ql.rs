@@ -0,0 +1,271 @@
+//! The [OWL 2 QL](https://www.w3.org/TR/owl2-profiles/#OWL_2_QL)
+//! profile.
+//!
+//! OWL 2 QL allows query answering to be rewritten into queries over
+//! a relational database. Subclass expressions are limited to
+//! classes and unqualified existential restrictions, while
+//! superclass expressions may also use intersections, complements and
+//! qualified existential restrictions on a class.
+use std::collections::HashSet;
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::Visit;
+
+use super::*;
+
+/// Return true if components of kind `kind` are allowed in OWL 2 QL.
+fn is_allowed_kind(kind: ComponentKind) -> bool {
+    !matches!(
+        kind,
+        ComponentKind::DisjointUnion
+            | ComponentKind::FunctionalObjectProperty
+            | ComponentKind::InverseFunctionalObjectProperty
+            | ComponentKind::TransitiveObjectProperty
+            | ComponentKind::FunctionalDataProperty
+            | ComponentKind::HasKey
+            | ComponentKind::SameIndividual
+            | ComponentKind::NegativeObjectPropertyAssertion
+            | ComponentKind::NegativeDataPropertyAssertion
+            | ComponentKind::Rule
+    )
+}
+
+/// Return true if `ce` is a subclass expression of OWL 2 QL.
+fn is_sub<A: ForIRI>(ce: &ClassExpression<A>) -> bool {
+    match ce {
+        ClassExpression::Class(_) | ClassExpression::DataSomeValuesFrom { .. } => true,
+        ClassExpression::ObjectSomeValuesFrom { bce, .. } => {
+            matches!(bce.as_ref(), ClassExpression::Class(c) if c.is_thing())
+        }
+        _ => false,
+    }
+}
+
+/// Collects the violations within a single component.
+struct Checker<A> {
+    /// Datatypes defined with `DatatypeDefinition` axioms
+    defined: HashSet<IRI<A>>,
+    /// True if literals in the current component are annotation values
+    annotation: bool,
+    found: Vec<(Construct<A>, ProfileRule)>,
+}
+
+impl<A: ForIRI> Checker<A> {
+    fn push_ce(&mut self, ce: &ClassExpression<A>, rule: ProfileRule) {
+        self.found
+            .push((Construct::ClassExpression(ce.clone()), rule));
+    }
+
+    fn check_super(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::Class(_) | ClassExpression::DataSomeValuesFrom { .. } => {}
+            ClassExpression::ObjectSomeValuesFrom { bce, .. }
+                if matches!(bce.as_ref(), ClassExpression::Class(_)) => {}
+            ClassExpression::ObjectIntersectionOf(v) => {
+                for ce in v {
+                    self.check_super(ce);
+                }
+            }
+            ClassExpression::ObjectComplementOf(bce) => {
+                if !is_sub(bce) {
+                    self.push_ce(bce, ProfileRule::SubClassExpression);
+                }
+            }
+            _ => self.push_ce(ce, ProfileRule::SuperClassExpression),
+        }
+    }
+}
+
+impl<A: ForIRI> ProfileVisit<A> for Checker<A> {
+    fn set_annotation(&mut self, annotation: bool) {
+        self.annotation = annotation;
+    }
+
+    fn found(&mut self) -> &mut Vec<(Construct<A>, ProfileRule)> {
+        &mut self.found
+    }
+}
+
+impl<A: ForIRI> Visit<A> for Checker<A> {
+    fn visit_component(&mut self, c: &Component<A>) {
+        let chain = matches!(
+            c,
+            Component::SubObjectPropertyOf(SubObjectPropertyOf {
+                sub: SubObjectPropertyExpression::ObjectPropertyChain(_),
+                ..
+            })
+        );
+        if chain || !is_allowed_kind(c.kind()) {
+            self.found.push((Construct::Axiom, ProfileRule::Axiom));
+            return;
+        }
+
+        for (ce, position) in class_expressions(c) {
+            match position {
+                Position::Sub if !is_sub(ce) => self.push_ce(ce, ProfileRule::SubClassExpression),
+                Position::Equivalent if !is_sub(ce) => {
+                    self.push_ce(ce, ProfileRule::EquivalentClassExpression)
+                }
+                Position::Super => self.check_super(ce),
+                Position::Assertion if !matches!(ce, ClassExpression::Class(_)) => {
+                    self.push_ce(ce, ProfileRule::ClassExpression)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_data_range(&mut self, dr: &DataRange<A>) {
+        match dr {
+            DataRange::Datatype(dt) => {
+                if !is_iri_in(&dt.0, el::DATATYPES) && !self.defined.contains(&dt.0) {
+                    self.found
+                        .push((Construct::DataRange(dr.clone()), ProfileRule::Datatype));
+                }
+            }
+            DataRange::DataIntersectionOf(_) => {}
+            _ => self
+                .found
+                .push((Construct::DataRange(dr.clone()), ProfileRule::DataRange)),
+        }
+    }
+
+    fn visit_literal(&mut self, l: &Literal<A>) {
+        if !self.annotation && !is_iri_in(&literal_datatype(l), el::DATATYPES) {
+            self.found
+                .push((Construct::Literal(l.clone()), ProfileRule::Literal));
+        }
+    }
+
+    fn visit_individual(&mut self, i: &Individual<A>) {
+        if let Individual::Anonymous(_) = i {
+            self.found.push((
+                Construct::Individual(i.clone()),
+                ProfileRule::AnonymousIndividual,
+            ));
+        }
+    }
+}
+
+/// Return every violation of the OWL 2 QL profile in `ont`.
+///
+/// OWL 2 QL allows the same datatypes as OWL 2 EL.
+pub fn violations<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<Violation<A>> {
+    violations_with(
+        ont,
+        Checker {
+            defined: defined_datatypes(ont),
+            annotation: false,
+            found: vec![],
+        },
+    )
+}
+
+/// Return true if `ont` is in the OWL 2 QL profile.
+pub fn is_ql<A: ForIRI, AA: ForIndex<A>>(ont: &ComponentMappedOntology<A, AA>) -> bool {
+    violations(ont).is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn check(ofn: &str) -> Vec<(Construct<RcStr>, ProfileRule)> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        violations(&ont)
+            .into_iter()
+            .map(|v| (v.construct, v.rule))
+            .collect()
+    }
+
+    #[test]
+    fn allowed() {
+        assert_eq!(
+            check(
+                "SubClassOf(ObjectSomeValuesFrom(ObjectInverseOf(:r) owl:Thing) :A)
+                 SubClassOf(:A ObjectIntersectionOf(:B ObjectComplementOf(:C)))
+                 SubClassOf(:A ObjectSomeValuesFrom(:r :B))
+                 EquivalentClasses(:A DataSomeValuesFrom(:d xsd:integer))
+                 DisjointClasses(:A :B)
+                 ObjectPropertyRange(:r ObjectSomeValuesFrom(:s :C))
+                 InverseObjectProperties(:r :s)
+                 ClassAssertion(:A :i)"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn positions() {
+        let b = Build::new_rc();
+        let some_b = ClassExpression::ObjectSomeValuesFrom {
+            ope: b.object_property("http://example.com/r").into(),
+            bce: Box::new(b.class("http://example.com/B").into()),
+        };
+
+        assert_eq!(
+            check("SubClassOf(ObjectSomeValuesFrom(:r :B) :A)"),
+            vec![(
+                Construct::ClassExpression(some_b.clone()),
+                ProfileRule::SubClassExpression
+            )]
+        );
+        assert_eq!(
+            check("EquivalentClasses(:A ObjectSomeValuesFrom(:r :B))"),
+            vec![(
+                Construct::ClassExpression(some_b.clone()),
+                ProfileRule::EquivalentClassExpression
+            )]
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectComplementOf(ObjectSomeValuesFrom(:r :B)))"),
+            vec![(
+                Construct::ClassExpression(some_b),
+                ProfileRule::SubClassExpression
+            )]
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectUnionOf(:B :C))")[0].1,
+            ProfileRule::SuperClassExpression
+        );
+        assert_eq!(
+            check("ClassAssertion(ObjectIntersectionOf(:A :B) :i)")[0].1,
+            ProfileRule::ClassExpression
+        );
+    }
+
+    #[test]
+    fn axioms() {
+        assert_eq!(
+            check(
+                "TransitiveObjectProperty(:r)
+                 SubObjectPropertyOf(ObjectPropertyChain(:r :s) :r)"
+            ),
+            vec![
+                (Construct::Axiom, ProfileRule::Axiom),
+                (Construct::Axiom, ProfileRule::Axiom)
+            ]
+        );
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(
+            check("DataPropertyRange(:d DataOneOf(\"1\"^^xsd:integer))")[0].1,
+            ProfileRule::DataRange
+        );
+        assert_eq!(
+            check("DataPropertyAssertion(:d :i \"1\"^^xsd:double)")[0].1,
+            ProfileRule::Literal
+        );
+    }
+}
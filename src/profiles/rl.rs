@@ -0,0 +1,305 @@
+//! The [OWL 2 RL](https://www.w3.org/TR/owl2-profiles/#OWL_2_RL)
+//! profile.
+//!
+//! OWL 2 RL can be implemented with a rule engine. Subclass
+//! expressions may use unions, enumerations and existential
+//! restrictions, while superclass expressions may use universal
+//! restrictions, complements and cardinality restrictions of zero or
+//! one; `owl:Thing` may only be used as the filler of a restriction.
+use std::collections::HashSet;
+
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::Visit;
+
+use super::*;
+
+/// The datatypes which are allowed in OWL 2 RL.
+pub(crate) const DATATYPES: &[&str] = &[
+    "rdf:PlainLiteral",
+    "rdf:XMLLiteral",
+    "rdfs:Literal",
+    "xsd:decimal",
+    "xsd:integer",
+    "xsd:nonNegativeInteger",
+    "xsd:nonPositiveInteger",
+    "xsd:positiveInteger",
+    "xsd:negativeInteger",
+    "xsd:long",
+    "xsd:int",
+    "xsd:short",
+    "xsd:byte",
+    "xsd:unsignedLong",
+    "xsd:unsignedInt",
+    "xsd:unsignedShort",
+    "xsd:unsignedByte",
+    "xsd:float",
+    "xsd:double",
+    "xsd:string",
+    "xsd:normalizedString",
+    "xsd:token",
+    "xsd:language",
+    "xsd:Name",
+    "xsd:NCName",
+    "xsd:NMTOKEN",
+    "xsd:boolean",
+    "xsd:hexBinary",
+    "xsd:base64Binary",
+    "xsd:anyURI",
+    "xsd:dateTime",
+    "xsd:dateTimeStamp",
+];
+
+/// Return true if components of kind `kind` are allowed in OWL 2 RL.
+fn is_allowed_kind(kind: ComponentKind) -> bool {
+    !matches!(
+        kind,
+        ComponentKind::DisjointUnion | ComponentKind::ReflexiveObjectProperty | ComponentKind::Rule
+    )
+}
+
+fn is_thing<A: ForIRI>(ce: &ClassExpression<A>) -> bool {
+    matches!(ce, ClassExpression::Class(c) if c.is_thing())
+}
+
+/// Collects the violations within a single component.
+struct Checker<A> {
+    /// Datatypes defined with `DatatypeDefinition` axioms
+    defined: HashSet<IRI<A>>,
+    /// True if literals in the current component are annotation values
+    annotation: bool,
+    found: Vec<(Construct<A>, ProfileRule)>,
+}
+
+impl<A: ForIRI> Checker<A> {
+    fn push_ce(&mut self, ce: &ClassExpression<A>, rule: ProfileRule) {
+        self.found
+            .push((Construct::ClassExpression(ce.clone()), rule));
+    }
+
+    fn check_sub(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::Class(_) if !is_thing(ce) => {}
+            ClassExpression::ObjectOneOf(_)
+            | ClassExpression::ObjectHasValue { .. }
+            | ClassExpression::DataSomeValuesFrom { .. }
+            | ClassExpression::DataHasValue { .. } => {}
+            ClassExpression::ObjectIntersectionOf(v) | ClassExpression::ObjectUnionOf(v) => {
+                for ce in v {
+                    self.check_sub(ce);
+                }
+            }
+            ClassExpression::ObjectSomeValuesFrom { bce, .. } => {
+                if !is_thing(bce) {
+                    self.check_sub(bce);
+                }
+            }
+            _ => self.push_ce(ce, ProfileRule::SubClassExpression),
+        }
+    }
+
+    fn check_super(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::Class(_) if !is_thing(ce) => {}
+            ClassExpression::ObjectHasValue { .. }
+            | ClassExpression::DataAllValuesFrom { .. }
+            | ClassExpression::DataHasValue { .. } => {}
+            ClassExpression::DataMaxCardinality { n, .. } if *n <= 1 => {}
+            ClassExpression::ObjectIntersectionOf(v) => {
+                for ce in v {
+                    self.check_super(ce);
+                }
+            }
+            ClassExpression::ObjectComplementOf(bce) => self.check_sub(bce),
+            ClassExpression::ObjectAllValuesFrom { bce, .. } => self.check_super(bce),
+            ClassExpression::ObjectMaxCardinality { n, bce, .. } if *n <= 1 => {
+                if !is_thing(bce) {
+                    self.check_sub(bce);
+                }
+            }
+            _ => self.push_ce(ce, ProfileRule::SuperClassExpression),
+        }
+    }
+
+    fn check_equivalent(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::Class(_) if !is_thing(ce) => {}
+            ClassExpression::ObjectHasValue { .. } | ClassExpression::DataHasValue { .. } => {}
+            ClassExpression::ObjectIntersectionOf(v) => {
+                for ce in v {
+                    self.check_equivalent(ce);
+                }
+            }
+            _ => self.push_ce(ce, ProfileRule::EquivalentClassExpression),
+        }
+    }
+}
+
+impl<A: ForIRI> ProfileVisit<A> for Checker<A> {
+    fn set_annotation(&mut self, annotation: bool) {
+        self.annotation = annotation;
+    }
+
+    fn found(&mut self) -> &mut Vec<(Construct<A>, ProfileRule)> {
+        &mut self.found
+    }
+}
+
+impl<A: ForIRI> Visit<A> for Checker<A> {
+    fn visit_component(&mut self, c: &Component<A>) {
+        if !is_allowed_kind(c.kind()) {
+            self.found.push((Construct::Axiom, ProfileRule::Axiom));
+            return;
+        }
+
+        for (ce, position) in class_expressions(c) {
+            match position {
+                Position::Sub => self.check_sub(ce),
+                Position::Super | Position::Assertion => self.check_super(ce),
+                Position::Equivalent => self.check_equivalent(ce),
+            }
+        }
+    }
+
+    fn visit_data_range(&mut self, dr: &DataRange<A>) {
+        match dr {
+            DataRange::Datatype(dt) => {
+                if !is_iri_in(&dt.0, DATATYPES) && !self.defined.contains(&dt.0) {
+                    self.found
+                        .push((Construct::DataRange(dr.clone()), ProfileRule::Datatype));
+                }
+            }
+            DataRange::DataIntersectionOf(_) => {}
+            _ => self
+                .found
+                .push((Construct::DataRange(dr.clone()), ProfileRule::DataRange)),
+        }
+    }
+
+    fn visit_literal(&mut self, l: &Literal<A>) {
+        if !self.annotation && !is_iri_in(&literal_datatype(l), DATATYPES) {
+            self.found
+                .push((Construct::Literal(l.clone()), ProfileRule::Literal));
+        }
+    }
+}
+
+/// Return every violation of the OWL 2 RL profile in `ont`.
+pub fn violations<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Vec<Violation<A>> {
+    violations_with(
+        ont,
+        Checker {
+            defined: defined_datatypes(ont),
+            annotation: false,
+            found: vec![],
+        },
+    )
+}
+
+/// Return true if `ont` is in the OWL 2 RL profile.
+pub fn is_rl<A: ForIRI, AA: ForIndex<A>>(ont: &ComponentMappedOntology<A, AA>) -> bool {
+    violations(ont).is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn check(ofn: &str) -> Vec<(Construct<RcStr>, ProfileRule)> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        violations(&ont)
+            .into_iter()
+            .map(|v| (v.construct, v.rule))
+            .collect()
+    }
+
+    #[test]
+    fn allowed() {
+        assert_eq!(
+            check(
+                "SubClassOf(ObjectUnionOf(:A ObjectSomeValuesFrom(:r owl:Thing)) :B)
+                 SubClassOf(ObjectOneOf(:i :j) ObjectAllValuesFrom(:r :C))
+                 SubClassOf(:A ObjectMaxCardinality(1 :r :B))
+                 SubClassOf(:A ObjectComplementOf(ObjectSomeValuesFrom(:r :B)))
+                 EquivalentClasses(:A ObjectIntersectionOf(:B ObjectHasValue(:r :i)))
+                 ObjectPropertyDomain(ObjectInverseOf(:r) :A)
+                 FunctionalObjectProperty(:r)
+                 SubObjectPropertyOf(ObjectPropertyChain(:r :s) :r)
+                 HasKey(:A (:r) ())
+                 DataPropertyAssertion(:d :i \"1.0\"^^xsd:double)"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn positions() {
+        let b = Build::new_rc();
+        let all_b = ClassExpression::ObjectAllValuesFrom {
+            ope: b.object_property("http://example.com/r").into(),
+            bce: Box::new(b.class("http://example.com/B").into()),
+        };
+
+        assert_eq!(
+            check("SubClassOf(ObjectAllValuesFrom(:r :B) :A)"),
+            vec![(
+                Construct::ClassExpression(all_b.clone()),
+                ProfileRule::SubClassExpression
+            )]
+        );
+        assert_eq!(
+            check("EquivalentClasses(:A ObjectAllValuesFrom(:r :B))"),
+            vec![(
+                Construct::ClassExpression(all_b.clone()),
+                ProfileRule::EquivalentClassExpression
+            )]
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectComplementOf(ObjectAllValuesFrom(:r :B)))"),
+            vec![(
+                Construct::ClassExpression(all_b),
+                ProfileRule::SubClassExpression
+            )]
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectSomeValuesFrom(:r :B))")[0].1,
+            ProfileRule::SuperClassExpression
+        );
+        assert_eq!(
+            check("SubClassOf(:A ObjectMaxCardinality(2 :r))")[0].1,
+            ProfileRule::SuperClassExpression
+        );
+        assert_eq!(
+            check("SubClassOf(owl:Thing :A)")[0].1,
+            ProfileRule::SubClassExpression
+        );
+    }
+
+    #[test]
+    fn axioms() {
+        assert_eq!(
+            check("ReflexiveObjectProperty(:r)"),
+            vec![(Construct::Axiom, ProfileRule::Axiom)]
+        );
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(
+            check("DataPropertyRange(:d DataUnionOf(xsd:integer xsd:string))")[0].1,
+            ProfileRule::DataRange
+        );
+        assert_eq!(
+            check("DataPropertyRange(:d owl:real)")[0].1,
+            ProfileRule::Datatype
+        );
+    }
+}
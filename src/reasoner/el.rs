@@ -0,0 +1,942 @@
+//! A consequence-based classifier for
+//! [OWL 2 EL](https://www.w3.org/TR/owl2-profiles/#OWL_2_EL).
+//!
+//! Axioms are first normalized, introducing a fresh concept for each
+//! complex class expression, and then saturated with the completion
+//! rules of EL++, in the style of CEL and ELK. Each named class and
+//! individual has a context which collects its subsumers and its
+//! relationships to other contexts; as EL has no disjunction, these
+//! are exactly the entailed subsumers.
+//!
+//! The classifier supports
+//!
+//!  - `SubClassOf`, `EquivalentClasses` and `DisjointClasses`
+//!  - `SubObjectPropertyOf`, including `ObjectPropertyChain`,
+//!    `EquivalentObjectProperties` and `TransitiveObjectProperty`
+//!  - `ObjectPropertyDomain` and `DataPropertyDomain`
+//!  - `ClassAssertion`, `ObjectPropertyAssertion`,
+//!    `DataPropertyAssertion`, `SameIndividual` and
+//!    `DifferentIndividuals`
+//!
+//! with the class expressions `ObjectIntersectionOf`,
+//! `ObjectSomeValuesFrom`, `ObjectHasValue` and `ObjectOneOf` with a
+//! single individual. Data property restrictions are treated as
+//! atomic concepts, compared structurally.
+//!
+//! Axioms using any other construct, and property ranges, are
+//! ignored. The classification is, therefore, always sound, but is
+//! only complete for the ontologies in OWL 2 EL without ranges or
+//! datatype reasoning.
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use super::Reasoner;
+use crate::error::HornedError;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::hierarchy::Hierarchy;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::OWL;
+
+/// The concept for `owl:Thing`.
+const TOP: usize = 0;
+/// The concept for `owl:Nothing`.
+const BOTTOM: usize = 1;
+
+/// The atoms which are given a concept during normalization.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Atom<A> {
+    Class(Class<A>),
+    Nominal(Individual<A>),
+    /// A data property restriction
+    Data(ClassExpression<A>),
+    /// The fresh concept for a complex class expression
+    Complex(ClassExpression<A>),
+}
+
+/// The normalized axioms, indexed for the completion rules.
+#[derive(Debug, Default)]
+struct Rules {
+    /// `A ⊑ B`, indexed by `A`
+    told: HashMap<usize, Vec<usize>>,
+    /// `A1 ⊓ ... ⊓ An ⊑ B`, indexed by each `Ai`
+    conjunctions: HashMap<usize, Vec<(Vec<usize>, usize)>>,
+    /// `A ⊑ ∃r.B`, indexed by `A`
+    exists_sup: HashMap<usize, Vec<(usize, usize)>>,
+    /// `∃r.A ⊑ B`, indexed by `A`
+    exists_sub: HashMap<usize, Vec<(usize, usize)>>,
+    /// The super properties of each property, including itself
+    role_sups: Vec<Vec<usize>>,
+    /// `r ∘ s ⊑ t`, indexed by `r`
+    chain_first: HashMap<usize, Vec<(usize, usize)>>,
+    /// `r ∘ s ⊑ t`, indexed by `s`
+    chain_second: HashMap<usize, Vec<(usize, usize)>>,
+    /// The concepts of individuals
+    nominals: Vec<usize>,
+}
+
+/// Collects every named class of an ontology.
+struct Signature<A>(BTreeSet<Class<A>>);
+
+impl<A: ForIRI> Visit<A> for Signature<A> {
+    fn visit_class(&mut self, c: &Class<A>) {
+        self.0.insert(c.clone());
+    }
+}
+
+/// Translates components into `Rules`.
+struct Normalizer<A> {
+    atoms: HashMap<Atom<A>, usize>,
+    next: usize,
+    roles: HashMap<ObjectProperty<A>, usize>,
+    role_told: Vec<Vec<usize>>,
+    /// Complex concepts already defined for positive (`true`) or
+    /// negative occurrence
+    defined: HashSet<(usize, bool)>,
+    data_domains: Vec<(DataProperty<A>, usize)>,
+    rules: Rules,
+}
+
+impl<A: ForIRI> Normalizer<A> {
+    fn new() -> Normalizer<A> {
+        Normalizer {
+            atoms: HashMap::new(),
+            next: BOTTOM + 1,
+            roles: HashMap::new(),
+            role_told: vec![],
+            defined: HashSet::new(),
+            data_domains: vec![],
+            rules: Default::default(),
+        }
+    }
+
+    fn atom(&mut self, atom: Atom<A>) -> usize {
+        let next = &mut self.next;
+        *self.atoms.entry(atom).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    fn nominal(&mut self, i: &Individual<A>) -> usize {
+        let len = self.atoms.len();
+        let n = self.atom(Atom::Nominal(i.clone()));
+        if self.atoms.len() > len {
+            self.rules.nominals.push(n);
+        }
+        n
+    }
+
+    fn fresh_role(&mut self) -> usize {
+        self.role_told.push(vec![]);
+        self.role_told.len() - 1
+    }
+
+    fn role(&mut self, ope: &ObjectPropertyExpression<A>) -> Option<usize> {
+        match ope {
+            ObjectPropertyExpression::ObjectProperty(op) => {
+                if let Some(r) = self.roles.get(op) {
+                    return Some(*r);
+                }
+                let r = self.fresh_role();
+                self.roles.insert(op.clone(), r);
+                Some(r)
+            }
+            ObjectPropertyExpression::InverseObjectProperty(_) => None,
+        }
+    }
+
+    fn told(&mut self, a: usize, b: usize) {
+        self.rules.told.entry(a).or_default().push(b);
+    }
+
+    fn conjunction(&mut self, v: Vec<usize>, b: usize) {
+        for a in v.iter().collect::<BTreeSet<_>>() {
+            self.rules
+                .conjunctions
+                .entry(*a)
+                .or_default()
+                .push((v.clone(), b));
+        }
+    }
+
+    fn exists_sup(&mut self, a: usize, r: usize, b: usize) {
+        self.rules.exists_sup.entry(a).or_default().push((r, b));
+    }
+
+    fn exists_sub(&mut self, r: usize, a: usize, b: usize) {
+        self.rules.exists_sub.entry(a).or_default().push((r, b));
+    }
+
+    fn chain(&mut self, r: usize, s: usize, t: usize) {
+        self.rules.chain_first.entry(r).or_default().push((s, t));
+        self.rules.chain_second.entry(s).or_default().push((r, t));
+    }
+
+    /// Return the concept for `ce`, adding the rules which define
+    /// it where it occurs positively (as a superclass) or negatively
+    /// (as a subclass). Return `None` if `ce` is outside EL.
+    fn concept(&mut self, ce: &ClassExpression<A>, positive: bool) -> Option<usize> {
+        let exists = |this: &mut Self, r: usize, f: usize| {
+            let id = this.atom(Atom::Complex(ce.clone()));
+            if this.defined.insert((id, positive)) {
+                if positive {
+                    this.exists_sup(id, r, f);
+                } else {
+                    this.exists_sub(r, f, id);
+                }
+            }
+            id
+        };
+
+        match ce {
+            ClassExpression::Class(c) if c.is_thing() => Some(TOP),
+            ClassExpression::Class(c) if c.is_nothing() => Some(BOTTOM),
+            ClassExpression::Class(c) => Some(self.atom(Atom::Class(c.clone()))),
+            ClassExpression::ObjectOneOf(v) if v.len() == 1 => Some(self.nominal(&v[0])),
+            ClassExpression::DataSomeValuesFrom { .. } | ClassExpression::DataHasValue { .. } => {
+                Some(self.atom(Atom::Data(ce.clone())))
+            }
+            ClassExpression::ObjectIntersectionOf(v) => {
+                let v = v
+                    .iter()
+                    .map(|ce| self.concept(ce, positive))
+                    .collect::<Option<Vec<_>>>()?;
+                let id = self.atom(Atom::Complex(ce.clone()));
+                if self.defined.insert((id, positive)) {
+                    if positive {
+                        for c in v {
+                            self.told(id, c);
+                        }
+                    } else {
+                        self.conjunction(v, id);
+                    }
+                }
+                Some(id)
+            }
+            ClassExpression::ObjectSomeValuesFrom { ope, bce } => {
+                let r = self.role(ope)?;
+                let f = self.concept(bce, positive)?;
+                Some(exists(self, r, f))
+            }
+            ClassExpression::ObjectHasValue { ope, i } => {
+                let r = self.role(ope)?;
+                let f = self.nominal(i);
+                Some(exists(self, r, f))
+            }
+            _ => None,
+        }
+    }
+
+    fn sub_class_of(&mut self, sub: &ClassExpression<A>, sup: &ClassExpression<A>) {
+        if let (Some(a), Some(b)) = (self.concept(sub, false), self.concept(sup, true)) {
+            self.told(a, b);
+        }
+    }
+
+    fn disjoint(&mut self, v: &[ClassExpression<A>]) {
+        let v: Option<Vec<_>> = v.iter().map(|ce| self.concept(ce, false)).collect();
+        if let Some(v) = v {
+            for (i, a) in v.iter().enumerate() {
+                for b in &v[i + 1..] {
+                    self.conjunction(vec![*a, *b], BOTTOM);
+                }
+            }
+        }
+    }
+
+    fn component(&mut self, c: &Component<A>) {
+        match c {
+            Component::SubClassOf(SubClassOf { sub, sup }) => self.sub_class_of(sub, sup),
+            Component::EquivalentClasses(EquivalentClasses(v)) => {
+                for (i, ce) in v.iter().enumerate() {
+                    self.sub_class_of(ce, &v[(i + 1) % v.len()]);
+                }
+            }
+            Component::DisjointClasses(DisjointClasses(v)) => self.disjoint(v),
+            Component::SubObjectPropertyOf(SubObjectPropertyOf { sub, sup }) => {
+                let v = match sub {
+                    SubObjectPropertyExpression::ObjectPropertyChain(v) => v.clone(),
+                    SubObjectPropertyExpression::ObjectPropertyExpression(ope) => {
+                        vec![ope.clone()]
+                    }
+                };
+                let v: Option<Vec<_>> = v.iter().map(|ope| self.role(ope)).collect();
+                if let (Some(v), Some(s)) = (v, self.role(sup)) {
+                    match v.len() {
+                        0 => {}
+                        1 => self.role_told[v[0]].push(s),
+                        n => {
+                            let mut u = v[0];
+                            for (i, r) in v[1..].iter().enumerate() {
+                                let t = if i == n - 2 { s } else { self.fresh_role() };
+                                self.chain(u, *r, t);
+                                u = t;
+                            }
+                        }
+                    }
+                }
+            }
+            Component::EquivalentObjectProperties(EquivalentObjectProperties(v)) => {
+                let v: Option<Vec<_>> = v.iter().map(|ope| self.role(ope)).collect();
+                if let Some(v) = v {
+                    for (i, r) in v.iter().enumerate() {
+                        self.role_told[*r].push(v[(i + 1) % v.len()]);
+                    }
+                }
+            }
+            Component::TransitiveObjectProperty(TransitiveObjectProperty(ope)) => {
+                if let Some(r) = self.role(ope) {
+                    self.chain(r, r, r);
+                }
+            }
+            Component::ObjectPropertyDomain(ObjectPropertyDomain { ope, ce }) => {
+                if let (Some(r), Some(c)) = (self.role(ope), self.concept(ce, true)) {
+                    self.exists_sub(r, TOP, c);
+                }
+            }
+            Component::DataPropertyDomain(DataPropertyDomain { dp, ce }) => {
+                if let Some(c) = self.concept(ce, true) {
+                    self.data_domains.push((dp.clone(), c));
+                }
+            }
+            Component::ClassAssertion(ClassAssertion { ce, i }) => {
+                let n = self.nominal(i);
+                if let Some(c) = self.concept(ce, true) {
+                    self.told(n, c);
+                }
+            }
+            Component::ObjectPropertyAssertion(ObjectPropertyAssertion { ope, from, to }) => {
+                let (a, b) = (self.nominal(from), self.nominal(to));
+                if let Some(r) = self.role(ope) {
+                    self.exists_sup(a, r, b);
+                }
+            }
+            Component::DataPropertyAssertion(DataPropertyAssertion { dp, from, to }) => {
+                let n = self.nominal(from);
+                let d = self.atom(Atom::Data(ClassExpression::DataHasValue {
+                    dp: dp.clone(),
+                    l: to.clone(),
+                }));
+                self.told(n, d);
+            }
+            Component::SameIndividual(SameIndividual(v)) => {
+                let v: Vec<_> = v.iter().map(|i| self.nominal(i)).collect();
+                for (i, n) in v.iter().enumerate() {
+                    self.told(*n, v[(i + 1) % v.len()]);
+                }
+            }
+            Component::DifferentIndividuals(DifferentIndividuals(v)) => {
+                let v: Vec<_> = v.iter().map(|i| self.nominal(i)).collect();
+                for (i, a) in v.iter().enumerate() {
+                    for b in &v[i + 1..] {
+                        self.conjunction(vec![*a, *b], BOTTOM);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add the rules which depend on every component, and return
+    /// them.
+    fn finish(mut self) -> Rules {
+        for (dp, c) in std::mem::take(&mut self.data_domains) {
+            let restricted: Vec<_> = self
+                .atoms
+                .iter()
+                .filter_map(|(atom, id)| match atom {
+                    Atom::Data(ClassExpression::DataSomeValuesFrom { dp: d, .. })
+                    | Atom::Data(ClassExpression::DataHasValue { dp: d, .. })
+                        if *d == dp =>
+                    {
+                        Some(*id)
+                    }
+                    _ => None,
+                })
+                .collect();
+            for a in restricted {
+                self.told(a, c);
+            }
+        }
+
+        self.rules.role_sups = (0..self.role_told.len())
+            .map(|r| {
+                let mut seen = BTreeSet::new();
+                let mut stack = vec![r];
+                while let Some(r) = stack.pop() {
+                    if seen.insert(r) {
+                        stack.extend(&self.role_told[r]);
+                    }
+                }
+                seen.into_iter().collect()
+            })
+            .collect();
+
+        self.rules
+    }
+}
+
+/// The subsumers of a concept and its relationships to others.
+#[derive(Debug, Default)]
+struct Context {
+    subsumers: HashSet<usize>,
+    successors: HashMap<usize, HashSet<usize>>,
+    predecessors: HashMap<usize, HashSet<usize>>,
+}
+
+enum Todo {
+    /// Add a subsumer to a context
+    Sub(usize, usize),
+    /// Relate one context to another with a property
+    Edge(usize, usize, usize),
+}
+
+/// Applies the completion rules until no more conclusions follow.
+struct Saturation<'a> {
+    rules: &'a Rules,
+    contexts: HashMap<usize, Context>,
+    todo: VecDeque<Todo>,
+}
+
+impl<'a> Saturation<'a> {
+    fn init(&mut self, c: usize) {
+        if let Entry::Vacant(e) = self.contexts.entry(c) {
+            e.insert(Default::default());
+            self.todo.push_back(Todo::Sub(c, c));
+            self.todo.push_back(Todo::Sub(c, TOP));
+        }
+    }
+
+    fn sub(&mut self, c: usize, d: usize) {
+        let rules = self.rules;
+        let ctx = self.contexts.get_mut(&c).unwrap();
+        if !ctx.subsumers.insert(d) {
+            return;
+        }
+        let todo = &mut self.todo;
+
+        for b in rules.told.get(&d).into_iter().flatten() {
+            todo.push_back(Todo::Sub(c, *b));
+        }
+        for (v, b) in rules.conjunctions.get(&d).into_iter().flatten() {
+            if v.iter().all(|a| ctx.subsumers.contains(a)) {
+                todo.push_back(Todo::Sub(c, *b));
+            }
+        }
+        for (r, b) in rules.exists_sup.get(&d).into_iter().flatten() {
+            todo.push_back(Todo::Edge(c, *r, *b));
+        }
+        for (r, b) in rules.exists_sub.get(&d).into_iter().flatten() {
+            for e in ctx.predecessors.get(r).into_iter().flatten() {
+                todo.push_back(Todo::Sub(*e, *b));
+            }
+        }
+        if d == BOTTOM {
+            for e in ctx.predecessors.values().flatten() {
+                todo.push_back(Todo::Sub(*e, BOTTOM));
+            }
+        }
+    }
+
+    fn edge(&mut self, c: usize, r: usize, d: usize) {
+        self.init(d);
+        let rules = self.rules;
+        let inserted = self
+            .contexts
+            .get_mut(&c)
+            .unwrap()
+            .successors
+            .entry(r)
+            .or_default()
+            .insert(d);
+        if !inserted {
+            return;
+        }
+        self.contexts
+            .get_mut(&d)
+            .unwrap()
+            .predecessors
+            .entry(r)
+            .or_default()
+            .insert(c);
+        let todo = &mut self.todo;
+
+        for s in &rules.role_sups[r] {
+            if *s != r {
+                todo.push_back(Todo::Edge(c, *s, d));
+            }
+        }
+
+        let dctx = &self.contexts[&d];
+        for e in &dctx.subsumers {
+            for (s, b) in rules.exists_sub.get(e).into_iter().flatten() {
+                if *s == r {
+                    todo.push_back(Todo::Sub(c, *b));
+                }
+            }
+        }
+        if dctx.subsumers.contains(&BOTTOM) {
+            todo.push_back(Todo::Sub(c, BOTTOM));
+        }
+        for (s, t) in rules.chain_first.get(&r).into_iter().flatten() {
+            for f in dctx.successors.get(s).into_iter().flatten() {
+                todo.push_back(Todo::Edge(c, *t, *f));
+            }
+        }
+
+        let cctx = &self.contexts[&c];
+        for (q, t) in rules.chain_second.get(&r).into_iter().flatten() {
+            for e in cctx.predecessors.get(q).into_iter().flatten() {
+                todo.push_back(Todo::Edge(*e, *t, d));
+            }
+        }
+    }
+
+    fn saturate(&mut self) {
+        while let Some(todo) = self.todo.pop_front() {
+            match todo {
+                Todo::Sub(c, d) => self.sub(c, d),
+                Todo::Edge(c, r, d) => self.edge(c, r, d),
+            }
+        }
+    }
+
+    /// Return the contexts reachable from `from`, including them.
+    fn reachable(&self, from: &[usize]) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut stack = from.to_vec();
+        while let Some(c) = stack.pop() {
+            if seen.insert(c) {
+                stack.extend(self.contexts[&c].successors.values().flatten());
+            }
+        }
+        seen
+    }
+
+    /// Apply the rule for nominals: if two contexts share a nominal
+    /// and one is reachable from the other, or from any nominal, the
+    /// first has the subsumers of the second. Return true if this
+    /// adds any subsumer.
+    fn nominals(&mut self) -> bool {
+        let from_nominals = self.reachable(&self.rules.nominals);
+        let mut found = vec![];
+        for n in &self.rules.nominals {
+            let holders: Vec<_> = self
+                .contexts
+                .iter()
+                .filter(|(_, ctx)| ctx.subsumers.contains(n))
+                .map(|(c, _)| *c)
+                .collect();
+            if holders.len() < 2 {
+                continue;
+            }
+            for c in &holders {
+                let reach = self.reachable(&[*c]);
+                for d in &holders {
+                    if c != d && (reach.contains(d) || from_nominals.contains(d)) {
+                        let subsumers = &self.contexts[c].subsumers;
+                        found.extend(
+                            self.contexts[d]
+                                .subsumers
+                                .iter()
+                                .filter(|e| !subsumers.contains(e))
+                                .map(|e| Todo::Sub(*c, *e)),
+                        );
+                    }
+                }
+            }
+        }
+        let changed = !found.is_empty();
+        self.todo.extend(found);
+        changed
+    }
+}
+
+/// The classification of an ontology.
+#[derive(Debug)]
+pub struct Classification<A> {
+    consistent: bool,
+    subsumers: HashMap<Class<A>, BTreeSet<Class<A>>>,
+    unsatisfiable: BTreeSet<Class<A>>,
+    hierarchy: Hierarchy<Class<A>>,
+    types: BTreeMap<Individual<A>, BTreeSet<Class<A>>>,
+}
+
+impl<A: ForIRI> Classification<A> {
+    /// Return false if the ontology is inconsistent, in which case
+    /// every class is unsatisfiable.
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    /// Return true if `c` may have instances.
+    pub fn is_satisfiable(&self, c: &Class<A>) -> bool {
+        self.consistent && !c.is_nothing() && !self.unsatisfiable.contains(c)
+    }
+
+    /// Return the unsatisfiable classes of the ontology, not including
+    /// `owl:Nothing`.
+    pub fn unsatisfiable(&self) -> &BTreeSet<Class<A>> {
+        &self.unsatisfiable
+    }
+
+    /// Return every named superclass of `c`, including its
+    /// equivalents and `owl:Thing`, but not `c`.
+    ///
+    /// Unsatisfiable classes are subclasses of every class, and are
+    /// not included.
+    pub fn supers(&self, c: &Class<A>) -> BTreeSet<Class<A>> {
+        self.subsumers.get(c).cloned().unwrap_or_default()
+    }
+
+    /// Return true if `sub` is entailed to be a subclass of `sup`.
+    pub fn is_subclass(&self, sub: &Class<A>, sup: &Class<A>) -> bool {
+        sub == sup
+            || sup.is_thing()
+            || !self.is_satisfiable(sub)
+            || self
+                .subsumers
+                .get(sub)
+                .is_some_and(|supers| supers.contains(sup))
+    }
+
+    /// Return the transitively reduced class hierarchy.
+    ///
+    /// Every satisfiable class is a subclass of its direct
+    /// superclasses, and equivalent classes are equivalent. The
+    /// unsatisfiable classes are equivalent to `owl:Nothing`.
+    pub fn hierarchy(&self) -> &Hierarchy<Class<A>> {
+        &self.hierarchy
+    }
+
+    /// Return every named class of `i`, including `owl:Thing`.
+    ///
+    /// Only individuals in the axioms have types. If the ontology is
+    /// inconsistent, they are not meaningful.
+    pub fn types(&self, i: &Individual<A>) -> BTreeSet<Class<A>> {
+        self.types.get(i).cloned().unwrap_or_default()
+    }
+
+    /// Return the individuals which have types.
+    pub fn individuals(&self) -> impl Iterator<Item = &Individual<A>> {
+        self.types.keys()
+    }
+}
+
+impl<A: ForIRI> Reasoner<A> for Classification<A> {
+    fn is_consistent(&self) -> Result<bool, HornedError> {
+        Ok(self.consistent)
+    }
+
+    fn unsatisfiable_classes(&self) -> Result<BTreeSet<Class<A>>, HornedError> {
+        Ok(self.unsatisfiable.clone())
+    }
+
+    fn equivalent_classes(&self, c: &Class<A>) -> Result<BTreeSet<Class<A>>, HornedError> {
+        Ok(self.hierarchy.equivalents(c))
+    }
+
+    fn super_classes(&self, c: &Class<A>, direct: bool) -> Result<BTreeSet<Class<A>>, HornedError> {
+        Ok(if direct {
+            self.hierarchy.direct_supers(c)
+        } else {
+            self.hierarchy.ancestors(c)
+        })
+    }
+
+    /// Return the instances of `ce`, which must be a named class.
+    fn instances(
+        &self,
+        ce: &ClassExpression<A>,
+        direct: bool,
+    ) -> Result<BTreeSet<Individual<A>>, HornedError> {
+        match ce {
+            _ if !self.consistent => Ok(self.types.keys().cloned().collect()),
+            ClassExpression::Class(c) => {
+                Ok(super::instances(&self.hierarchy, &self.types, c, direct))
+            }
+            _ => Err(HornedError::invalid(format!(
+                "Instances can only be found for named classes: {:?}",
+                ce
+            ))),
+        }
+    }
+}
+
+/// Build the transitively reduced hierarchy of `subsumers`.
+fn hierarchy<A: ForIRI>(
+    subsumers: &HashMap<Class<A>, BTreeSet<Class<A>>>,
+    unsatisfiable: &BTreeSet<Class<A>>,
+    nothing: &Class<A>,
+) -> Hierarchy<Class<A>> {
+    let mut hierarchy = Hierarchy::new();
+    let equivalent = |a: &Class<A>, b: &Class<A>| subsumers[b].contains(a);
+    let rep = |a: &Class<A>| {
+        subsumers[a]
+            .iter()
+            .filter(|b| equivalent(a, b))
+            .chain(Some(a))
+            .min()
+            .unwrap()
+            .clone()
+    };
+
+    for (a, supers) in subsumers {
+        let r = rep(a);
+        if r != *a {
+            hierarchy.insert_equivalent(&r, a);
+            continue;
+        }
+        let strict: BTreeSet<_> = supers
+            .iter()
+            .filter(|b| !equivalent(a, b))
+            .map(rep)
+            .collect();
+        for b in &strict {
+            if !strict.iter().any(|c| c != b && subsumers[c].contains(b)) {
+                hierarchy.insert_sub(a, b);
+            }
+        }
+    }
+    for c in unsatisfiable {
+        hierarchy.insert_equivalent(nothing, c);
+    }
+    hierarchy
+}
+
+/// Classify `ont`.
+pub fn classify<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Classification<A> {
+    let mut walk = Walk::new(Signature(BTreeSet::new()));
+    let mut normalizer = Normalizer::new();
+    for ac in ont.i().iter() {
+        walk.annotated_component(ac);
+        normalizer.component(&ac.component);
+    }
+
+    let build = Build::new();
+    let thing = build.class(OWL::Thing.as_ref());
+    let nothing = build.class(OWL::Nothing.as_ref());
+    let mut classes: Vec<_> = walk
+        .into_visit()
+        .0
+        .into_iter()
+        .filter(|c| !c.is_thing() && !c.is_nothing())
+        .map(|c| (normalizer.atom(Atom::Class(c.clone())), c))
+        .collect();
+    classes.push((TOP, thing));
+    let individuals: Vec<_> = normalizer
+        .atoms
+        .iter()
+        .filter_map(|(atom, id)| match atom {
+            Atom::Nominal(i) => Some((*id, i.clone())),
+            _ => None,
+        })
+        .collect();
+    let rules = normalizer.finish();
+
+    let mut saturation = Saturation {
+        rules: &rules,
+        contexts: HashMap::new(),
+        todo: VecDeque::new(),
+    };
+    for (id, _) in &classes {
+        saturation.init(*id);
+    }
+    for n in &rules.nominals {
+        saturation.init(*n);
+    }
+    saturation.saturate();
+    while saturation.nominals() {
+        saturation.saturate();
+    }
+
+    let contexts = saturation.contexts;
+    let unsat = |c: &usize| contexts[c].subsumers.contains(&BOTTOM);
+    let consistent = !unsat(&TOP) && !rules.nominals.iter().any(unsat);
+    let names: HashMap<_, _> = classes.iter().cloned().collect();
+
+    let mut subsumers = HashMap::new();
+    let mut unsatisfiable = BTreeSet::new();
+    for (id, c) in &classes {
+        if !consistent || unsat(id) {
+            unsatisfiable.insert(c.clone());
+        } else {
+            let supers = contexts[id]
+                .subsumers
+                .iter()
+                .filter_map(|s| names.get(s))
+                .filter(|s| *s != c)
+                .cloned()
+                .collect();
+            subsumers.insert(c.clone(), supers);
+        }
+    }
+
+    let types = individuals
+        .into_iter()
+        .map(|(id, i)| {
+            let types = contexts[&id]
+                .subsumers
+                .iter()
+                .filter_map(|s| names.get(s))
+                .cloned()
+                .collect();
+            (i, types)
+        })
+        .collect();
+
+    let hierarchy = hierarchy(&subsumers, &unsatisfiable, &nothing);
+    Classification {
+        consistent,
+        subsumers,
+        unsatisfiable,
+        hierarchy,
+        types,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn classify_ofn(ofn: &str) -> Classification<RcStr> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        classify(&ont)
+    }
+
+    fn class(s: &str) -> Class<RcStr> {
+        Build::new_rc().class(format!("http://example.com/{}", s))
+    }
+
+    fn classes(v: &[&str]) -> BTreeSet<Class<RcStr>> {
+        v.iter().map(|s| class(s)).collect()
+    }
+
+    fn supers(c: &Classification<RcStr>, s: &str) -> BTreeSet<Class<RcStr>> {
+        c.supers(&class(s))
+            .into_iter()
+            .filter(|c| !c.is_thing())
+            .collect()
+    }
+
+    #[test]
+    fn existential() {
+        let c = classify_ofn(
+            "EquivalentClasses(:A ObjectIntersectionOf(:B ObjectSomeValuesFrom(:r :C)))
+             SubClassOf(:D ObjectIntersectionOf(:B ObjectSomeValuesFrom(:r :E)))
+             SubClassOf(:E :C)
+             SubClassOf(ObjectSomeValuesFrom(:s :A) :F)
+             SubClassOf(:G ObjectSomeValuesFrom(:s :D))",
+        );
+        assert_eq!(supers(&c, "D"), classes(&["A", "B"]));
+        assert_eq!(supers(&c, "G"), classes(&["F"]));
+        assert!(c.is_subclass(&class("D"), &class("A")));
+        assert!(!c.is_subclass(&class("A"), &class("D")));
+        assert!(c.unsatisfiable().is_empty());
+    }
+
+    #[test]
+    fn chains() {
+        let c = classify_ofn(
+            "SubObjectPropertyOf(ObjectPropertyChain(:partOf :partOf :partOf) :ancestor)
+             TransitiveObjectProperty(:locatedIn)
+             SubObjectPropertyOf(:partOf :locatedIn)
+             ObjectPropertyDomain(:locatedIn :Located)
+             SubClassOf(:A ObjectSomeValuesFrom(:partOf :B))
+             SubClassOf(:B ObjectSomeValuesFrom(:partOf :C))
+             SubClassOf(:C ObjectSomeValuesFrom(:partOf :D))
+             SubClassOf(ObjectSomeValuesFrom(:ancestor :D) :AncestorOfD)
+             SubClassOf(ObjectSomeValuesFrom(:locatedIn :D) :InD)",
+        );
+        assert_eq!(supers(&c, "A"), classes(&["AncestorOfD", "InD", "Located"]));
+        assert_eq!(supers(&c, "B"), classes(&["InD", "Located"]));
+        assert_eq!(supers(&c, "D"), classes(&[]));
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        let c = classify_ofn(
+            "DisjointClasses(:B :C)
+             SubClassOf(:A ObjectIntersectionOf(:B :C))
+             SubClassOf(:D ObjectSomeValuesFrom(:r :A))
+             SubClassOf(:E :B)
+             SubClassOf(:F owl:Nothing)",
+        );
+        assert_eq!(*c.unsatisfiable(), classes(&["A", "D", "F"]));
+        assert!(c.is_consistent());
+        assert!(c.is_subclass(&class("A"), &class("E")));
+        assert_eq!(
+            c.hierarchy()
+                .equivalents(&Build::new_rc().class(OWL::Nothing.as_ref())),
+            classes(&["A", "D", "F"])
+        );
+    }
+
+    #[test]
+    fn nominals() {
+        let c = classify_ofn(
+            "ClassAssertion(:A :i)
+             SubClassOf(:B ObjectSomeValuesFrom(:r ObjectOneOf(:i)))
+             SubClassOf(ObjectSomeValuesFrom(:r :A) :C)
+             SubClassOf(:D ObjectHasValue(:s :j))
+             SubClassOf(:H ObjectSomeValuesFrom(:r :E))
+             SubClassOf(:E ObjectOneOf(:j))
+             SubClassOf(:E :F)
+             SubClassOf(ObjectSomeValuesFrom(:s :F) :G)",
+        );
+        assert_eq!(supers(&c, "B"), classes(&["C"]));
+        // :H may have no instances, so :j need not be an :F
+        assert_eq!(supers(&c, "D"), classes(&[]));
+
+        let c = classify_ofn(
+            "ClassAssertion(:H :a)
+             SubClassOf(:D ObjectHasValue(:s :j))
+             SubClassOf(:H ObjectSomeValuesFrom(:r :E))
+             SubClassOf(:E ObjectOneOf(:j))
+             SubClassOf(:E :F)
+             SubClassOf(ObjectSomeValuesFrom(:s :F) :G)",
+        );
+        assert_eq!(supers(&c, "D"), classes(&["G"]));
+    }
+
+    #[test]
+    fn inconsistent() {
+        let c = classify_ofn(
+            "DisjointClasses(:A :B)
+             ClassAssertion(:A :i)
+             ClassAssertion(:B :i)
+             SubClassOf(:C :D)",
+        );
+        assert!(!c.is_consistent());
+        assert!(!c.is_satisfiable(&class("C")));
+        assert!(c.is_subclass(&class("D"), &class("A")));
+    }
+
+    #[test]
+    fn taxonomy() {
+        let c = classify_ofn(
+            "SubClassOf(:B :A)
+             SubClassOf(:C :B)
+             SubClassOf(:C :A)
+             EquivalentClasses(:C :D)
+             SubClassOf(:E ObjectIntersectionOf(:A DataHasValue(:d \"1\"^^xsd:integer)))
+             DataPropertyDomain(:d :F)",
+        );
+        let h = c.hierarchy();
+        let thing = Build::new_rc().class(OWL::Thing.as_ref());
+        assert_eq!(h.direct_supers(&class("C")), classes(&["B"]));
+        assert_eq!(h.equivalents(&class("D")), classes(&["C"]));
+        assert_eq!(h.direct_supers(&class("E")), classes(&["A", "F"]));
+        assert_eq!(h.direct_subs(&thing), classes(&["A", "F"]));
+        assert_eq!(h.roots(), vec![thing].into_iter().collect());
+    }
+}
@@ -0,0 +1,395 @@
+//! Reasoning with an external program.
+//!
+//! An [`External`] reasoner writes an ontology in OWL Functional
+//! Syntax to the standard input of a command, and reads the axioms
+//! which it entails from the standard output of the command, also in
+//! OWL Functional Syntax. The command should report
+//!
+//!  - `SubClassOf` and `EquivalentClasses` axioms between named
+//!    classes, with unsatisfiable classes equivalent to `owl:Nothing`
+//!  - `ClassAssertion` axioms with named classes
+//!
+//! and an inconsistent ontology with `SubClassOf(owl:Thing
+//! owl:Nothing)`. Other axioms are only used to decide entailment.
+//!
+//! Most reasoners can be run in this way with a short wrapper script.
+//! A command which exits unsuccessfully results in an error.
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ffi::{OsStr, OsString};
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+
+use super::Reasoner;
+use crate::error::HornedError;
+use crate::io::ofn::reader::ComponentReader;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::hierarchy::Hierarchy;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::OWL;
+
+/// A command which reasons over ontologies.
+#[derive(Clone, Debug)]
+pub struct External {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl External {
+    /// Create a reasoner which runs `program`.
+    pub fn new<S: AsRef<OsStr>>(program: S) -> External {
+        External {
+            program: program.as_ref().to_os_string(),
+            args: vec![],
+        }
+    }
+
+    /// Add an argument to the command.
+    pub fn arg<S: AsRef<OsStr>>(mut self, arg: S) -> External {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    /// Add several arguments to the command.
+    pub fn args<I, S>(mut self, args: I) -> External
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_os_string()));
+        self
+    }
+
+    /// Run the command over `ont`, returning the entailments which it
+    /// reports.
+    pub fn reason<A: ForIRI, AA: ForIndex<A>>(
+        &self,
+        ont: &ComponentMappedOntology<A, AA>,
+    ) -> Result<Entailments<A>, HornedError> {
+        let mut input = vec![];
+        crate::io::ofn::writer::write(&mut input, ont, None)?;
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+
+        // The command need not read all of its input
+        if let Ok(Err(e)) = writer.join() {
+            if e.kind() != ErrorKind::BrokenPipe {
+                return Err(e.into());
+            }
+        }
+        if !output.status.success() {
+            return Err(HornedError::CommandError(format!(
+                "{} failed with {}: {}",
+                self.program.to_string_lossy(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let build = Build::new();
+        let mut components: HashSet<_> = ont.i().iter().map(|ac| ac.component.clone()).collect();
+        for ac in ComponentReader::new(&output.stdout[..], &build) {
+            components.insert(ac?.component);
+        }
+        Ok(Entailments::new(components))
+    }
+}
+
+/// The entailments of an ontology, as reported by an [`External`]
+/// reasoner, together with its asserted axioms.
+#[derive(Debug)]
+pub struct Entailments<A> {
+    consistent: bool,
+    components: HashSet<Component<A>>,
+    hierarchy: Hierarchy<Class<A>>,
+    types: BTreeMap<Individual<A>, BTreeSet<Class<A>>>,
+}
+
+impl<A: ForIRI> Entailments<A> {
+    fn new(components: HashSet<Component<A>>) -> Entailments<A> {
+        let mut hierarchy = Hierarchy::new();
+        let mut types: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+        for c in &components {
+            match c {
+                Component::SubClassOf(SubClassOf {
+                    sub: ClassExpression::Class(sub),
+                    sup: ClassExpression::Class(sup),
+                }) => hierarchy.insert_sub(sub, sup),
+                Component::EquivalentClasses(EquivalentClasses(v)) => {
+                    let classes: Vec<_> = v
+                        .iter()
+                        .filter_map(|ce| match ce {
+                            ClassExpression::Class(c) => Some(c),
+                            _ => None,
+                        })
+                        .collect();
+                    for pair in classes.windows(2) {
+                        hierarchy.insert_equivalent(pair[0], pair[1]);
+                    }
+                }
+                Component::ClassAssertion(ClassAssertion {
+                    ce: ClassExpression::Class(c),
+                    i,
+                }) => {
+                    types.entry(i.clone()).or_default().insert(c.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let build = Build::new();
+        let nothing = build.class(OWL::Nothing.as_ref());
+        let mut bottom = hierarchy.equivalents(&nothing);
+        bottom.extend(hierarchy.descendants(&nothing));
+        bottom.insert(nothing);
+        let consistent = !bottom.contains(&build.class(OWL::Thing.as_ref()))
+            && types.values().flatten().all(|c| !bottom.contains(c));
+
+        Entailments {
+            consistent,
+            components,
+            hierarchy,
+            types,
+        }
+    }
+}
+
+impl<A: ForIRI> Reasoner<A> for Entailments<A> {
+    fn is_consistent(&self) -> Result<bool, HornedError> {
+        Ok(self.consistent)
+    }
+
+    fn unsatisfiable_classes(&self) -> Result<BTreeSet<Class<A>>, HornedError> {
+        let nothing = Build::new().class(OWL::Nothing.as_ref());
+        let mut unsatisfiable = if self.consistent {
+            let mut bottom = self.hierarchy.equivalents(&nothing);
+            bottom.extend(self.hierarchy.descendants(&nothing));
+            bottom
+        } else {
+            self.hierarchy.entities()
+        };
+        unsatisfiable.remove(&nothing);
+        Ok(unsatisfiable)
+    }
+
+    fn equivalent_classes(&self, c: &Class<A>) -> Result<BTreeSet<Class<A>>, HornedError> {
+        Ok(self.hierarchy.equivalents(c))
+    }
+
+    fn super_classes(&self, c: &Class<A>, direct: bool) -> Result<BTreeSet<Class<A>>, HornedError> {
+        let ancestors = self.hierarchy.ancestors(c);
+        if !direct {
+            return Ok(ancestors);
+        }
+        // The reported hierarchy need not be transitively reduced
+        Ok(ancestors
+            .iter()
+            .filter(|b| {
+                !ancestors
+                    .iter()
+                    .any(|d| self.hierarchy.ancestors(d).contains(b))
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Return the instances of `ce`, which must be a named class.
+    fn instances(
+        &self,
+        ce: &ClassExpression<A>,
+        direct: bool,
+    ) -> Result<BTreeSet<Individual<A>>, HornedError> {
+        match ce {
+            _ if !self.consistent => Ok(self.types.keys().cloned().collect()),
+            ClassExpression::Class(c) => {
+                Ok(super::instances(&self.hierarchy, &self.types, c, direct))
+            }
+            _ => Err(HornedError::invalid(format!(
+                "Instances can only be found for named classes: {:?}",
+                ce
+            ))),
+        }
+    }
+
+    /// Return true if `c` is asserted or reported, or otherwise
+    /// follows from them as for [`Reasoner::is_entailed`].
+    fn is_entailed(&self, c: &Component<A>) -> Result<bool, HornedError> {
+        if self.components.contains(c) {
+            return Ok(true);
+        }
+        super::entailed(self, c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+    use crate::reasoner::el::classify;
+
+    use pretty_assertions::assert_eq;
+
+    const ONTOLOGY: &str = "
+  SubClassOf(:A :B)
+  SubClassOf(:B :C)
+  EquivalentClasses(:C :D)
+  ClassAssertion(:A :i)
+  ClassAssertion(:B :j)
+";
+
+    fn ontology() -> ComponentMappedOntology<RcStr, RcAnnotatedComponent> {
+        crate::io::ofn::reader::read_example(ONTOLOGY)
+    }
+
+    /// A stub reasoner, which checks its input and prints `output`.
+    fn stub(output: &str) -> External {
+        External::new("sh").arg("-c").arg(format!(
+            "grep -q 'http://example.com/A' && printf '%s' 'Prefix(:=<http://example.com/>)
+Prefix(owl:=<http://www.w3.org/2002/07/owl#>)
+Ontology({})'",
+            output
+        ))
+    }
+
+    fn class(s: &str) -> Class<RcStr> {
+        Build::new_rc().class(format!("http://example.com/{}", s))
+    }
+
+    fn classes(v: &[&str]) -> BTreeSet<Class<RcStr>> {
+        v.iter().map(|s| class(s)).collect()
+    }
+
+    fn ind(s: &str) -> Individual<RcStr> {
+        Build::new_rc()
+            .named_individual(format!("http://example.com/{}", s))
+            .into()
+    }
+
+    /// Check the answers of `r` for `ONTOLOGY`.
+    fn check(r: &dyn Reasoner<RcStr>) {
+        let named = |s: BTreeSet<Class<RcStr>>| -> BTreeSet<Class<RcStr>> {
+            s.into_iter().filter(|c| !c.is_thing()).collect()
+        };
+        assert!(r.is_consistent().unwrap());
+        assert!(r.unsatisfiable_classes().unwrap().is_empty());
+        assert_eq!(r.equivalent_classes(&class("C")).unwrap(), classes(&["D"]));
+        assert_eq!(
+            named(r.super_classes(&class("A"), true).unwrap()),
+            classes(&["B"])
+        );
+        assert_eq!(
+            named(r.super_classes(&class("A"), false).unwrap()),
+            classes(&["B", "C", "D"])
+        );
+
+        let instances = |c, direct| r.instances(&class(c).into(), direct).unwrap();
+        assert_eq!(
+            instances("D", false),
+            vec![ind("i"), ind("j")].into_iter().collect()
+        );
+        assert_eq!(instances("D", true), BTreeSet::new());
+        assert_eq!(instances("B", true), vec![ind("j")].into_iter().collect());
+
+        let b = Build::new_rc();
+        assert!(r
+            .is_entailed(
+                &ClassAssertion {
+                    ce: class("D").into(),
+                    i: ind("i")
+                }
+                .into()
+            )
+            .unwrap());
+        assert!(r
+            .is_entailed(
+                &SubClassOf {
+                    sub: class("A").into(),
+                    sup: class("D").into()
+                }
+                .into()
+            )
+            .unwrap());
+        assert!(!r
+            .is_entailed(
+                &SubClassOf {
+                    sub: class("D").into(),
+                    sup: class("A").into()
+                }
+                .into()
+            )
+            .unwrap());
+        assert!(r
+            .is_entailed(&DeclareClass(b.class("http://example.com/X")).into())
+            .is_err());
+    }
+
+    #[test]
+    fn external() {
+        let r = stub(
+            "SubClassOf(:A :C) SubClassOf(:A :D) SubClassOf(:B :D)
+             ClassAssertion(:C :i) ClassAssertion(:D :j)",
+        )
+        .reason(&ontology())
+        .unwrap();
+        check(&r);
+    }
+
+    #[test]
+    fn same_as_el() {
+        check(&classify(&ontology()));
+    }
+
+    #[test]
+    fn inconsistent() {
+        let r = stub("SubClassOf(owl:Thing owl:Nothing)")
+            .reason(&ontology())
+            .unwrap();
+        assert!(!r.is_consistent().unwrap());
+        assert!(r.unsatisfiable_classes().unwrap().contains(&class("A")));
+        assert!(r
+            .is_entailed(
+                &SubClassOf {
+                    sub: class("C").into(),
+                    sup: class("A").into()
+                }
+                .into()
+            )
+            .unwrap());
+
+        let r = stub("EquivalentClasses(:B owl:Nothing)")
+            .reason(&ontology())
+            .unwrap();
+        assert!(!r.is_consistent().unwrap());
+    }
+
+    #[test]
+    fn unsatisfiable() {
+        let r = stub("SubClassOf(:E owl:Nothing)")
+            .reason(&ontology())
+            .unwrap();
+        assert!(r.is_consistent().unwrap());
+        assert_eq!(r.unsatisfiable_classes().unwrap(), classes(&["E"]));
+    }
+
+    #[test]
+    fn failure() {
+        let e = External::new("sh")
+            .args(["-c", "echo broken >&2; exit 3"].iter())
+            .reason(&ontology())
+            .unwrap_err();
+        assert!(matches!(e, HornedError::CommandError(ref s) if s.contains("broken")));
+
+        let e = stub("SubClassOf(").reason(&ontology()).unwrap_err();
+        assert!(matches!(e, HornedError::ParserError(..)));
+    }
+}
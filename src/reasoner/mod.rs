@@ -0,0 +1,136 @@
+//! Reasoners for OWL ontologies.
+//!
+//! Reasoning is performed over a `ComponentMappedOntology`, and
+//! returns results which do not borrow from it, so that the ontology
+//! may be changed afterwards.
+//!
+//! [`el`] classifies ontologies in the OWL 2 EL profile, while
+//! [`rl`] materializes the entailments of the OWL 2 RL rules.
+//! [`swrl`] runs the SWRL rules of an ontology over its assertions.
+//!
+//! The [`Reasoner`] trait gives a common interface to reasoners, so
+//! that one may be exchanged for another. It is implemented by the
+//! [`el`] classification, and by [`external`] reasoners which run as
+//! a separate process.
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::HornedError;
+use crate::model::*;
+use crate::ontology::hierarchy::Hierarchy;
+
+pub mod el;
+pub mod external;
+pub mod rl;
+pub mod swrl;
+
+/// A reasoner, which answers queries about an ontology.
+///
+/// Queries which a reasoner cannot answer, such as those about class
+/// expressions which it does not support, return an error.
+pub trait Reasoner<A: ForIRI> {
+    /// Return false if the ontology is inconsistent.
+    fn is_consistent(&self) -> Result<bool, HornedError>;
+
+    /// Return the unsatisfiable classes, not including `owl:Nothing`.
+    fn unsatisfiable_classes(&self) -> Result<BTreeSet<Class<A>>, HornedError>;
+
+    /// Return the classes which are equivalent to `c`, not including
+    /// `c`.
+    fn equivalent_classes(&self, c: &Class<A>) -> Result<BTreeSet<Class<A>>, HornedError>;
+
+    /// Return the named superclasses of `c`, not including its
+    /// equivalents, or only the direct superclasses if `direct`.
+    fn super_classes(&self, c: &Class<A>, direct: bool) -> Result<BTreeSet<Class<A>>, HornedError>;
+
+    /// Return the named instances of `ce`, or only those which are
+    /// not instances of a more specific class if `direct`.
+    fn instances(
+        &self,
+        ce: &ClassExpression<A>,
+        direct: bool,
+    ) -> Result<BTreeSet<Individual<A>>, HornedError>;
+
+    /// Return true if `c` is entailed by the ontology.
+    ///
+    /// By default, this decides `SubClassOf` and `EquivalentClasses`
+    /// axioms between named classes, and `ClassAssertion` axioms,
+    /// with the other methods of the reasoner.
+    fn is_entailed(&self, c: &Component<A>) -> Result<bool, HornedError> {
+        entailed(self, c)
+    }
+}
+
+/// Decide whether `c` is entailed, with the queries of `r`.
+fn entailed<A: ForIRI, R: Reasoner<A> + ?Sized>(
+    r: &R,
+    c: &Component<A>,
+) -> Result<bool, HornedError> {
+    if !r.is_consistent()? {
+        return Ok(true);
+    }
+    let subclass = |sub: &Class<A>, sup: &Class<A>| -> Result<bool, HornedError> {
+        Ok(sub == sup
+            || sub.is_nothing()
+            || sup.is_thing()
+            || r.unsatisfiable_classes()?.contains(sub)
+            || r.equivalent_classes(sub)?.contains(sup)
+            || r.super_classes(sub, false)?.contains(sup))
+    };
+    let class = |ce: &ClassExpression<A>| match ce {
+        ClassExpression::Class(c) => Some(c.clone()),
+        _ => None,
+    };
+
+    match c {
+        Component::SubClassOf(SubClassOf { sub, sup }) => {
+            if let (Some(sub), Some(sup)) = (class(sub), class(sup)) {
+                return subclass(&sub, &sup);
+            }
+        }
+        Component::EquivalentClasses(EquivalentClasses(v)) => {
+            if let Some(classes) = v.iter().map(class).collect::<Option<Vec<_>>>() {
+                for pair in classes.windows(2) {
+                    if !subclass(&pair[0], &pair[1])? || !subclass(&pair[1], &pair[0])? {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
+            }
+        }
+        Component::ClassAssertion(ClassAssertion { ce, i }) => {
+            return Ok(r.instances(ce, false)?.contains(i));
+        }
+        _ => {}
+    }
+    Err(HornedError::invalid(format!(
+        "Cannot decide the entailment of {:?}",
+        c
+    )))
+}
+
+/// Return the instances of `c` in `hierarchy`, given the `types` of
+/// each individual.
+fn instances<A: ForIRI>(
+    hierarchy: &Hierarchy<Class<A>>,
+    types: &BTreeMap<Individual<A>, BTreeSet<Class<A>>>,
+    c: &Class<A>,
+    direct: bool,
+) -> BTreeSet<Individual<A>> {
+    let mut same = hierarchy.equivalents(c);
+    same.insert(c.clone());
+    let below = hierarchy.descendants(c);
+
+    types
+        .iter()
+        .filter(|(_, types)| {
+            let specific = types.iter().any(|t| below.contains(t));
+            let member = c.is_thing() || types.iter().any(|t| same.contains(t));
+            if direct {
+                member && !specific
+            } else {
+                member || specific
+            }
+        })
+        .map(|(i, _)| i.clone())
+        .collect()
+}
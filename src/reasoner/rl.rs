@@ -0,0 +1,1384 @@
+//! A forward-chaining materializer for
+//! [OWL 2 RL](https://www.w3.org/TR/owl2-profiles/#OWL_2_RL).
+//!
+//! The rules of the [OWL 2 RL/RDF rule
+//! tables](https://www.w3.org/TR/owl2-profiles/#Reasoning_in_OWL_2_RL_and_RDF_Graphs_using_Rules)
+//! are applied to the axioms of an ontology, rather than to their
+//! RDF mapping, until no new facts follow. Each class expression of
+//! the ontology takes the place of the blank node which would
+//! represent it, so the `cls-*` rules apply wherever a class
+//! expression is used.
+//!
+//! The `prp-*`, `cls-*`, `cax-*`, `scm-*` and `eq-*` rules are
+//! applied. Individuals which are the same are merged, which has the
+//! effect of the `eq-rep-*` rules. The datatype rules (`dt-*`) are
+//! not applied, although data property values are matched against
+//! the data ranges of restrictions.
+//!
+//! The entailed class assertions with named classes, object property
+//! assertions with named properties, same individuals, and subclass
+//! relationships between named classes are returned as new
+//! components, along with each inconsistency which a rule detects.
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use crate::datatypes::same_literal;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::visitor::immutable::{Visit, Walk};
+use crate::vocab::OWL;
+
+/// A rule which detects an inconsistency.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum InconsistencyRule {
+    /// Two individuals are both the same and different.
+    EqDiff1,
+    /// Two individuals in a `DifferentIndividuals` of more than two
+    /// are the same.
+    EqDiff2,
+    /// An irreflexive property relates an individual to itself.
+    PrpIrp,
+    /// An asymmetric property relates two individuals both ways.
+    PrpAsyp,
+    /// Two disjoint properties relate the same individuals.
+    PrpPdw,
+    /// Two properties in a `DisjointObjectProperties` of more than two
+    /// relate the same individuals.
+    PrpAdp,
+    /// A negative object property assertion is contradicted.
+    PrpNpa1,
+    /// A negative data property assertion is contradicted.
+    PrpNpa2,
+    /// An individual is an instance of `owl:Nothing`.
+    ClsNothing2,
+    /// An individual is an instance of a class and its complement.
+    ClsCom,
+    /// An individual has a value for a property with a max
+    /// cardinality of zero.
+    ClsMaxc1,
+    /// An individual has a value in a class for a property with a max
+    /// qualified cardinality of zero.
+    ClsMaxqc1,
+    /// An individual is an instance of two disjoint classes.
+    CaxDw,
+    /// An individual is an instance of two classes in a
+    /// `DisjointClasses` of more than two.
+    CaxAdc,
+}
+
+impl Display for InconsistencyRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            InconsistencyRule::EqDiff1 => "eq-diff1",
+            InconsistencyRule::EqDiff2 => "eq-diff2",
+            InconsistencyRule::PrpIrp => "prp-irp",
+            InconsistencyRule::PrpAsyp => "prp-asyp",
+            InconsistencyRule::PrpPdw => "prp-pdw",
+            InconsistencyRule::PrpAdp => "prp-adp",
+            InconsistencyRule::PrpNpa1 => "prp-npa1",
+            InconsistencyRule::PrpNpa2 => "prp-npa2",
+            InconsistencyRule::ClsNothing2 => "cls-nothing2",
+            InconsistencyRule::ClsCom => "cls-com",
+            InconsistencyRule::ClsMaxc1 => "cls-maxc1",
+            InconsistencyRule::ClsMaxqc1 => "cls-maxqc1",
+            InconsistencyRule::CaxDw => "cax-dw",
+            InconsistencyRule::CaxAdc => "cax-adc",
+        })
+    }
+}
+
+/// An inconsistency detected by a rule, with the individuals involved.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Inconsistency<A> {
+    pub rule: InconsistencyRule,
+    pub individuals: Vec<Individual<A>>,
+}
+
+/// The result of materializing an ontology.
+#[derive(Debug)]
+pub struct Materialization<A> {
+    /// The entailed components which are not in the ontology.
+    pub components: Vec<AnnotatedComponent<A>>,
+    pub inconsistencies: Vec<Inconsistency<A>>,
+}
+
+impl<A> Materialization<A> {
+    /// Return true if no rule detected an inconsistency.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+/// A named object property, and whether it is inverted.
+type Role<A> = (ObjectProperty<A>, bool);
+
+fn role<A: ForIRI>(ope: &ObjectPropertyExpression<A>) -> Role<A> {
+    match ope {
+        ObjectPropertyExpression::ObjectProperty(op) => (op.clone(), false),
+        ObjectPropertyExpression::InverseObjectProperty(op) => (op.clone(), true),
+    }
+}
+
+fn flip<A: ForIRI>(r: &Role<A>) -> Role<A> {
+    (r.0.clone(), !r.1)
+}
+
+/// Return `(x, y)`, swapped if `inverse`.
+fn orient<A: ForIRI>(
+    inverse: bool,
+    x: &Individual<A>,
+    y: &Individual<A>,
+) -> (Individual<A>, Individual<A>) {
+    if inverse {
+        (y.clone(), x.clone())
+    } else {
+        (x.clone(), y.clone())
+    }
+}
+
+/// Collects every class expression of an ontology.
+struct Expressions<A>(BTreeSet<ClassExpression<A>>);
+
+impl<A: ForIRI> Visit<A> for Expressions<A> {
+    fn visit_class_expression(&mut self, ce: &ClassExpression<A>) {
+        self.0.insert(ce.clone());
+    }
+}
+
+type Index<K, T> = HashMap<K, Vec<T>>;
+type Key<A> = (ClassExpression<A>, Vec<Role<A>>, Vec<DataProperty<A>>);
+
+/// The terminological axioms, and the class expressions, indexed for
+/// the rules.
+///
+/// Where a property may be inverted, rules are indexed by the named
+/// property, with a flag which is true if the rule is about its
+/// inverse.
+struct Schema<A> {
+    /// Subclass relationships, told or from the `scm-*` rules
+    sup: HashMap<ClassExpression<A>, BTreeSet<ClassExpression<A>>>,
+    disjoint: Index<ClassExpression<A>, (ClassExpression<A>, InconsistencyRule)>,
+    complement: HashMap<ClassExpression<A>, ClassExpression<A>>,
+    /// The intersections containing each class expression
+    intersections: Index<ClassExpression<A>, ClassExpression<A>>,
+    /// `(inverse, filler, restriction)` for `ObjectSomeValuesFrom`
+    some: Index<ObjectProperty<A>, (bool, ClassExpression<A>, ClassExpression<A>)>,
+    /// `(role, restriction)` for `ObjectSomeValuesFrom`, by filler
+    some_filler: Index<ClassExpression<A>, (Role<A>, ClassExpression<A>)>,
+    /// `(inverse, restriction, filler)` for `ObjectAllValuesFrom`
+    all: Index<ObjectProperty<A>, (bool, ClassExpression<A>, ClassExpression<A>)>,
+    /// `(inverse, value, restriction)` for `ObjectHasValue`
+    has_value: Index<ObjectProperty<A>, (bool, Individual<A>, ClassExpression<A>)>,
+    data_some: Index<DataProperty<A>, (DataRange<A>, ClassExpression<A>)>,
+    data_has_value: Index<DataProperty<A>, (Literal<A>, ClassExpression<A>)>,
+
+    /// `(inverse, super property)`
+    sub_props: Index<ObjectProperty<A>, (bool, Role<A>)>,
+    domains: Index<ObjectProperty<A>, (bool, ClassExpression<A>)>,
+    ranges: Index<ObjectProperty<A>, (bool, ClassExpression<A>)>,
+    functional: HashSet<Role<A>>,
+    symmetric: HashSet<ObjectProperty<A>>,
+    transitive: HashSet<ObjectProperty<A>>,
+    irreflexive: HashSet<ObjectProperty<A>>,
+    asymmetric: HashSet<ObjectProperty<A>>,
+    chains: Vec<(Vec<Role<A>>, Role<A>)>,
+    /// `(inverse, disjoint property, rule)`
+    disjoint_props: Index<ObjectProperty<A>, (bool, Role<A>, InconsistencyRule)>,
+    /// `(class, object properties, data properties)` for `HasKey`
+    keys: Vec<Key<A>>,
+
+    data_sub: Index<DataProperty<A>, DataProperty<A>>,
+    data_domains: Index<DataProperty<A>, ClassExpression<A>>,
+
+    different: Vec<(Individual<A>, Individual<A>, InconsistencyRule)>,
+    negative: Vec<(Role<A>, Individual<A>, Individual<A>)>,
+    negative_data: Vec<(DataProperty<A>, Individual<A>, Literal<A>)>,
+}
+
+impl<A: ForIRI> Default for Schema<A> {
+    fn default() -> Self {
+        Schema {
+            sup: HashMap::new(),
+            disjoint: HashMap::new(),
+            complement: HashMap::new(),
+            intersections: HashMap::new(),
+            some: HashMap::new(),
+            some_filler: HashMap::new(),
+            all: HashMap::new(),
+            has_value: HashMap::new(),
+            data_some: HashMap::new(),
+            data_has_value: HashMap::new(),
+            sub_props: HashMap::new(),
+            domains: HashMap::new(),
+            ranges: HashMap::new(),
+            functional: HashSet::new(),
+            symmetric: HashSet::new(),
+            transitive: HashSet::new(),
+            irreflexive: HashSet::new(),
+            asymmetric: HashSet::new(),
+            chains: vec![],
+            disjoint_props: HashMap::new(),
+            keys: vec![],
+            data_sub: HashMap::new(),
+            data_domains: HashMap::new(),
+            different: vec![],
+            negative: vec![],
+            negative_data: vec![],
+        }
+    }
+}
+
+impl<A: ForIRI> Schema<A> {
+    /// Add a subclass relationship, returning true if it is new.
+    fn sub_class(&mut self, sub: &ClassExpression<A>, sup: &ClassExpression<A>) -> bool {
+        self.sup.entry(sub.clone()).or_default().insert(sup.clone())
+    }
+
+    fn sub_prop(&mut self, sub: &Role<A>, sup: Role<A>) {
+        self.sub_props
+            .entry(sub.0.clone())
+            .or_default()
+            .push((sub.1, sup));
+    }
+
+    /// Return every superclass of `ce`, including itself.
+    fn supers(&self, ce: &ClassExpression<A>) -> HashSet<ClassExpression<A>> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![ce];
+        while let Some(ce) = stack.pop() {
+            if seen.insert(ce.clone()) {
+                stack.extend(self.sup.get(ce).into_iter().flatten());
+            }
+        }
+        seen
+    }
+
+    /// Return every super property of `r`, including itself.
+    fn super_roles(&self, r: &Role<A>) -> HashSet<Role<A>> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![r.clone()];
+        while let Some(r) = stack.pop() {
+            if seen.insert(r.clone()) {
+                for (inverse, sup) in self.sub_props.get(&r.0).into_iter().flatten() {
+                    stack.push(if *inverse == r.1 {
+                        sup.clone()
+                    } else {
+                        flip(sup)
+                    });
+                }
+            }
+        }
+        seen
+    }
+
+    /// Index `ce` for the `cls-*` rules and add the subclass
+    /// relationships of `scm-int` and `scm-uni`.
+    fn expression(&mut self, ce: &ClassExpression<A>) {
+        match ce {
+            ClassExpression::ObjectIntersectionOf(v) => {
+                for c in v.iter().collect::<BTreeSet<_>>() {
+                    self.sub_class(ce, c);
+                    self.intersections
+                        .entry(c.clone())
+                        .or_default()
+                        .push(ce.clone());
+                }
+            }
+            ClassExpression::ObjectUnionOf(v) => {
+                for c in v {
+                    self.sub_class(c, ce);
+                }
+            }
+            ClassExpression::ObjectComplementOf(c) => {
+                self.complement.insert((**c).clone(), ce.clone());
+                self.complement.insert(ce.clone(), (**c).clone());
+            }
+            ClassExpression::ObjectSomeValuesFrom { ope, bce } => {
+                let r = role(ope);
+                self.some
+                    .entry(r.0.clone())
+                    .or_default()
+                    .push((r.1, (**bce).clone(), ce.clone()));
+                self.some_filler
+                    .entry((**bce).clone())
+                    .or_default()
+                    .push((r, ce.clone()));
+            }
+            ClassExpression::ObjectAllValuesFrom { ope, bce } => {
+                let r = role(ope);
+                self.all
+                    .entry(r.0)
+                    .or_default()
+                    .push((r.1, ce.clone(), (**bce).clone()));
+            }
+            ClassExpression::ObjectHasValue { ope, i } => {
+                let r = role(ope);
+                self.has_value
+                    .entry(r.0)
+                    .or_default()
+                    .push((r.1, i.clone(), ce.clone()));
+            }
+            ClassExpression::DataSomeValuesFrom { dp, dr } => {
+                self.data_some
+                    .entry(dp.clone())
+                    .or_default()
+                    .push((dr.clone(), ce.clone()));
+            }
+            ClassExpression::DataHasValue { dp, l } => {
+                self.data_has_value
+                    .entry(dp.clone())
+                    .or_default()
+                    .push((l.clone(), ce.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply `scm-svf1`, `scm-svf2`, `scm-avf1`, `scm-avf2` and
+    /// `scm-hv` to the class expressions until no new subclass
+    /// relationships follow.
+    fn restrictions(
+        &mut self,
+        expressions: &BTreeSet<ClassExpression<A>>,
+        thing: &ClassExpression<A>,
+    ) {
+        let restrictions: Vec<_> = expressions
+            .iter()
+            .filter_map(|ce| match ce {
+                ClassExpression::ObjectSomeValuesFrom { ope, bce }
+                | ClassExpression::ObjectAllValuesFrom { ope, bce } => {
+                    Some((ce, role(ope), Some(&**bce), None))
+                }
+                ClassExpression::ObjectHasValue { ope, i } => Some((ce, role(ope), None, Some(i))),
+                _ => None,
+            })
+            .collect();
+        let super_roles: HashMap<_, _> = restrictions
+            .iter()
+            .map(|(_, r, _, _)| (r.clone(), self.super_roles(r)))
+            .collect();
+
+        loop {
+            let mut found = vec![];
+            for (a, ra, fa, ia) in &restrictions {
+                let supers = fa.map(|fa| self.supers(fa));
+                for (b, rb, fb, ib) in &restrictions {
+                    if a == b {
+                        continue;
+                    }
+                    let sub = match (a, b) {
+                        (
+                            ClassExpression::ObjectSomeValuesFrom { .. },
+                            ClassExpression::ObjectSomeValuesFrom { .. },
+                        ) => {
+                            let fb = fb.unwrap();
+                            super_roles[ra].contains(rb)
+                                && (fb == thing || supers.as_ref().unwrap().contains(fb))
+                        }
+                        (
+                            ClassExpression::ObjectAllValuesFrom { .. },
+                            ClassExpression::ObjectAllValuesFrom { .. },
+                        ) => {
+                            super_roles[rb].contains(ra)
+                                && supers.as_ref().unwrap().contains(fb.unwrap())
+                        }
+                        (
+                            ClassExpression::ObjectHasValue { .. },
+                            ClassExpression::ObjectHasValue { .. },
+                        ) => ia == ib && super_roles[ra].contains(rb),
+                        _ => false,
+                    };
+                    if sub {
+                        found.push(((*a).clone(), (*b).clone()));
+                    }
+                }
+            }
+
+            let mut added = false;
+            for (a, b) in found {
+                added |= self.sub_class(&a, &b);
+            }
+            if !added {
+                break;
+            }
+        }
+    }
+}
+
+enum Fact<A> {
+    Type(Individual<A>, ClassExpression<A>),
+    Edge(ObjectProperty<A>, Individual<A>, Individual<A>),
+    Data(DataProperty<A>, Individual<A>, Literal<A>),
+    Same(Individual<A>, Individual<A>),
+}
+
+type Edges<A> = HashMap<ObjectProperty<A>, HashMap<Individual<A>, HashSet<Individual<A>>>>;
+type Values<A> = HashMap<DataProperty<A>, HashMap<Individual<A>, HashSet<Literal<A>>>>;
+
+/// Applies the rules to the assertional facts until no more follow.
+///
+/// Facts are kept only for the representative of each set of same
+/// individuals.
+struct Materializer<A> {
+    schema: Schema<A>,
+    thing: ClassExpression<A>,
+    nothing: ClassExpression<A>,
+    parent: HashMap<Individual<A>, Individual<A>>,
+    members: HashMap<Individual<A>, BTreeSet<Individual<A>>>,
+    types: HashMap<Individual<A>, HashSet<ClassExpression<A>>>,
+    fwd: Edges<A>,
+    bwd: Edges<A>,
+    data: Values<A>,
+    todo: VecDeque<Fact<A>>,
+    inconsistencies: BTreeSet<Inconsistency<A>>,
+}
+
+impl<A: ForIRI> Materializer<A> {
+    fn find(&self, i: &Individual<A>) -> Individual<A> {
+        let mut i = i;
+        while let Some(p) = self.parent.get(i) {
+            i = p;
+        }
+        i.clone()
+    }
+
+    fn inconsistent(&mut self, rule: InconsistencyRule, individuals: Vec<Individual<A>>) {
+        self.inconsistencies
+            .insert(Inconsistency { rule, individuals });
+    }
+
+    fn add_role(&mut self, r: &Role<A>, x: &Individual<A>, y: &Individual<A>) {
+        let (x, y) = orient(r.1, x, y);
+        self.todo.push_back(Fact::Edge(r.0.clone(), x, y));
+    }
+
+    fn successors(&self, r: &Role<A>, x: &Individual<A>) -> Vec<Individual<A>> {
+        let edges = if r.1 { &self.bwd } else { &self.fwd };
+        edges
+            .get(&r.0)
+            .and_then(|m| m.get(x))
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    fn has_role(&self, r: &Role<A>, x: &Individual<A>, y: &Individual<A>) -> bool {
+        let (x, y) = orient(r.1, x, y);
+        self.fwd
+            .get(&r.0)
+            .and_then(|m| m.get(&x))
+            .is_some_and(|s| s.contains(&y))
+    }
+
+    fn has_type(&self, x: &Individual<A>, ce: &ClassExpression<A>) -> bool {
+        *ce == self.thing || self.types.get(x).is_some_and(|t| t.contains(ce))
+    }
+
+    /// Note that `x` is an individual, so that it is an instance of
+    /// `owl:Thing`.
+    fn individual(&mut self, x: &Individual<A>) {
+        if !self.types.contains_key(x) {
+            self.todo
+                .push_back(Fact::Type(x.clone(), self.thing.clone()));
+        }
+    }
+
+    fn ty(&mut self, x: Individual<A>, ce: ClassExpression<A>) {
+        if !self.types.entry(x.clone()).or_default().insert(ce.clone()) {
+            return;
+        }
+        let schema = &self.schema;
+        let mut found = vec![];
+
+        if ce == self.nothing {
+            self.inconsistencies.insert(Inconsistency {
+                rule: InconsistencyRule::ClsNothing2,
+                individuals: vec![x.clone()],
+            });
+        }
+        // cax-sco, cax-eqc1, cax-eqc2, cls-int2, cls-uni
+        for d in schema.sup.get(&ce).into_iter().flatten() {
+            found.push(Fact::Type(x.clone(), d.clone()));
+        }
+        // cls-int1
+        for i in schema.intersections.get(&ce).into_iter().flatten() {
+            if let ClassExpression::ObjectIntersectionOf(v) = i {
+                if v.iter().all(|c| self.has_type(&x, c)) {
+                    found.push(Fact::Type(x.clone(), i.clone()));
+                }
+            }
+        }
+        // cls-svf1, for a new instance of the filler
+        for (r, restriction) in schema.some_filler.get(&ce).into_iter().flatten() {
+            for w in self.successors(&flip(r), &x) {
+                found.push(Fact::Type(w, restriction.clone()));
+            }
+        }
+        match &ce {
+            // cls-hv1
+            ClassExpression::ObjectHasValue { ope, i } => {
+                let (a, b) = orient(role(ope).1, &x, i);
+                found.push(Fact::Edge(role(ope).0, a, b));
+            }
+            // cls-avf
+            ClassExpression::ObjectAllValuesFrom { ope, bce } => {
+                for y in self.successors(&role(ope), &x) {
+                    found.push(Fact::Type(y, (**bce).clone()));
+                }
+            }
+            ClassExpression::DataHasValue { dp, l } => {
+                found.push(Fact::Data(dp.clone(), x.clone(), l.clone()));
+            }
+            _ => {}
+        }
+
+        // cax-dw, cax-adc
+        let mut inconsistencies = vec![];
+        for (d, rule) in schema.disjoint.get(&ce).into_iter().flatten() {
+            if self.has_type(&x, d) {
+                inconsistencies.push(*rule);
+            }
+        }
+        // cls-com
+        if let Some(c) = schema.complement.get(&ce) {
+            if self.has_type(&x, c) {
+                inconsistencies.push(InconsistencyRule::ClsCom);
+            }
+        }
+
+        self.todo.extend(found);
+        for rule in inconsistencies {
+            self.inconsistent(rule, vec![x.clone()]);
+        }
+    }
+
+    fn edge(&mut self, p: ObjectProperty<A>, x: Individual<A>, y: Individual<A>) {
+        let inserted = self
+            .fwd
+            .entry(p.clone())
+            .or_default()
+            .entry(x.clone())
+            .or_default()
+            .insert(y.clone());
+        if !inserted {
+            return;
+        }
+        self.bwd
+            .entry(p.clone())
+            .or_default()
+            .entry(y.clone())
+            .or_default()
+            .insert(x.clone());
+        self.individual(&x);
+        self.individual(&y);
+
+        let schema = &self.schema;
+        let mut found = vec![];
+        let mut inconsistencies = vec![];
+
+        // prp-dom, prp-rng
+        for (inverse, c) in schema.domains.get(&p).into_iter().flatten() {
+            found.push(Fact::Type(orient(*inverse, &x, &y).0, c.clone()));
+        }
+        for (inverse, c) in schema.ranges.get(&p).into_iter().flatten() {
+            found.push(Fact::Type(orient(*inverse, &x, &y).1, c.clone()));
+        }
+        // prp-spo1, prp-eqp1, prp-eqp2, prp-inv1, prp-inv2
+        for (inverse, sup) in schema.sub_props.get(&p).into_iter().flatten() {
+            let (a, b) = orient(*inverse, &x, &y);
+            let (a, b) = orient(sup.1, &a, &b);
+            found.push(Fact::Edge(sup.0.clone(), a, b));
+        }
+        // prp-symp
+        if schema.symmetric.contains(&p) {
+            found.push(Fact::Edge(p.clone(), y.clone(), x.clone()));
+        }
+        // prp-trp
+        if schema.transitive.contains(&p) {
+            for z in self.successors(&(p.clone(), false), &y) {
+                found.push(Fact::Edge(p.clone(), x.clone(), z));
+            }
+            for w in self.successors(&(p.clone(), true), &x) {
+                found.push(Fact::Edge(p.clone(), w, y.clone()));
+            }
+        }
+        // prp-fp, prp-ifp
+        if schema.functional.contains(&(p.clone(), false)) {
+            for z in self.successors(&(p.clone(), false), &x) {
+                found.push(Fact::Same(y.clone(), z));
+            }
+        }
+        if schema.functional.contains(&(p.clone(), true)) {
+            for w in self.successors(&(p.clone(), true), &y) {
+                found.push(Fact::Same(x.clone(), w));
+            }
+        }
+        // prp-irp
+        if schema.irreflexive.contains(&p) && x == y {
+            inconsistencies.push((InconsistencyRule::PrpIrp, vec![x.clone()]));
+        }
+        // prp-asyp
+        if schema.asymmetric.contains(&p) && self.has_role(&(p.clone(), false), &y, &x) {
+            inconsistencies.push((InconsistencyRule::PrpAsyp, vec![x.clone(), y.clone()]));
+        }
+        // prp-pdw, prp-adp
+        for (inverse, other, rule) in schema.disjoint_props.get(&p).into_iter().flatten() {
+            let (a, b) = orient(*inverse, &x, &y);
+            if self.has_role(other, &a, &b) {
+                inconsistencies.push((*rule, vec![a, b]));
+            }
+        }
+        // cls-svf1, cls-svf2
+        for (inverse, filler, restriction) in schema.some.get(&p).into_iter().flatten() {
+            let (a, b) = orient(*inverse, &x, &y);
+            if self.has_type(&b, filler) {
+                found.push(Fact::Type(a, restriction.clone()));
+            }
+        }
+        // cls-hv2
+        for (inverse, i, restriction) in schema.has_value.get(&p).into_iter().flatten() {
+            let (a, b) = orient(*inverse, &x, &y);
+            if self.find(i) == b {
+                found.push(Fact::Type(a, restriction.clone()));
+            }
+        }
+        // cls-avf, for a new relationship
+        for (inverse, restriction, filler) in schema.all.get(&p).into_iter().flatten() {
+            let (a, b) = orient(*inverse, &x, &y);
+            if self.has_type(&a, restriction) {
+                found.push(Fact::Type(b, filler.clone()));
+            }
+        }
+
+        self.todo.extend(found);
+        for (rule, individuals) in inconsistencies {
+            self.inconsistent(rule, individuals);
+        }
+    }
+
+    fn data(&mut self, dp: DataProperty<A>, x: Individual<A>, l: Literal<A>) {
+        let inserted = self
+            .data
+            .entry(dp.clone())
+            .or_default()
+            .entry(x.clone())
+            .or_default()
+            .insert(l.clone());
+        if !inserted {
+            return;
+        }
+        self.individual(&x);
+
+        let schema = &self.schema;
+        let mut found = vec![];
+        // prp-dom
+        for c in schema.data_domains.get(&dp).into_iter().flatten() {
+            found.push(Fact::Type(x.clone(), c.clone()));
+        }
+        // prp-spo1, prp-eqp1, prp-eqp2
+        for sup in schema.data_sub.get(&dp).into_iter().flatten() {
+            found.push(Fact::Data(sup.clone(), x.clone(), l.clone()));
+        }
+        // cls-hv2
+        for (value, restriction) in schema.data_has_value.get(&dp).into_iter().flatten() {
+            if same_literal(value, &l) {
+                found.push(Fact::Type(x.clone(), restriction.clone()));
+            }
+        }
+        // cls-svf1
+        for (dr, restriction) in schema.data_some.get(&dp).into_iter().flatten() {
+            if dr.contains(&l) {
+                found.push(Fact::Type(x.clone(), restriction.clone()));
+            }
+        }
+        self.todo.extend(found);
+    }
+
+    /// Merge the facts of `a` and `b`, as the `eq-rep-*` rules would
+    /// copy them.
+    fn same(&mut self, a: Individual<A>, b: Individual<A>) {
+        if a == b {
+            return;
+        }
+        let (keep, merge) = if a < b { (a, b) } else { (b, a) };
+        self.parent.insert(merge.clone(), keep.clone());
+        let merged = self
+            .members
+            .remove(&merge)
+            .unwrap_or_else(|| vec![merge.clone()].into_iter().collect());
+        self.members
+            .entry(keep.clone())
+            .or_insert_with(|| vec![keep.clone()].into_iter().collect())
+            .extend(merged);
+
+        for ce in self.types.remove(&merge).into_iter().flatten() {
+            self.todo.push_back(Fact::Type(keep.clone(), ce));
+        }
+        for (p, edges) in self.fwd.iter_mut() {
+            for y in edges.remove(&merge).into_iter().flatten() {
+                if let Some(s) = self.bwd.get_mut(p).and_then(|m| m.get_mut(&y)) {
+                    s.remove(&merge);
+                }
+                let y = if y == merge { keep.clone() } else { y };
+                self.todo.push_back(Fact::Edge(p.clone(), keep.clone(), y));
+            }
+        }
+        for (p, edges) in self.bwd.iter_mut() {
+            for x in edges.remove(&merge).into_iter().flatten() {
+                if let Some(s) = self.fwd.get_mut(p).and_then(|m| m.get_mut(&x)) {
+                    s.remove(&merge);
+                }
+                self.todo.push_back(Fact::Edge(p.clone(), x, keep.clone()));
+            }
+        }
+        for (dp, values) in self.data.iter_mut() {
+            for l in values.remove(&merge).into_iter().flatten() {
+                self.todo.push_back(Fact::Data(dp.clone(), keep.clone(), l));
+            }
+        }
+    }
+
+    /// Apply the rules which need more than one new fact at once:
+    /// property chains, max cardinalities and keys.
+    fn global(&mut self) {
+        let mut found = vec![];
+        let mut inconsistencies = vec![];
+
+        // prp-spo2
+        for (chain, sup) in &self.schema.chains {
+            let edges = if chain[0].1 { &self.bwd } else { &self.fwd };
+            let starts: Vec<_> = edges
+                .get(&chain[0].0)
+                .into_iter()
+                .flat_map(|m| m.keys())
+                .cloned()
+                .collect();
+            for x in starts {
+                let mut frontier: HashSet<_> = vec![x.clone()].into_iter().collect();
+                for r in chain {
+                    frontier = frontier
+                        .iter()
+                        .flat_map(|y| self.successors(r, y))
+                        .collect();
+                }
+                for z in frontier {
+                    if !self.has_role(sup, &x, &z) {
+                        let (a, b) = orient(sup.1, &x, &z);
+                        found.push(Fact::Edge(sup.0.clone(), a, b));
+                    }
+                }
+            }
+        }
+
+        // cls-maxc1, cls-maxc2, cls-maxqc1 to cls-maxqc4
+        for (x, types) in &self.types {
+            for ce in types {
+                if let ClassExpression::ObjectMaxCardinality { n, ope, bce } = ce {
+                    let ys: Vec<_> = self
+                        .successors(&role(ope), x)
+                        .into_iter()
+                        .filter(|y| self.has_type(y, bce))
+                        .collect();
+                    match n {
+                        0 if !ys.is_empty() => {
+                            let rule = if **bce == self.thing {
+                                InconsistencyRule::ClsMaxc1
+                            } else {
+                                InconsistencyRule::ClsMaxqc1
+                            };
+                            inconsistencies.push((rule, vec![x.clone(), ys[0].clone()]));
+                        }
+                        1 => {
+                            for y in ys.iter().skip(1) {
+                                found.push(Fact::Same(ys[0].clone(), y.clone()));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // prp-key
+        for (ce, roles, dps) in &self.schema.keys {
+            let instances: Vec<_> = self.types.keys().filter(|x| self.has_type(x, ce)).collect();
+            let values = |x: &Individual<A>| {
+                let objects: Vec<HashSet<_>> = roles
+                    .iter()
+                    .map(|r| self.successors(r, x).into_iter().collect())
+                    .collect();
+                let data: Vec<HashSet<_>> = dps
+                    .iter()
+                    .map(|dp| {
+                        self.data
+                            .get(dp)
+                            .and_then(|m| m.get(x))
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                (objects, data)
+            };
+            let values: Vec<_> = instances.iter().map(|x| values(x)).collect();
+            for (i, (ox, dx)) in values.iter().enumerate() {
+                for (j, (oy, dy)) in values.iter().enumerate().skip(i + 1) {
+                    let shared = ox.iter().zip(oy).all(|(a, b)| !a.is_disjoint(b))
+                        && dx.iter().zip(dy).all(|(a, b)| !a.is_disjoint(b));
+                    if shared {
+                        found.push(Fact::Same(instances[i].clone(), instances[j].clone()));
+                    }
+                }
+            }
+        }
+
+        self.todo.extend(found);
+        for (rule, individuals) in inconsistencies {
+            self.inconsistent(rule, individuals);
+        }
+    }
+
+    /// Apply the rules which contradict assertions about individuals:
+    /// `eq-diff1`, `eq-diff2`, `prp-npa1` and `prp-npa2`.
+    fn contradictions(&mut self) {
+        let mut inconsistencies = vec![];
+        for (a, b, rule) in &self.schema.different {
+            if self.find(a) == self.find(b) {
+                inconsistencies.push((*rule, vec![a.clone(), b.clone()]));
+            }
+        }
+        for (r, a, b) in &self.schema.negative {
+            if self.has_role(r, &self.find(a), &self.find(b)) {
+                inconsistencies.push((InconsistencyRule::PrpNpa1, vec![a.clone(), b.clone()]));
+            }
+        }
+        for (dp, a, l) in &self.schema.negative_data {
+            let asserted = self
+                .data
+                .get(dp)
+                .and_then(|m| m.get(&self.find(a)))
+                .is_some_and(|s| s.iter().any(|asserted| same_literal(asserted, l)));
+            if asserted {
+                inconsistencies.push((InconsistencyRule::PrpNpa2, vec![a.clone()]));
+            }
+        }
+        for (rule, individuals) in inconsistencies {
+            self.inconsistent(rule, individuals);
+        }
+    }
+
+    fn run(&mut self) {
+        loop {
+            while let Some(fact) = self.todo.pop_front() {
+                match fact {
+                    Fact::Type(x, ce) => self.ty(self.find(&x), ce),
+                    Fact::Edge(p, x, y) => self.edge(p, self.find(&x), self.find(&y)),
+                    Fact::Data(dp, x, l) => self.data(dp, self.find(&x), l),
+                    Fact::Same(a, b) => self.same(self.find(&a), self.find(&b)),
+                }
+            }
+            self.global();
+            if self.todo.is_empty() {
+                break;
+            }
+        }
+        self.contradictions();
+    }
+
+    fn members(&self, x: &Individual<A>) -> BTreeSet<Individual<A>> {
+        self.members
+            .get(x)
+            .cloned()
+            .unwrap_or_else(|| vec![x.clone()].into_iter().collect())
+    }
+}
+
+/// Return every pair of `v`.
+fn pairs<T>(v: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    v.iter()
+        .enumerate()
+        .flat_map(move |(i, a)| v[i + 1..].iter().map(move |b| (a, b)))
+}
+
+/// Load the component `c` into the materializer `m`.
+fn load<A: ForIRI>(m: &mut Materializer<A>, c: &Component<A>) {
+    let s = &mut m.schema;
+    match c {
+        Component::SubClassOf(SubClassOf { sub, sup }) => {
+            s.sub_class(sub, sup);
+        }
+        Component::EquivalentClasses(EquivalentClasses(v)) => {
+            for (i, ce) in v.iter().enumerate() {
+                s.sub_class(ce, &v[(i + 1) % v.len()]);
+            }
+        }
+        Component::DisjointClasses(DisjointClasses(v)) => {
+            let rule = if v.len() == 2 {
+                InconsistencyRule::CaxDw
+            } else {
+                InconsistencyRule::CaxAdc
+            };
+            for (a, b) in pairs(v) {
+                s.disjoint
+                    .entry(a.clone())
+                    .or_default()
+                    .push((b.clone(), rule));
+                s.disjoint
+                    .entry(b.clone())
+                    .or_default()
+                    .push((a.clone(), rule));
+            }
+        }
+        Component::DisjointUnion(DisjointUnion(c, v)) => {
+            load(
+                m,
+                &EquivalentClasses(vec![
+                    c.clone().into(),
+                    ClassExpression::ObjectUnionOf(v.clone()),
+                ])
+                .into(),
+            );
+            load(m, &DisjointClasses(v.clone()).into());
+        }
+        Component::SubObjectPropertyOf(SubObjectPropertyOf { sub, sup }) => match sub {
+            SubObjectPropertyExpression::ObjectPropertyExpression(ope) => {
+                s.sub_prop(&role(ope), role(sup))
+            }
+            SubObjectPropertyExpression::ObjectPropertyChain(v) if v.len() == 1 => {
+                s.sub_prop(&role(&v[0]), role(sup))
+            }
+            SubObjectPropertyExpression::ObjectPropertyChain(v) if !v.is_empty() => {
+                s.chains.push((v.iter().map(role).collect(), role(sup)))
+            }
+            _ => {}
+        },
+        Component::EquivalentObjectProperties(EquivalentObjectProperties(v)) => {
+            for (i, ope) in v.iter().enumerate() {
+                s.sub_prop(&role(ope), role(&v[(i + 1) % v.len()]));
+            }
+        }
+        Component::InverseObjectProperties(InverseObjectProperties(p, q)) => {
+            s.sub_prop(&(p.clone(), false), (q.clone(), true));
+            s.sub_prop(&(q.clone(), false), (p.clone(), true));
+        }
+        Component::DisjointObjectProperties(DisjointObjectProperties(v)) => {
+            let rule = if v.len() == 2 {
+                InconsistencyRule::PrpPdw
+            } else {
+                InconsistencyRule::PrpAdp
+            };
+            for (a, b) in pairs(v) {
+                let (a, b) = (role(a), role(b));
+                s.disjoint_props
+                    .entry(a.0.clone())
+                    .or_default()
+                    .push((a.1, b.clone(), rule));
+                s.disjoint_props
+                    .entry(b.0)
+                    .or_default()
+                    .push((b.1, a, rule));
+            }
+        }
+        Component::ObjectPropertyDomain(ObjectPropertyDomain { ope, ce }) => {
+            let r = role(ope);
+            s.domains.entry(r.0).or_default().push((r.1, ce.clone()));
+        }
+        Component::ObjectPropertyRange(ObjectPropertyRange { ope, ce }) => {
+            let r = role(ope);
+            s.ranges.entry(r.0).or_default().push((r.1, ce.clone()));
+        }
+        Component::FunctionalObjectProperty(FunctionalObjectProperty(ope)) => {
+            s.functional.insert(role(ope));
+        }
+        Component::InverseFunctionalObjectProperty(InverseFunctionalObjectProperty(ope)) => {
+            s.functional.insert(flip(&role(ope)));
+        }
+        Component::SymmetricObjectProperty(SymmetricObjectProperty(ope)) => {
+            s.symmetric.insert(role(ope).0);
+        }
+        Component::TransitiveObjectProperty(TransitiveObjectProperty(ope)) => {
+            s.transitive.insert(role(ope).0);
+        }
+        Component::IrreflexiveObjectProperty(IrreflexiveObjectProperty(ope)) => {
+            s.irreflexive.insert(role(ope).0);
+        }
+        Component::AsymmetricObjectProperty(AsymmetricObjectProperty(ope)) => {
+            s.asymmetric.insert(role(ope).0);
+        }
+        Component::SubDataPropertyOf(SubDataPropertyOf { sub, sup }) => {
+            s.data_sub.entry(sub.clone()).or_default().push(sup.clone());
+        }
+        Component::EquivalentDataProperties(EquivalentDataProperties(v)) => {
+            for (i, dp) in v.iter().enumerate() {
+                s.data_sub
+                    .entry(dp.clone())
+                    .or_default()
+                    .push(v[(i + 1) % v.len()].clone());
+            }
+        }
+        Component::DataPropertyDomain(DataPropertyDomain { dp, ce }) => {
+            s.data_domains
+                .entry(dp.clone())
+                .or_default()
+                .push(ce.clone());
+        }
+        Component::HasKey(HasKey { ce, vpe }) => {
+            let mut roles = vec![];
+            let mut dps = vec![];
+            for pe in vpe {
+                match pe {
+                    PropertyExpression::ObjectPropertyExpression(ope) => roles.push(role(ope)),
+                    PropertyExpression::DataProperty(dp) => dps.push(dp.clone()),
+                    PropertyExpression::AnnotationProperty(_) => {}
+                }
+            }
+            s.keys.push((ce.clone(), roles, dps));
+        }
+        Component::DifferentIndividuals(DifferentIndividuals(v)) => {
+            let rule = if v.len() == 2 {
+                InconsistencyRule::EqDiff1
+            } else {
+                InconsistencyRule::EqDiff2
+            };
+            for (a, b) in pairs(v) {
+                s.different.push((a.clone(), b.clone(), rule));
+            }
+        }
+        Component::NegativeObjectPropertyAssertion(NegativeObjectPropertyAssertion {
+            ope,
+            from,
+            to,
+        }) => {
+            s.negative.push((role(ope), from.clone(), to.clone()));
+        }
+        Component::NegativeDataPropertyAssertion(NegativeDataPropertyAssertion {
+            dp,
+            from,
+            to,
+        }) => {
+            s.negative_data.push((dp.clone(), from.clone(), to.clone()));
+        }
+        Component::ClassAssertion(ClassAssertion { ce, i }) => {
+            m.todo.push_back(Fact::Type(i.clone(), ce.clone()));
+        }
+        Component::ObjectPropertyAssertion(ObjectPropertyAssertion { ope, from, to }) => {
+            m.add_role(&role(ope), from, to);
+        }
+        Component::DataPropertyAssertion(DataPropertyAssertion { dp, from, to }) => {
+            m.todo
+                .push_back(Fact::Data(dp.clone(), from.clone(), to.clone()));
+        }
+        Component::SameIndividual(SameIndividual(v)) => {
+            for i in v.iter().skip(1) {
+                m.todo.push_back(Fact::Same(v[0].clone(), i.clone()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Materialize the entailments of `ont` under the OWL 2 RL/RDF rules.
+pub fn materialize<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Materialization<A> {
+    let build = Build::new();
+    let thing: ClassExpression<A> = build.class(OWL::Thing.as_ref()).into();
+    let mut m = Materializer {
+        schema: Default::default(),
+        thing: thing.clone(),
+        nothing: build.class(OWL::Nothing.as_ref()).into(),
+        parent: HashMap::new(),
+        members: HashMap::new(),
+        types: HashMap::new(),
+        fwd: HashMap::new(),
+        bwd: HashMap::new(),
+        data: HashMap::new(),
+        todo: VecDeque::new(),
+        inconsistencies: BTreeSet::new(),
+    };
+
+    let mut walk = Walk::new(Expressions(BTreeSet::new()));
+    for ac in ont.i().iter() {
+        walk.annotated_component(ac);
+        load(&mut m, &ac.component);
+        if let Component::DisjointUnion(DisjointUnion(_, v)) = &ac.component {
+            walk.as_mut_visit()
+                .0
+                .insert(ClassExpression::ObjectUnionOf(v.clone()));
+        }
+    }
+    let expressions = walk.into_visit().0;
+    for ce in &expressions {
+        m.schema.expression(ce);
+        // cls-oo
+        if let ClassExpression::ObjectOneOf(v) = ce {
+            for i in v {
+                m.todo.push_back(Fact::Type(i.clone(), ce.clone()));
+            }
+        }
+    }
+    m.schema.restrictions(&expressions, &thing);
+    m.run();
+
+    let asserted: HashSet<_> = ont.i().iter().map(|ac| &ac.component).collect();
+    let mut asserted_same = HashSet::new();
+    for c in &asserted {
+        if let Component::SameIndividual(SameIndividual(v)) = c {
+            for (a, b) in pairs(v) {
+                asserted_same.insert((a.clone(), b.clone()));
+                asserted_same.insert((b.clone(), a.clone()));
+            }
+        }
+    }
+
+    let mut components = BTreeSet::new();
+    // scm-sco, scm-eqc1, scm-eqc2 and the other scm rules
+    for ce in &expressions {
+        if let ClassExpression::Class(a) = ce {
+            for sup in m.schema.supers(ce) {
+                if let ClassExpression::Class(b) = &sup {
+                    if a != b && !b.is_thing() && !a.is_nothing() {
+                        components.insert(Component::SubClassOf(SubClassOf {
+                            sub: ce.clone(),
+                            sup,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    for (x, types) in &m.types {
+        for ce in types {
+            if let ClassExpression::Class(c) = ce {
+                if !c.is_thing() {
+                    for i in m.members(x) {
+                        components.insert(Component::ClassAssertion(ClassAssertion {
+                            ce: ce.clone(),
+                            i,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    for (p, edges) in &m.fwd {
+        for (x, ys) in edges {
+            for y in ys {
+                for from in m.members(x) {
+                    for to in m.members(y) {
+                        components.insert(Component::ObjectPropertyAssertion(
+                            ObjectPropertyAssertion {
+                                ope: p.clone().into(),
+                                from: from.clone(),
+                                to,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    for members in m.members.values() {
+        let members: Vec<_> = members.iter().cloned().collect();
+        for (a, b) in pairs(&members) {
+            if !asserted_same.contains(&(a.clone(), b.clone())) {
+                components.insert(Component::SameIndividual(SameIndividual(vec![
+                    a.clone(),
+                    b.clone(),
+                ])));
+            }
+        }
+    }
+
+    Materialization {
+        components: components
+            .into_iter()
+            .filter(|c| !asserted.contains(c))
+            .map(|c| c.into())
+            .collect(),
+        inconsistencies: m.inconsistencies.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn materialize_ofn(ofn: &str) -> Materialization<RcStr> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        materialize(&ont)
+    }
+
+    fn iri(s: &str) -> String {
+        format!("http://example.com/{}", s)
+    }
+
+    fn ind(s: &str) -> Individual<RcStr> {
+        Build::new_rc().named_individual(iri(s)).into()
+    }
+
+    fn class_assertion(c: &str, i: &str) -> Component<RcStr> {
+        ClassAssertion {
+            ce: Build::new_rc().class(iri(c)).into(),
+            i: ind(i),
+        }
+        .into()
+    }
+
+    fn property_assertion(p: &str, from: &str, to: &str) -> Component<RcStr> {
+        ObjectPropertyAssertion {
+            ope: Build::new_rc().object_property(iri(p)).into(),
+            from: ind(from),
+            to: ind(to),
+        }
+        .into()
+    }
+
+    fn components(m: &Materialization<RcStr>) -> Vec<Component<RcStr>> {
+        m.components.iter().map(|ac| ac.component.clone()).collect()
+    }
+
+    #[test]
+    fn class_hierarchy() {
+        let m = materialize_ofn(
+            "SubClassOf(:A :B)
+             EquivalentClasses(:B :C)
+             SubClassOf(ObjectIntersectionOf(:C :D) :E)
+             ClassAssertion(:A :i)
+             ClassAssertion(:D :i)",
+        );
+        let c = components(&m);
+        for component in [
+            class_assertion("B", "i"),
+            class_assertion("C", "i"),
+            class_assertion("E", "i"),
+            SubClassOf {
+                sub: Build::new_rc().class(iri("A")).into(),
+                sup: Build::new_rc().class(iri("C")).into(),
+            }
+            .into(),
+        ] {
+            assert!(c.contains(&component), "{:?}", component);
+        }
+        assert!(!c.contains(&class_assertion("A", "i")));
+        assert!(m.is_consistent());
+    }
+
+    #[test]
+    fn properties() {
+        let m = materialize_ofn(
+            "TransitiveObjectProperty(:partOf)
+             SubObjectPropertyOf(:directPartOf :partOf)
+             InverseObjectProperties(:partOf :hasPart)
+             SymmetricObjectProperty(:near)
+             SubObjectPropertyOf(ObjectPropertyChain(:partOf :near) :nearPart)
+             ObjectPropertyDomain(:partOf :Part)
+             ObjectPropertyRange(ObjectInverseOf(:hasPart) :Whole)
+             ObjectPropertyAssertion(:directPartOf :a :b)
+             ObjectPropertyAssertion(:directPartOf :b :c)
+             ObjectPropertyAssertion(:near :c :d)",
+        );
+        let c = components(&m);
+        for component in [
+            property_assertion("partOf", "a", "c"),
+            property_assertion("hasPart", "c", "a"),
+            property_assertion("near", "d", "c"),
+            property_assertion("nearPart", "a", "d"),
+            class_assertion("Part", "a"),
+            class_assertion("Whole", "c"),
+        ] {
+            assert!(c.contains(&component), "{:?}", component);
+        }
+    }
+
+    #[test]
+    fn restrictions() {
+        let m = materialize_ofn(
+            "SubClassOf(ObjectSomeValuesFrom(:r :B) :C)
+             SubClassOf(:D ObjectAllValuesFrom(:r :B))
+             SubClassOf(:E ObjectHasValue(:s :k))
+             SubClassOf(DataHasValue(:d \"01\"^^xsd:integer) :F)
+             SubClassOf(DataSomeValuesFrom(:d xsd:integer) :G)
+             ClassAssertion(:D :i)
+             ObjectPropertyAssertion(:r :i :j)
+             ClassAssertion(:E :l)
+             DataPropertyAssertion(:d :m \"1\"^^xsd:integer)",
+        );
+        let c = components(&m);
+        for component in [
+            class_assertion("B", "j"),
+            class_assertion("C", "i"),
+            property_assertion("s", "l", "k"),
+            class_assertion("F", "m"),
+            class_assertion("G", "m"),
+        ] {
+            assert!(c.contains(&component), "{:?}", component);
+        }
+    }
+
+    #[test]
+    fn max_cardinality_without_successors() {
+        let m = materialize_ofn(
+            "SubClassOf(:A ObjectMaxCardinality(1 :r))
+             ClassAssertion(:A :a)",
+        );
+        assert!(m.is_consistent());
+    }
+
+    #[test]
+    fn same_individuals() {
+        let m = materialize_ofn(
+            "FunctionalObjectProperty(:hasMother)
+             ObjectPropertyAssertion(:hasMother :a :m1)
+             ObjectPropertyAssertion(:hasMother :a :m2)
+             ClassAssertion(:Woman :m1)
+             HasKey(:Person () (:id))
+             ClassAssertion(:Person :p1)
+             ClassAssertion(:Person :p2)
+             DataPropertyAssertion(:id :p1 \"1\")
+             DataPropertyAssertion(:id :p2 \"1\")",
+        );
+        let c = components(&m);
+        for component in [
+            SameIndividual(vec![ind("m1"), ind("m2")]).into(),
+            SameIndividual(vec![ind("p1"), ind("p2")]).into(),
+            class_assertion("Woman", "m2"),
+        ] {
+            assert!(c.contains(&component), "{:?}", component);
+        }
+        assert!(m.is_consistent());
+    }
+
+    #[test]
+    fn inconsistencies() {
+        let m = materialize_ofn(
+            "DisjointClasses(:A :B)
+             SubClassOf(:C :B)
+             ClassAssertion(:A :i)
+             ClassAssertion(:C :i)
+             IrreflexiveObjectProperty(:r)
+             ObjectPropertyAssertion(:r :j :j)
+             SameIndividual(:k :l)
+             DifferentIndividuals(:k :l)
+             NegativeObjectPropertyAssertion(:s :k :m)
+             ObjectPropertyAssertion(:s :l :m)
+             NegativeDataPropertyAssertion(:d :n \"01\"^^xsd:integer)
+             DataPropertyAssertion(:d :n \"1\"^^xsd:integer)",
+        );
+        assert_eq!(
+            m.inconsistencies
+                .iter()
+                .map(|i| i.rule.to_string())
+                .collect::<Vec<_>>(),
+            vec!["eq-diff1", "prp-irp", "prp-npa1", "prp-npa2", "cax-dw"]
+        );
+        assert_eq!(m.inconsistencies[3].individuals, vec![ind("n")]);
+        assert_eq!(m.inconsistencies[4].individuals, vec![ind("i")]);
+    }
+}
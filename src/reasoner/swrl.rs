@@ -0,0 +1,1134 @@
+//! An evaluator for [SWRL](https://www.w3.org/Submission/SWRL/) rules.
+//!
+//! The `Rule` components of an ontology are run over its assertions
+//! until no new facts follow. Rules are DL-safe: variables are bound
+//! only to the individuals and literals of the assertions, so a rule
+//! whose head would introduce a new individual cannot fire.
+//!
+//! Body atoms match the `ClassAssertion`, `ObjectPropertyAssertion`,
+//! `DataPropertyAssertion`, `SameIndividual` and
+//! `DifferentIndividuals` facts, asserted or derived. Class atoms are
+//! matched structurally, with no reasoning over the classes, so it
+//! may be useful to materialize the ontology first, with
+//! [`rl`](super::rl). Individuals which are the same are represented
+//! by one of them.
+//!
+//! The core comparison, arithmetic and string built-ins of the
+//! `swrlb:` namespace are supported.
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+
+use crate::datatypes::{compare, value, Decimal, Value};
+use crate::error::HornedError;
+use crate::model::*;
+use crate::ontology::component_mapped::ComponentMappedOntology;
+use crate::ontology::indexed::ForIndex;
+use crate::vocab::Namespace;
+
+/// The namespace of the SWRL built-ins.
+pub const SWRLB: &str = "http://www.w3.org/2003/11/swrlb#";
+
+/// The value bound to a variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Term<A> {
+    Individual(Individual<A>),
+    Literal(Literal<A>),
+}
+
+type Binding<A> = HashMap<Variable<A>, Term<A>>;
+
+/// The kinds of built-in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    /// A test of all of its arguments
+    Test,
+    /// A function of all but the first argument, which is the result
+    Function,
+}
+
+fn kind(name: &str) -> Option<Kind> {
+    match name {
+        "equal"
+        | "notEqual"
+        | "lessThan"
+        | "lessThanOrEqual"
+        | "greaterThan"
+        | "greaterThanOrEqual"
+        | "stringEqualIgnoreCase"
+        | "contains"
+        | "containsIgnoreCase"
+        | "startsWith"
+        | "endsWith"
+        | "matches" => Some(Kind::Test),
+        "add" | "subtract" | "multiply" | "divide" | "integerDivide" | "mod" | "pow"
+        | "unaryPlus" | "unaryMinus" | "abs" | "ceiling" | "floor" | "round" | "stringConcat"
+        | "substring" | "stringLength" | "normalizeSpace" | "upperCase" | "lowerCase"
+        | "substringBefore" | "substringAfter" => Some(Kind::Function),
+        _ => None,
+    }
+}
+
+/// A numeric value, promoted as XPath promotes them.
+#[derive(Clone, Debug, PartialEq)]
+enum Number {
+    Integer(i128),
+    Decimal(Decimal),
+    Double(f64),
+}
+
+impl Number {
+    fn to_f64(&self) -> f64 {
+        match self {
+            Number::Integer(i) => *i as f64,
+            Number::Decimal(d) => d.to_f64(),
+            Number::Double(f) => *f,
+        }
+    }
+
+    fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Number::Integer(i) => Decimal::parse(&i.to_string()),
+            Number::Decimal(d) => Some(d.clone()),
+            Number::Double(_) => None,
+        }
+    }
+
+    /// Apply an operation to `self` and `other`, as integers if both
+    /// are, as `f64` if either is a double, and otherwise as decimals.
+    fn apply(
+        &self,
+        other: &Number,
+        int: impl Fn(i128, i128) -> Option<i128>,
+        decimal: impl Fn(Decimal, Decimal) -> Option<Decimal>,
+        float: impl Fn(f64, f64) -> f64,
+    ) -> Option<Number> {
+        match (self, other) {
+            (Number::Integer(a), Number::Integer(b)) => int(*a, *b).map(Number::Integer),
+            (Number::Double(_), _) | (_, Number::Double(_)) => {
+                Some(Number::Double(float(self.to_f64(), other.to_f64())))
+            }
+            _ => decimal(self.to_decimal()?, other.to_decimal()?).map(Number::Decimal),
+        }
+    }
+
+    fn map(
+        &self,
+        int: impl Fn(i128) -> Option<i128>,
+        decimal: impl Fn(Decimal) -> Option<Decimal>,
+        float: impl Fn(f64) -> f64,
+    ) -> Option<Number> {
+        match self {
+            Number::Integer(i) => int(*i).map(Number::Integer),
+            Number::Decimal(d) => decimal(d.clone()).map(Number::Decimal),
+            Number::Double(f) => Some(Number::Double(float(*f))),
+        }
+    }
+}
+
+/// The decimal nearest to the result of an operation which is only
+/// computed as `f64`.
+fn inexact(f: f64) -> Option<Decimal> {
+    f.is_finite()
+        .then(|| Decimal::parse(&f.to_string()))
+        .flatten()
+}
+
+fn number<A: ForIRI>(l: &Literal<A>) -> Option<Number> {
+    match value(l).ok()?? {
+        Value::Decimal(d) => Some(match d.to_i128() {
+            Some(i) => Number::Integer(i),
+            None => Number::Decimal(d),
+        }),
+        Value::Double(f) => Some(Number::Double(f)),
+        Value::Float(f) => Some(Number::Double(f as f64)),
+        _ => None,
+    }
+}
+
+fn typed<A: ForIRI>(build: &Build<A>, literal: String, datatype: &str) -> Literal<A> {
+    Literal::Datatype {
+        literal,
+        datatype_iri: build.iri(format!("{}{}", Namespace::XSD.as_ref(), datatype)),
+    }
+}
+
+fn number_literal<A: ForIRI>(build: &Build<A>, n: Number) -> Literal<A> {
+    match n {
+        Number::Integer(i) => typed(build, i.to_string(), "integer"),
+        Number::Decimal(d) => typed(build, d.to_string(), "decimal"),
+        Number::Double(f) => {
+            let s = if f.is_nan() {
+                "NaN".to_string()
+            } else if f.is_infinite() {
+                if f > 0.0 { "INF" } else { "-INF" }.to_string()
+            } else {
+                f.to_string()
+            };
+            typed(build, s, "double")
+        }
+    }
+}
+
+fn string<A: ForIRI>(s: String) -> Literal<A> {
+    Literal::Simple { literal: s }
+}
+
+/// Return true if `a` and `b` have the same value.
+fn equal<A: ForIRI>(a: &Literal<A>, b: &Literal<A>) -> bool {
+    if number(a).is_some() && number(b).is_some() {
+        return order(a, b) == Some(std::cmp::Ordering::Equal);
+    }
+    match (value(a), value(b)) {
+        (Ok(Some(va)), Ok(Some(vb))) => va == vb,
+        _ => a == b,
+    }
+}
+
+/// Compare `a` and `b`, if they have ordered values of the same kind.
+///
+/// Decimals are compared exactly, and are promoted to `f64` only to
+/// compare them with a double.
+fn order<A: ForIRI>(a: &Literal<A>, b: &Literal<A>) -> Option<std::cmp::Ordering> {
+    if let (Some(a), Some(b)) = (number(a), number(b)) {
+        if matches!(a, Number::Double(_)) || matches!(b, Number::Double(_)) {
+            return a.to_f64().partial_cmp(&b.to_f64());
+        }
+    }
+    match (value(a).ok()??, value(b).ok()??) {
+        (Value::String(a), Value::String(b)) => Some(a.cmp(&b)),
+        (a, b) => compare(&a, &b),
+    }
+}
+
+/// Evaluate the test built-in `name` over `args`.
+fn test<A: ForIRI>(name: &str, args: &[Literal<A>]) -> bool {
+    use std::cmp::Ordering::*;
+    let s: Vec<&str> = args.iter().map(|l| l.literal().as_str()).collect();
+    match (name, args) {
+        ("equal", [a, b]) => equal(a, b),
+        ("notEqual", [a, b]) => !equal(a, b),
+        ("lessThan", [a, b]) => order(a, b) == Some(Less),
+        ("lessThanOrEqual", [a, b]) => matches!(order(a, b), Some(Less | Equal)),
+        ("greaterThan", [a, b]) => order(a, b) == Some(Greater),
+        ("greaterThanOrEqual", [a, b]) => matches!(order(a, b), Some(Greater | Equal)),
+        ("stringEqualIgnoreCase", [_, _]) => s[0].to_lowercase() == s[1].to_lowercase(),
+        ("contains", [_, _]) => s[0].contains(s[1]),
+        ("containsIgnoreCase", [_, _]) => s[0].to_lowercase().contains(&s[1].to_lowercase()),
+        ("startsWith", [_, _]) => s[0].starts_with(s[1]),
+        ("endsWith", [_, _]) => s[0].ends_with(s[1]),
+        ("matches", [_, _]) => regex::Regex::new(s[1]).is_ok_and(|re| re.is_match(s[0])),
+        _ => false,
+    }
+}
+
+/// Evaluate the function built-in `name` over `args`, not including
+/// the result.
+fn function<A: ForIRI>(build: &Build<A>, name: &str, args: &[Literal<A>]) -> Option<Literal<A>> {
+    let s: Vec<&str> = args.iter().map(|l| l.literal().as_str()).collect();
+    let numeric = || args.iter().map(number).collect::<Option<Vec<_>>>();
+
+    let n = match (name, args.len()) {
+        ("add", _) => numeric()?.iter().try_fold(Number::Integer(0), |a, b| {
+            a.apply(b, i128::checked_add, |a, b| Some(a + b), |a, b| a + b)
+        })?,
+        ("multiply", _) => numeric()?.iter().try_fold(Number::Integer(1), |a, b| {
+            a.apply(b, i128::checked_mul, |a, b| Some(a * b), |a, b| a * b)
+        })?,
+        ("subtract", 2) => {
+            let v = numeric()?;
+            v[0].apply(&v[1], i128::checked_sub, |a, b| Some(a - b), |a, b| a - b)?
+        }
+        ("divide", 2) => {
+            let v = numeric()?;
+            match (&v[0], &v[1]) {
+                (_, Number::Integer(0)) => return None,
+                (Number::Integer(a), Number::Integer(b)) if a % b == 0 => Number::Integer(a / b),
+                (a, b) => {
+                    let a = match a {
+                        Number::Integer(_) => Number::Decimal(a.to_decimal()?),
+                        _ => a.clone(),
+                    };
+                    a.apply(
+                        b,
+                        |_, _| None,
+                        |a, b| inexact(a.to_f64() / b.to_f64()),
+                        |a, b| a / b,
+                    )?
+                }
+            }
+        }
+        ("integerDivide", 2) => {
+            let v = numeric()?;
+            match (&v[0], &v[1]) {
+                (Number::Integer(a), Number::Integer(b)) => Number::Integer(a.checked_div(*b)?),
+                (a, b) if b.to_f64() != 0.0 => {
+                    Number::Integer((a.to_f64() / b.to_f64()).trunc() as i128)
+                }
+                _ => return None,
+            }
+        }
+        ("mod", 2) => {
+            let v = numeric()?;
+            v[0].apply(
+                &v[1],
+                i128::checked_rem,
+                |a, b| inexact(a.to_f64() % b.to_f64()),
+                |a, b| a % b,
+            )?
+        }
+        ("pow", 2) => {
+            let v = numeric()?;
+            v[0].apply(
+                &v[1],
+                |a, b| a.checked_pow(u32::try_from(b).ok()?),
+                |a, b| inexact(a.to_f64().powf(b.to_f64())),
+                f64::powf,
+            )?
+        }
+        ("unaryPlus", 1) => numeric()?.swap_remove(0),
+        ("unaryMinus", 1) => numeric()?[0].map(i128::checked_neg, |d| Some(-d), |f| -f)?,
+        ("abs", 1) => numeric()?[0].map(
+            i128::checked_abs,
+            |d| Some(if d.is_negative() { -d } else { d }),
+            f64::abs,
+        )?,
+        ("ceiling", 1) => numeric()?[0].map(Some, |d| inexact(d.to_f64().ceil()), f64::ceil)?,
+        ("floor", 1) => numeric()?[0].map(Some, |d| inexact(d.to_f64().floor()), f64::floor)?,
+        ("round", 1) => numeric()?[0].map(
+            Some,
+            |d| inexact((d.to_f64() + 0.5).floor()),
+            |f| (f + 0.5).floor(),
+        )?,
+
+        ("stringConcat", _) => return Some(string(s.concat())),
+        ("substring", 2 | 3) => {
+            let chars: Vec<char> = s[0].chars().collect();
+            let start = number(&args[1])?.to_f64().round();
+            let end = match args.get(2) {
+                Some(l) => start + number(l)?.to_f64().round(),
+                None => f64::INFINITY,
+            };
+            return Some(string(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| {
+                        let p = (*i + 1) as f64;
+                        p >= start && p < end
+                    })
+                    .map(|(_, c)| c)
+                    .collect(),
+            ));
+        }
+        ("stringLength", 1) => Number::Integer(s[0].chars().count() as i128),
+        ("normalizeSpace", 1) => {
+            return Some(string(
+                s[0].split_whitespace().collect::<Vec<_>>().join(" "),
+            ))
+        }
+        ("upperCase", 1) => return Some(string(s[0].to_uppercase())),
+        ("lowerCase", 1) => return Some(string(s[0].to_lowercase())),
+        ("substringBefore", 2) => {
+            return Some(string(
+                s[0].split_once(s[1])
+                    .map(|(b, _)| b)
+                    .unwrap_or("")
+                    .to_string(),
+            ))
+        }
+        ("substringAfter", 2) => {
+            return Some(string(
+                s[0].split_once(s[1])
+                    .map(|(_, a)| a)
+                    .unwrap_or("")
+                    .to_string(),
+            ))
+        }
+        _ => return None,
+    };
+    Some(number_literal(build, n))
+}
+
+/// The name of the built-in `pred`, if it is supported.
+fn builtin<A: ForIRI>(pred: &IRI<A>) -> Result<(&str, Kind), HornedError> {
+    pred.strip_prefix(SWRLB)
+        .and_then(|name| kind(name).map(|k| (name, k)))
+        .ok_or_else(|| HornedError::invalid(format!("Unsupported SWRL built-in: {}", pred)))
+}
+
+/// A fact which may be asserted or derived.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Fact<A> {
+    Class(ClassExpression<A>, Individual<A>),
+    Object(ObjectProperty<A>, Individual<A>, Individual<A>),
+    Data(DataProperty<A>, Individual<A>, Literal<A>),
+    Same(Individual<A>, Individual<A>),
+    Different(Individual<A>, Individual<A>),
+}
+
+impl<A: ForIRI> Fact<A> {
+    fn component(self) -> Component<A> {
+        match self {
+            Fact::Class(ce, i) => ClassAssertion { ce, i }.into(),
+            Fact::Object(op, from, to) => ObjectPropertyAssertion {
+                ope: op.into(),
+                from,
+                to,
+            }
+            .into(),
+            Fact::Data(dp, from, to) => DataPropertyAssertion { dp, from, to }.into(),
+            Fact::Same(a, b) => SameIndividual(pair(a, b)).into(),
+            Fact::Different(a, b) => DifferentIndividuals(pair(a, b)).into(),
+        }
+    }
+}
+
+fn pair<A: ForIRI>(a: Individual<A>, b: Individual<A>) -> Vec<Individual<A>> {
+    if a <= b {
+        vec![a, b]
+    } else {
+        vec![b, a]
+    }
+}
+
+type Pairs<A, T> = HashSet<(Individual<A>, T)>;
+
+/// The facts, kept only for the representative of each set of same
+/// individuals.
+struct Facts<A> {
+    parent: HashMap<Individual<A>, Individual<A>>,
+    individuals: BTreeSet<Individual<A>>,
+    types: HashMap<ClassExpression<A>, HashSet<Individual<A>>>,
+    objects: HashMap<ObjectProperty<A>, Pairs<A, Individual<A>>>,
+    data: HashMap<DataProperty<A>, Pairs<A, Literal<A>>>,
+    different: Pairs<A, Individual<A>>,
+}
+
+impl<A: ForIRI> Facts<A> {
+    fn find(&self, i: &Individual<A>) -> Individual<A> {
+        let mut i = i;
+        while let Some(p) = self.parent.get(i) {
+            i = p;
+        }
+        i.clone()
+    }
+
+    /// Add `fact`, returning true if it is new.
+    fn add(&mut self, fact: Fact<A>) -> bool {
+        match fact {
+            Fact::Class(ce, i) => {
+                let i = self.find(&i);
+                self.individuals.insert(i.clone());
+                self.types.entry(ce).or_default().insert(i)
+            }
+            Fact::Object(op, from, to) => {
+                let (from, to) = (self.find(&from), self.find(&to));
+                self.individuals.insert(from.clone());
+                self.individuals.insert(to.clone());
+                self.objects.entry(op).or_default().insert((from, to))
+            }
+            Fact::Data(dp, from, to) => {
+                let from = self.find(&from);
+                self.individuals.insert(from.clone());
+                self.data.entry(dp).or_default().insert((from, to))
+            }
+            Fact::Same(a, b) => {
+                let (a, b) = (self.find(&a), self.find(&b));
+                if a == b {
+                    return false;
+                }
+                let (keep, merge) = if a < b { (a, b) } else { (b, a) };
+                self.parent.insert(merge, keep);
+                self.normalize();
+                true
+            }
+            Fact::Different(a, b) => {
+                let (a, b) = (self.find(&a), self.find(&b));
+                self.individuals.insert(a.clone());
+                self.individuals.insert(b.clone());
+                self.different.insert((b.clone(), a.clone()));
+                self.different.insert((a, b))
+            }
+        }
+    }
+
+    /// Replace every individual with its representative.
+    fn normalize(&mut self) {
+        let find = |i: &Individual<A>| self.find(i);
+        let individuals = self.individuals.iter().map(find).collect();
+        let types = self
+            .types
+            .iter()
+            .map(|(ce, v)| (ce.clone(), v.iter().map(find).collect()))
+            .collect();
+        let objects = self
+            .objects
+            .iter()
+            .map(|(op, v)| {
+                (
+                    op.clone(),
+                    v.iter().map(|(a, b)| (find(a), find(b))).collect(),
+                )
+            })
+            .collect();
+        let data = self
+            .data
+            .iter()
+            .map(|(dp, v)| {
+                (
+                    dp.clone(),
+                    v.iter().map(|(a, l)| (find(a), l.clone())).collect(),
+                )
+            })
+            .collect();
+        let different = self
+            .different
+            .iter()
+            .map(|(a, b)| (find(a), find(b)))
+            .collect();
+
+        self.individuals = individuals;
+        self.types = types;
+        self.objects = objects;
+        self.data = data;
+        self.different = different;
+    }
+
+    /// Return the individual `arg`, if it is bound.
+    fn individual(&self, b: &Binding<A>, arg: &IArgument<A>) -> Option<Individual<A>> {
+        match arg {
+            IArgument::Individual(i) => Some(self.find(i)),
+            IArgument::Variable(v) => match b.get(v) {
+                Some(Term::Individual(i)) => Some(i.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Unify `arg` with the individual `i`.
+    fn unify_i(&self, b: &Binding<A>, arg: &IArgument<A>, i: &Individual<A>) -> Option<Binding<A>> {
+        match arg {
+            IArgument::Individual(c) => (self.find(c) == *i).then(|| b.clone()),
+            IArgument::Variable(v) => unify(b, v, Term::Individual(i.clone())),
+        }
+    }
+}
+
+/// Return the literal `arg`, if it is bound.
+fn literal<A: ForIRI>(b: &Binding<A>, arg: &DArgument<A>) -> Option<Literal<A>> {
+    match arg {
+        DArgument::Literal(l) => Some(l.clone()),
+        DArgument::Variable(v) => match b.get(v) {
+            Some(Term::Literal(l)) => Some(l.clone()),
+            _ => None,
+        },
+    }
+}
+
+/// Bind `v` to `t`, if it is not bound to something else.
+fn unify<A: ForIRI>(b: &Binding<A>, v: &Variable<A>, t: Term<A>) -> Option<Binding<A>> {
+    match b.get(v) {
+        Some(bound) => (*bound == t).then(|| b.clone()),
+        None => {
+            let mut b = b.clone();
+            b.insert(v.clone(), t);
+            Some(b)
+        }
+    }
+}
+
+fn unify_d<A: ForIRI>(b: &Binding<A>, arg: &DArgument<A>, l: &Literal<A>) -> Option<Binding<A>> {
+    match arg {
+        DArgument::Literal(c) => (c == l).then(|| b.clone()),
+        DArgument::Variable(v) => unify(b, v, Term::Literal(l.clone())),
+    }
+}
+
+fn atom_variables<A: ForIRI>(atom: &Atom<A>) -> Vec<&Variable<A>> {
+    fn i<A>(arg: &IArgument<A>) -> Option<&Variable<A>> {
+        match arg {
+            IArgument::Variable(v) => Some(v),
+            _ => None,
+        }
+    }
+    fn d<A>(arg: &DArgument<A>) -> Option<&Variable<A>> {
+        match arg {
+            DArgument::Variable(v) => Some(v),
+            _ => None,
+        }
+    }
+    match atom {
+        Atom::BuiltInAtom { args, .. } => args.iter().filter_map(d).collect(),
+        Atom::ClassAtom { arg, .. } => i(arg).into_iter().collect(),
+        Atom::DataPropertyAtom { args, .. } => d(&args.0).into_iter().chain(d(&args.1)).collect(),
+        Atom::DataRangeAtom { arg, .. } => d(arg).into_iter().collect(),
+        Atom::ObjectPropertyAtom { args: (a, b), .. }
+        | Atom::SameIndividualAtom(a, b)
+        | Atom::DifferentIndividualsAtom(a, b) => i(a).into_iter().chain(i(b)).collect(),
+    }
+}
+
+/// Return the body of `rule` in an order in which it can be evaluated,
+/// or an error if it is not safe.
+///
+/// Atoms which match facts come first, then data ranges and built-ins
+/// once their arguments are bound.
+fn plan<A: ForIRI>(rule: &Rule<A>) -> Result<Vec<&Atom<A>>, HornedError> {
+    let (mut plan, mut rest): (Vec<_>, Vec<_>) = rule
+        .body
+        .iter()
+        .partition(|atom| !matches!(atom, Atom::BuiltInAtom { .. } | Atom::DataRangeAtom { .. }));
+    let mut bound: HashSet<_> = plan.iter().flat_map(|atom| atom_variables(atom)).collect();
+
+    for atom in &rest {
+        if let Atom::BuiltInAtom { pred, args } = atom {
+            builtin(pred)?;
+            if args.is_empty() {
+                return Err(HornedError::invalid(format!(
+                    "SWRL built-in without arguments: {}",
+                    pred
+                )));
+            }
+        }
+    }
+
+    while !rest.is_empty() {
+        let ready = rest.iter().position(|atom| {
+            let variables = atom_variables(atom);
+            match atom {
+                Atom::BuiltInAtom { pred, args } => match builtin(pred) {
+                    Ok((_, Kind::Function)) => args[1..].iter().all(|arg| match arg {
+                        DArgument::Variable(v) => bound.contains(v),
+                        _ => true,
+                    }),
+                    _ => variables.iter().all(|v| bound.contains(v)),
+                },
+                _ => variables.iter().all(|v| bound.contains(v)),
+            }
+        });
+        match ready {
+            Some(n) => {
+                let atom = rest.remove(n);
+                bound.extend(atom_variables(atom));
+                plan.push(atom);
+            }
+            None => {
+                return Err(HornedError::invalid(format!(
+                    "SWRL rule has an unbound variable in its body: {:?}",
+                    rest[0]
+                )))
+            }
+        }
+    }
+
+    for atom in &rule.head {
+        if let Some(v) = atom_variables(atom)
+            .into_iter()
+            .find(|v| !bound.contains(v))
+        {
+            return Err(HornedError::invalid(format!(
+                "SWRL rule has a variable in its head which is not in its body: {}",
+                v.0
+            )));
+        }
+    }
+    Ok(plan)
+}
+
+/// Return every extension of `b` which matches `atom`.
+fn matches<A: ForIRI>(
+    facts: &Facts<A>,
+    build: &Build<A>,
+    atom: &Atom<A>,
+    b: &Binding<A>,
+) -> Vec<Binding<A>> {
+    match atom {
+        Atom::ClassAtom { pred, arg } => {
+            let instances = facts.types.get(pred);
+            match facts.individual(b, arg) {
+                Some(i) => instances
+                    .filter(|s| s.contains(&i))
+                    .map(|_| b.clone())
+                    .into_iter()
+                    .collect(),
+                None => instances
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| facts.unify_i(b, arg, i))
+                    .collect(),
+            }
+        }
+        Atom::ObjectPropertyAtom { pred, args } => {
+            let (op, inverse) = match pred {
+                ObjectPropertyExpression::ObjectProperty(op) => (op, false),
+                ObjectPropertyExpression::InverseObjectProperty(op) => (op, true),
+            };
+            facts
+                .objects
+                .get(op)
+                .into_iter()
+                .flatten()
+                .filter_map(|(x, y)| {
+                    let (x, y) = if inverse { (y, x) } else { (x, y) };
+                    facts
+                        .unify_i(b, &args.0, x)
+                        .and_then(|b| facts.unify_i(&b, &args.1, y))
+                })
+                .collect()
+        }
+        Atom::DataPropertyAtom { pred, args } => facts
+            .data
+            .get(pred)
+            .into_iter()
+            .flatten()
+            .filter_map(|(i, l)| {
+                let b = match &args.0 {
+                    DArgument::Variable(v) => unify(b, v, Term::Individual(i.clone()))?,
+                    DArgument::Literal(_) => return None,
+                };
+                unify_d(&b, &args.1, l)
+            })
+            .collect(),
+        Atom::SameIndividualAtom(x, y) => match (facts.individual(b, x), facts.individual(b, y)) {
+            (Some(i), _) => facts.unify_i(b, y, &i).into_iter().collect(),
+            (None, Some(i)) => facts.unify_i(b, x, &i).into_iter().collect(),
+            (None, None) => facts
+                .individuals
+                .iter()
+                .filter_map(|i| facts.unify_i(b, x, i).and_then(|b| facts.unify_i(&b, y, i)))
+                .collect(),
+        },
+        Atom::DifferentIndividualsAtom(x, y) => facts
+            .different
+            .iter()
+            .filter_map(|(i, j)| facts.unify_i(b, x, i).and_then(|b| facts.unify_i(&b, y, j)))
+            .collect(),
+        Atom::DataRangeAtom { pred, arg } => literal(b, arg)
+            .filter(|l| pred.contains(l))
+            .map(|_| b.clone())
+            .into_iter()
+            .collect(),
+        Atom::BuiltInAtom { pred, args } => {
+            let (name, kind) = match builtin(pred) {
+                Ok(builtin) => builtin,
+                Err(_) => return vec![],
+            };
+            let rest: Option<Vec<_>> = args[1..].iter().map(|arg| literal(b, arg)).collect();
+            let rest = match rest {
+                Some(rest) => rest,
+                None => return vec![],
+            };
+            match kind {
+                Kind::Test => {
+                    let mut all = vec![];
+                    all.extend(literal(b, &args[0]));
+                    all.extend(rest);
+                    if all.len() == args.len() && test(name, &all) {
+                        vec![b.clone()]
+                    } else {
+                        vec![]
+                    }
+                }
+                Kind::Function => match (function(build, name, &rest), literal(b, &args[0])) {
+                    (Some(result), Some(l)) if equal(&result, &l) => vec![b.clone()],
+                    (Some(result), None) => unify_d(b, &args[0], &result).into_iter().collect(),
+                    _ => vec![],
+                },
+            }
+        }
+    }
+}
+
+/// Return the fact of the head atom `atom` for the binding `b`.
+fn head<A: ForIRI>(facts: &Facts<A>, atom: &Atom<A>, b: &Binding<A>) -> Option<Fact<A>> {
+    let i = |arg| facts.individual(b, arg);
+    match atom {
+        Atom::ClassAtom { pred, arg } => Some(Fact::Class(pred.clone(), i(arg)?)),
+        Atom::ObjectPropertyAtom { pred, args } => match pred {
+            ObjectPropertyExpression::ObjectProperty(op) => {
+                Some(Fact::Object(op.clone(), i(&args.0)?, i(&args.1)?))
+            }
+            ObjectPropertyExpression::InverseObjectProperty(op) => {
+                Some(Fact::Object(op.clone(), i(&args.1)?, i(&args.0)?))
+            }
+        },
+        Atom::DataPropertyAtom { pred, args } => match (&args.0, literal(b, &args.1)) {
+            (DArgument::Variable(v), Some(l)) => match b.get(v) {
+                Some(Term::Individual(x)) => Some(Fact::Data(pred.clone(), x.clone(), l)),
+                _ => None,
+            },
+            _ => None,
+        },
+        Atom::SameIndividualAtom(x, y) => Some(Fact::Same(i(x)?, i(y)?)),
+        Atom::DifferentIndividualsAtom(x, y) => Some(Fact::Different(i(x)?, i(y)?)),
+        Atom::BuiltInAtom { .. } | Atom::DataRangeAtom { .. } => None,
+    }
+}
+
+/// Run the SWRL rules of `ont` until no new facts follow, returning
+/// the derived components which are not in `ont`.
+///
+/// An error is returned if a rule is not safe, or uses an unsupported
+/// built-in.
+pub fn infer<A: ForIRI, AA: ForIndex<A>>(
+    ont: &ComponentMappedOntology<A, AA>,
+) -> Result<Vec<AnnotatedComponent<A>>, HornedError> {
+    let mut facts = Facts {
+        parent: HashMap::new(),
+        individuals: BTreeSet::new(),
+        types: HashMap::new(),
+        objects: HashMap::new(),
+        data: HashMap::new(),
+        different: HashSet::new(),
+    };
+    let mut rules = vec![];
+    for ac in ont.i().iter() {
+        match &ac.component {
+            Component::Rule(rule) => rules.push((rule, plan(rule)?)),
+            Component::DeclareNamedIndividual(DeclareNamedIndividual(i)) => {
+                facts.individuals.insert(i.clone().into());
+            }
+            Component::ClassAssertion(ClassAssertion { ce, i }) => {
+                facts.add(Fact::Class(ce.clone(), i.clone()));
+            }
+            Component::ObjectPropertyAssertion(ObjectPropertyAssertion { ope, from, to }) => {
+                let (op, from, to) = match ope {
+                    ObjectPropertyExpression::ObjectProperty(op) => (op, from, to),
+                    ObjectPropertyExpression::InverseObjectProperty(op) => (op, to, from),
+                };
+                facts.add(Fact::Object(op.clone(), from.clone(), to.clone()));
+            }
+            Component::DataPropertyAssertion(DataPropertyAssertion { dp, from, to }) => {
+                facts.add(Fact::Data(dp.clone(), from.clone(), to.clone()));
+            }
+            Component::SameIndividual(SameIndividual(v)) => {
+                for i in v.iter().skip(1) {
+                    facts.add(Fact::Same(v[0].clone(), i.clone()));
+                }
+            }
+            Component::DifferentIndividuals(DifferentIndividuals(v)) => {
+                for (n, a) in v.iter().enumerate() {
+                    for b in &v[n + 1..] {
+                        facts.add(Fact::Different(a.clone(), b.clone()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let build = Build::new();
+    let mut derived = BTreeSet::new();
+    loop {
+        let mut found = vec![];
+        for (rule, plan) in &rules {
+            let mut bindings = vec![HashMap::new()];
+            for atom in plan {
+                bindings = bindings
+                    .iter()
+                    .flat_map(|b| matches(&facts, &build, atom, b))
+                    .collect();
+            }
+            for b in &bindings {
+                found.extend(rule.head.iter().filter_map(|atom| head(&facts, atom, b)));
+            }
+        }
+
+        let mut changed = false;
+        for fact in found {
+            if facts.add(fact.clone()) {
+                changed = true;
+                derived.insert(fact.component());
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let asserted: HashSet<_> = ont.i().iter().map(|ac| &ac.component).collect();
+    Ok(derived
+        .into_iter()
+        .filter(|c| !asserted.contains(c))
+        .map(|c| c.into())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::{RcAnnotatedComponent, RcStr};
+
+    use pretty_assertions::assert_eq;
+
+    fn infer_ofn(ofn: &str) -> Result<Vec<Component<RcStr>>, HornedError> {
+        let ont: ComponentMappedOntology<RcStr, RcAnnotatedComponent> =
+            crate::io::ofn::reader::read_example(ofn);
+        Ok(infer(&ont)?.into_iter().map(|ac| ac.component).collect())
+    }
+
+    fn iri(s: &str) -> String {
+        format!("http://example.com/{}", s)
+    }
+
+    fn ind(s: &str) -> Individual<RcStr> {
+        Build::new_rc().named_individual(iri(s)).into()
+    }
+
+    fn integer(s: &str) -> Literal<RcStr> {
+        typed(&Build::new_rc(), s.to_string(), "integer")
+    }
+
+    #[test]
+    fn chain() {
+        let c = infer_ofn(
+            "ObjectPropertyAssertion(:hasParent :a :b)
+             ObjectPropertyAssertion(:hasParent :b :c)
+             ObjectPropertyAssertion(:hasBrother :b :d)
+             DLSafeRule(Body(
+                 ObjectPropertyAtom(:hasParent Variable(:x) Variable(:y))
+                 ObjectPropertyAtom(:hasParent Variable(:y) Variable(:z)))
+               Head(ObjectPropertyAtom(:hasGrandparent Variable(:x) Variable(:z))))
+             DLSafeRule(Body(
+                 ObjectPropertyAtom(:hasGrandparent Variable(:x) Variable(:z)))
+               Head(ClassAtom(:Grandchild Variable(:x))))
+             DLSafeRule(Body(
+                 ObjectPropertyAtom(:hasParent Variable(:x) Variable(:y))
+                 ObjectPropertyAtom(:hasBrother Variable(:y) Variable(:z)))
+               Head(ObjectPropertyAtom(:hasUncle Variable(:x) Variable(:z))))",
+        )
+        .unwrap();
+        let b = Build::new_rc();
+        assert_eq!(
+            c,
+            vec![
+                ClassAssertion {
+                    ce: b.class(iri("Grandchild")).into(),
+                    i: ind("a")
+                }
+                .into(),
+                ObjectPropertyAssertion {
+                    ope: b.object_property(iri("hasGrandparent")).into(),
+                    from: ind("a"),
+                    to: ind("c")
+                }
+                .into(),
+                ObjectPropertyAssertion {
+                    ope: b.object_property(iri("hasUncle")).into(),
+                    from: ind("a"),
+                    to: ind("d")
+                }
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn builtins() {
+        let c = infer_ofn(
+            "DataPropertyAssertion(:age :a \"17\"^^xsd:integer)
+             DataPropertyAssertion(:age :b \"42\"^^xsd:integer)
+             DataPropertyAssertion(:name :b \"Bob\")
+             DLSafeRule(Body(
+                 DataPropertyAtom(:age Variable(:x) Variable(:n))
+                 BuiltInAtom(swrlb:greaterThanOrEqual Variable(:n) \"18\"^^xsd:integer)
+                 BuiltInAtom(swrlb:add Variable(:m) Variable(:n) \"1\"^^xsd:integer))
+               Head(ClassAtom(:Adult Variable(:x)) DataPropertyAtom(:nextAge Variable(:x) Variable(:m))))
+             DLSafeRule(Body(
+                 DataPropertyAtom(:name Variable(:x) Variable(:s))
+                 BuiltInAtom(swrlb:stringConcat Variable(:t) Variable(:s) \"!\")
+                 BuiltInAtom(swrlb:startsWith Variable(:s) \"B\"))
+               Head(DataPropertyAtom(:shout Variable(:x) Variable(:t))))",
+        )
+        .unwrap();
+        let b = Build::new_rc();
+        assert_eq!(
+            c,
+            vec![
+                ClassAssertion {
+                    ce: b.class(iri("Adult")).into(),
+                    i: ind("b")
+                }
+                .into(),
+                DataPropertyAssertion {
+                    dp: b.data_property(iri("nextAge")),
+                    from: ind("b"),
+                    to: integer("43")
+                }
+                .into(),
+                DataPropertyAssertion {
+                    dp: b.data_property(iri("shout")),
+                    from: ind("b"),
+                    to: Literal::Simple {
+                        literal: "Bob!".to_string()
+                    }
+                }
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_individuals() {
+        let c = infer_ofn(
+            "ObjectPropertyAssertion(:hasMother :a :m1)
+             ObjectPropertyAssertion(:hasMother :a :m2)
+             ClassAssertion(:Woman :m2)
+             DLSafeRule(Body(
+                 ObjectPropertyAtom(:hasMother Variable(:x) Variable(:y))
+                 ObjectPropertyAtom(:hasMother Variable(:x) Variable(:z)))
+               Head(SameIndividualAtom(Variable(:y) Variable(:z))))
+             DLSafeRule(Body(
+                 ObjectPropertyAtom(:hasMother Variable(:x) Variable(:y))
+                 ClassAtom(:Woman Variable(:y)))
+               Head(ClassAtom(:Daughter Variable(:x))))",
+        )
+        .unwrap();
+        assert!(c.contains(&SameIndividual(vec![ind("m1"), ind("m2")]).into()));
+        assert!(c.contains(
+            &ClassAssertion {
+                ce: Build::new_rc().class(iri("Daughter")).into(),
+                i: ind("a")
+            }
+            .into()
+        ));
+    }
+
+    #[test]
+    fn functions() {
+        let b = Build::new_rc();
+        let f = |name, args: &[Literal<RcStr>]| function(&b, name, args);
+        let s = |s: &str| string::<RcStr>(s.to_string());
+
+        assert_eq!(
+            f("multiply", &[integer("6"), integer("7")]),
+            Some(integer("42"))
+        );
+        assert_eq!(
+            f("integerDivide", &[integer("7"), integer("2")]),
+            Some(integer("3"))
+        );
+        assert_eq!(f("divide", &[integer("1"), integer("0")]), None);
+        assert_eq!(f("abs", &[integer("-3")]), Some(integer("3")));
+        assert_eq!(f("stringLength", &[s("héllo")]), Some(integer("5")));
+        assert_eq!(
+            f("substring", &[s("motor car"), integer("6")]),
+            Some(s(" car"))
+        );
+        assert_eq!(
+            f("substringBefore", &[s("tattoo"), s("too")]),
+            Some(s("tat"))
+        );
+        assert!(test("lessThan", &[integer("2"), integer("10")]));
+        assert!(test(
+            "equal",
+            &[integer("2"), typed(&b, "2.0".into(), "double")]
+        ));
+        assert!(test("matches", &[s("abc"), s("^a.c$")]));
+        // Beyond the precision of f64
+        assert!(!test(
+            "equal",
+            &[integer("9007199254740993"), integer("9007199254740992")]
+        ));
+        assert!(test(
+            "lessThan",
+            &[
+                typed(&b, "0.10000000000000000001".into(), "decimal"),
+                typed(&b, "0.10000000000000000002".into(), "decimal")
+            ]
+        ));
+        assert!(test(
+            "equal",
+            &[
+                integer("100000000000000000000000000000000000001"),
+                typed(
+                    &b,
+                    "100000000000000000000000000000000000001.0".into(),
+                    "decimal"
+                )
+            ]
+        ));
+        assert!(!test("containsIgnoreCase", &[s("abc"), s("D")]));
+
+        let d = |s: &str| typed(&b, s.into(), "decimal");
+        assert_eq!(f("add", &[d("0.1"), d("0.2")]), Some(d("0.3")));
+        assert_eq!(f("subtract", &[d("0.3"), integer("1")]), Some(d("-0.7")));
+        assert_eq!(f("multiply", &[d("1.1"), d("1.1")]), Some(d("1.21")));
+        assert_eq!(f("divide", &[integer("1"), integer("4")]), Some(d("0.25")));
+    }
+
+    #[test]
+    fn decimal_builtins() {
+        let c = infer_ofn(
+            "DataPropertyAssertion(:p :a \"0.1\"^^xsd:decimal)
+             DLSafeRule(Body(
+                 DataPropertyAtom(:p Variable(:x) Variable(:v))
+                 BuiltInAtom(swrlb:add \"0.3\"^^xsd:decimal Variable(:v) \"0.2\"^^xsd:decimal))
+               Head(ClassAtom(:A Variable(:x))))
+             DLSafeRule(Body(
+                 DataPropertyAtom(:p Variable(:x) Variable(:v))
+                 BuiltInAtom(swrlb:add Variable(:w) Variable(:v) \"0.2\"^^xsd:decimal))
+               Head(DataPropertyAtom(:q Variable(:x) Variable(:w))))",
+        )
+        .unwrap();
+        let b = Build::new_rc();
+        assert_eq!(
+            c,
+            vec![
+                ClassAssertion {
+                    ce: b.class(iri("A")).into(),
+                    i: ind("a")
+                }
+                .into(),
+                DataPropertyAssertion {
+                    dp: b.data_property(iri("q")),
+                    from: ind("a"),
+                    to: typed(&b, "0.3".into(), "decimal")
+                }
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsafe_rules() {
+        assert!(infer_ofn(
+            "DLSafeRule(Body(ClassAtom(:A Variable(:x))) Head(ClassAtom(:B Variable(:y))))"
+        )
+        .is_err());
+        assert!(infer_ofn(
+            "DLSafeRule(Body(ClassAtom(:A Variable(:x))
+                 BuiltInAtom(swrlb:lessThan Variable(:n) \"1\"^^xsd:integer))
+               Head(ClassAtom(:B Variable(:x))))"
+        )
+        .is_err());
+        assert!(infer_ofn(
+            "DLSafeRule(Body(DataPropertyAtom(:d Variable(:x) Variable(:n))
+                 BuiltInAtom(swrlb:unknown Variable(:n)))
+               Head(ClassAtom(:B Variable(:x))))"
+        )
+        .is_err());
+
+        // The syntax requires arguments, but the model does not
+        let b = Build::new_rc();
+        let x: IArgument<RcStr> = b.variable(iri("x")).into();
+        let rule = Rule {
+            head: vec![Atom::ClassAtom {
+                pred: b.class(iri("B")).into(),
+                arg: x.clone(),
+            }],
+            body: vec![
+                Atom::ClassAtom {
+                    pred: b.class(iri("A")).into(),
+                    arg: x,
+                },
+                Atom::BuiltInAtom {
+                    pred: b.iri("http://www.w3.org/2003/11/swrlb#add"),
+                    args: vec![],
+                },
+            ],
+        };
+        assert!(plan(&rule).is_err());
+    }
+}